@@ -0,0 +1,97 @@
+//! Collecting a structure over a connector with a round trip latency, with and without
+//! batched reads of the pointer targets.
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use memflow::prelude::v1::*;
+use memflow_pointer_debug::{FakeMemory, PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+
+/// Latency of every request to the connector, like a DMA card or a network connector
+const LATENCY: Duration = Duration::from_micros(100);
+
+#[repr(C)]
+#[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+struct Team {
+    id: u64,
+    score: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+struct Player {
+    id: u64,
+    health: u32,
+    armor: u32,
+    team: Pointer64<Team>,
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+struct World {
+    tick: u64,
+    players: [Pointer64<Player>; 16],
+}
+
+/// Memory that waits for [`LATENCY`] on every request, however many ranges it reads
+struct SlowMemory(FakeMemory);
+
+impl MemoryView for SlowMemory {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        std::thread::sleep(LATENCY);
+        self.0.read_raw_iter(data)
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.0.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.0.metadata()
+    }
+}
+
+/// A world whose players are allocated next to each other and whose teams are scattered
+fn world(mem: &mut FakeMemory) -> World {
+    let mut players = [Pointer64::null(); 16];
+    for (i, player) in players.iter_mut().enumerate() {
+        let i = i as u64;
+        let team = mem.place(
+            0x10_0000 + (i % 4) * 0x1000,
+            &Team {
+                id: i % 4,
+                score: 10,
+            },
+        );
+        *player = mem.place(
+            0x1000 + i * core::mem::size_of::<Player>() as u64,
+            &Player {
+                id: i,
+                health: 100,
+                armor: 50,
+                team,
+            },
+        );
+    }
+    World { tick: 1, players }
+}
+
+fn batch_reads(c: &mut Criterion) {
+    let mut fake = FakeMemory::new();
+    let world = world(&mut fake);
+    let mut mem = SlowMemory(fake);
+
+    let mut group = c.benchmark_group("collect_slow_connector");
+    for batch in [false, true] {
+        let config = PointerPrintConfig::new().batch_reads(batch);
+        let name = if batch { "batched" } else { "one_by_one" };
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(world.pointer_collect_with_config(&mut mem, &config)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, batch_reads);
+criterion_main!(benches);
@@ -0,0 +1,834 @@
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::*;
+
+/// Radix used to print an integer field
+#[derive(Clone, Copy)]
+pub enum NumberFormat {
+    Hex,
+    Bin,
+    Oct,
+}
+
+/// Byte order of a field given with `endian = "..."`
+#[derive(Clone, Copy)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Options set on the type through `#[pointer_debug(...)]`
+#[derive(Default)]
+pub struct TypeAttrs {
+    /// Size the type must have
+    pub expected_size: Option<LitInt>,
+    /// Field of a union printed unless a selector picks another one
+    pub active: Option<LitStr>,
+}
+
+impl TypeAttrs {
+    /// Parse every `#[pointer_debug(...)]` attribute on the type
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("pointer_debug")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                other => {
+                    return Err(Error::new_spanned(other, "expected `#[pointer_debug(...)]`"));
+                }
+            };
+
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("active") => {
+                        out.active = Some(match &nv.lit {
+                            Lit::Str(s) => s.clone(),
+                            other => return Err(Error::new_spanned(other, "expected a field name string")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("expected_size") => {
+                        out.expected_size = Some(match &nv.lit {
+                            Lit::Int(int) => int.clone(),
+                            other => return Err(Error::new_spanned(other, "expected an integer")),
+                        });
+                    }
+                    other => {
+                        return Err(Error::new_spanned(other, "unknown pointer_debug attribute"));
+                    }
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Options set on a field through `#[pointer_debug(...)]`
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// Exclude the field from the output entirely
+    pub skip: bool,
+    /// The field is padding and never printed
+    pub pad: bool,
+    /// Name printed instead of the field identifier
+    pub rename: Option<String>,
+    /// Collect an embedded structure through its `DerefDebugPrint` implementation
+    pub nested: bool,
+    /// The field is a `PointerLike` type whose name doesn't contain `Pointer`
+    pub pointer: bool,
+    /// Number of levels followed below this field, replacing the remaining global depth
+    pub max_depth: Option<usize>,
+    /// Print the field in a different radix
+    pub format: Option<NumberFormat>,
+    /// Print an integer field as the names of its set bits
+    pub flags: Option<FlagsAttr>,
+    /// Print an integer field as the variant of this enum it converts to
+    pub enum_repr: Option<Type>,
+    /// Print an integer field as a date and time
+    pub timestamp: Option<TimestampKind>,
+    /// Read the pointer target as a NUL-terminated string of at most this many bytes
+    pub cstr: Option<usize>,
+    /// Read the pointer target as a UTF-16 string
+    pub wstr: Option<WideString>,
+    /// Read a run of elements starting at the pointer target
+    pub array: Option<ArrayAttr>,
+    /// Read this many bytes at the pointer target and print them as a hex dump
+    pub hexdump: Option<usize>,
+    /// Read the pointers of a NULL-terminated array at the pointer target, at most this many
+    pub ptr_array: Option<usize>,
+    /// Iterate a linked list through this field
+    pub follow_chain: Option<ChainAttr>,
+    /// Walk the `LIST_ENTRY` list headed by this field
+    pub list: Option<ListAttr>,
+    /// Walk the entry chains of a hash table through this pointer to its bucket heads
+    pub buckets: Option<BucketsAttr>,
+    /// Print the field with a custom function
+    pub with: Option<WithFn>,
+    /// Read a structure of this type at the address held by an integer field
+    pub deref_as: Option<Type>,
+    /// Clear the address bits outside this mask before following a pointer field
+    pub ptr_mask: Option<u64>,
+    /// The bits cleared by `ptr_mask` are not a tag and are not printed, set by `addr_width`
+    pub untagged: bool,
+    /// Name of the memory view the pointer target is read from
+    pub view: Option<String>,
+    /// Condition on the fields of `self` that has to hold for a pointer field to be followed
+    pub deref_if: Option<Expr>,
+    /// Read the pointer target as the type mapped from the value of a sibling field
+    pub dyn_type: Option<DynTypeAttr>,
+    /// Collect an embedded union as the field selected by the value of this sibling field
+    pub select_by: Option<Member>,
+    /// Value of the selector picking this field of a union
+    pub when: Option<u64>,
+    /// The field is a vtable pointer printed as the module and export it points into
+    pub vtable: bool,
+    /// The field is a function pointer printed as the module export it points to
+    pub function: bool,
+    /// Print `<redacted>` instead of the value
+    pub redact: Option<RedactAttr>,
+    /// Byte order of the field, regardless of the byte order of the memory
+    pub endian: Option<Endian>,
+}
+
+/// Options of the `redact` attribute
+pub struct RedactAttr {
+    /// Collect the value and print a hash of it
+    pub hash: bool,
+}
+
+/// Encoding of a `timestamp` field
+#[derive(Clone, Copy)]
+pub enum TimestampKind {
+    FileTime,
+    UnixSecs,
+    UnixMillis,
+}
+
+/// Names of the bits of a `flags` field
+pub enum FlagsAttr {
+    /// Names and values listed in `flags(READ = 0x1, ...)`
+    List(Vec<(String, u64)>),
+    /// A `bitflags!` type given with `flags = "Type"`
+    Type(Box<Type>),
+}
+
+/// Options of the `with` and `with_mem` attributes
+pub struct WithFn {
+    /// Function formatting the field
+    pub path: Path,
+    /// Pass the memory view to the function as well
+    pub mem: bool,
+}
+
+/// Options of the `list` attribute
+pub struct ListAttr {
+    /// Type of the structures linked by the list
+    pub record: Type,
+    /// Expression evaluating to the offset of the `LIST_ENTRY` inside `record`
+    pub offset: TokenStream,
+    /// Maximum number of records read
+    pub max: usize,
+}
+
+/// Options of the `buckets` attribute
+pub struct BucketsAttr {
+    /// Sibling field holding the number of buckets
+    pub count_field: Member,
+    /// Field of the entries linking to the next entry of the bucket
+    pub next: Member,
+    /// Maximum number of buckets read
+    pub max: usize,
+    /// Maximum number of entries read per bucket
+    pub max_chain: usize,
+}
+
+/// Options of the `follow_chain` attribute
+pub struct ChainAttr {
+    /// Maximum number of chain elements read
+    pub max: usize,
+}
+
+/// Options of the `dyn_type` attribute
+pub struct DynTypeAttr {
+    /// Sibling field holding the type id
+    pub by: Member,
+    /// Type read at the pointer for each type id
+    pub map: Vec<(u64, Path)>,
+}
+
+/// Options of the `array` and `slice` attributes
+pub struct ArrayAttr {
+    /// Sibling field holding the number of elements, `max` elements are read without one
+    pub len_field: Option<Member>,
+    /// Maximum number of elements read
+    pub max: usize,
+}
+
+/// Default maximum number of elements read for arrays
+const DEFAULT_ARRAY_MAX: usize = 64;
+
+/// Default maximum number of elements read for chains and lists
+const DEFAULT_CHAIN_MAX: usize = 32;
+
+/// Default maximum number of pointers read for NULL-terminated pointer arrays and hash
+/// table buckets
+const DEFAULT_PTR_ARRAY_MAX: usize = 256;
+
+/// Options of the `wstr` attribute
+pub struct WideString {
+    /// Sibling field holding the string length in bytes
+    pub len_field: Option<Member>,
+    /// Maximum number of UTF-16 code units read
+    pub max_len: usize,
+}
+
+/// Default maximum number of bytes read for string pointers
+const DEFAULT_STRING_LEN: usize = 256;
+
+/// Error spanning the `pointer_debug` attributes of a field
+fn conflict(attrs: &[Attribute], message: &str) -> Error {
+    let attr = attrs.iter().find(|attr| attr.path.is_ident("pointer_debug"));
+    Error::new_spanned(attr, message)
+}
+
+/// Parse a string literal naming a sibling field, tuple fields are named by their index
+fn parse_member(lit: &Lit) -> Result<Member> {
+    match lit {
+        Lit::Str(s) => s.parse(),
+        other => Err(Error::new_spanned(other, "expected a field name string")),
+    }
+}
+
+/// Parse a string literal naming a function
+fn parse_path(lit: &Lit) -> Result<Path> {
+    match lit {
+        Lit::Str(s) => s.parse(),
+        other => Err(Error::new_spanned(other, "expected a function path string")),
+    }
+}
+
+/// Parse an integer literal
+fn parse_usize(lit: &Lit) -> Result<usize> {
+    match lit {
+        Lit::Int(int) => int.base10_parse(),
+        other => Err(Error::new_spanned(other, "expected an integer")),
+    }
+}
+
+/// Parse `flags(READ = 0x1, WRITE = 0x2)`
+fn parse_flags(list: &MetaList) -> Result<FlagsAttr> {
+    let mut flags = Vec::new();
+    for nested in list.nested.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.get_ident().is_some() => {
+                let value = match &nv.lit {
+                    Lit::Int(int) => int.base10_parse::<u64>()?,
+                    other => return Err(Error::new_spanned(other, "expected an integer")),
+                };
+                flags.push((nv.path.get_ident().unwrap().to_string(), value));
+            }
+            other => return Err(Error::new_spanned(other, "expected `NAME = value`")),
+        }
+    }
+    if flags.is_empty() {
+        return Err(Error::new_spanned(list, "`flags` requires at least one `NAME = value`"));
+    }
+    Ok(FlagsAttr::List(flags))
+}
+
+/// An entry of `map(1 = "Player", 2 = "Npc")` in a `dyn_type` attribute
+struct DynTypeEntry {
+    id: LitInt,
+    ty: Path,
+}
+
+impl Parse for DynTypeEntry {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let id = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let ty: LitStr = input.parse()?;
+        Ok(Self { id, ty: ty.parse()? })
+    }
+}
+
+/// Rewrite `map(1 = "Player", 2 = "Npc")` into `map(Player = 1, Npc = 2)`, which unlike the
+/// ids on the left can be parsed as a `Meta`. Other tokens are kept as they are
+fn normalize_dyn_type_map(tokens: TokenStream) -> TokenStream {
+    let mut out = Vec::new();
+    let mut after_map = false;
+    for token in tokens {
+        let token = match token {
+            TokenTree::Group(group) => {
+                let parsed = (after_map && group.delimiter() == Delimiter::Parenthesis)
+                    .then(|| Punctuated::<DynTypeEntry, Token![,]>::parse_terminated.parse2(group.stream()).ok())
+                    .flatten();
+                let stream = match parsed {
+                    Some(entries) => {
+                        let entries = entries.iter().map(|DynTypeEntry { id, ty }| quote! { #ty = #id });
+                        quote! { #(#entries),* }
+                    }
+                    None => normalize_dyn_type_map(group.stream()),
+                };
+                let mut normalized = Group::new(group.delimiter(), stream);
+                normalized.set_span(group.span());
+                TokenTree::Group(normalized)
+            }
+            token => token,
+        };
+        after_map = matches!(&token, TokenTree::Ident(ident) if ident == "map");
+        out.push(token);
+    }
+    out.into_iter().collect()
+}
+
+/// Parse `dyn_type(by = "class_id", map(1 = "Player", 2 = "Npc"))`, the types can also be
+/// given like `map(Player = 1, Npc = 2)`
+fn parse_dyn_type(list: &MetaList) -> Result<DynTypeAttr> {
+    let mut by = None;
+    let mut map = Vec::new();
+
+    for nested in list.nested.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("by") => {
+                by = Some(parse_member(&nv.lit)?);
+            }
+            NestedMeta::Meta(Meta::List(types)) if types.path.is_ident("map") => {
+                for nested in types.nested.iter() {
+                    match nested {
+                        NestedMeta::Meta(Meta::NameValue(nv)) => {
+                            let id = match &nv.lit {
+                                Lit::Int(int) => int.base10_parse::<u64>()?,
+                                other => return Err(Error::new_spanned(other, "expected an integer")),
+                            };
+                            map.push((id, nv.path.clone()));
+                        }
+                        other => return Err(Error::new_spanned(other, "expected `id = \"Type\"`")),
+                    }
+                }
+            }
+            other => {
+                return Err(Error::new_spanned(
+                    other,
+                    "expected `by = \"...\"` or `map(id = \"Type\", ...)`",
+                ));
+            }
+        }
+    }
+
+    let by = by.ok_or_else(|| Error::new_spanned(list, "`dyn_type` requires `by = \"...\"`"))?;
+    if map.is_empty() {
+        return Err(Error::new_spanned(list, "`dyn_type` requires at least one `id = \"Type\"` in `map(...)`"));
+    }
+    Ok(DynTypeAttr { by, map })
+}
+
+/// Parse `list(entry = "Type", field = "links", max = 32)`, `offset = 0x448` can be given
+/// instead of `field`
+fn parse_list(list: &MetaList) -> Result<ListAttr> {
+    let mut record = None;
+    let mut field = None;
+    let mut offset = None;
+    let mut max = DEFAULT_CHAIN_MAX;
+
+    for nested in list.nested.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("entry") => {
+                record = Some(match &nv.lit {
+                    Lit::Str(s) => s.parse::<Type>()?,
+                    other => return Err(Error::new_spanned(other, "expected a type name string")),
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("field") => {
+                field = Some(parse_member(&nv.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("offset") => {
+                offset = Some(parse_usize(&nv.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                max = parse_usize(&nv.lit)?;
+            }
+            other => {
+                return Err(Error::new_spanned(
+                    other,
+                    "expected `entry = \"...\"`, `field = \"...\"`, `offset = ...` or `max = ...`",
+                ));
+            }
+        }
+    }
+
+    let record =
+        record.ok_or_else(|| Error::new_spanned(list, "`list` requires `entry = \"...\"`"))?;
+    let offset = match (field, offset) {
+        (Some(field), None) => quote! { ::core::mem::offset_of!(#record, #field) },
+        (None, Some(offset)) => quote! { #offset },
+        _ => {
+            return Err(Error::new_spanned(
+                list,
+                "`list` requires either `field = \"...\"` or `offset = ...`",
+            ));
+        }
+    };
+
+    Ok(ListAttr { record, offset, max })
+}
+
+/// Parse `buckets(count = "bucket_count", next = "next", max = 256, max_chain = 32)`
+fn parse_buckets(list: &MetaList) -> Result<BucketsAttr> {
+    let mut count_field = None;
+    let mut next = None;
+    let mut max = DEFAULT_PTR_ARRAY_MAX;
+    let mut max_chain = DEFAULT_CHAIN_MAX;
+
+    for nested in list.nested.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("count") => {
+                count_field = Some(parse_member(&nv.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("next") => {
+                next = Some(parse_member(&nv.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                max = parse_usize(&nv.lit)?;
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max_chain") => {
+                max_chain = parse_usize(&nv.lit)?;
+            }
+            other => {
+                return Err(Error::new_spanned(
+                    other,
+                    "expected `count = \"...\"`, `next = \"...\"`, `max = ...` or `max_chain = ...`",
+                ));
+            }
+        }
+    }
+
+    let count_field = count_field
+        .ok_or_else(|| Error::new_spanned(list, "`buckets` requires `count = \"...\"`"))?;
+    let next =
+        next.ok_or_else(|| Error::new_spanned(list, "`buckets` requires `next = \"...\"`"))?;
+    Ok(BucketsAttr { count_field, next, max, max_chain })
+}
+
+impl FieldAttrs {
+    /// Parse every `#[pointer_debug(...)]` attribute on a field
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("pointer_debug")) {
+            let mut attr = attr.clone();
+            attr.tokens = normalize_dyn_type_map(attr.tokens);
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                other => {
+                    return Err(Error::new_spanned(other, "expected `#[pointer_debug(...)]`"));
+                }
+            };
+
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => out.skip = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("pad") => out.pad = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("nested") => out.nested = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("pointer") => out.pointer = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("vtable") => out.vtable = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("function") => out.function = true,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("redact") => {
+                        out.redact = Some(RedactAttr { hash: false });
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("redact") => {
+                        let mut hash = false;
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("hash") => hash = true,
+                                other => {
+                                    return Err(Error::new_spanned(other, "expected `hash`"));
+                                }
+                            }
+                        }
+                        out.redact = Some(RedactAttr { hash });
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("cstr") => {
+                        out.cstr = Some(DEFAULT_STRING_LEN);
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("cstr") => {
+                        let mut max_len = DEFAULT_STRING_LEN;
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max_len") => {
+                                    max_len = parse_usize(&nv.lit)?;
+                                }
+                                other => {
+                                    return Err(Error::new_spanned(other, "expected `max_len = ...`"));
+                                }
+                            }
+                        }
+                        out.cstr = Some(max_len);
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("wstr") => {
+                        out.wstr = Some(WideString {
+                            len_field: None,
+                            max_len: DEFAULT_STRING_LEN,
+                        });
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("wstr") => {
+                        let mut wstr = WideString {
+                            len_field: None,
+                            max_len: DEFAULT_STRING_LEN,
+                        };
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max_len") => {
+                                    wstr.max_len = parse_usize(&nv.lit)?;
+                                }
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("len_field") => {
+                                    wstr.len_field = Some(parse_member(&nv.lit)?);
+                                }
+                                other => {
+                                    return Err(Error::new_spanned(
+                                        other,
+                                        "expected `len_field = \"...\"` or `max_len = ...`",
+                                    ));
+                                }
+                            }
+                        }
+                        out.wstr = Some(wstr);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("hexdump") => {
+                        out.hexdump = Some(parse_usize(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("array") || list.path.is_ident("slice") => {
+                        if out.array.is_some() {
+                            return Err(Error::new_spanned(list, "`array` and `slice` can not be combined"));
+                        }
+                        let mut len_field = None;
+                        let mut max = DEFAULT_ARRAY_MAX;
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("len") => {
+                                    len_field = Some(parse_member(&nv.lit)?);
+                                }
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                                    max = parse_usize(&nv.lit)?;
+                                }
+                                other => {
+                                    return Err(Error::new_spanned(
+                                        other,
+                                        "expected `len = \"...\"` or `max = ...`",
+                                    ));
+                                }
+                            }
+                        }
+                        // Slices without a length read `max` elements
+                        if len_field.is_none() && list.path.is_ident("array") {
+                            return Err(Error::new_spanned(list, "`array` requires `len = \"...\"`"));
+                        }
+                        out.array = Some(ArrayAttr { len_field, max });
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("slice") => {
+                        if out.array.is_some() {
+                            return Err(Error::new_spanned(path, "`array` and `slice` can not be combined"));
+                        }
+                        out.array = Some(ArrayAttr { len_field: None, max: DEFAULT_ARRAY_MAX });
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("ptr_array_null_terminated") => {
+                        out.ptr_array = Some(DEFAULT_PTR_ARRAY_MAX);
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("ptr_array_null_terminated") => {
+                        let mut max = DEFAULT_PTR_ARRAY_MAX;
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                                    max = parse_usize(&nv.lit)?;
+                                }
+                                other => {
+                                    return Err(Error::new_spanned(other, "expected `max = ...`"));
+                                }
+                            }
+                        }
+                        out.ptr_array = Some(max);
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("follow_chain") => {
+                        out.follow_chain = Some(ChainAttr {
+                            max: DEFAULT_CHAIN_MAX,
+                        });
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("follow_chain") => {
+                        let mut max = DEFAULT_CHAIN_MAX;
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                                    max = parse_usize(&nv.lit)?;
+                                }
+                                other => {
+                                    return Err(Error::new_spanned(other, "expected `max = ...`"));
+                                }
+                            }
+                        }
+                        out.follow_chain = Some(ChainAttr { max });
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("list") => {
+                        out.list = Some(parse_list(list)?);
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("buckets") => {
+                        out.buckets = Some(parse_buckets(list)?);
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("flags") => {
+                        out.flags = Some(parse_flags(list)?);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("flags") => {
+                        out.flags = Some(FlagsAttr::Type(Box::new(match &nv.lit {
+                            Lit::Str(s) => s.parse::<Type>()?,
+                            other => return Err(Error::new_spanned(other, "expected a type name string")),
+                        })));
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("timestamp") => {
+                        out.timestamp = Some(match &nv.lit {
+                            Lit::Str(s) if s.value() == "filetime" => TimestampKind::FileTime,
+                            Lit::Str(s) if s.value() == "unix_secs" => TimestampKind::UnixSecs,
+                            Lit::Str(s) if s.value() == "unix_millis" => TimestampKind::UnixMillis,
+                            other => {
+                                return Err(Error::new_spanned(
+                                    other,
+                                    "expected one of \"filetime\", \"unix_secs\" or \"unix_millis\"",
+                                ));
+                            }
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("enum_repr") => {
+                        out.enum_repr = Some(match &nv.lit {
+                            Lit::Str(s) => s.parse::<Type>()?,
+                            other => return Err(Error::new_spanned(other, "expected a type name string")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") || nv.path.is_ident("with_mem") => {
+                        if out.with.is_some() {
+                            return Err(Error::new_spanned(nv, "`with` and `with_mem` can only be given once"));
+                        }
+                        out.with = Some(WithFn {
+                            path: parse_path(&nv.lit)?,
+                            mem: nv.path.is_ident("with_mem"),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                        out.rename = Some(match &nv.lit {
+                            Lit::Str(s) if !s.value().is_empty() => s.value(),
+                            other => return Err(Error::new_spanned(other, "expected a non-empty name string")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("deref_as") => {
+                        out.deref_as = Some(match &nv.lit {
+                            Lit::Str(s) => s.parse::<Type>()?,
+                            other => return Err(Error::new_spanned(other, "expected a type name string")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("ptr_mask") || nv.path.is_ident("tag_bits") || nv.path.is_ident("addr_width") => {
+                        if out.ptr_mask.is_some() {
+                            return Err(Error::new_spanned(nv, "`ptr_mask`, `tag_bits` and `addr_width` can only be given once"));
+                        }
+                        let value = match &nv.lit {
+                            Lit::Int(int) => int.base10_parse::<u64>()?,
+                            other => return Err(Error::new_spanned(other, "expected an integer")),
+                        };
+                        out.ptr_mask = Some(if nv.path.is_ident("ptr_mask") {
+                            value
+                        } else if nv.path.is_ident("addr_width") {
+                            if !(1..64).contains(&value) {
+                                return Err(Error::new_spanned(&nv.lit, "expected an address width between 1 and 63 bits"));
+                            }
+                            // Only the low bits hold the address, the others are garbage
+                            out.untagged = true;
+                            (1u64 << value) - 1
+                        } else if (1..64).contains(&value) {
+                            // The low bits hold the tag
+                            !((1u64 << value) - 1)
+                        } else {
+                            return Err(Error::new_spanned(&nv.lit, "expected between 1 and 63 tag bits"));
+                        });
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("phys") => {
+                        if out.view.is_some() {
+                            return Err(Error::new_spanned(path, "`view` and `phys` can only be given once"));
+                        }
+                        // The name `PHYSICAL_VIEW` of the crate
+                        out.view = Some("phys".to_string());
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("view") => {
+                        if out.view.is_some() {
+                            return Err(Error::new_spanned(nv, "`view` and `phys` can only be given once"));
+                        }
+                        out.view = Some(match &nv.lit {
+                            Lit::Str(s) => s.value(),
+                            other => return Err(Error::new_spanned(other, "expected a view name string")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("deref_if") => {
+                        out.deref_if = Some(match &nv.lit {
+                            Lit::Str(s) => s.parse::<Expr>()?,
+                            other => return Err(Error::new_spanned(other, "expected a condition string")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("dyn_type") => {
+                        out.dyn_type = Some(parse_dyn_type(list)?);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("select_by") => {
+                        out.select_by = Some(parse_member(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("when") => {
+                        out.when = Some(match &nv.lit {
+                            Lit::Int(int) => int.base10_parse::<u64>()?,
+                            other => return Err(Error::new_spanned(other, "expected an integer")),
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max_depth") => {
+                        out.max_depth = Some(parse_usize(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("endian") => {
+                        out.endian = Some(match &nv.lit {
+                            Lit::Str(s) if s.value() == "little" => Endian::Little,
+                            Lit::Str(s) if s.value() == "big" => Endian::Big,
+                            other => {
+                                return Err(Error::new_spanned(other, "expected \"little\" or \"big\""));
+                            }
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("format") => {
+                        out.format = Some(match &nv.lit {
+                            Lit::Str(s) if s.value() == "hex" => NumberFormat::Hex,
+                            Lit::Str(s) if s.value() == "bin" => NumberFormat::Bin,
+                            Lit::Str(s) if s.value() == "oct" => NumberFormat::Oct,
+                            other => {
+                                return Err(Error::new_spanned(
+                                    other,
+                                    "expected one of \"hex\", \"bin\" or \"oct\"",
+                                ));
+                            }
+                        });
+                    }
+                    other => {
+                        return Err(Error::new_spanned(other, "unknown pointer_debug attribute"));
+                    }
+                }
+            }
+        }
+
+        let targets = [
+            out.cstr.is_some(),
+            out.wstr.is_some(),
+            out.array.is_some(),
+            out.hexdump.is_some(),
+            out.ptr_array.is_some(),
+            out.buckets.is_some(),
+            out.follow_chain.is_some(),
+            out.list.is_some(),
+        ];
+        if targets.iter().filter(|set| **set).count() > 1 {
+            return Err(conflict(
+                attrs,
+                "`cstr`, `wstr`, `array`, `slice`, `hexdump`, `ptr_array_null_terminated`, `buckets`, `follow_chain` and `list` can not be combined",
+            ));
+        }
+
+        let others = out.nested
+            || out.format.is_some()
+            || out.has_target_override()
+            || out.list.is_some();
+        let displays = [
+            ("flags", out.flags.is_some()),
+            ("enum_repr", out.enum_repr.is_some()),
+            ("timestamp", out.timestamp.is_some()),
+        ];
+        let display_count = displays.iter().filter(|(_, set)| *set).count();
+        if let Some((name, _)) = displays.iter().find(|(_, set)| *set)
+            && (others || out.max_depth.is_some() || display_count > 1)
+        {
+            return Err(conflict(attrs, &format!("`{}` can not be combined with other attributes", name)));
+        }
+        let others = others || display_count > 0;
+        if out.ptr_mask.is_some() && (out.nested || out.follow_chain.is_some() || out.list.is_some() || display_count > 0) {
+            return Err(conflict(attrs, "`ptr_mask`, `tag_bits` and `addr_width` can not be combined with `nested`, `follow_chain`, `list` or attributes formatting the value"));
+        }
+        if out.dyn_type.is_some() && (others || out.deref_as.is_some() || out.with.is_some()) {
+            return Err(conflict(attrs, "`dyn_type` can only be combined with `max_depth`, `ptr_mask`, `tag_bits` and `addr_width`"));
+        }
+        if out.select_by.is_some() && (others || out.dyn_type.is_some() || out.deref_as.is_some() || out.ptr_mask.is_some() || out.with.is_some()) {
+            return Err(conflict(attrs, "`select_by` can only be combined with `max_depth`"));
+        }
+        if out.deref_if.is_some() && out.with.is_some() {
+            return Err(conflict(attrs, "`deref_if` can not be combined with `with`"));
+        }
+        if out.view.is_some() && out.with.is_some() {
+            return Err(conflict(attrs, "`view` and `phys` can not be combined with `with`"));
+        }
+        if out.vtable && out.with.is_some() {
+            return Err(conflict(attrs, "`vtable` can not be combined with `with`"));
+        }
+        if out.function && out.with.is_some() {
+            return Err(conflict(attrs, "`function` can not be combined with `with`"));
+        }
+        if out.function && out.vtable {
+            return Err(conflict(attrs, "`function` can not be combined with `vtable`"));
+        }
+        if out.with.is_some() && (others || out.max_depth.is_some() || out.deref_as.is_some() || out.ptr_mask.is_some()) {
+            return Err(conflict(attrs, "`with` can not be combined with other attributes"));
+        }
+        if out.deref_as.is_some() && others {
+            return Err(conflict(attrs, "`deref_as` can only be combined with `max_depth`"));
+        }
+
+        Ok(out)
+    }
+
+    /// Returns true if an attribute changes how the pointer target is read
+    pub fn has_target_override(&self) -> bool {
+        self.cstr.is_some()
+            || self.wstr.is_some()
+            || self.array.is_some()
+            || self.hexdump.is_some()
+            || self.ptr_array.is_some()
+            || self.buckets.is_some()
+            || self.follow_chain.is_some()
+    }
+}
@@ -0,0 +1,889 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::{Expr, Ident, Index, Member, Type};
+
+use crate::attrs::{ArrayAttr, BucketsAttr, ChainAttr, DynTypeAttr, FieldAttrs, FlagsAttr, ListAttr, NumberFormat, TimestampKind, WideString};
+
+/// Name a field is bound to in the generated code.
+///
+/// Prefixed so fields can't shadow the locals of the generated code, e.g. a field named `mem`.
+pub fn binding(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => format_ident!("__field_{}", ident.unraw()),
+        Member::Unnamed(index) => format_ident!("__field_{}", index.index),
+    }
+}
+
+/// Expression reading the sibling field `member`
+fn sibling(member: &Member) -> TokenStream {
+    let binding = binding(member);
+    quote! { (*#binding) }
+}
+
+/// Pointer to a `ty` at the address held by an integer field
+fn deref_as_pointer(access: &TokenStream, ty: &Type) -> TokenStream {
+    quote! { ::memflow::types::Pointer64::<#ty>::from(#access as u64) }
+}
+
+/// Generate the expression collecting the field `member` into a `PointerDebugValue`.
+///
+/// Fields that `follow` pointers are collected through their `DerefDebugPrint` implementation,
+/// integer and float fields of type `ty` are printed with the numeric options of the
+/// configuration.
+pub fn field_value(member: &Member, attrs: &FieldAttrs, ty: &Type, follow: bool, pointer: bool) -> TokenStream {
+    let access = sibling(member);
+    match attrs.ptr_mask {
+        // Everything below reads the pointer with its tag bits cleared
+        Some(mask) => {
+            let masked = quote! { __masked };
+            let value = conditional_value(&masked, view_value(&masked, access_value(&masked, member, attrs, ty, follow, pointer), attrs), attrs);
+            quote! {{
+                let (__masked, _) = ::memflow_pointer_debug::TraversalState::mask_pointer(&#access, #mask);
+                #value
+            }}
+        }
+        None => conditional_value(&access, view_value(&access, access_value(&access, member, attrs, ty, follow, pointer), attrs), attrs),
+    }
+}
+
+/// Print the vtable or function pointer `member` as the symbol `symbolize`, a method of the
+/// configuration, names it if the configured symbols know its address, `value` otherwise
+pub fn symbol_value(member: &Member, value: TokenStream, attrs: &FieldAttrs, pointer: bool, symbolize: TokenStream) -> TokenStream {
+    let access = sibling(member);
+    let address = match (pointer, attrs.ptr_mask) {
+        (true, Some(mask)) => quote! {
+            ::memflow_pointer_debug::PointerLike::pointer_address(&::memflow_pointer_debug::TraversalState::mask_pointer(&#access, #mask).0)
+        },
+        (true, None) => quote! { ::memflow_pointer_debug::PointerLike::pointer_address(&#access) },
+        (false, _) => quote! { #access as u64 },
+    };
+    quote! {
+        match state.config.#symbolize(#address) {
+            Some(symbol) => ::memflow_pointer_debug::PointerDebugValue::Value(symbol),
+            None => #value,
+        }
+    }
+}
+
+/// Collect `value` only if the `deref_if` condition holds, otherwise show the pointer read by
+/// `access` without reading its target
+fn conditional_value(access: &TokenStream, value: TokenStream, attrs: &FieldAttrs) -> TokenStream {
+    match &attrs.deref_if {
+        Some(condition) => {
+            let condition = condition_tokens(condition);
+            quote! {
+                if #condition {
+                    #value
+                } else {
+                    ::memflow_pointer_debug::PointerDebugValue::Pointer {
+                        address: ::memflow_pointer_debug::PointerLike::pointer_address(&#access),
+                        target: ::memflow_pointer_debug::PointerTarget::NotFollowed,
+                    }
+                }
+            }
+        }
+        None => value,
+    }
+}
+
+/// Collect `value` reading from the memory view named by the `view` attribute, the pointer
+/// read by `access` is not followed if the view isn't registered
+fn view_value(access: &TokenStream, value: TokenStream, attrs: &FieldAttrs) -> TokenStream {
+    let address = match attrs.deref_as {
+        Some(_) => quote! { #access as u64 },
+        None => quote! { ::memflow_pointer_debug::PointerLike::pointer_address(&#access) },
+    };
+    match &attrs.view {
+        Some(name) => quote! {
+            match state.config.views.get(#name).cloned() {
+                Some(mut view) => {
+                    let mem = &mut view;
+                    #value
+                }
+                None => ::memflow_pointer_debug::PointerDebugValue::Pointer {
+                    address: #address,
+                    target: ::memflow_pointer_debug::PointerTarget::NotFollowed,
+                },
+            }
+        },
+        None => value,
+    }
+}
+
+/// Rewrite the `self.field` accesses of a `deref_if` condition to the field bindings, so the
+/// condition works the same in structures, packed structures, enum variants and unions
+fn condition_tokens(condition: &Expr) -> TokenStream {
+    rewrite_self_fields(condition.to_token_stream())
+}
+
+/// Replace `self.field` and `self.0` in `tokens` and the groups inside them
+fn rewrite_self_fields(tokens: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut out = TokenStream::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let (TokenTree::Ident(this), Some(TokenTree::Punct(dot)), Some(field)) = (&tokens[i], tokens.get(i + 1), tokens.get(i + 2))
+            && this == "self"
+            && dot.as_char() == '.'
+        {
+            let member = match field {
+                TokenTree::Ident(ident) => Some(Member::Named(ident.clone())),
+                TokenTree::Literal(literal) => literal.to_string().parse().ok().map(|index| Member::Unnamed(Index { index, span: literal.span() })),
+                _ => None,
+            };
+            if let Some(member) = member {
+                out.extend(sibling(&member));
+                i += 3;
+                continue;
+            }
+        }
+        match &tokens[i] {
+            TokenTree::Group(group) => {
+                let mut rewritten = proc_macro2::Group::new(group.delimiter(), rewrite_self_fields(group.stream()));
+                rewritten.set_span(group.span());
+                out.extend([TokenTree::Group(rewritten)]);
+            }
+            other => out.extend([other.clone()]),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Generate the expression collecting the field read by `access`, `pointer` fields are
+/// followed through their `PointerLike` implementation
+fn access_value(access: &TokenStream, member: &Member, attrs: &FieldAttrs, ty: &Type, follow: bool, pointer: bool) -> TokenStream {
+    if let Some(with) = &attrs.with {
+        let path = &with.path;
+        let mem = with.mem.then(|| quote! { , mem });
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Value(#path(&#access #mem))
+        }
+    } else if let Some(wstr) = &attrs.wstr {
+        wstr_value(access, wstr)
+    } else if let Some(max_len) = attrs.cstr {
+        cstr_value(access, max_len)
+    } else if let Some(len) = attrs.hexdump {
+        hexdump_value(access, len)
+    } else if let Some(array) = &attrs.array {
+        array_value(access, array)
+    } else if let Some(max) = attrs.ptr_array {
+        ptr_array_value(access, max)
+    } else if let Some(list) = &attrs.list {
+        list_value(access, list)
+    } else if let Some(buckets) = &attrs.buckets {
+        buckets_value(access, buckets)
+    } else if let Some(chain) = &attrs.follow_chain {
+        chain_value(access, member, chain)
+    } else if let Some(dyn_type) = &attrs.dyn_type {
+        dyn_type_value(access, dyn_type)
+    } else if let Some(ty) = &attrs.deref_as {
+        let pointer = deref_as_pointer(access, ty);
+        quote! {
+            ::memflow_pointer_debug::DerefDebugPrint::pointer_collect_value(&#pointer, mem, depth, state)
+        }
+    } else if let Some(selector) = &attrs.select_by {
+        let selector = sibling(selector);
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Nested(::memflow_pointer_debug::__private::Box::new(
+                #access.pointer_collect_selected(Some(#selector as u64), mem, depth, state)
+            ))
+        }
+    } else if pointer && follow {
+        quote! {
+            ::memflow_pointer_debug::PointerLike::collect_target(&#access, mem, depth, state)
+        }
+    } else if follow && crate::is_pointer_array(ty) {
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Nested(::memflow_pointer_debug::__private::Box::new(
+                state.collect_pointer_array(&#access, mem, depth)
+            ))
+        }
+    } else if follow {
+        quote! {
+            ::memflow_pointer_debug::DerefDebugPrint::pointer_collect_value(&#access, mem, depth, state)
+        }
+    } else if let Some(flags) = &attrs.flags {
+        flags_value(access, flags)
+    } else if let Some(kind) = attrs.timestamp {
+        let kind = match kind {
+            TimestampKind::FileTime => quote! { FileTime },
+            TimestampKind::UnixSecs => quote! { UnixSecs },
+            TimestampKind::UnixMillis => quote! { UnixMillis },
+        };
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Value(
+                ::memflow_pointer_debug::format_timestamp(#access as i64, ::memflow_pointer_debug::TimestampFormat::#kind)
+            )
+        }
+    } else if let Some(ty) = &attrs.enum_repr {
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Value(
+                match <#ty as ::core::convert::TryFrom<_>>::try_from(#access) {
+                    Ok(variant) => ::memflow_pointer_debug::__private::format!("{:?}", variant),
+                    Err(_) => ::memflow_pointer_debug::__private::format!("Unknown({})", #access),
+                }
+            )
+        }
+    } else if let Some(format) = attrs.format {
+        format_value(access, format)
+    } else if crate::is_number_type(ty) {
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Value(
+                ::memflow_pointer_debug::FormatNumber::format_number(&#access, &state.config)
+            )
+        }
+    } else {
+        quote! {
+            ::memflow_pointer_debug::PointerDebugValue::Value(::memflow_pointer_debug::__private::format!("{:?}", #access))
+        }
+    }
+}
+
+/// Follow the pointer read by `access` as the type mapped from the value of the type id
+/// field, ids that are not mapped are followed as the declared target type
+fn dyn_type_value(access: &TokenStream, dyn_type: &DynTypeAttr) -> TokenStream {
+    let selector = sibling(&dyn_type.by);
+    let arms = dyn_type.map.iter().map(|(id, ty)| quote! {
+        #id => ::memflow_pointer_debug::DerefDebugPrint::pointer_collect_value(
+            &::memflow::types::Pointer64::<#ty>::from(::memflow_pointer_debug::PointerLike::pointer_address(&#access)),
+            mem,
+            depth,
+            state
+        ),
+    });
+    quote! {
+        match #selector as u64 {
+            #(#arms)*
+            _ => ::memflow_pointer_debug::PointerLike::collect_target(&#access, mem, depth, state),
+        }
+    }
+}
+
+/// Generate the statement adding the pointer targets of the followed field `member`
+pub fn field_targets(member: &Member, attrs: &FieldAttrs, pointer: bool) -> TokenStream {
+    let access = sibling(member);
+    let value = match (&attrs.deref_as, attrs.ptr_mask) {
+        (Some(ty), _) => deref_as_pointer(&access, ty),
+        (None, Some(mask)) => quote! {
+            ::memflow_pointer_debug::TraversalState::mask_pointer(&#access, #mask).0
+        },
+        (None, None) => access,
+    };
+    if pointer || attrs.deref_as.is_some() {
+        quote! {
+            targets.push(::memflow_pointer_debug::TraversalState::pointer_target(&#value));
+        }
+    } else {
+        quote! {
+            ::memflow_pointer_debug::DerefDebugPrint::pointer_targets(&#value, targets);
+        }
+    }
+}
+
+/// Print the embedded field `member` with the printer registered for its type if there is
+/// one, collect `value` otherwise
+pub fn printed_value(member: &Member, value: TokenStream) -> TokenStream {
+    let access = sibling(member);
+    quote! {
+        match state.printed_value(&#access, mem) {
+            Some(value) => value,
+            None => #value,
+        }
+    }
+}
+
+/// Print the bits of the tagged pointer `member` outside `mask`
+pub fn tag_value(member: &Member, mask: u64) -> TokenStream {
+    let access = sibling(member);
+    quote! {
+        ::memflow_pointer_debug::PointerDebugValue::Value(::memflow_pointer_debug::__private::format!(
+            "{:#x}",
+            ::memflow_pointer_debug::TraversalState::mask_pointer(&#access, #mask).1
+        ))
+    }
+}
+
+/// Collect `value` with the depth limit set to `max_depth` levels below the current node,
+/// the previous limit is restored for the following fields
+pub fn with_max_depth(value: TokenStream, max_depth: usize) -> TokenStream {
+    quote! {{
+        let max_depth = ::core::mem::replace(&mut state.max_depth, depth + 1 + #max_depth);
+        let value = #value;
+        state.max_depth = max_depth;
+        value
+    }}
+}
+
+/// Read `len` elements starting at the pointer and collect each of them, slices without a
+/// length field read up to their maximum
+fn array_value(access: &TokenStream, array: &ArrayAttr) -> TokenStream {
+    let max = array.max;
+    // Slices without a length field are read up to their maximum
+    let len = match &array.len_field {
+        Some(len_field) => {
+            let len_field = sibling(len_field);
+            quote! { #len_field as usize }
+        }
+        None => quote! { state.element_limit(#max) },
+    };
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+        let type_name = ::memflow_pointer_debug::TraversalState::target_type_name(&#access);
+        let len = #len;
+        let count = len.min(state.element_limit(#max));
+
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if state.is_visited(address, type_name) {
+            ::memflow_pointer_debug::PointerTarget::AlreadyVisited
+        } else if depth + 1 >= state.max_depth {
+            ::memflow_pointer_debug::PointerTarget::MaxDepth
+        } else if !state.take_pointer_budget(&#access, depth, count) {
+            ::memflow_pointer_debug::PointerTarget::BudgetExhausted
+        } else {
+            state.visit(address, type_name);
+
+            // All elements are fetched with a single read
+            let span = state.deref_span(address, depth);
+            let values = ::memflow_pointer_debug::PointerLike::read_targets(&#access, mem, count);
+            span.read_done();
+            let target = match values {
+                Ok(mut values) => {
+                    let mut raw_bytes: ::memflow_pointer_debug::__private::Vec<_> = values.iter().map(|value| state.raw_bytes(value)).collect();
+                    for value in values.iter_mut() {
+                        state.to_host_order(value);
+                    }
+                    // The pointers of all elements are read together as well
+                    state.prefetch_targets(values.as_slice(), mem, depth + 1);
+                    let items = values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| {
+                            state.enter_index(i);
+                            let mut node = state.collect_node(value, mem, depth + 1);
+                            state.leave_field();
+                            node.set_address(address + (i * ::core::mem::size_of_val(value)) as u64, ::core::mem::align_of_val(value));
+                            node.raw_bytes = raw_bytes[i].take();
+                            ::memflow_pointer_debug::PointerTarget::Node(::memflow_pointer_debug::__private::Box::new(node))
+                        })
+                        .collect();
+                    ::memflow_pointer_debug::PointerTarget::Array { len, items }
+                },
+                Err(e) => ::memflow_pointer_debug::PointerTarget::ReadError(e),
+            };
+            state.finish_deref(span, &target);
+            target
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Read the pointers starting at the pointer target up to the first NULL entry and collect
+/// the target of each of them, they are listed with their addresses like list records
+fn ptr_array_value(access: &TokenStream, max: usize) -> TokenStream {
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+        let type_name = ::memflow_pointer_debug::TraversalState::target_type_name(&#access);
+
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if state.is_visited(address, type_name) {
+            ::memflow_pointer_debug::PointerTarget::AlreadyVisited
+        } else if depth + 1 >= state.max_depth {
+            ::memflow_pointer_debug::PointerTarget::MaxDepth
+        } else {
+            state.visit(address, type_name);
+
+            // The length isn't known up front, the entries are read one at a time
+            let span = state.deref_span(address, depth);
+            let (_, entry_size) = ::memflow_pointer_debug::TraversalState::pointer_target(&#access);
+            let mut entries = ::memflow_pointer_debug::__private::Vec::new();
+            let mut end = None;
+            let mut more = false;
+            loop {
+                let entry_address = address + (entries.len() * entry_size) as u64;
+                if !state.take_budget(entry_address, depth, 0, entry_size) {
+                    end = Some((entry_address, ::memflow_pointer_debug::PointerTarget::BudgetExhausted));
+                    break;
+                }
+                let slot = ::memflow_pointer_debug::PointerLike::with_address(&#access, entry_address);
+                match ::memflow_pointer_debug::PointerLike::read_target(&slot, mem) {
+                    Ok(mut entry) => {
+                        ::memflow_pointer_debug::PointerLike::to_host_order(&mut entry, state.config.byte_order);
+                        if ::memflow_pointer_debug::PointerLike::pointer_address(&entry) == 0 {
+                            break;
+                        }
+                        if entries.len() >= state.element_limit(#max) {
+                            more = true;
+                            break;
+                        }
+                        entries.push(entry);
+                    }
+                    Err(e) => {
+                        end = Some((entry_address, ::memflow_pointer_debug::PointerTarget::ReadError(e.into())));
+                        break;
+                    }
+                }
+            }
+            span.read_done();
+
+            // The targets of all entries are read together
+            state.prefetch_targets(entries.as_slice(), mem, depth);
+            let mut items: ::memflow_pointer_debug::__private::Vec<_> = entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    state.enter_index(i);
+                    let item = ::memflow_pointer_debug::PointerLike::collect_pointee(entry, mem, depth, state);
+                    state.leave_field();
+                    (::memflow_pointer_debug::PointerLike::pointer_address(entry), item)
+                })
+                .collect();
+            // An entry that couldn't be read ends the array
+            items.extend(end);
+            let target = ::memflow_pointer_debug::PointerTarget::List { items, more };
+            state.finish_deref(span, &target);
+            target
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Read the bucket heads of a hash table and walk the entry chain of every bucket through
+/// the `next` field of the entries.
+///
+/// Buckets are listed by index with the entries of their chains below them like list
+/// records, empty buckets are null.
+/// Entries are marked visited before any of them is collected so the `next` fields of the
+/// entries and entries in several buckets refer back to them instead of nesting.
+fn buckets_value(access: &TokenStream, buckets: &BucketsAttr) -> TokenStream {
+    let count_field = sibling(&buckets.count_field);
+    let next = &buckets.next;
+    let max = buckets.max;
+    let max_chain = buckets.max_chain;
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+        let type_name = ::memflow_pointer_debug::TraversalState::target_type_name(&#access);
+        let (_, head_size) = ::memflow_pointer_debug::TraversalState::pointer_target(&#access);
+        let len = #count_field as usize;
+        let count = len.min(state.element_limit(#max));
+
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if state.is_visited(address, type_name) {
+            ::memflow_pointer_debug::PointerTarget::AlreadyVisited
+        } else if depth + 1 >= state.max_depth {
+            ::memflow_pointer_debug::PointerTarget::MaxDepth
+        } else if !state.take_budget(address, depth, 0, count * head_size) {
+            ::memflow_pointer_debug::PointerTarget::BudgetExhausted
+        } else {
+            state.visit(address, type_name);
+
+            // All bucket heads are fetched with a single read
+            let span = state.deref_span(address, depth);
+            let heads = ::memflow_pointer_debug::PointerLike::read_targets(&#access, mem, count);
+            span.read_done();
+            let target = match heads {
+                Ok(heads) => {
+                    let mut items = ::memflow_pointer_debug::__private::Vec::new();
+                    for (bucket, mut head) in heads.into_iter().enumerate() {
+                        ::memflow_pointer_debug::PointerLike::to_host_order(&mut head, state.config.byte_order);
+                        let head_address = ::memflow_pointer_debug::PointerLike::pointer_address(&head);
+                        let entry_type = ::memflow_pointer_debug::TraversalState::target_type_name(&head);
+                        state.enter_index(bucket);
+
+                        // Read the whole chain first, marking its entries visited
+                        let mut entries = ::memflow_pointer_debug::__private::Vec::new();
+                        let mut end = None;
+                        let mut more = false;
+                        let mut link = head;
+                        loop {
+                            let link_address = ::memflow_pointer_debug::PointerLike::pointer_address(&link);
+                            if let Some(target) = state.skip_target(link_address) {
+                                if target != ::memflow_pointer_debug::PointerTarget::Null {
+                                    end = Some((link_address, target));
+                                }
+                                break;
+                            }
+                            if state.is_visited(link_address, entry_type) {
+                                end = Some((link_address, ::memflow_pointer_debug::PointerTarget::AlreadyVisited));
+                                break;
+                            }
+                            if entries.len() >= state.element_limit(#max_chain) {
+                                more = true;
+                                break;
+                            }
+                            state.enter_index(entries.len());
+                            let budget = state.take_pointer_budget(&link, depth, 1);
+                            state.leave_field();
+                            if !budget {
+                                end = Some((link_address, ::memflow_pointer_debug::PointerTarget::BudgetExhausted));
+                                break;
+                            }
+                            state.visit(link_address, entry_type);
+
+                            let span = state.deref_span(link_address, depth);
+                            let value = ::memflow_pointer_debug::PointerLike::read_target(&link, mem);
+                            span.read_done();
+                            match value {
+                                Ok(mut value) => {
+                                    let raw_bytes = state.raw_bytes(&value);
+                                    state.apply_offsets(&mut value, link_address, mem);
+                                    state.to_host_order(&mut value);
+                                    link = value.#next;
+                                    entries.push((link_address, value, raw_bytes, span));
+                                }
+                                Err(e) => {
+                                    let target = ::memflow_pointer_debug::PointerTarget::ReadError(e.into());
+                                    state.finish_deref(span, &target);
+                                    end = Some((link_address, target));
+                                    break;
+                                }
+                            }
+                        }
+
+                        let item = if entries.is_empty() && !more {
+                            // Empty buckets and invalid heads are shown as the head is
+                            end.map_or(::memflow_pointer_debug::PointerTarget::Null, |(_, target)| target)
+                        } else {
+                            let mut chain = ::memflow_pointer_debug::__private::Vec::new();
+                            for (i, (entry_address, value, raw_bytes, span)) in entries.into_iter().enumerate() {
+                                state.enter_index(i);
+                                state.chain_element = true;
+                                let mut node = state.collect_node(&value, mem, depth + 1);
+                                state.leave_field();
+                                node.set_address(entry_address, ::core::mem::align_of_val(&value));
+                                node.raw_bytes = raw_bytes;
+                                let target = ::memflow_pointer_debug::PointerTarget::Node(::memflow_pointer_debug::__private::Box::new(node));
+                                state.finish_deref(span, &target);
+                                chain.push((entry_address, target));
+                            }
+                            chain.extend(end);
+                            ::memflow_pointer_debug::PointerTarget::List { items: chain, more }
+                        };
+                        state.leave_field();
+                        items.push((head_address, item));
+                    }
+                    ::memflow_pointer_debug::PointerTarget::List { items, more: count < len }
+                },
+                Err(e) => ::memflow_pointer_debug::PointerTarget::ReadError(e),
+            };
+            state.finish_deref(span, &target);
+            target
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Iterate a linked list through a `next: Pointer64<Self>` style field.
+///
+/// Every element is collected one level below the node owning the chain instead of one
+/// level below the previous element, so long chains don't run into the depth limit.
+fn chain_value(access: &TokenStream, member: &Member, chain: &ChainAttr) -> TokenStream {
+    let max = chain.max;
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+        let type_name = ::memflow_pointer_debug::TraversalState::target_type_name(&#access);
+
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if chain_element {
+            // The element owning the chain is iterating it already
+            ::memflow_pointer_debug::PointerTarget::ChainLink
+        } else if state.is_visited(address, type_name) {
+            ::memflow_pointer_debug::PointerTarget::AlreadyVisited
+        } else if depth + 1 >= state.max_depth {
+            ::memflow_pointer_debug::PointerTarget::MaxDepth
+        } else {
+            let mut items = ::memflow_pointer_debug::__private::Vec::new();
+            let mut more = false;
+            let mut link = #access;
+            // Revisits don't apply to a chain looping back to its own elements
+            let mut links = ::memflow_pointer_debug::__private::HashSet::new();
+
+            loop {
+                let link_address = ::memflow_pointer_debug::PointerLike::pointer_address(&link);
+
+                // A null link terminates the chain, invalid links are shown
+                if let Some(target) = state.skip_target(link_address) {
+                    if target != ::memflow_pointer_debug::PointerTarget::Null {
+                        items.push(target);
+                    }
+                    break;
+                }
+
+                if links.contains(&link_address) || state.is_visited(link_address, type_name) {
+                    items.push(::memflow_pointer_debug::PointerTarget::AlreadyVisited);
+                    break;
+                }
+
+                if items.len() >= state.element_limit(#max) {
+                    more = true;
+                    break;
+                }
+
+                state.enter_index(items.len());
+                if !state.take_pointer_budget(&link, depth, 1) {
+                    state.leave_field();
+                    items.push(::memflow_pointer_debug::PointerTarget::BudgetExhausted);
+                    break;
+                }
+
+                links.insert(link_address);
+                state.visit(link_address, type_name);
+
+                let span = state.deref_span(link_address, depth);
+                let value = ::memflow_pointer_debug::PointerLike::read_target(&link, mem);
+                span.read_done();
+                match value {
+                    Ok(mut value) => {
+                        let raw_bytes = state.raw_bytes(&value);
+                        state.apply_offsets(&mut value, link_address, mem);
+                        state.to_host_order(&mut value);
+                        state.chain_element = true;
+                        let mut node = state.collect_node(&value, mem, depth + 1);
+                        state.leave_field();
+                        node.set_address(link_address, ::core::mem::align_of_val(&value));
+                        node.raw_bytes = raw_bytes;
+                        let target = ::memflow_pointer_debug::PointerTarget::Node(::memflow_pointer_debug::__private::Box::new(node));
+                        state.finish_deref(span, &target);
+                        items.push(target);
+                        link = value.#member;
+                    },
+                    Err(e) => {
+                        state.leave_field();
+                        let target = ::memflow_pointer_debug::PointerTarget::ReadError(e.into());
+                        state.finish_deref(span, &target);
+                        items.push(target);
+                        break;
+                    }
+                }
+            }
+
+            ::memflow_pointer_debug::PointerTarget::Chain { items, more }
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Walk the `LIST_ENTRY` list headed by an embedded `ListEntry64` field and collect the
+/// containing records, they are listed one level below the owner like chain elements
+fn list_value(access: &TokenStream, list: &ListAttr) -> TokenStream {
+    let record = &list.record;
+    let offset = &list.offset;
+    let max = list.max;
+    quote! {{
+        let head: ::memflow_pointer_debug::ListEntry64 = #access;
+        let address = head.flink;
+
+        let target = if depth + 1 >= state.max_depth {
+            ::memflow_pointer_debug::PointerTarget::MaxDepth
+        } else {
+            let mut walker = ::memflow_pointer_debug::ListWalker::new(head);
+            let mut items = ::memflow_pointer_debug::__private::Vec::new();
+            let mut more = false;
+            let type_name = ::core::any::type_name::<#record>();
+            // Revisits don't apply to a list looping back to its own records
+            let mut records = ::memflow_pointer_debug::__private::HashSet::new();
+
+            loop {
+                let link_address = walker.next_address().to_umem();
+                if let Some(target) = state.skip_target(link_address) {
+                    if target != ::memflow_pointer_debug::PointerTarget::Null {
+                        items.push((link_address, target));
+                    }
+                    break;
+                }
+                let entry = match walker.next_entry(mem) {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => break,
+                    Err(e) => {
+                        items.push((link_address, ::memflow_pointer_debug::PointerTarget::ReadError(e)));
+                        break;
+                    }
+                };
+
+                let record_address = ::memflow_pointer_debug::containing_record(entry, #offset).to_umem();
+
+                // Lists that loop without getting back to the head end at the first repeat
+                if records.contains(&record_address) || state.is_visited(record_address, type_name) {
+                    items.push((record_address, ::memflow_pointer_debug::PointerTarget::AlreadyVisited));
+                    break;
+                }
+
+                if items.len() >= state.element_limit(#max) {
+                    more = true;
+                    break;
+                }
+
+                // The entry was read already, it is accounted for along with its record
+                let bytes = ::core::mem::size_of::<::memflow_pointer_debug::ListEntry64>()
+                    + ::core::mem::size_of::<#record>();
+                state.enter_index(items.len());
+                if !state.take_budget(record_address, depth, 1, bytes) {
+                    state.leave_field();
+                    items.push((record_address, ::memflow_pointer_debug::PointerTarget::BudgetExhausted));
+                    break;
+                }
+
+                records.insert(record_address);
+                state.visit(record_address, type_name);
+
+                let span = state.deref_span(record_address, depth);
+                let value = ::memflow::mem::MemoryView::read::<#record>(mem, record_address.into());
+                span.read_done();
+                match value {
+                    Ok(mut value) => {
+                        let raw_bytes = state.raw_bytes(&value);
+                        state.to_host_order(&mut value);
+                        let mut node = state.collect_node(&value, mem, depth + 1);
+                        state.leave_field();
+                        node.set_address(record_address, ::core::mem::align_of_val(&value));
+                        node.raw_bytes = raw_bytes;
+                        let target = ::memflow_pointer_debug::PointerTarget::Node(::memflow_pointer_debug::__private::Box::new(node));
+                        state.finish_deref(span, &target);
+                        items.push((record_address, target));
+                    },
+                    Err(e) => {
+                        state.leave_field();
+                        let target = ::memflow_pointer_debug::PointerTarget::ReadError(e.into());
+                        state.finish_deref(span, &target);
+                        items.push((record_address, target));
+                        break;
+                    }
+                }
+            }
+
+            ::memflow_pointer_debug::PointerTarget::List { items, more }
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Read the pointer target as a NUL-terminated string
+fn cstr_value(access: &TokenStream, max_len: usize) -> TokenStream {
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+
+        // Strings are leaves, they don't take part in cycle detection
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if !state.take_budget(address, depth, 0, state.config.string_limit(#max_len)) {
+            ::memflow_pointer_debug::PointerTarget::BudgetExhausted
+        } else {
+            let span = state.deref_span(address, depth);
+            let target = match state.read_cstr(mem, address.into(), #max_len) {
+                Ok(text) => ::memflow_pointer_debug::PointerTarget::Text(text),
+                Err(e) => ::memflow_pointer_debug::PointerTarget::ReadError(e),
+            };
+            span.read_done();
+            state.finish_deref(span, &target);
+            target
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Read `len` bytes at the pointer target for a hex dump
+fn hexdump_value(access: &TokenStream, len: usize) -> TokenStream {
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+
+        // Raw bytes are leaves like strings
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if !state.take_budget(address, depth, 0, #len) {
+            ::memflow_pointer_debug::PointerTarget::BudgetExhausted
+        } else {
+            let span = state.deref_span(address, depth);
+            let bytes = ::memflow::mem::MemoryView::read_raw(mem, address.into(), #len);
+            let target = match ::memflow::error::PartialResultExt::data_part(bytes) {
+                Ok(bytes) => ::memflow_pointer_debug::PointerTarget::Bytes(bytes),
+                Err(e) => ::memflow_pointer_debug::PointerTarget::ReadError(e),
+            };
+            span.read_done();
+            state.finish_deref(span, &target);
+            target
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Read the pointer target as a UTF-16 string
+fn wstr_value(access: &TokenStream, wstr: &WideString) -> TokenStream {
+    let max_len = wstr.max_len;
+    let len_bytes = match &wstr.len_field {
+        Some(len_field) => {
+            let len_field = sibling(len_field);
+            quote! { Some(#len_field as usize) }
+        }
+        None => quote! { None },
+    };
+    quote! {{
+        let address = ::memflow_pointer_debug::PointerLike::pointer_address(&#access);
+
+        // Strings are leaves, they don't take part in cycle detection
+        let target = if let Some(target) = state.skip_target(address) {
+            target
+        } else if !state.take_budget(address, depth, 0, state.config.string_limit(#max_len * 2)) {
+            ::memflow_pointer_debug::PointerTarget::BudgetExhausted
+        } else {
+            let span = state.deref_span(address, depth);
+            let target = match state.read_wstr(mem, address.into(), #len_bytes, #max_len) {
+                Ok(text) => ::memflow_pointer_debug::PointerTarget::Text(text),
+                Err(e) => ::memflow_pointer_debug::PointerTarget::ReadError(e),
+            };
+            span.read_done();
+            state.finish_deref(span, &target);
+            target
+        };
+
+        ::memflow_pointer_debug::PointerDebugValue::Pointer { address, target }
+    }}
+}
+
+/// Print an integer as the names of its set bits
+fn flags_value(access: &TokenStream, flags: &FlagsAttr) -> TokenStream {
+    let names = match flags {
+        FlagsAttr::List(flags) => {
+            let names = flags.iter().map(|(name, value)| quote! { (#name, #value) });
+            quote! { &[#(#names),*] }
+        }
+        FlagsAttr::Type(ty) => quote! {
+            &<#ty>::all()
+                .iter_names()
+                .map(|(name, flag)| (name, flag.bits() as u64))
+                .collect::<::memflow_pointer_debug::__private::Vec<_>>()
+        },
+    };
+    quote! {
+        ::memflow_pointer_debug::PointerDebugValue::Value(
+            ::memflow_pointer_debug::format_flags(#access as u64, #names)
+        )
+    }
+}
+
+/// Print an integer in the given radix, padded to the full width of its type
+fn format_value(access: &TokenStream, format: NumberFormat) -> TokenStream {
+    // The width includes the prefix
+    let (digits, spec) = match format {
+        NumberFormat::Hex => (quote! { size * 2 }, quote! { "{:#0width$x}" }),
+        NumberFormat::Bin => (quote! { size * 8 }, quote! { "{:#0width$b}" }),
+        NumberFormat::Oct => (quote! { (size * 8).div_ceil(3) }, quote! { "{:#0width$o}" }),
+    };
+    quote! {{
+        let size = ::core::mem::size_of_val(&#access);
+        ::memflow_pointer_debug::PointerDebugValue::Value(
+            ::memflow_pointer_debug::__private::format!(#spec, #access, width = 2 + #digits)
+        )
+    }}
+}
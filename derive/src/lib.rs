@@ -1,8 +1,13 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{punctuated::Punctuated, *};
+use syn::{ext::IdentExt, punctuated::Punctuated, *};
 
-#[proc_macro_derive(PointerDerefDebugPrint)]
+mod attrs;
+mod field;
+
+use attrs::{Endian, FieldAttrs, TypeAttrs};
+
+#[proc_macro_derive(PointerDerefDebugPrint, attributes(pointer_debug))]
 pub fn pointer_deref_debug_print(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
@@ -26,117 +31,906 @@ pub fn pointer_deref_debug_print(input: TokenStream) -> TokenStream {
             let param_ident = &type_param.ident;
             // Add 'static bound for all type parameters
             new_where_clause.predicates.push(parse_quote!(
-                #param_ident: ::std::fmt::Debug + 'static
+                #param_ident: ::core::fmt::Debug + 'static
+            ));
+        }
+    }
+    
+    // Parameters reached through followed fields, e.g. `T` of `next: Pointer64<Node<T>>`,
+    // are traversed as well instead of printed with `Debug`
+    for (param_ident, pointer) in followed_params(&input.data, generics) {
+        if pointer {
+            new_where_clause.predicates.push(parse_quote!(
+                #param_ident: ::memflow::dataview::Pod + ::memflow_pointer_debug::DerefDebugPrint
+            ));
+        } else {
+            new_where_clause.predicates.push(parse_quote!(
+                #param_ident: ::memflow_pointer_debug::DerefDebugPrint
             ));
         }
     }
     
-    // Generate field debug code by iterating through fields
-    let field_debugs = if let Data::Struct(data) = &input.data {
-        data.fields.iter().map(|field| {
-            let field_name = &field.ident;
-            let field_name_str = field_name.as_ref().unwrap().to_string();
-            
-            if field_name_str.contains("_pad") {
-                // Don't print padding fields
-                return quote! {}; // Empty quote
-            }
-            
-            // Check if this field is a pointer type
-            let is_pointer = match &field.ty {
-                Type::Path(TypePath { path, .. }) => path.segments.iter().any(|seg| {
-                    let ident_str = seg.ident.to_string();
-                    ident_str.contains("Pointer")
-                }),
-                _ => false,
+    let type_attrs = match TypeAttrs::parse(&input.attrs) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    
+    // A wrong size shifts every field after the mistake, the build fails instead. Sizes of
+    // types with type or const parameters are only known once they are used, lifetimes don't
+    // change the size
+    let size_assert = |ty: proc_macro2::TokenStream, size: &LitInt| quote! {
+        assert!(
+            ::core::mem::size_of::<#ty>() == #size,
+            concat!("size of `", stringify!(#name), "` is not `expected_size = ", stringify!(#size), "`")
+        )
+    };
+    let (size_item, size_check) = match &type_attrs.expected_size {
+        Some(size) if generics.type_params().next().is_none() && generics.const_params().next().is_none() => {
+            let lifetimes = generics.lifetimes().map(|_| quote! { 'static });
+            let assert = size_assert(quote! { #name<#(#lifetimes),*> }, size);
+            (quote! { const _: () = #assert; }, quote! { node.size = Some(::core::mem::size_of::<Self>()); })
+        }
+        Some(size) => {
+            let assert = size_assert(quote! { Self }, size);
+            (quote! {}, quote! {
+                const { #assert };
+                node.size = Some(::core::mem::size_of::<Self>());
+            })
+        }
+        None => (quote! {}, quote! {}),
+    };
+    
+    // Unions collect the selected field through an inherent method
+    let mut union_items = quote! {};
+    
+    // Fields collected one after the other by the `collect` closure of
+    // `pointer_collect_fields_with`
+    let collect_each = quote! {
+        indices.iter().map(|&index| ::memflow_pointer_debug::DerefDebugPrint::pointer_collect_field(self, index, mem, depth, state, chain_element)).collect()
+    };
+    let is_union = matches!(input.data, Data::Union(_));
+    
+    // Generate the node type name, the field count and the field collection code for the input
+    let (type_name, field_count, collect, targets, locations) = match &input.data {
+        Data::Struct(data) => {
+            if let Some(active) = &type_attrs.active {
+                return Error::new_spanned(active, "`active` is only supported on unions").to_compile_error().into();
+            }
+            let (collects, targets, _) = match collect_fields(&data.fields, Owner::Struct, generics) {
+                Ok(fields) => fields,
+                Err(e) => return e.to_compile_error().into(),
+            };
+            let bindings = if is_packed(&input.attrs) {
+                field_copies(&data.fields)
+            } else {
+                let pattern = field_pattern(&data.fields);
+                quote! {
+                    // Fields keep their names, they don't have to be snake case
+                    #[allow(unused_variables, non_snake_case)]
+                    let Self #pattern = self;
+                }
             };
             
-            // Extract the field type for better display
-            let field_type = match &field.ty {
-                Type::Path(TypePath { path, .. }) => {
-                    if let Some(segment) = path.segments.last() {
-                        let type_name = segment.ident.to_string();
-                        quote! { #type_name }
-                    } else {
-                        quote! { "Unknown" }
+            let type_name = quote! { stringify!(#name) };
+            let count = collects.len();
+            let collect = quote! {
+                #bindings
+                match index {
+                    #(#collects)*
+                    _ => None,
+                }
+            };
+            let targets = quote! {
+                #bindings
+                #(#targets)*
+            };
+            let locations = field_locations(&data.fields);
+            let locations = quote! {
+                match name {
+                    #(#locations)*
+                    _ => None,
+                }
+            };
+            (type_name, quote! { #count }, collect, targets, locations)
+        },
+        Data::Enum(data) if data.variants.is_empty() => {
+            // Values of enums without variants can't exist
+            return TokenStream::from(quote! {
+                impl #impl_generics ::memflow_pointer_debug::DerefDebugPrint for #name #ty_generics #new_where_clause {
+                    fn pointer_collect_internal<M: ::memflow::mem::MemoryView>(
+                        &self,
+                        mem: &mut M,
+                        depth: usize,
+                        state: &mut ::memflow_pointer_debug::TraversalState
+                    ) -> ::memflow_pointer_debug::PointerDebugNode {
+                        match *self {}
                     }
-                },
-                _ => quote! { "Unknown" },
+                }
+            });
+        },
+        Data::Enum(data) => {
+            let mut names = Vec::new();
+            let mut counts = Vec::new();
+            let mut arms = Vec::new();
+            let mut target_arms = Vec::new();
+            for variant in data.variants.iter() {
+                let variant_name = &variant.ident;
+                let (collects, targets, _) = match collect_fields(&variant.fields, Owner::Variant, generics) {
+                    Ok(fields) => fields,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                let pattern = field_pattern(&variant.fields);
+                
+                // Only the active variant is printed, its name is part of the type name
+                names.push(quote! {
+                    Self::#variant_name { .. } => concat!(stringify!(#name), "::", stringify!(#variant_name)),
+                });
+                let count = collects.len();
+                counts.push(quote! {
+                    Self::#variant_name { .. } => #count,
+                });
+                arms.push(quote! {
+                    #[allow(unused_variables, non_snake_case)]
+                    Self::#variant_name #pattern => match index {
+                        #(#collects)*
+                        _ => None,
+                    },
+                });
+                target_arms.push(quote! {
+                    #[allow(unused_variables, non_snake_case)]
+                    Self::#variant_name #pattern => {
+                        #(#targets)*
+                    },
+                });
+            }
+            
+            (
+                quote! { match *self { #(#names)* } },
+                quote! { match *self { #(#counts)* } },
+                quote! { match self { #(#arms)* } },
+                quote! { match self { #(#target_arms)* } },
+                // Fields of enums can't be moved without knowing the variant
+                quote! { None },
+            )
+        },
+        Data::Union(data) => {
+            let fields = Fields::Named(data.fields.clone());
+            let (collects, _, arm_fields) = match collect_fields(&fields, Owner::Union, generics) {
+                Ok(fields) => fields,
+                Err(e) => return e.to_compile_error().into(),
             };
             
-            if is_pointer {
+            // Every interpretation is a copy read from the bytes of the union, which is safe
+            // for `Pod` fields of a `Pod` union
+            let bindings = data.fields.named.iter().map(|field| {
+                let member = Member::Named(field.ident.clone().unwrap());
+                let binding = field::binding(&member);
+                let ty = &field.ty;
                 quote! {
-                    // Get the address to check if we've seen it before
-                    let address = self.#field_name.address().to_umem();
-                    
-                    //println!("{}  {}: {} Pointer @ {:#x}", indent, #field_name_str, #field_type, address);
+                    #[allow(unused_variables, non_snake_case)]
+                    let #binding = &::memflow::dataview::PodMethods::as_data_view(self).read::<#ty>(0);
+                }
+            });
+            new_where_clause.predicates.push(parse_quote!(Self: ::memflow::dataview::Pod));
+            
+            let mut cases = Vec::new();
+            for (index, field) in data.fields.named.iter().enumerate() {
+                if let Ok(FieldAttrs { when: Some(value), .. }) = FieldAttrs::parse(&field.attrs) {
+                    cases.push(quote! { #value => Some(#index), });
+                }
+            }
+            let default_active = match &type_attrs.active {
+                Some(active) => match data.fields.named.iter().position(|field| field.ident.as_ref().is_some_and(|ident| *ident == active.value())) {
+                    Some(index) => quote! { Some(#index) },
+                    None => return Error::new_spanned(active, "`active` does not name a field of the union").to_compile_error().into(),
+                },
+                None => quote! { None },
+            };
+            
+            let type_name = quote! { stringify!(#name) };
+            let count = collects.len();
+            let collect = quote! {
+                #(#bindings)*
+                match index {
+                    #(#collects)*
+                    _ => None,
+                }
+            };
+            let selected = quote! {
+                impl #impl_generics #name #ty_generics #new_where_clause {
+                    /// Collect the field picked by the `when` value equal to `selector`, the
+                    /// `active` field or all fields if there is none.
+                    ///
+                    /// Called for fields with `#[pointer_debug(select_by = "...")]`.
+                    #[doc(hidden)]
+                    pub fn pointer_collect_selected<M: ::memflow::mem::MemoryView>(
+                        &self,
+                        selector: Option<u64>,
+                        mem: &mut M,
+                        depth: usize,
+                        state: &mut ::memflow_pointer_debug::TraversalState
+                    ) -> ::memflow_pointer_debug::PointerDebugNode {
+                        self.pointer_collect_selected_with(selector, mem, depth, state, &mut |indices, mem, state, chain_element| {
+                            #collect_each
+                        })
+                    }
                     
-                    // Only process this pointer if we haven't seen it before
-                    if !visited_addresses.contains(&address) {
-                        // Add this address to our visited set
-                        visited_addresses.insert(address);
+                    /// [`pointer_collect_selected`](Self::pointer_collect_selected) with the
+                    /// fields collected by `collect`
+                    #[doc(hidden)]
+                    pub fn pointer_collect_selected_with<M: ::memflow::mem::MemoryView>(
+                        &self,
+                        selector: Option<u64>,
+                        mem: &mut M,
+                        depth: usize,
+                        state: &mut ::memflow_pointer_debug::TraversalState,
+                        collect: &mut ::memflow_pointer_debug::FieldCollector<'_, M>
+                    ) -> ::memflow_pointer_debug::PointerDebugNode {
+                        let mut node = ::memflow_pointer_debug::PointerDebugNode::new(#type_name);
+                        #size_check
                         
-                        // Read the pointer value using the memory view
-                        match self.#field_name.read(mem) {
-                            Ok(value) => {
-                                // Get the type name if possible through any means available
-                                // This is a placeholder - the actual type name will come from the value itself
-                                let indent_next = "  ".repeat(depth + 1);
-                                print!("{}  {}->", indent, #field_name_str);
-                                
-                                // Call the recursive method, which will print the opening brace
-                                value.pointer_debug_internal(mem, depth + 1, max_depth, visited_addresses);
-                            },
-                            Err(e) => {
-                                println!("{}  {} → Error reading: {}", indent, #field_name_str, e);
-                            }
+                        let chain_element = ::core::mem::take(&mut state.chain_element);
+                        
+                        if depth >= state.max_depth {
+                            return node;
                         }
-                    } else {
-                        println!("{}  {} → Already visited address {:#x}", indent, #field_name_str, address);
+                        
+                        // Declaration index of the field printed, selectors without a field
+                        // fall back to the default
+                        let default: Option<usize> = #default_active;
+                        let active = match selector {
+                            #[allow(unreachable_patterns)]
+                            Some(selector) => match selector {
+                                #(#cases)*
+                                _ => default,
+                            },
+                            None => default,
+                        };
+                        let arm_fields: [usize; #count] = [#(#arm_fields),*];
+                        let indices: ::memflow_pointer_debug::__private::Vec<usize> = (0..#count)
+                            .filter(|index| active.is_none_or(|active| active == arm_fields[*index]))
+                            .collect();
+                        node.fields.extend(collect(&indices, mem, state, chain_element).into_iter().flatten());
+                        state.record_locations(self, &mut node);
+                        
+                        node
                     }
                 }
-            } else {
-                quote! {
-                    println!("{}  {}: {} = {:?}", indent, #field_name_str, #field_type, self.#field_name);
+            };
+            union_items = selected;
+            
+            
+            let locations = field_locations(&fields);
+            let locations = quote! {
+                match name {
+                    #(#locations)*
+                    _ => None,
                 }
-            }
-        })
+            };
+            // The pointers of interpretations that are not printed are not read ahead
+            (type_name, quote! { #count }, collect, quote! {}, locations)
+        },
+    };
+    
+    // Enums and unions are kept in the byte order they were read in
+    let host_order = match &input.data {
+        Data::Struct(data) => match host_order_fields(&data.fields, is_packed(&input.attrs)) {
+            Ok(converts) => quote! {
+                #[allow(unused_variables)]
+                fn pointer_to_host_order(&mut self, order: ::memflow_pointer_debug::ByteOrder) {
+                    #(#converts)*
+                }
+            },
+            Err(e) => return e.to_compile_error().into(),
+        },
+        _ => quote! {},
+    };
+    
+    // Every declared field is described, including the ones that are not printed
+    let schema_fields = match &input.data {
+        Data::Struct(data) => field_schemas(&data.fields, None),
+        Data::Enum(data) => data.variants.iter().flat_map(|variant| field_schemas(&variant.fields, Some(&variant.ident))).collect(),
+        Data::Union(data) => field_schemas(&Fields::Named(data.fields.clone()), None),
+    };
+    
+    let collect_fields_with = if is_union {
+        quote! {
+            self.pointer_collect_selected_with(None, mem, depth, state, collect)
+        }
     } else {
-        // Return empty token stream if input is not a struct
-        return TokenStream::from(quote! {
-            compile_error!("PointerDerefDebugPrint can only be derived for structs");
-        });
+        quote! {
+                let mut node = ::memflow_pointer_debug::PointerDebugNode::new(#type_name);
+                #size_check
+                
+                // Elements of a followed chain don't follow it again themselves
+                let chain_element = ::core::mem::take(&mut state.chain_element);
+                
+                if depth >= state.max_depth {
+                    return node;
+                }
+                
+                // The targets of all pointer fields are read at once
+                state.prefetch_targets(self, mem, depth);
+                
+                let indices: ::memflow_pointer_debug::__private::Vec<usize> = (0..self.pointer_field_count()).collect();
+                node.fields.extend(collect(&indices, mem, state, chain_element).into_iter().flatten());
+                state.record_locations(self, &mut node);
+                
+                node
+        }
     };
     
     // Generate the DerefDebugPrint implementation with proper generics and bounds
     let expanded = quote! {
+        #size_item
+        
+        #union_items
+        
         impl #impl_generics ::memflow_pointer_debug::DerefDebugPrint for #name #ty_generics #new_where_clause {
-            fn pointer_debug_internal<M: ::memflow::mem::MemoryView>(
+            fn pointer_collect_internal<M: ::memflow::mem::MemoryView>(
                 &self, 
                 mem: &mut M, 
                 depth: usize, 
-                max_depth: usize,
-                visited_addresses: &mut ::std::collections::HashSet<u64>
-            ) {
-                if depth >= max_depth {
-                    return;
-                }
-                
-                let indent = "  ".repeat(depth);
-                
-                // If this is the first level (depth > 0), print without a newline
-                if depth > 0 {
-                    println!(" {}", stringify!(#name));
-                } else {
-                    println!("{}{} {{", indent, stringify!(#name));
-                }
-                
-                #(#field_debugs)*
-                
-                println!("{}}}", indent);
+                state: &mut ::memflow_pointer_debug::TraversalState
+            ) -> ::memflow_pointer_debug::PointerDebugNode {
+                self.pointer_collect_fields_with(mem, depth, state, &mut |indices, mem, state, chain_element| {
+                    #collect_each
+                })
+            }
+            
+            fn pointer_collect_fields_with<M: ::memflow::mem::MemoryView>(
+                &self,
+                mem: &mut M,
+                depth: usize,
+                state: &mut ::memflow_pointer_debug::TraversalState,
+                collect: &mut ::memflow_pointer_debug::FieldCollector<'_, M>
+            ) -> ::memflow_pointer_debug::PointerDebugNode {
+                #collect_fields_with
+            }
+            
+            fn pointer_type_name(&self) -> ::memflow_pointer_debug::__private::String {
+                ::memflow_pointer_debug::__private::String::from(#type_name)
+            }
+            
+            fn pointer_field_count(&self) -> usize {
+                #field_count
+            }
+            
+            #[allow(unused_variables)]
+            fn pointer_collect_field<M: ::memflow::mem::MemoryView>(
+                &self,
+                index: usize,
+                mem: &mut M,
+                depth: usize,
+                state: &mut ::memflow_pointer_debug::TraversalState,
+                chain_element: bool
+            ) -> Option<::memflow_pointer_debug::PointerDebugField> {
+                #collect
+            }
+            
+            fn pointer_targets(&self, targets: &mut ::memflow_pointer_debug::__private::Vec<(u64, usize)>) {
+                #targets
+            }
+            
+            #host_order
+            
+            #[allow(unused_variables)]
+            fn pointer_field_location(name: &str) -> Option<(usize, usize)> {
+                #locations
+            }
+            
+            fn pointer_field_offset(&self, name: &str) -> Option<(usize, usize)> {
+                Self::pointer_field_location(name)
+            }
+            
+            fn pointer_debug_schema() -> ::memflow_pointer_debug::TypeSchema {
+                let mut schema = ::memflow_pointer_debug::TypeSchema::new(stringify!(#name), ::core::mem::size_of::<Self>());
+                schema.fields = ::memflow_pointer_debug::__private::vec![#(#schema_fields),*];
+                schema
             }
         }
     };
     
     TokenStream::from(expanded)
-}
\ No newline at end of file
+}
+
+/// Pattern binding every field to its `field::binding` name, e.g. `{ next: __field_next }`
+fn field_pattern(fields: &Fields) -> proc_macro2::TokenStream {
+    let bindings = fields.iter().enumerate().map(|(index, field)| {
+        let member = field_member(index, field);
+        let binding = field::binding(&member);
+        quote! { #member: #binding }
+    });
+    quote! { { #(#bindings),* } }
+}
+
+/// Returns true if the type is declared `#[repr(packed)]` or `#[repr(C, packed(N))]`
+fn is_packed(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("repr") && attr.tokens.to_string().contains("packed"))
+}
+
+/// Statements binding a reference to a copy of every field to its `field::binding` name,
+/// e.g. `let __field_next = &{ self.next };`. Fields of packed structures may be unaligned,
+/// they can't be referenced in place
+fn field_copies(fields: &Fields) -> proc_macro2::TokenStream {
+    let bindings = fields.iter().enumerate().map(|(index, field)| {
+        let member = field_member(index, field);
+        let binding = field::binding(&member);
+        quote! {
+            #[allow(unused_variables, non_snake_case)]
+            let #binding = &{ self.#member };
+        }
+    });
+    quote! { #(#bindings)* }
+}
+
+/// Generate the statements converting every field of a structure to the byte order of the
+/// host, see `DerefDebugPrint::pointer_to_host_order`. Scalars, pointers, nested structures
+/// and arrays of them are converted, fields marked `endian` keep their own byte order
+fn host_order_fields(fields: &Fields, packed: bool) -> Result<Vec<proc_macro2::TokenStream>> {
+    let mut converts = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = field_member(index, field);
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        if attrs.skip || attrs.pad {
+            continue;
+        }
+        let ty = &field.ty;
+        let convert = if is_pointer_type(ty, &attrs) {
+            quote! { ::memflow_pointer_debug::PointerLike::to_host_order }
+        } else if attrs.nested || quote!(#ty).to_string().contains("Pointer") || is_scalar_type(ty) {
+            quote! { ::memflow_pointer_debug::DerefDebugPrint::pointer_to_host_order }
+        } else if attrs.endian.is_some() {
+            return Err(Error::new_spanned(ty, "`endian` is only supported on integer, float and pointer fields and arrays of them"));
+        } else {
+            continue;
+        };
+        let order = match attrs.endian {
+            Some(Endian::Little) => quote! { ::memflow_pointer_debug::ByteOrder::Little },
+            Some(Endian::Big) => quote! { ::memflow_pointer_debug::ByteOrder::Big },
+            None => quote! { order },
+        };
+        converts.push(if packed {
+            // Fields of packed structures may be unaligned, they are converted in a copy
+            quote! {
+                let mut value = self.#member;
+                #convert(&mut value, #order);
+                self.#member = value;
+            }
+        } else {
+            quote! { #convert(&mut self.#member, #order); }
+        });
+    }
+    Ok(converts)
+}
+
+/// Returns true if `ty` is an integer, a float, an address or an array of them or of pointers
+fn is_scalar_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { path, .. }) => is_number_type(ty) || path.is_ident("Address"),
+        Type::Array(array) => is_scalar_type(&array.elem) || is_pointer_type(&array.elem, &FieldAttrs::default()),
+        _ => false,
+    }
+}
+
+/// Returns true if `ty` is a fixed-size array of pointers like `[Pointer64<Part>; 16]`
+fn is_pointer_array(ty: &Type) -> bool {
+    matches!(ty, Type::Array(array) if is_pointer_type(&array.elem, &FieldAttrs::default()))
+}
+
+/// Returns true if `ty` is a primitive integer or float type
+fn is_number_type(ty: &Type) -> bool {
+    const NUMBERS: [&str; 15] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64", "umem",
+    ];
+    match ty {
+        Type::Path(TypePath { path, .. }) => NUMBERS.iter().any(|number| path.is_ident(number)),
+        _ => false,
+    }
+}
+
+/// Member accessing a field, the index is used for tuple fields
+fn field_member(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
+}
+
+/// Generate the match arms returning the offset and size of each field by its printed name
+fn field_locations(fields: &Fields) -> Vec<proc_macro2::TokenStream> {
+    fields.iter().enumerate().map(|(index, field)| {
+        let member = field_member(index, field);
+        // Invalid attributes are reported by `collect_fields`
+        let rename = FieldAttrs::parse(&field.attrs).ok().and_then(|attrs| attrs.rename);
+        let name = rename.unwrap_or_else(|| match &member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        });
+        let ty = &field.ty;
+        quote! {
+            #name => Some((::core::mem::offset_of!(Self, #member), ::core::mem::size_of::<#ty>())),
+        }
+    }).collect()
+}
+
+/// Generate the `FieldSchema` of each field, fields of enum variants have no offset
+fn field_schemas(fields: &Fields, variant: Option<&Ident>) -> Vec<proc_macro2::TokenStream> {
+    fields.iter().enumerate().map(|(index, field)| {
+        let member = field_member(index, field);
+        // Invalid attributes are reported by `collect_fields`
+        let attrs = FieldAttrs::parse(&field.attrs).unwrap_or_default();
+        let name = attrs.rename.clone().unwrap_or_else(|| match &member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        });
+        let ty = &field.ty;
+        let ty_str = type_string(ty);
+        let (variant, offset) = match variant {
+            Some(variant) => {
+                let variant = variant.to_string();
+                (quote! { Some(::memflow_pointer_debug::__private::String::from(#variant)) }, quote! { None })
+            }
+            None => (quote! { None }, quote! { Some(::core::mem::offset_of!(Self, #member)) }),
+        };
+        let pointer = is_pointer_type(ty, &attrs) || attrs.deref_as.is_some();
+        let target = match attrs.deref_as.as_ref().or_else(|| if pointer { pointer_target(ty) } else { None }) {
+            Some(target) => {
+                let target = type_string(target);
+                quote! { Some(::memflow_pointer_debug::__private::String::from(#target)) }
+            }
+            None => quote! { None },
+        };
+        quote! {
+            ::memflow_pointer_debug::FieldSchema {
+                name: ::memflow_pointer_debug::__private::String::from(#name),
+                variant: #variant,
+                ty: ::memflow_pointer_debug::__private::String::from(#ty_str),
+                offset: #offset,
+                size: ::core::mem::size_of::<#ty>(),
+                pointer: #pointer,
+                target: #target,
+            }
+        }
+    }).collect()
+}
+
+/// Returns true if the field is followed as a pointer: its type is named like a memflow
+/// pointer or it is marked `pointer`
+fn is_pointer_type(ty: &Type, attrs: &FieldAttrs) -> bool {
+    attrs.pointer || match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.iter().any(|seg| {
+            let ident_str = seg.ident.to_string();
+            ident_str.contains("Pointer")
+        }),
+        _ => false,
+    }
+}
+
+/// Type parameters named in the types of the followed fields with whether one of them reaches
+/// the parameter through a pointer, its values are then read from memory and must be `Pod`.
+/// Fields printed with `with` don't bound them, malformed attributes are reported by
+/// `collect_fields`
+fn followed_params(data: &Data, generics: &Generics) -> Vec<(Ident, bool)> {
+    let fields: Vec<&Field> = match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|variant| variant.fields.iter()).collect(),
+        Data::Union(data) => data.fields.named.iter().collect(),
+    };
+    let mut followed: Vec<(Ident, Option<bool>)> = generics.type_params().map(|param| (param.ident.clone(), None)).collect();
+    for field in fields {
+        let Ok(attrs) = FieldAttrs::parse(&field.attrs) else {
+            continue;
+        };
+        if attrs.skip || attrs.pad || attrs.with.is_some() {
+            continue;
+        }
+        let ty = &field.ty;
+        let pointer = is_pointer_type(ty, &attrs) || attrs.deref_as.is_some() || quote!(#ty).to_string().contains("Pointer");
+        if !pointer && !attrs.nested {
+            continue;
+        }
+        let mut tokens = quote!(#ty);
+        if let Some(deref_as) = &attrs.deref_as {
+            tokens.extend(quote!(#deref_as));
+        }
+        let mut named = Vec::new();
+        collect_idents(tokens, &mut named);
+        for (param, through_pointer) in &mut followed {
+            if named.contains(param) {
+                *through_pointer = Some(through_pointer.unwrap_or(false) || pointer);
+            }
+        }
+    }
+    followed.into_iter().filter_map(|(param, pointer)| Some((param, pointer?))).collect()
+}
+
+/// Returns true if `ty` names neither a type parameter nor a lifetime of the type nor `Self`,
+/// its values can be looked up in a `PrinterRegistry` by their `TypeId`. Const parameters like
+/// the `N` of `[u8; N]` don't matter
+fn is_static_type(ty: &Type, generics: &Generics) -> bool {
+    let mut idents = Vec::new();
+    collect_idents(quote!(#ty), &mut idents);
+    let lifetime = quote!(#ty).into_iter().any(|token| has_lifetime(&token));
+    !lifetime && !idents.iter().any(|ident| ident == "Self" || generics.type_params().any(|param| param.ident == *ident))
+}
+
+/// Returns true if `token` is or contains the quote of a lifetime
+fn has_lifetime(token: &proc_macro2::TokenTree) -> bool {
+    match token {
+        proc_macro2::TokenTree::Punct(punct) => punct.as_char() == '\'',
+        proc_macro2::TokenTree::Group(group) => group.stream().into_iter().any(|token| has_lifetime(&token)),
+        _ => false,
+    }
+}
+
+/// Push every identifier of `tokens` to `idents`, including those inside brackets
+fn collect_idents(tokens: proc_macro2::TokenStream, idents: &mut Vec<Ident>) {
+    for token in tokens {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) => idents.push(ident),
+            proc_macro2::TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+/// Type pointed to by a pointer type, the last type argument, e.g. `T` of `Pointer64<T>` and
+/// `Pointer<u32, T>`
+fn pointer_target(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(args) = &path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().rev().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Type as written in the source, e.g. `Pointer64<Team>` and `[u8; 16]` instead of the
+/// spacing of the token stream
+fn type_string(ty: &Type) -> String {
+    let tokens = quote!(#ty).to_string();
+    let chars: Vec<char> = tokens.chars().collect();
+    let mut out = String::new();
+    for (index, c) in chars.iter().enumerate() {
+        let tight = |c: Option<&char>| c.is_some_and(|c| "<>[]()&*:;,".contains(*c));
+        if *c == ' ' && (tight(index.checked_sub(1).and_then(|index| chars.get(index))) || tight(chars.get(index + 1))) {
+            continue;
+        }
+        out.push(*c);
+        if *c == ',' || *c == ';' {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+/// The kind of type whose fields are collected
+#[derive(Clone, Copy, PartialEq)]
+enum Owner {
+    Struct,
+    Variant,
+    Union,
+}
+
+/// Returns true if `member` is named like a vtable pointer, e.g. `vtable`, `__vfptr`,
+/// `_vptr` or `vftable`
+fn is_vtable_name(member: &Member) -> bool {
+    let Member::Named(ident) = member else {
+        return false;
+    };
+    let name = ident.unraw().to_string().to_ascii_lowercase();
+    let name = name.trim_start_matches('_');
+    ["vtable", "vtbl", "vftable", "vfptr", "vptr"].iter().any(|prefix| name == *prefix || name.starts_with(&format!("{}_", prefix)))
+}
+
+/// Generate the match arms collecting each field by its index, the statements adding the
+/// pointer targets of the followed fields to `targets` and the declaration index of the field
+/// of every arm, the fields must be bound by `field_pattern`
+fn collect_fields(fields: &Fields, owner: Owner, generics: &Generics) -> Result<(Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>, Vec<usize>)> {
+    let mut field_collects = Vec::new();
+    let mut field_targets = Vec::new();
+    let mut arm_fields = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = field_member(index, field);
+        let field_name_str = match &member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        
+        if attrs.skip || attrs.pad {
+            // Don't print skipped or padding fields
+            continue;
+        }
+        
+        // Renamed fields are printed and looked up by their new name only
+        let field_name_str = attrs.rename.clone().unwrap_or(field_name_str);
+        
+        // Check if this field is a pointer type, other `PointerLike` types are marked
+        let is_pointer = is_pointer_type(&field.ty, &attrs);
+        
+        // Containers of pointers like `[Pointer64<T>; 8]` or `Option<Pointer64<T>>` are
+        // collected through their `DerefDebugPrint` implementation
+        let field_ty = &field.ty;
+        let contains_pointer = !is_pointer && quote!(#field_ty).to_string().contains("Pointer");
+        
+        // Extract the field type for better display
+        let field_type = match &field.ty {
+            Type::Path(TypePath { path, .. }) => {
+                if let Some(segment) = path.segments.last() {
+                    let type_name = segment.ident.to_string();
+                    quote! { #type_name }
+                } else {
+                    quote! { "Unknown" }
+                }
+            },
+            _ => quote! { "Unknown" },
+        };
+        
+        if is_pointer && attrs.nested {
+            return Err(Error::new_spanned(&field.ty, "`nested` is not needed on pointer fields, their targets are always followed"));
+        }
+        
+        if attrs.nested && (attrs.format.is_some() || attrs.has_target_override() || attrs.list.is_some()) {
+            return Err(Error::new_spanned(&field.ty, "`nested` can not be combined with other attributes"));
+        }
+        
+        if (is_pointer || contains_pointer) && attrs.format.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`format` is not supported on pointer fields"));
+        }
+        
+        if !is_pointer && attrs.has_target_override() {
+            return Err(Error::new_spanned(&field.ty, "`cstr`, `wstr`, `array`, `slice`, `hexdump`, `ptr_array_null_terminated`, `buckets` and `follow_chain` are only supported on pointer fields"));
+        }
+        
+        if is_pointer && attrs.list.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`list` is only supported on embedded `ListEntry64` list heads"));
+        }
+        
+        if owner != Owner::Struct && attrs.follow_chain.is_some() {
+            // The next link can't be taken out of an element without knowing its variant
+            return Err(Error::new_spanned(&field.ty, "`follow_chain` is not supported in enum variants and unions"));
+        }
+        
+        if owner != Owner::Struct && attrs.endian.is_some() {
+            // Enums and unions are kept in the byte order they were read in
+            return Err(Error::new_spanned(&field.ty, "`endian` is only supported on fields of structures"));
+        }
+        
+        if owner != Owner::Union && attrs.when.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`when` is only supported on fields of unions"));
+        }
+        
+        if !is_pointer && attrs.deref_as.is_none() && attrs.view.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`view` and `phys` are only supported on pointer and `deref_as` fields"));
+        }
+        
+        if !is_pointer && attrs.deref_if.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`deref_if` is only supported on pointer fields"));
+        }
+        
+        if !is_pointer && attrs.dyn_type.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`dyn_type` is only supported on pointer fields"));
+        }
+        
+        if (is_pointer || contains_pointer) && attrs.select_by.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`select_by` is only supported on embedded unions"));
+        }
+        
+        if (is_pointer || contains_pointer) && attrs.flags.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`flags` is only supported on integer fields"));
+        }
+        
+        if (is_pointer || contains_pointer) && attrs.enum_repr.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`enum_repr` is only supported on integer fields"));
+        }
+        
+        if (is_pointer || contains_pointer) && attrs.timestamp.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`timestamp` is only supported on integer fields"));
+        }
+        
+        if !is_pointer && attrs.ptr_mask.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`ptr_mask`, `tag_bits` and `addr_width` are only supported on pointer fields"));
+        }
+        
+        if (is_pointer || contains_pointer) && attrs.deref_as.is_some() {
+            return Err(Error::new_spanned(&field.ty, "`deref_as` is only supported on integer fields"));
+        }
+        
+        // Pointers and addresses named like a vtable pointer of C++, MSVC and Itanium
+        // compilers, unless printed by a custom function
+        let is_address = match &field.ty {
+            Type::Path(TypePath { path, .. }) => path.is_ident("u64") || path.is_ident("u32") || path.is_ident("usize") || path.is_ident("umem"),
+            _ => false,
+        };
+        let vtable = attrs.vtable || (attrs.with.is_none() && (is_pointer || is_address) && is_vtable_name(&member));
+        if attrs.vtable && !is_pointer && !is_address {
+            return Err(Error::new_spanned(&field.ty, "`vtable` is only supported on pointer and address fields"));
+        }
+        if attrs.function && !is_pointer && !is_address {
+            return Err(Error::new_spanned(&field.ty, "`function` is only supported on pointer and address fields"));
+        }
+        // Other address fields are printed as a function if they point into executable memory
+        // of a module, unless they are printed in another way
+        let code = !vtable && !attrs.function && is_address && attrs.with.is_none() && attrs.format.is_none() && attrs.flags.is_none() && attrs.enum_repr.is_none() && attrs.timestamp.is_none() && attrs.deref_as.is_none();
+        
+        // Fields printed by a custom function are not followed
+        let follow = (is_pointer || contains_pointer || attrs.nested || attrs.deref_as.is_some() || attrs.select_by.is_some()) && attrs.with.is_none();
+        if !follow && attrs.max_depth.is_some() && attrs.list.is_none() {
+            return Err(Error::new_spanned(&field.ty, "`max_depth` is only supported on fields that are followed"));
+        }
+        
+        // Fields read through their attributes, with their own depth limit or that may not be
+        // followed at all are not batched. Arrays of pointers batch the elements up to the
+        // element limit themselves
+        // Redacted fields without a hash are not read at all
+        let collected = attrs.redact.as_ref().is_none_or(|redact| redact.hash);
+        if follow && collected && !is_pointer_array(&field.ty) && !attrs.has_target_override() && attrs.list.is_none() && attrs.dyn_type.is_none() && attrs.deref_if.is_none() && attrs.view.is_none() && attrs.max_depth.is_none() && !vtable && !attrs.function {
+            field_targets.push(field::field_targets(&member, &attrs, is_pointer));
+        }
+        
+        let mut value = field::field_value(&member, &attrs, &field.ty, follow, is_pointer);
+        // Registered printers need the field type to be `'static`, which only types without
+        // generic parameters are known to be
+        let printable = is_static_type(&field.ty, generics) && !is_pointer && !vtable && attrs.with.is_none() && attrs.format.is_none() && attrs.flags.is_none() && attrs.enum_repr.is_none() && attrs.timestamp.is_none() && attrs.deref_as.is_none() && attrs.select_by.is_none() && attrs.list.is_none();
+        if printable {
+            value = field::printed_value(&member, value);
+        }
+        if let Some(max_depth) = attrs.max_depth {
+            value = field::with_max_depth(value, max_depth);
+        }
+        if vtable {
+            value = field::symbol_value(&member, value, &attrs, is_pointer, quote! { symbolize });
+        } else if attrs.function {
+            value = field::symbol_value(&member, value, &attrs, is_pointer, quote! { symbolize_function });
+        } else if code {
+            value = field::symbol_value(&member, value, &attrs, is_pointer, quote! { symbolize_code });
+        }
+        match &attrs.redact {
+            Some(redact) if redact.hash => value = quote! { (#value).redacted_hash() },
+            Some(_) => value = quote! { ::memflow_pointer_debug::PointerDebugValue::redacted() },
+            None => {}
+        }
+        
+        let field_index = field_collects.len();
+        arm_fields.push(index);
+        // Fields can still be padding by their name, see `PointerPrintConfig::is_padding`, or
+        // be left out by the path filters
+        field_collects.push(quote! {
+            #field_index if !state.config.is_padding(#field_name_str) && state.is_selected(#field_name_str) => {
+                state.enter_field(#field_name_str);
+                let value = #value;
+                state.leave_field();
+                Some(::memflow_pointer_debug::PointerDebugField {
+                    name: ::memflow_pointer_debug::__private::String::from(#field_name_str),
+                    type_name: ::memflow_pointer_debug::__private::String::from(#field_type),
+                    value,
+                })
+            },
+        });
+        
+        // The bits stripped from a tagged pointer are printed right after it
+        if let Some(mask) = attrs.ptr_mask.filter(|_| !attrs.untagged) {
+            let tag_name_str = format!("{}_tag", field_name_str);
+            let tag = field::tag_value(&member, mask);
+            let field_index = field_collects.len();
+            arm_fields.push(index);
+            field_collects.push(quote! {
+                #field_index if !state.config.is_padding(#tag_name_str) && state.is_selected(#tag_name_str) => {
+                    Some(::memflow_pointer_debug::PointerDebugField {
+                        name: ::memflow_pointer_debug::__private::String::from(#tag_name_str),
+                        type_name: ::memflow_pointer_debug::__private::String::from("u64"),
+                        value: #tag,
+                    })
+                },
+            });
+        }
+    }
+    Ok((field_collects, field_targets, arm_fields))
+}
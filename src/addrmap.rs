@@ -0,0 +1,303 @@
+//! Flat listing of the visited nodes for annotation tools.
+
+use core::fmt::Write;
+
+use memflow::error::Result;
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+
+use crate::node::item_address;
+use crate::prelude::*;
+use crate::read::read_each;
+use crate::retry::RetryView;
+use crate::{
+    DerefDebugPrint, HashMap, HashSet, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+    PointerTarget, TraversalState,
+};
+
+/// A node of an [`AddressMap`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddressEntry {
+    /// Address the node was read from
+    pub address: u64,
+    /// Number of bytes the node was read as, `None` if it is not known
+    pub size: Option<usize>,
+    /// Type the node was read as
+    pub type_name: String,
+    /// Path of the pointer leading to the node from the root like `*.team` or
+    /// `players[2].name`
+    pub path: String,
+}
+
+/// Every node of a traversal as `address, size, type, path`, sorted by address.
+///
+/// The listing is meant to be imported into IDA, Ghidra or x64dbg as labels and comments.
+/// [`to_csv`](Self::to_csv) writes it with a header line, [`to_json`](Self::to_json) as an
+/// array of objects. A node read several times as the same type is listed once, under the
+/// path it was reached through first. The root value is only listed if it has an address,
+/// e.g. when a pointer to the structure is mapped.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{AddressMap, PointerDerefDebugPrint, PointerPrintConfig};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     _pad: u32,
+///     team: Pointer64<Team>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Team {
+///     id: u64,
+/// }
+///
+/// fn export(mem: &mut impl MemoryView, player: Pointer64<Player>) -> std::io::Result<()> {
+///     let map = AddressMap::collect(&player, mem, &PointerPrintConfig::new());
+///     // address,size,type,path
+///     // 0x7ffe12340000,16,Player,*
+///     // 0x7ffe12350000,8,Team,*.team
+///     std::fs::write("player.csv", map.to_csv())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddressMap {
+    /// The nodes in ascending order of their address
+    pub entries: Vec<AddressEntry>,
+}
+
+impl AddressMap {
+    /// Collect `value` with the options of `config` and list its nodes with the number of
+    /// bytes each of them was read as
+    pub fn collect<T, M>(value: &T, mem: &mut M, config: &PointerPrintConfig) -> Self
+    where
+        T: DerefDebugPrint + ?Sized,
+        M: MemoryView,
+    {
+        let mut sizes = HashMap::new();
+        let mut mem = RetryView::new(mem, config);
+        let mut view = SizeView {
+            mem: &mut mem,
+            sizes: &mut sizes,
+        };
+        let mut state = TraversalState::with_config(config);
+        let node = value.pointer_collect_internal(&mut view, 0, &mut state);
+        Self::build(&node, &sizes)
+    }
+
+    /// List the nodes of a collected tree. The tree doesn't record how many bytes were
+    /// read, only the sizes of types checked with `expected_size` and of nodes holding
+    /// their raw bytes are known
+    pub fn from_node(root: &PointerDebugNode) -> Self {
+        Self::build(root, &HashMap::new())
+    }
+
+    fn build(root: &PointerDebugNode, sizes: &HashMap<u64, usize>) -> Self {
+        let mut builder = Builder {
+            sizes,
+            seen: HashSet::new(),
+            entries: Vec::new(),
+        };
+        builder.push_node(root, "", None);
+        builder.push_fields(root, "");
+        let mut entries = builder.entries;
+        entries.sort_by_key(|entry| entry.address);
+        Self { entries }
+    }
+
+    /// Returns true if no node was read from memory
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the listing as CSV with the header `address,size,type,path`, addresses in hex
+    /// and unknown sizes left empty
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("address,size,type,path\n");
+        for entry in &self.entries {
+            let size = entry.size.map(|size| size.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{:#x},{},{},{}",
+                entry.address,
+                size,
+                csv_field(&entry.type_name),
+                csv_field(&entry.path)
+            );
+        }
+        out
+    }
+
+    /// Write the listing as a JSON array of `{"address", "size", "type", "path"}` objects,
+    /// addresses as hex strings and unknown sizes as `null`
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (i, entry) in self.entries.iter().enumerate() {
+            let size = entry.size.map(|size| size.to_string());
+            let _ = write!(
+                out,
+                "{}\n  {{\"address\": \"{:#x}\", \"size\": {}, \"type\": {}, \"path\": {}}}",
+                if i == 0 { "" } else { "," },
+                entry.address,
+                size.as_deref().unwrap_or("null"),
+                json_string(&entry.type_name),
+                json_string(&entry.path)
+            );
+        }
+        out.push_str(if self.entries.is_empty() {
+            "]\n"
+        } else {
+            "\n]\n"
+        });
+        out
+    }
+}
+
+/// Walks a tree and lists its nodes
+struct Builder<'a> {
+    /// Number of bytes read at each address
+    sizes: &'a HashMap<u64, usize>,
+    /// Nodes listed so far by address and type
+    seen: HashSet<(u64, String)>,
+    entries: Vec<AddressEntry>,
+}
+
+impl Builder<'_> {
+    /// List `node` reached through `path`, `size` is the stride of the array holding it
+    fn push_node(&mut self, node: &PointerDebugNode, path: &str, size: Option<usize>) {
+        let Some(address) = node.address else {
+            return;
+        };
+        if !self.seen.insert((address, node.type_name.clone())) {
+            return;
+        }
+        let size = node
+            .size
+            .or(node.raw_bytes.as_ref().map(Vec::len))
+            .or(size)
+            .or_else(|| self.sizes.get(&address).copied());
+        self.entries.push(AddressEntry {
+            address,
+            size,
+            type_name: node.type_name.clone(),
+            path: path.to_string(),
+        });
+    }
+
+    /// List the nodes the pointers of `node`, reached through `path`, lead to
+    fn push_fields(&mut self, node: &PointerDebugNode, path: &str) {
+        for field in &node.fields {
+            let path = if path.is_empty() || field.name.starts_with('[') {
+                format!("{}{}", path, field.name)
+            } else {
+                format!("{}.{}", path, field.name)
+            };
+            match &field.value {
+                PointerDebugValue::Pointer { address, target } => {
+                    self.push_target(*address, target, &path, None)
+                }
+                PointerDebugValue::Nested(nested) => self.push_fields(nested, &path),
+                PointerDebugValue::Value(_) => {}
+            }
+        }
+    }
+
+    /// List the nodes of `target` at `address` reached through `path`
+    fn push_target(
+        &mut self,
+        address: u64,
+        target: &PointerTarget,
+        path: &str,
+        size: Option<usize>,
+    ) {
+        match target {
+            PointerTarget::Node(node) => {
+                self.push_node(node, path, size);
+                self.push_fields(node, path);
+            }
+            PointerTarget::Array { items, .. } => {
+                // The elements are read in one go, each takes up the distance to the next
+                let addresses: Vec<u64> = items
+                    .iter()
+                    .map(|item| item_address(item, address))
+                    .collect();
+                let stride = match addresses.as_slice() {
+                    [first, second, ..] => second.checked_sub(*first).map(|stride| stride as usize),
+                    [_] => self.sizes.get(&address).copied(),
+                    [] => None,
+                };
+                for (i, (item, item_address)) in items.iter().zip(addresses).enumerate() {
+                    self.push_target(item_address, item, &format!("{}[{}]", path, i), stride);
+                }
+            }
+            PointerTarget::Chain { items, .. } => {
+                for (i, item) in items.iter().enumerate() {
+                    self.push_target(address, item, &format!("{}[{}]", path, i), None);
+                }
+            }
+            PointerTarget::List { items, .. } => {
+                for (i, (item_address, item)) in items.iter().enumerate() {
+                    self.push_target(*item_address, item, &format!("{}[{}]", path, i), None);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Quote a CSV field if it holds a separator, a quote or a line break
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Quote and escape a JSON string
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Records how many bytes were read at each address, the first read of an address wins
+struct SizeView<'a, M> {
+    mem: &'a mut M,
+    sizes: &'a mut HashMap<u64, usize>,
+}
+
+impl<M: MemoryView> MemoryView for SizeView<'_, M> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        read_each(data, |address, buf| {
+            let read = self.mem.read_raw_into(address, buf).is_ok();
+            if read {
+                self.sizes.entry(address.to_umem()).or_insert(buf.len());
+            }
+            read
+        })
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.mem.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.mem.metadata()
+    }
+}
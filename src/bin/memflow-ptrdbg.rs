@@ -0,0 +1,300 @@
+//! Print a structure of a process described by a layout file, following its pointers.
+//!
+//! ```text
+//! memflow-ptrdbg --connector qemu --os win32 --layout game.json --type Player \
+//!     game.exe "[game.exe+0x1a2b30]+0x10"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Parser, ValueEnum};
+use memflow::prelude::v1::*;
+use memflow_pointer_debug::{
+    AddressEntry, AddressMap, Layouts, OutputFormat, PointerGraph, PointerPrint, PointerPrintConfig,
+};
+
+/// Errors of memflow, the layout file and the arguments are all reported the same way
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Output formats of the dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Tree,
+    Compact,
+    Ron,
+    Yaml,
+    Json,
+    Dot,
+    Mermaid,
+    Graphml,
+    Html,
+    /// `address,size,type,path` of every node, e.g. to import labels into a disassembler
+    AddressCsv,
+    /// The same listing as JSON
+    AddressJson,
+}
+
+/// Print a structure of a process described by a layout file, following its pointers
+#[derive(Debug, Parser)]
+#[command(name = "memflow-ptrdbg", version)]
+struct Args {
+    /// Connector plugin to read memory through, e.g. `qemu` or `kvm`
+    #[arg(short, long)]
+    connector: Option<String>,
+    /// Arguments of the connector
+    #[arg(long, default_value = "")]
+    connector_args: String,
+    /// OS plugin running on top of the connector
+    #[arg(short, long, default_value = "win32")]
+    os: String,
+    /// Arguments of the OS plugin
+    #[arg(long, default_value = "")]
+    os_args: String,
+    /// Layout file: JSON layouts, a C header with the `cparse` feature, a PDB with the `pdb`
+    /// feature or an ELF with DWARF information with the `dwarf` feature
+    #[arg(short, long)]
+    layout: PathBuf,
+    /// Size of a pointer in bytes, e.g. 4 for 32-bit and WOW64 processes. C headers default to
+    /// the pointer size of the process, the other layout files to the one they declare
+    #[arg(long, value_parser = PossibleValuesParser::new(["4", "8"]).map(|size| size.parse::<usize>().unwrap()))]
+    pointer_size: Option<usize>,
+    /// Name of the structure to print
+    #[arg(short = 't', long = "type")]
+    type_name: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Tree)]
+    format: Format,
+    /// Maximum recursion depth
+    #[arg(short, long, default_value_t = 5)]
+    depth: usize,
+    /// Follow pointers at any depth until no new targets are found or this many nodes were
+    /// read, instead of stopping at `--depth`
+    #[arg(long, value_name = "MAX_NODES")]
+    auto_depth: Option<usize>,
+    /// Print the target address of every pointer
+    #[arg(long)]
+    show_addresses: bool,
+    /// Print the bytes of every structure as a hex dump after its fields
+    #[arg(long)]
+    show_raw_bytes: bool,
+    /// Print the structures pointed to level by level after the fields of the root
+    #[arg(long)]
+    breadth_first: bool,
+    /// Name of the process to read
+    process: String,
+    /// Address of the structure, a number, a module name, `+`/`-` of those and `[..]` to read
+    /// a pointer, e.g. `[game.exe+0x1a2b30]+0x10`
+    address: String,
+}
+
+fn main() {
+    if let Err(error) = run(Args::parse()) {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    let inventory = Inventory::scan();
+    let os_args = args.os_args.parse()?;
+    let os = match &args.connector {
+        Some(connector) => inventory
+            .builder()
+            .connector(connector)
+            .args(args.connector_args.parse()?)
+            .os(&args.os)
+            .args(os_args)
+            .build()?,
+        None => inventory.builder().os(&args.os).args(os_args).build()?,
+    };
+    let mut process = os.into_process_by_name(&args.process)?;
+
+    let process_pointer_size = process.info().proc_arch.into_obj().bits() as usize / 8;
+    let layouts = load_layouts(
+        &args.layout,
+        &args.type_name,
+        args.pointer_size,
+        process_pointer_size,
+    )?;
+    if layouts.get(&args.type_name).is_none() {
+        return Err(format!("no layout called `{}`", args.type_name).into());
+    }
+
+    let address = Expression {
+        text: &args.address,
+        position: 0,
+    }
+    .evaluate(&mut process)?;
+    let value = layouts.read(&mut process, &args.type_name, address)?;
+
+    let config = PointerPrintConfig::new()
+        .max_depth(args.depth)
+        .show_addresses(args.show_addresses)
+        .show_raw_bytes(args.show_raw_bytes)
+        .breadth_first(args.breadth_first);
+    let config = match args.auto_depth {
+        Some(max_nodes) => config.auto_depth(max_nodes),
+        None => config,
+    };
+    let config = match args.format {
+        Format::Compact => config.format(OutputFormat::Compact),
+        Format::Ron => config.format(OutputFormat::Ron),
+        _ => config,
+    };
+    if matches!(args.format, Format::AddressCsv | Format::AddressJson) {
+        let mut map = AddressMap::collect(&value, &mut process, &config);
+        // The structure itself was read by the layout, not by the traversal
+        let root = AddressEntry {
+            address: address.to_umem(),
+            size: Some(value.bytes().len()),
+            type_name: args.type_name.clone(),
+            path: String::new(),
+        };
+        let at = map
+            .entries
+            .partition_point(|entry| entry.address < root.address);
+        map.entries.insert(at, root);
+        match args.format {
+            Format::AddressCsv => print!("{}", map.to_csv()),
+            _ => print!("{}", map.to_json()),
+        }
+        return Ok(());
+    }
+    let tree = value.pointer_collect_with_config(&mut process, &config);
+    match args.format {
+        Format::Tree | Format::Compact | Format::Ron => print!("{}", tree.display(&config)),
+        Format::Yaml => print!("{}", tree.to_yaml()),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&tree)?),
+        Format::Dot => print!("{}", PointerGraph::from_node(&tree).to_dot()),
+        Format::Mermaid => print!("{}", PointerGraph::from_node(&tree).to_mermaid()),
+        Format::Graphml => print!("{}", PointerGraph::from_node(&tree).to_graphml()),
+        Format::Html => print!("{}", tree.to_html()),
+        Format::AddressCsv | Format::AddressJson => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Read the layouts in `path`, the format is picked by the extension. A `pointer_size` given
+/// on the command line replaces the one of the file, C headers don't declare one and use the
+/// pointer size of the process
+fn load_layouts(
+    path: &Path,
+    type_name: &str,
+    pointer_size: Option<usize>,
+    #[cfg_attr(not(feature = "cparse"), allow(unused_variables))] process_pointer_size: usize,
+) -> Result<Layouts> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let layouts: Layouts = match extension.as_str() {
+        "json" => Layouts::load(path)?,
+        #[cfg(feature = "cparse")]
+        "h" | "hpp" => Layouts::new()
+            .pointer_size(pointer_size.unwrap_or(process_pointer_size))
+            .c_header(&std::fs::read_to_string(path)?)?,
+        #[cfg(feature = "pdb")]
+        "pdb" => Layouts::from_pdb(path, type_name)?,
+        #[cfg(feature = "dwarf")]
+        _ => Layouts::from_dwarf(path, type_name)?,
+        #[cfg(not(feature = "dwarf"))]
+        _ => {
+            let _ = type_name;
+            return Err(format!("unsupported layout file `{}`", path.display()).into());
+        }
+    };
+    Ok(match pointer_size {
+        Some(pointer_size) => layouts.pointer_size(pointer_size),
+        None => layouts,
+    })
+}
+
+/// An address expression like `[game.exe+0x1a2b30]+0x10`
+struct Expression<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Expression<'a> {
+    /// Evaluate the whole expression in the address space of `process`
+    fn evaluate(&mut self, process: &mut (impl Process + MemoryView)) -> Result<Address> {
+        let address = self.sum(process)?;
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            None => Ok(address),
+            Some(c) => Err(self.error(&format!("unexpected `{}`", c))),
+        }
+    }
+
+    /// Terms joined by `+` and `-`
+    fn sum(&mut self, process: &mut (impl Process + MemoryView)) -> Result<Address> {
+        let mut address = self.term(process)?;
+        loop {
+            self.skip_whitespace();
+            if self.eat('+') {
+                address = address.wrapping_add(self.term(process)?);
+            } else if self.eat('-') {
+                address = address.wrapping_sub(self.term(process)?);
+            } else {
+                return Ok(address);
+            }
+        }
+    }
+
+    /// A number, a module base or a pointer read with `[..]`
+    fn term(&mut self, process: &mut (impl Process + MemoryView)) -> Result<Address> {
+        self.skip_whitespace();
+        if self.eat('[') {
+            let address = self.sum(process)?;
+            self.skip_whitespace();
+            if !self.eat(']') {
+                return Err(self.error("expected `]`"));
+            }
+            let arch = process.info().proc_arch.into_obj();
+            return Ok(process.read_addr_arch(arch, address).data_part()?);
+        }
+
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '+' | '-' | '[' | ']'))
+            .unwrap_or(rest.len());
+        let token = &rest[..len];
+        if token.is_empty() {
+            return Err(self.error("expected a number or module name"));
+        }
+        self.position += len;
+
+        let number = match token.strip_prefix("0x").or(token.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => token.parse().ok(),
+        };
+        match number {
+            Some(number) => Ok(Address::from(number)),
+            None => Ok(process.module_by_name(token)?.base),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consume `c` if it comes next
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.rest().starts_with(c);
+        if found {
+            self.position += c.len_utf8();
+        }
+        found
+    }
+
+    fn error(&self, message: &str) -> Box<dyn std::error::Error> {
+        format!("address `{}` at {}: {}", self.text, self.position, message).into()
+    }
+}
@@ -0,0 +1,142 @@
+//! Page cache for the reads of a single traversal.
+
+use memflow::cglue::{CTup2, CTup3};
+use memflow::error::Result;
+use memflow::mem::{
+    MemOps, MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps, opt_call,
+};
+use memflow::types::Address;
+
+use crate::HashMap;
+use crate::prelude::*;
+
+/// Granularity of the cache
+const PAGE_SIZE: u64 = 0x1000;
+
+/// A [`MemoryView`] that reads whole pages and keeps them until it is dropped.
+///
+/// Pointers in a dump tend to land on the same handful of pages, wrapping the memory for
+/// the duration of one print means each of those pages is only fetched from the connector
+/// once. Pages that failed to read are remembered as well and fail again without a read.
+///
+/// The cache is never invalidated except for writes made through the view itself, create
+/// a new one for every traversal so changes to the target memory are picked up. Not to be
+/// confused with memflow's `CachedView`, which caches across calls with a time-based
+/// validator.
+///
+/// ```rust
+/// use memflow::prelude::v1::{MemoryView, Pod, Pointer64};
+/// use memflow_pointer_debug::{CachedView, PointerDerefDebugPrint, PointerPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// fn print(mem: &mut impl MemoryView, node: &Node) {
+///     node.pointer_print(&mut CachedView::new(mem));
+/// }
+/// ```
+pub struct CachedView<'a, M> {
+    mem: &'a mut M,
+    /// Contents of every page read so far, `None` if reading it failed
+    pages: HashMap<u64, Option<Box<[u8]>>>,
+}
+
+impl<'a, M: MemoryView> CachedView<'a, M> {
+    /// Wrap `mem`, the cache starts out empty
+    pub fn new(mem: &'a mut M) -> Self {
+        Self {
+            mem,
+            pages: HashMap::new(),
+        }
+    }
+
+    /// Forget every cached page
+    pub fn clear(&mut self) {
+        self.pages.clear();
+    }
+
+    /// Number of pages that were fetched, including the ones that failed to read
+    pub fn cached_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Contents of the page starting at `page`, it is read on the first access
+    fn page(&mut self, page: u64) -> Option<&[u8]> {
+        let mem = &mut *self.mem;
+        self.pages
+            .entry(page)
+            .or_insert_with(|| {
+                let mut data = vec![0u8; PAGE_SIZE as usize].into_boxed_slice();
+                mem.read_raw_into(page.into(), &mut data).ok().map(|_| data)
+            })
+            .as_deref()
+    }
+
+    /// Drop the cached pages overlapping `len` bytes at `address`
+    fn invalidate(&mut self, address: Address, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let first = address.to_umem() & !(PAGE_SIZE - 1);
+        let last = address.to_umem().saturating_add(len as u64 - 1) & !(PAGE_SIZE - 1);
+        for page in (first..=last).step_by(PAGE_SIZE as usize) {
+            self.pages.remove(&page);
+        }
+    }
+}
+
+impl<M: MemoryView> MemoryView for CachedView<'_, M> {
+    fn read_raw_iter(
+        &mut self,
+        MemOps {
+            inp,
+            mut out,
+            mut out_fail,
+        }: ReadRawMemOps,
+    ) -> Result<()> {
+        for CTup3(mut address, mut meta, buf) in inp {
+            let mut buf: &mut [u8] = buf.into();
+
+            // Requests are split at page boundaries, each part succeeds or fails on its own
+            while !buf.is_empty() {
+                let page = address.to_umem() & !(PAGE_SIZE - 1);
+                let offset = (address.to_umem() - page) as usize;
+                let len = buf.len().min(PAGE_SIZE as usize - offset);
+                let (chunk, rest) = core::mem::take(&mut buf).split_at_mut(len);
+
+                match self.page(page) {
+                    Some(data) => {
+                        chunk.copy_from_slice(&data[offset..offset + len]);
+                        opt_call(out.as_deref_mut(), CTup2(meta, chunk.into()));
+                    }
+                    None => {
+                        opt_call(out_fail.as_deref_mut(), CTup2(meta, chunk.into()));
+                    }
+                }
+
+                address += len;
+                meta += len;
+                buf = rest;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_raw_iter(&mut self, MemOps { inp, out, out_fail }: WriteRawMemOps) -> Result<()> {
+        let writes: Vec<_> = inp.collect();
+        for CTup3(address, _, data) in &writes {
+            self.invalidate(*address, data.len());
+        }
+        MemOps::with_raw(writes.into_iter(), out, out_fail, |data| {
+            self.mem.write_raw_iter(data)
+        })
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.mem.metadata()
+    }
+}
@@ -0,0 +1,954 @@
+//! Runtime layouts parsed from C structure declarations.
+
+use core::error;
+use core::fmt;
+
+use crate::prelude::*;
+use crate::{FieldType, HashMap, Layout, LayoutField, Layouts};
+
+/// A C header that could not be parsed, see [`Layouts::c_header`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CHeaderError {
+    /// Line of the header the error was found in, starting at 1
+    pub line: usize,
+    /// What went wrong
+    pub message: String,
+}
+
+impl fmt::Display for CHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for CHeaderError {}
+
+impl Layouts {
+    /// Add the structures and unions declared in a C header.
+    ///
+    /// Offsets and sizes are computed with the usual C alignment rules for the
+    /// [`pointer_size`](Layouts::pointer_size) of the layouts, `#pragma pack` and
+    /// `__attribute__((packed))` are honored. Typedefs, enums, nested and anonymous
+    /// structures and unions, arrays, bitfields and function pointers are understood, the
+    /// fixed size integers of `stdint.h` and the integer types of `windows.h` like `DWORD`
+    /// are known without including anything. `long` is 4 bytes and bitfields share an
+    /// integer only with bitfields of the same size like on Windows, `void` and function
+    /// pointers are printed as plain addresses. Other preprocessor directives,
+    /// functions and variables are skipped.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{FieldType, Layouts};
+    ///
+    /// let layouts = Layouts::new()
+    ///     .c_header(
+    ///         r#"
+    ///         typedef struct _TEAM {
+    ///             uint64_t id;
+    ///         } TEAM;
+    ///
+    ///         struct Player {
+    ///             uint32_t health;
+    ///             TEAM *team;
+    ///             char name[16];
+    ///         };
+    ///         "#,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let player = layouts.get("Player").unwrap();
+    /// assert_eq!(player.size, 0x20);
+    /// assert_eq!(player.fields[1].offset, 0x8);
+    /// assert_eq!(player.fields[1].ty, FieldType::pointer(FieldType::named("_TEAM")));
+    /// ```
+    pub fn c_header(self, header: &str) -> Result<Self, CHeaderError> {
+        let mut parser = Parser {
+            tokens: lex(header)?,
+            pos: 0,
+            layouts: self,
+            aligns: HashMap::new(),
+            typedefs: HashMap::new(),
+            constants: HashMap::new(),
+            pack: None,
+            pack_stack: Vec::new(),
+            anonymous: 0,
+        };
+        parser.header()?;
+        Ok(parser.layouts)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Punct(char),
+    /// A string or character literal, only skipped
+    Literal,
+    /// `#pragma pack(...)`
+    Pack(Pack),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pack {
+    /// `pack(N)`, `pack()` resets to the natural alignment
+    Set(Option<usize>),
+    /// `pack(push)` or `pack(push, N)`
+    Push(Option<usize>),
+    /// `pack(pop)`
+    Pop,
+}
+
+fn error(line: usize, message: impl Into<String>) -> CHeaderError {
+    CHeaderError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Split a header into tokens with their line, comments and preprocessor directives other
+/// than `#pragma pack` are dropped
+fn lex(header: &str) -> Result<Vec<(Token, usize)>, CHeaderError> {
+    let chars: Vec<char> = header.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c == '#' && line_start {
+            // Directives end at a newline that isn't escaped
+            let start = i;
+            while i < chars.len() && (chars[i] != '\n' || chars[i - 1] == '\\') {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            let directive: String = chars[start + 1..i].iter().collect();
+            if let Some(pack) = pragma_pack(&directive) {
+                tokens.push((Token::Pack(pack), line));
+            }
+            continue;
+        }
+        line_start = false;
+
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push((Token::Literal, line));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let number = parse_number(&literal)
+                .ok_or_else(|| error(line, format!("invalid number `{}`", literal)))?;
+            tokens.push((Token::Number(number), line));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), line));
+        } else {
+            tokens.push((Token::Punct(c), line));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// An integer literal like `16`, `0x10` or `010`, suffixes like `u` or `ULL` are ignored
+fn parse_number(literal: &str) -> Option<u64> {
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16).ok()
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u64::from_str_radix(&digits[1..], 8).ok()
+    } else {
+        digits.parse().ok()
+    }
+}
+
+/// The packing set by a `pragma pack` directive, `None` for other directives
+fn pragma_pack(directive: &str) -> Option<Pack> {
+    let rest = directive.trim().strip_prefix("pragma")?.trim();
+    let args = rest.strip_prefix("pack")?.trim();
+    let args = args.strip_prefix('(')?.strip_suffix(')')?;
+    let args: Vec<&str> = args.split(',').map(str::trim).collect();
+    let size = |arg: Option<&&str>| arg.and_then(|arg| parse_number(arg)).map(|n| n as usize);
+    Some(match args[0] {
+        "push" => Pack::Push(size(args.get(1))),
+        "pop" => Pack::Pop,
+        _ => Pack::Set(size(args.first())),
+    })
+}
+
+/// A member of a structure before its offset is known
+struct Member {
+    name: Option<String>,
+    ty: FieldType,
+    bits: Option<u64>,
+    line: usize,
+}
+
+/// A declarator like `*name[4]`, applied to the type it follows
+struct Declarator {
+    name: Option<String>,
+    ty: Option<FieldType>,
+    bits: Option<u64>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    layouts: Layouts,
+    /// Alignment of every structure in bytes
+    aligns: HashMap<String, usize>,
+    /// The types defined with `typedef`, `None` for `void`
+    typedefs: HashMap<String, Option<FieldType>>,
+    /// Values of enum constants, usable as array lengths
+    constants: HashMap<String, u64>,
+    /// The current `#pragma pack`
+    pack: Option<usize>,
+    pack_stack: Vec<Option<usize>>,
+    /// Number of anonymous structures seen, for their names
+    anonymous: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), CHeaderError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", c)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> CHeaderError {
+        let found = match self.peek() {
+            Some(Token::Ident(name)) => format!("`{}`", name),
+            Some(Token::Number(n)) => format!("`{}`", n),
+            Some(Token::Punct(c)) => format!("`{}`", c),
+            Some(Token::Literal) => "a literal".to_string(),
+            Some(Token::Pack(_)) => "`#pragma pack`".to_string(),
+            None => "the end of the header".to_string(),
+        };
+        error(
+            self.line(),
+            format!("expected {}, found {}", expected, found),
+        )
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Skip tokens up to the `close` matching an already consumed `open`
+    fn skip_group(&mut self, open: char, close: char) -> Result<(), CHeaderError> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(Token::Punct(c)) if c == open => depth += 1,
+                Some(Token::Punct(c)) if c == close => depth -= 1,
+                Some(_) => {}
+                None => return Err(error(self.line(), format!("missing `{}`", close))),
+            }
+        }
+        Ok(())
+    }
+
+    /// Skip `__attribute__((...))` and `__declspec(...)`, returns true if one of them
+    /// asked for a packed structure
+    fn attributes(&mut self) -> Result<bool, CHeaderError> {
+        let mut packed = false;
+        while let Some(Token::Ident(name)) = self.peek() {
+            if name != "__attribute__" && name != "__declspec" {
+                break;
+            }
+            self.pos += 1;
+            self.expect('(')?;
+            let start = self.pos;
+            self.skip_group('(', ')')?;
+            packed |= self.tokens[start..self.pos]
+                .iter()
+                .any(|(token, _)| matches!(token, Token::Ident(name) if name == "packed" || name == "__packed__"));
+        }
+        Ok(packed)
+    }
+
+    fn header(&mut self) -> Result<(), CHeaderError> {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Pack(pack) => {
+                    self.pos += 1;
+                    match pack {
+                        Pack::Set(size) => self.pack = size,
+                        Pack::Push(size) => {
+                            self.pack_stack.push(self.pack);
+                            if size.is_some() {
+                                self.pack = size;
+                            }
+                        }
+                        Pack::Pop => self.pack = self.pack_stack.pop().flatten(),
+                    }
+                }
+                Token::Punct(';') => self.pos += 1,
+                Token::Ident(name) if name == "typedef" => {
+                    self.pos += 1;
+                    self.typedef()?;
+                }
+                // `extern "C" {` only wraps declarations
+                Token::Ident(name)
+                    if name == "extern"
+                        && self.tokens.get(self.pos + 1).map(|(t, _)| t)
+                            == Some(&Token::Literal) =>
+                {
+                    self.pos += 2;
+                    self.eat('{');
+                }
+                Token::Punct('}') => self.pos += 1,
+                _ => self.declaration()?,
+            }
+        }
+        Ok(())
+    }
+
+    /// `typedef <type> <declarators>;`
+    fn typedef(&mut self) -> Result<(), CHeaderError> {
+        let mut ty = self.specifiers()?;
+        let mut anonymous = match &ty {
+            Some(FieldType::Struct(name)) if is_anonymous(name) => Some(name.clone()),
+            _ => None,
+        };
+
+        loop {
+            let mut declarator = self.declarator(ty.clone())?;
+            let name = declarator
+                .name
+                .ok_or_else(|| self.unexpected("a typedef name"))?;
+            // `typedef struct { ... } Name;` names the structure itself
+            if declarator.ty == ty
+                && let Some(anonymous) = anonymous.take()
+            {
+                self.rename(&anonymous, &name);
+                ty = Some(FieldType::named(name.clone()));
+                declarator.ty = ty.clone();
+            }
+            self.typedefs.insert(name, declarator.ty);
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.expect(';')
+    }
+
+    /// Give an anonymous structure the name of its typedef
+    fn rename(&mut self, from: &str, to: &str) {
+        if let Some(mut layout) = self.layouts.layouts.remove(from) {
+            layout.name = to.to_string();
+            self.layouts.insert(layout);
+        }
+        if let Some(align) = self.aligns.remove(from) {
+            self.aligns.insert(to.to_string(), align);
+        }
+    }
+
+    /// A declaration outside of a structure: a structure, enum, function or variable
+    fn declaration(&mut self) -> Result<(), CHeaderError> {
+        // Functions and variables of types that aren't declared don't matter either
+        let known = match self.peek() {
+            Some(Token::Ident(name)) => {
+                SPECIFIERS.contains(&name.as_str())
+                    || builtin(name, self.layouts.pointer_size).is_some()
+                    || self.typedefs.contains_key(name)
+            }
+            _ => false,
+        };
+        if known {
+            self.specifiers()?;
+        }
+        // Only the types matter, skip the declarators of functions and variables
+        loop {
+            match self.next() {
+                Some(Token::Punct(';')) | None => return Ok(()),
+                Some(Token::Punct('{')) => return self.skip_group('{', '}'),
+                Some(Token::Punct('(')) => self.skip_group('(', ')')?,
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// The type named by declaration specifiers like `const unsigned long`, `None` for
+    /// `void`
+    fn specifiers(&mut self) -> Result<Option<FieldType>, CHeaderError> {
+        let line = self.line();
+        let mut ty: Option<Option<FieldType>> = None;
+        let (mut signed, mut unsigned, mut short, mut long) = (false, false, false, 0);
+        let (mut char, mut int) = (false, false);
+
+        while let Some(Token::Ident(name)) = self.peek().cloned() {
+            match name.as_str() {
+                name if QUALIFIERS.contains(&name) => {}
+                "__attribute__" | "__declspec" => {
+                    self.attributes()?;
+                    continue;
+                }
+                "signed" => signed = true,
+                "unsigned" => unsigned = true,
+                "short" => short = true,
+                "long" => long += 1,
+                "char" => char = true,
+                "int" => int = true,
+                "struct" | "union" if ty.is_none() => {
+                    self.pos += 1;
+                    ty = Some(Some(self.record(name == "union")?));
+                    continue;
+                }
+                "enum" if ty.is_none() => {
+                    self.pos += 1;
+                    ty = Some(Some(self.enumeration()?));
+                    continue;
+                }
+                _ if ty.is_some() || signed || unsigned || short || long > 0 || char || int => {
+                    break;
+                }
+                _ => match builtin(&name, self.layouts.pointer_size) {
+                    Some(builtin) => ty = Some(builtin),
+                    None => match self.typedefs.get(&name) {
+                        Some(typedef) => ty = Some(typedef.clone()),
+                        None => return Err(error(line, format!("unknown type `{}`", name))),
+                    },
+                },
+            }
+            self.pos += 1;
+        }
+
+        let integer = |size: u32| match (size, unsigned) {
+            (1, false) => FieldType::I8,
+            (1, true) => FieldType::U8,
+            (2, false) => FieldType::I16,
+            (2, true) => FieldType::U16,
+            (4, false) => FieldType::I32,
+            (4, true) => FieldType::U32,
+            (_, false) => FieldType::I64,
+            (_, true) => FieldType::U64,
+        };
+        match ty {
+            Some(ty) => Ok(ty),
+            None if char => Ok(Some(integer(1))),
+            None if short => Ok(Some(integer(2))),
+            None if long >= 2 => Ok(Some(integer(8))),
+            None if signed || unsigned || long > 0 || int => Ok(Some(integer(4))),
+            None => Err(self.unexpected("a type")),
+        }
+    }
+
+    /// `struct [name] [{ members }]` after the keyword
+    fn record(&mut self, union: bool) -> Result<FieldType, CHeaderError> {
+        let mut packed = self.attributes()?;
+        let name = self.ident();
+        packed |= self.attributes()?;
+        if !self.eat('{') {
+            let name = name.ok_or_else(|| self.unexpected("a structure name or `{`"))?;
+            return Ok(FieldType::named(name));
+        }
+
+        let name = name.unwrap_or_else(|| {
+            self.anonymous += 1;
+            format!("<anonymous>#{}", self.anonymous)
+        });
+        let mut members = Vec::new();
+        while !self.eat('}') {
+            if self.peek().is_none() {
+                return Err(error(self.line(), format!("missing `}}` of `{}`", name)));
+            }
+            if let Some(Token::Pack(_)) = self.peek() {
+                return Err(error(self.line(), "`#pragma pack` inside a structure"));
+            }
+            if self.eat(';') {
+                continue;
+            }
+            self.members(&mut members)?;
+        }
+        packed |= self.attributes()?;
+
+        let pack = if packed { Some(1) } else { self.pack };
+        let layout = self.layout(name, union, members, pack)?;
+        Ok(FieldType::named(layout))
+    }
+
+    /// The members declared by one declaration inside a structure
+    fn members(&mut self, members: &mut Vec<Member>) -> Result<(), CHeaderError> {
+        let line = self.line();
+        let ty = self.specifiers()?;
+        // `struct { ... };` without a name embeds the members of an anonymous structure
+        if self.eat(';') {
+            if let Some(FieldType::Struct(name)) = &ty
+                && is_anonymous(name)
+            {
+                members.push(Member {
+                    name: None,
+                    ty: FieldType::named(name.clone()),
+                    bits: None,
+                    line,
+                });
+            }
+            return Ok(());
+        }
+
+        loop {
+            let line = self.line();
+            let declarator = self.declarator(ty.clone())?;
+            let ty = declarator
+                .ty
+                .ok_or_else(|| error(line, "member of type `void`"))?;
+            members.push(Member {
+                name: declarator.name,
+                ty,
+                bits: declarator.bits,
+                line,
+            });
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.attributes()?;
+        self.expect(';')
+    }
+
+    /// A declarator applied to `ty`: pointers, a name, arrays and a bitfield width
+    fn declarator(&mut self, ty: Option<FieldType>) -> Result<Declarator, CHeaderError> {
+        let mut ty = ty;
+        while self.eat('*') {
+            while let Some(Token::Ident(name)) = self.peek() {
+                if !matches!(
+                    name.as_str(),
+                    "const" | "volatile" | "restrict" | "__restrict"
+                ) {
+                    break;
+                }
+                self.pos += 1;
+            }
+            ty = Some(self.pointer(ty));
+        }
+        self.attributes()?;
+
+        let name = if self.eat('(') {
+            // A function pointer like `int (*callback)(int)` or `void (__cdecl *name)()`
+            while let Some(Token::Ident(name)) = self.peek() {
+                if !name.starts_with("__") {
+                    break;
+                }
+                self.pos += 1;
+            }
+            let mut pointers = 0;
+            while self.eat('*') {
+                pointers += 1;
+            }
+            let name = self.ident();
+            let mut lens = Vec::new();
+            while self.eat('[') {
+                lens.push(self.array_len()?);
+            }
+            self.expect(')')?;
+            self.expect('(')?;
+            self.skip_group('(', ')')?;
+            if pointers == 0 {
+                return Err(error(self.line(), "function declared inside a structure"));
+            }
+            ty = Some(self.address());
+            for _ in 1..pointers {
+                ty = Some(self.pointer(ty));
+            }
+            for len in lens.into_iter().rev() {
+                ty = ty.map(|ty| FieldType::array(ty, len));
+            }
+            name
+        } else {
+            self.ident()
+        };
+
+        // A function type like `typedef void Callback(int)`, only usable behind a pointer
+        if self.eat('(') {
+            self.skip_group('(', ')')?;
+            ty = None;
+        }
+
+        let mut lens = Vec::new();
+        while self.eat('[') {
+            lens.push(self.array_len()?);
+        }
+        // `char name[2][4]` is two arrays of four characters
+        for len in lens.into_iter().rev() {
+            ty = ty.map(|ty| FieldType::array(ty, len));
+        }
+
+        let bits = if self.eat(':') {
+            Some(self.constant()?)
+        } else {
+            None
+        };
+        Ok(Declarator { name, ty, bits })
+    }
+
+    /// A pointer to `target`, pointers to `void` and functions are plain addresses
+    fn pointer(&self, target: Option<FieldType>) -> FieldType {
+        match target {
+            Some(target) => FieldType::pointer(target),
+            None => self.address(),
+        }
+    }
+
+    /// An integer as large as a pointer
+    fn address(&self) -> FieldType {
+        match self.layouts.pointer_size {
+            4 => FieldType::U32,
+            _ => FieldType::U64,
+        }
+    }
+
+    /// The length of an array after its `[`, including the `]`
+    fn array_len(&mut self) -> Result<usize, CHeaderError> {
+        // Flexible array members have no length
+        if self.eat(']') {
+            return Ok(0);
+        }
+        let len = self.constant()?;
+        self.expect(']')?;
+        Ok(len as usize)
+    }
+
+    /// A number or an enum constant, possibly combined with `+ - * << |`
+    fn constant(&mut self) -> Result<u64, CHeaderError> {
+        let mut value = self.operand()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct(c @ ('+' | '-' | '*' | '|'))) => *c,
+                Some(Token::Punct('<'))
+                    if self.tokens.get(self.pos + 1).map(|(t, _)| t)
+                        == Some(&Token::Punct('<')) =>
+                {
+                    self.pos += 1;
+                    '<'
+                }
+                _ => return Ok(value),
+            };
+            self.pos += 1;
+            let rhs = self.operand()?;
+            value = match op {
+                '+' => value.wrapping_add(rhs),
+                '-' => value.wrapping_sub(rhs),
+                '*' => value.wrapping_mul(rhs),
+                '|' => value | rhs,
+                _ => value.checked_shl(rhs as u32).unwrap_or(0),
+            };
+        }
+    }
+
+    fn operand(&mut self) -> Result<u64, CHeaderError> {
+        let line = self.line();
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(Token::Punct('-')) => Ok(self.operand()?.wrapping_neg()),
+            Some(Token::Punct('(')) => {
+                let value = self.constant()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(Token::Ident(name)) => self
+                .constants
+                .get(&name)
+                .copied()
+                .ok_or_else(|| error(line, format!("unknown constant `{}`", name))),
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a constant"))
+            }
+        }
+    }
+
+    /// `enum [name] [: type] [{ constants }]` after the keyword, enums are `int` unless
+    /// they have another underlying type
+    fn enumeration(&mut self) -> Result<FieldType, CHeaderError> {
+        self.attributes()?;
+        self.ident();
+        let ty = if self.eat(':') {
+            self.specifiers()?
+                .ok_or_else(|| error(self.line(), "enum of type `void`"))?
+        } else {
+            FieldType::I32
+        };
+        if !self.eat('{') {
+            return Ok(ty);
+        }
+
+        let mut next = 0u64;
+        while !self.eat('}') {
+            let name = self
+                .ident()
+                .ok_or_else(|| self.unexpected("an enum constant"))?;
+            if self.eat('=') {
+                next = self.constant()?;
+            }
+            self.constants.insert(name, next);
+            next = next.wrapping_add(1);
+            if !self.eat(',') {
+                self.expect('}')?;
+                break;
+            }
+        }
+        Ok(ty)
+    }
+
+    /// Alignment of a value of `ty` in bytes
+    fn align(&self, ty: &FieldType) -> usize {
+        match ty {
+            FieldType::Pointer(_) => self.layouts.pointer_size,
+            FieldType::Array(element, _) => self.align(element),
+            FieldType::Struct(name) => self.aligns.get(name).copied().unwrap_or(1),
+            scalar => scalar.size(&self.layouts).unwrap_or(1),
+        }
+    }
+
+    /// Place the members of a structure, add its layout and return its name
+    fn layout(
+        &mut self,
+        name: String,
+        union: bool,
+        members: Vec<Member>,
+        pack: Option<usize>,
+    ) -> Result<String, CHeaderError> {
+        let mut fields = Vec::new();
+        let mut offset = 0usize;
+        let mut size = 0usize;
+        let mut align = 1;
+        // The integer the last bitfield is stored in: its offset, size and used bits
+        let mut unit: Option<(usize, usize, u64)> = None;
+
+        for member in members {
+            let member_size = member.ty.size(&self.layouts).ok_or_else(|| {
+                error(
+                    member.line,
+                    format!("`{}` is used before it is defined", member.ty),
+                )
+            })?;
+            let member_align = pack.map_or(self.align(&member.ty), |pack| {
+                self.align(&member.ty).min(pack.max(1))
+            });
+            align = align.max(member_align);
+
+            let member_offset = if union {
+                0
+            } else if let Some(bits) = member.bits {
+                match unit {
+                    Some((start, unit_size, used))
+                        if bits > 0
+                            && unit_size == member_size
+                            && used + bits <= unit_size as u64 * 8 =>
+                    {
+                        unit = Some((start, unit_size, used + bits));
+                        start
+                    }
+                    // A width of 0 only ends the current integer
+                    _ if bits == 0 => {
+                        unit = None;
+                        offset = offset.next_multiple_of(member_align);
+                        continue;
+                    }
+                    _ => {
+                        let start = offset.next_multiple_of(member_align);
+                        offset = start + member_size;
+                        unit = Some((start, member_size, bits));
+                        start
+                    }
+                }
+            } else {
+                unit = None;
+                let start = offset.next_multiple_of(member_align);
+                offset = start + member_size;
+                start
+            };
+            size = size.max(member_offset + member_size);
+
+            match member.name {
+                Some(name) => fields.push(LayoutField {
+                    name,
+                    offset: member_offset,
+                    ty: member.ty,
+                }),
+                // Members of anonymous structures belong to the structure around them
+                None if member.bits.is_none() => {
+                    if let FieldType::Struct(inner) = &member.ty
+                        && is_anonymous(inner)
+                        && let Some(inner) = self.layouts.layouts.remove(inner)
+                    {
+                        fields.extend(inner.fields.into_iter().map(|field| LayoutField {
+                            offset: field.offset + member_offset,
+                            ..field
+                        }));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        let size = size.next_multiple_of(align);
+        self.aligns.insert(name.clone(), align);
+        self.layouts.insert(Layout {
+            name: name.clone(),
+            size,
+            fields,
+        });
+        Ok(name)
+    }
+}
+
+/// Keywords that don't change the type they are used with
+const QUALIFIERS: &[&str] = &[
+    "const",
+    "volatile",
+    "restrict",
+    "static",
+    "extern",
+    "register",
+    "inline",
+    "__inline",
+    "__forceinline",
+    "__unaligned",
+    "__restrict",
+    "__cdecl",
+    "__stdcall",
+    "__fastcall",
+];
+
+/// Keywords a declaration can start with
+const SPECIFIERS: &[&str] = &[
+    "const",
+    "volatile",
+    "static",
+    "extern",
+    "register",
+    "inline",
+    "__inline",
+    "__forceinline",
+    "__attribute__",
+    "__declspec",
+    "signed",
+    "unsigned",
+    "short",
+    "long",
+    "char",
+    "int",
+    "struct",
+    "union",
+    "enum",
+];
+
+/// Names of anonymous structures, which are given the name of their typedef or embedded
+/// into the structure around them
+fn is_anonymous(name: &str) -> bool {
+    name.starts_with("<anonymous>")
+}
+
+/// Types known without a declaration, `None` inside for `void`
+fn builtin(name: &str, pointer_size: usize) -> Option<Option<FieldType>> {
+    let address = if pointer_size == 4 {
+        FieldType::U32
+    } else {
+        FieldType::U64
+    };
+    let signed_address = if pointer_size == 4 {
+        FieldType::I32
+    } else {
+        FieldType::I64
+    };
+    Some(Some(match name {
+        "void" | "VOID" => return Some(None),
+        "CHAR" | "int8_t" | "INT8" => FieldType::I8,
+        "_Bool" | "bool" | "BOOLEAN" => FieldType::Bool,
+        "float" | "FLOAT" => FieldType::F32,
+        "double" => FieldType::F64,
+        "uint8_t" | "BYTE" | "UCHAR" | "UINT8" => FieldType::U8,
+        "int16_t" | "SHORT" | "INT16" => FieldType::I16,
+        "uint16_t" | "WORD" | "USHORT" | "UINT16" | "WCHAR" | "wchar_t" | "char16_t" => {
+            FieldType::U16
+        }
+        "int32_t" | "INT" | "LONG" | "INT32" | "BOOL" | "NTSTATUS" | "HRESULT" => FieldType::I32,
+        "uint32_t" | "DWORD" | "UINT" | "ULONG" | "UINT32" | "char32_t" => FieldType::U32,
+        "int64_t" | "LONGLONG" | "LONG64" | "INT64" => FieldType::I64,
+        "uint64_t" | "QWORD" | "ULONGLONG" | "ULONG64" | "DWORD64" | "UINT64" => FieldType::U64,
+        "size_t" | "uintptr_t" | "SIZE_T" | "ULONG_PTR" | "UINT_PTR" | "DWORD_PTR" => address,
+        "ssize_t" | "intptr_t" | "ptrdiff_t" | "LONG_PTR" | "INT_PTR" => signed_address,
+        "PVOID" | "LPVOID" | "HANDLE" | "HMODULE" | "HINSTANCE" => address,
+        _ => return None,
+    }))
+}
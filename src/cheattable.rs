@@ -0,0 +1,358 @@
+//! Cheat Engine tables of the values reached by a traversal.
+
+use core::fmt::Write;
+
+use memflow::mem::MemoryView;
+
+use crate::dot::escape_xml;
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::retry::RetryView;
+use crate::{
+    DerefDebugPrint, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget,
+    TraversalState,
+};
+
+/// Version of the table format written, the one of Cheat Engine 7.5
+const TABLE_VERSION: u32 = 45;
+
+/// A value of a [`CheatTable`] and the pointer chain leading to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheatEntry {
+    /// Path of the field like `*.team.id` or `players[2].health`
+    pub path: String,
+    /// Type the field was read as
+    pub type_name: String,
+    /// Size of the field in bytes
+    pub size: usize,
+    /// Address the chain starts at as Cheat Engine writes it, e.g. `7FFE12340010` or
+    /// `game.exe+1A2B30`
+    pub address: String,
+    /// Offsets added after reading a pointer at the address and at every offset before, in
+    /// the order they are applied. The value is at the address itself if there are none
+    pub offsets: Vec<u64>,
+}
+
+/// Cheat Engine entries for every value of a traversal.
+///
+/// Every scalar field of the tree becomes an entry of the type it was read as, integers,
+/// floats and `bool` as the Cheat Engine types of the same size and anything else like
+/// arrays as an array of bytes. An entry follows the same pointers the traversal followed:
+/// its address is the pointer nearest to the root with a known address and its offsets lead
+/// from there to the field, so it still finds the value once the structures pointed to move.
+/// Elements of `follow_chain` and `list` fields are entered at their address.
+///
+/// Offsets within the structures are only known for trees collected with
+/// [`show_offsets`](PointerPrintConfig::show_offsets) or
+/// [`sort_by_offset`](PointerPrintConfig::sort_by_offset), which
+/// [`collect`](Self::collect) sets. [`to_ct`](Self::to_ct) writes the `.CT` file.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{CheatTable, PointerDerefDebugPrint, PointerPrintConfig};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     _pad: u32,
+///     team: Pointer64<Team>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Team {
+///     id: u64,
+/// }
+///
+/// fn export(mem: &mut impl MemoryView, player: Pointer64<Player>) -> std::io::Result<()> {
+///     let table = CheatTable::collect(&player, mem, &PointerPrintConfig::new());
+///     // *.health   4 Bytes  7FFE12340000
+///     // *.team.id  8 Bytes  [7FFE12340008]+0
+///     std::fs::write("player.CT", table.to_ct())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheatTable {
+    /// The values in the order they are printed
+    pub entries: Vec<CheatEntry>,
+}
+
+impl CheatTable {
+    /// Collect `value` with the options of `config` and list its values
+    pub fn collect<T, M>(value: &T, mem: &mut M, config: &PointerPrintConfig) -> Self
+    where
+        T: DerefDebugPrint + ?Sized,
+        M: MemoryView,
+    {
+        let config = config.clone().show_offsets(true);
+        let mut state = TraversalState::with_config(&config);
+        let node = value.pointer_collect_internal(&mut RetryView::new(mem, &config), 0, &mut state);
+        Self::from_node(&node)
+    }
+
+    /// List the values of a collected tree, the chains start at the nodes read from memory
+    pub fn from_node(root: &PointerDebugNode) -> Self {
+        let mut table = Self::default();
+        table.push_fields(root, root.address.map(Chain::at), "");
+        table
+    }
+
+    /// List the values of a collected tree whose root value is stored at `base`, a Cheat
+    /// Engine address like `game.exe+1A2B30`. Every chain starts there, so the table keeps
+    /// working after the process restarts
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     CheatTable, PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget,
+    /// };
+    ///
+    /// let field = |name: &str, type_name: &str, value| PointerDebugField {
+    ///     name: name.to_string(),
+    ///     type_name: type_name.to_string(),
+    ///     value,
+    /// };
+    ///
+    /// // A player holding a pointer to its team, collected with `show_offsets`
+    /// let mut team = PointerDebugNode::new("Team");
+    /// team.address = Some(0x2000);
+    /// team.fields.push(field("id", "i32", PointerDebugValue::Value("7".to_string())));
+    /// team.field_locations.insert("id".to_string(), (0x10, 4));
+    ///
+    /// let mut player = PointerDebugNode::new("Player");
+    /// player.fields.push(field("health", "u32", PointerDebugValue::Value("100".to_string())));
+    /// player.fields.push(field(
+    ///     "team",
+    ///     "Pointer64",
+    ///     PointerDebugValue::Pointer {
+    ///         address: 0x2000,
+    ///         target: PointerTarget::Node(Box::new(team)),
+    ///     },
+    /// ));
+    /// player.field_locations.insert("health".to_string(), (0x0, 4));
+    /// player.field_locations.insert("team".to_string(), (0x8, 8));
+    ///
+    /// let table = CheatTable::from_node_at(&player, "game.exe+1A2B30");
+    /// assert_eq!(table.entries[0].address, "game.exe+1A2B30");
+    /// assert!(table.entries[0].offsets.is_empty());
+    ///
+    /// let id = &table.entries[1];
+    /// assert_eq!(id.path, "team.id");
+    /// assert_eq!(id.address, "game.exe+1A2B30+8");
+    /// assert_eq!(id.offsets, [0x10]);
+    /// assert!(table.to_ct().contains("<ShowAsSigned>1</ShowAsSigned>"));
+    ///
+    /// // Without a base the player has no address, only the team read from 0x2000 is entered
+    /// let table = CheatTable::from_node(&player);
+    /// assert_eq!(table.entries.len(), 1);
+    /// assert_eq!(table.entries[0].address, "2010");
+    /// ```
+    pub fn from_node_at(root: &PointerDebugNode, base: &str) -> Self {
+        let mut table = Self::default();
+        let chain = Chain {
+            symbol: Some(base.to_string()),
+            address: 0,
+            offsets: Vec::new(),
+        };
+        table.push_fields(root, Some(chain), "");
+        table
+    }
+
+    /// Returns true if no value was found
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the entries as a Cheat Engine table
+    pub fn to_ct(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        let _ = writeln!(
+            out,
+            "<CheatTable CheatEngineTableVersion=\"{}\">",
+            TABLE_VERSION
+        );
+        out.push_str("  <CheatEntries>\n");
+        for (id, entry) in self.entries.iter().enumerate() {
+            let (variable_type, signed) = variable_type(&entry.type_name, entry.size);
+            out.push_str("    <CheatEntry>\n");
+            let _ = writeln!(out, "      <ID>{}</ID>", id);
+            let _ = writeln!(
+                out,
+                "      <Description>\"{}\"</Description>",
+                escape_xml(&entry.path)
+            );
+            if signed {
+                out.push_str("      <ShowAsSigned>1</ShowAsSigned>\n");
+            }
+            let _ = writeln!(out, "      <VariableType>{}</VariableType>", variable_type);
+            if variable_type == "Array of byte" {
+                let _ = writeln!(out, "      <ByteLength>{}</ByteLength>", entry.size);
+            }
+            let _ = writeln!(
+                out,
+                "      <Address>{}</Address>",
+                escape_xml(&entry.address)
+            );
+            if !entry.offsets.is_empty() {
+                // Cheat Engine lists the offset applied last first
+                out.push_str("      <Offsets>\n");
+                for offset in entry.offsets.iter().rev() {
+                    let _ = writeln!(out, "        <Offset>{:X}</Offset>", offset);
+                }
+                out.push_str("      </Offsets>\n");
+            }
+            out.push_str("    </CheatEntry>\n");
+        }
+        out.push_str("  </CheatEntries>\n</CheatTable>\n");
+        out
+    }
+
+    /// List the values of the fields of `node`, which starts at `chain` and is reached
+    /// through `path`
+    fn push_fields(&mut self, node: &PointerDebugNode, chain: Option<Chain>, path: &str) {
+        for field in &node.fields {
+            let path = if path.is_empty() || field.name.starts_with('[') {
+                format!("{}{}", path, field.name)
+            } else {
+                format!("{}.{}", path, field.name)
+            };
+            // The target of a pointer is its only field and starts where the pointer does
+            let location = match node.field_locations.get(&field.name) {
+                Some(location) => Some(*location),
+                None if field.name == "*" => Some((0, 0)),
+                None => None,
+            };
+            let field_chain = chain
+                .as_ref()
+                .zip(location)
+                .map(|(chain, (offset, _))| chain.add(offset as u64));
+            match &field.value {
+                PointerDebugValue::Value(_) => {
+                    if let (Some(chain), Some((_, size))) = (field_chain, location) {
+                        self.push_entry(path, &field.type_name, size, chain);
+                    }
+                }
+                PointerDebugValue::Nested(nested) => self.push_fields(nested, field_chain, &path),
+                PointerDebugValue::Pointer { address, target } => {
+                    let chain = match field_chain {
+                        Some(chain) => chain.deref(),
+                        None => Chain::at(*address),
+                    };
+                    self.push_target(target, *address, chain, path);
+                }
+            }
+        }
+    }
+
+    /// List the values of `target`, which starts at `address` found through `chain`
+    fn push_target(&mut self, target: &PointerTarget, address: u64, chain: Chain, path: String) {
+        match target {
+            PointerTarget::Node(node) if node.fields.is_empty() && node.value.is_some() => {
+                if let Some(size) = node.size.or_else(|| scalar_size(&node.type_name)) {
+                    self.push_entry(path, &node.type_name, size, chain);
+                }
+            }
+            PointerTarget::Node(node) => self.push_fields(node, Some(chain), &path),
+            PointerTarget::Array { items, .. } => {
+                for (i, item) in items.iter().enumerate() {
+                    let item_address = item_address(item, address);
+                    let chain = chain.add(item_address.wrapping_sub(address));
+                    self.push_target(item, item_address, chain, format!("{}[{}]", path, i));
+                }
+            }
+            PointerTarget::Chain { items, .. } => {
+                let addresses = chain_addresses(address, items);
+                for (i, (item, address)) in items.iter().zip(addresses).enumerate() {
+                    let path = format!("{}[{}]", path, i);
+                    self.push_target(item, address, Chain::at(address), path);
+                }
+            }
+            PointerTarget::List { items, .. } => {
+                for (i, (address, item)) in items.iter().enumerate() {
+                    let path = format!("{}[{}]", path, i);
+                    self.push_target(item, *address, Chain::at(*address), path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_entry(&mut self, path: String, type_name: &str, size: usize, chain: Chain) {
+        let address = match (chain.symbol, chain.address) {
+            (Some(symbol), 0) => symbol,
+            (Some(symbol), address) => format!("{}+{:X}", symbol, address),
+            (None, address) => format!("{:X}", address),
+        };
+        self.entries.push(CheatEntry {
+            path,
+            type_name: type_name.to_string(),
+            size,
+            address,
+            offsets: chain.offsets,
+        });
+    }
+}
+
+/// Where a value is: `symbol + address`, then a pointer is read and the next offset added
+/// for every offset
+#[derive(Debug, Clone)]
+struct Chain {
+    symbol: Option<String>,
+    address: u64,
+    offsets: Vec<u64>,
+}
+
+impl Chain {
+    fn at(address: u64) -> Self {
+        Self {
+            symbol: None,
+            address,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// The value `offset` bytes further
+    fn add(&self, offset: u64) -> Self {
+        let mut chain = self.clone();
+        match chain.offsets.last_mut() {
+            Some(last) => *last = last.wrapping_add(offset),
+            None => chain.address = chain.address.wrapping_add(offset),
+        }
+        chain
+    }
+
+    /// The value the pointer stored here points to
+    fn deref(mut self) -> Self {
+        self.offsets.push(0);
+        self
+    }
+}
+
+/// The Cheat Engine type of a value and whether it is shown as signed, values that aren't
+/// primitives are arrays of bytes
+fn variable_type(type_name: &str, size: usize) -> (&'static str, bool) {
+    if scalar_size(type_name) != Some(size) {
+        return ("Array of byte", false);
+    }
+    match (type_name, size) {
+        ("f32", _) => ("Float", false),
+        ("f64", _) => ("Double", false),
+        (_, 1) => ("Byte", type_name.starts_with('i')),
+        (_, 2) => ("2 Bytes", type_name.starts_with('i')),
+        (_, 4) => ("4 Bytes", type_name.starts_with('i')),
+        _ => ("8 Bytes", type_name.starts_with('i')),
+    }
+}
+
+/// Size of the primitive types
+fn scalar_size(type_name: &str) -> Option<usize> {
+    Some(match type_name {
+        "u8" | "i8" | "bool" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" => 4,
+        "u64" | "i64" | "f64" | "usize" | "isize" => 8,
+        _ => return None,
+    })
+}
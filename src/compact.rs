@@ -0,0 +1,182 @@
+//! Single-line rendering of a collected tree.
+
+use core::fmt::Write;
+
+use crate::node::{Shown, chain_addresses, is_hidden, item_address, quoted_text, shown_elements};
+use crate::prelude::*;
+use crate::{ErrorPolicy, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget};
+
+/// Render `node` as `Type {field: value, ptr -> Type {..}}` on a single line.
+///
+/// The line is cut at `max_line_length` characters, ending in `...` if it was cut.
+pub(crate) fn to_compact(node: &PointerDebugNode, config: &PointerPrintConfig) -> String {
+    let mut line = String::new();
+    write_node(&mut line, node, config);
+
+    if let Some(max) = config.max_line_length
+        && line.chars().count() > max
+    {
+        line = line.chars().take(max.saturating_sub(3)).collect();
+        line.push_str("...");
+    }
+    line
+}
+
+fn write_node(out: &mut String, node: &PointerDebugNode, config: &PointerPrintConfig) {
+    out.push_str(&node.type_name);
+    if node.misaligned {
+        out.push_str(" ⚠ misaligned");
+    }
+    if let Some(address) = node.address.filter(|_| config.show_addresses) {
+        write!(out, " @ {:#x}", address).unwrap();
+    }
+    if let Some(region) = node.address.and_then(|address| config.region(address)) {
+        write!(out, " [{}]", region).unwrap();
+    }
+    if let Some(value) = &node.value {
+        write!(out, " = {}", value).unwrap();
+        return;
+    }
+
+    out.push_str(" {");
+    let mut first = true;
+    for shown in node.shown_fields(config) {
+        let field = match shown {
+            Shown::Item(_, field) => field,
+            Shown::Gap(more) => {
+                if !first {
+                    out.push_str(", ");
+                }
+                first = false;
+                write!(out, "... ({} more)", more).unwrap();
+                continue;
+            }
+        };
+        if let PointerDebugValue::Pointer { target, .. } = &field.value
+            && is_hidden(target, config)
+        {
+            continue;
+        }
+        if !first {
+            out.push_str(", ");
+        }
+        first = false;
+
+        out.push_str(&field.name);
+        match &field.value {
+            PointerDebugValue::Value(value) => write!(out, ": {}", value).unwrap(),
+            PointerDebugValue::Pointer { address, target } => {
+                write_target(out, *address, target, config)
+            }
+            PointerDebugValue::Nested(node) => {
+                out.push_str(": ");
+                write_node(out, node, config);
+            }
+        }
+    }
+    out.push('}');
+}
+
+/// Write the target of a pointer following its label
+fn write_target(
+    out: &mut String,
+    address: u64,
+    target: &PointerTarget,
+    config: &PointerPrintConfig,
+) {
+    match target {
+        PointerTarget::Null => out.push_str(": NULL"),
+        PointerTarget::Invalid => write!(out, ": <invalid {:#x}>", address).unwrap(),
+        PointerTarget::Unmapped => write!(out, ": <unmapped {:#x}>", address).unwrap(),
+        PointerTarget::BudgetExhausted => {
+            write!(out, ": <budget exhausted {:#x}>", address).unwrap()
+        }
+        PointerTarget::Cancelled => write!(out, ": <cancelled {:#x}>", address).unwrap(),
+        PointerTarget::NotFollowed => write!(out, ": <not followed {:#x}>", address).unwrap(),
+        _ => {
+            out.push_str(" -> ");
+            write_item(out, address, target, config);
+        }
+    }
+}
+
+/// Write a pointer target or an element of an array, chain or list
+fn write_item(out: &mut String, address: u64, target: &PointerTarget, config: &PointerPrintConfig) {
+    match target {
+        PointerTarget::Node(node) => write_node(out, node, config),
+        PointerTarget::AlreadyVisited => write!(out, "<visited {:#x}>", address).unwrap(),
+        PointerTarget::MaxDepth => out.push_str("{..}"),
+        PointerTarget::Text(text) => out.push_str(&quoted_text(text, config)),
+        PointerTarget::Bytes(bytes) => write!(out, "b\"{}\"", bytes.escape_ascii()).unwrap(),
+        PointerTarget::Array { len, items } => {
+            let unread = len.saturating_sub(items.len());
+            let items = items
+                .iter()
+                .map(|item| (item_address(item, address), item))
+                .collect();
+            write_items(out, items, unread, None, config);
+        }
+        PointerTarget::Chain { items, more } => {
+            let rest = more.then_some("...");
+            let items = chain_addresses(address, items)
+                .into_iter()
+                .zip(items)
+                .collect();
+            write_items(out, items, 0, rest, config);
+        }
+        PointerTarget::List { items, more } => {
+            let rest = more.then_some("...");
+            let items = items
+                .iter()
+                .map(|(address, item)| (*address, item))
+                .collect();
+            write_items(out, items, 0, rest, config);
+        }
+        PointerTarget::ChainLink => write!(out, "<next {:#x}>", address).unwrap(),
+        PointerTarget::ReadError(_) if config.error_policy == ErrorPolicy::Placeholder => {
+            out.push_str("<unreadable>")
+        }
+        PointerTarget::ReadError(e) => write!(out, "<error: {}>", e).unwrap(),
+        PointerTarget::Null => out.push_str("NULL"),
+        PointerTarget::Invalid => write!(out, "<invalid {:#x}>", address).unwrap(),
+        PointerTarget::Unmapped => write!(out, "<unmapped {:#x}>", address).unwrap(),
+        PointerTarget::BudgetExhausted => write!(out, "<budget exhausted {:#x}>", address).unwrap(),
+        PointerTarget::Cancelled => write!(out, "<cancelled {:#x}>", address).unwrap(),
+        PointerTarget::NotFollowed => write!(out, "<not followed {:#x}>", address).unwrap(),
+    }
+}
+
+/// Write the elements of an array, chain or list as `[a, b, rest]`, followed by
+/// `... (N more)` for the `unread` elements after them
+fn write_items(
+    out: &mut String,
+    items: Vec<(u64, &PointerTarget)>,
+    unread: usize,
+    rest: Option<&str>,
+    config: &PointerPrintConfig,
+) {
+    out.push('[');
+    let mut first = true;
+    for shown in shown_elements(&items, unread, config) {
+        if let Shown::Item(_, (_, item)) = shown
+            && is_hidden(item, config)
+        {
+            continue;
+        }
+        if !first {
+            out.push_str(", ");
+        }
+        first = false;
+        match shown {
+            Shown::Item(_, (address, item)) => write_item(out, *address, item, config),
+            Shown::Gap(more) => write!(out, "... ({} more)", more).unwrap(),
+        }
+    }
+    if let Some(rest) = rest {
+        if !first {
+            out.push_str(", ");
+        }
+        out.push_str(rest);
+    }
+    out.push(']');
+}
@@ -0,0 +1,1344 @@
+//! Options for collecting and printing a structure.
+
+use alloc::sync::Arc;
+use core::fmt;
+use core::ops::Range;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use memflow::mem::{MemoryView, PhysicalMemory};
+
+use crate::prelude::*;
+use crate::{
+    ByteOrder, HashMap, HealthSummary, MappedMemory, MemoryRegions, Offsets, PHYSICAL_VIEW,
+    PrinterRegistry, ProgressCallback, ProgressEvent, Radix, Region, RetryPolicy, SharedView,
+    StreamCallback, StreamSender, StringDecoding, Symbols,
+};
+
+/// How read errors are handled when printing with a [`PointerPrintConfig`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorPolicy {
+    /// Print `Error reading: ...` in place of the target and carry on
+    #[default]
+    Show,
+    /// Leave pointers that failed to read out of the output
+    Hide,
+    /// Don't print anything if a read failed, the failures are returned instead
+    Fail,
+    /// Print `field: <unreadable>` in place of the target without the error
+    Placeholder,
+    /// Stop reading at the first failed read, what was read up to it is printed and the
+    /// failure is returned. The pointers left are printed as `field: <cancelled 0x...>`
+    Abort,
+}
+
+/// Layout of the printed output, see [`PointerPrintConfig::format`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputFormat {
+    /// One field per line, nested structures are indented
+    #[default]
+    Tree,
+    /// The whole structure on a single line, e.g. `Node {value: 1, next -> Node {..}}`
+    Compact,
+    /// RON style `Node(value: 1, next: #1 Node(..))`, nodes that are pointed to again are
+    /// labeled and the later pointers printed as `-> #1`
+    Ron,
+}
+
+/// Which elements of a long array, chain or list are printed, see
+/// [`PointerPrintConfig::max_printed_elements`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ElementTruncation {
+    /// The first elements followed by `... (N more)`
+    #[default]
+    Head,
+    /// The first and the last elements with `... (N more)` in between
+    HeadTail,
+}
+
+/// Decides whether a pointer target may be read, see [`PointerPrintConfig::valid_address`]
+#[derive(Clone)]
+pub struct AddressPredicate(Arc<dyn Fn(u64) -> bool + Send + Sync>);
+
+impl AddressPredicate {
+    /// Wrap a predicate returning true for addresses that may be read
+    pub fn new(predicate: impl Fn(u64) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(predicate))
+    }
+
+    /// Returns true if `address` may be read
+    pub fn check(&self, address: u64) -> bool {
+        (self.0)(address)
+    }
+}
+
+impl fmt::Debug for AddressPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AddressPredicate(..)")
+    }
+}
+
+/// Predicates are only equal to clones of themselves
+impl PartialEq for AddressPredicate {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for AddressPredicate {}
+
+/// Aborts a running traversal from another thread, see [`PointerPrintConfig::cancellation`].
+///
+/// Clones share the same flag.
+///
+/// ```rust
+/// use memflow_pointer_debug::{CancellationToken, PointerPrintConfig};
+///
+/// let token = CancellationToken::new();
+/// let config = PointerPrintConfig::new().cancellation(token.clone());
+///
+/// // e.g. from the UI thread
+/// token.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use an existing flag, the traversal is cancelled once it is set
+    pub fn from_flag(flag: Arc<AtomicBool>) -> Self {
+        Self(flag)
+    }
+
+    /// Cancel every traversal using this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if [`cancel`](Self::cancel) was called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Tokens are only equal to clones of themselves
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// Where printed dumps are logged, see [`PointerPrintConfig::log`]
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogOutput {
+    /// Target of the log records
+    pub target: String,
+    /// Level of the log records
+    pub level: log::Level,
+}
+
+/// Returns true if `address` is a canonical x86-64 address, i.e. bits 47 to 63 are all equal
+pub fn is_canonical_x64(address: u64) -> bool {
+    let upper = address >> 47;
+    upper == 0 || upper == 0x1ffff
+}
+
+/// Options for [`PointerPrint::pointer_print_with_config`](crate::PointerPrint::pointer_print_with_config).
+///
+/// The defaults produce the same output as
+/// [`PointerPrint::pointer_print`](crate::PointerPrint::pointer_print), every option can be
+/// changed with the builder methods of the same name.
+///
+/// ```rust
+/// use memflow_pointer_debug::{ErrorPolicy, PointerPrintConfig};
+///
+/// let config = PointerPrintConfig::new()
+///     .max_depth(8)
+///     .indent("    ")
+///     .max_elements(16)
+///     .show_type_names(false)
+///     .show_addresses(true)
+///     .error_policy(ErrorPolicy::Hide);
+/// ```
+///
+/// Pointers into memory that is guaranteed to be garbage can be excluded from reading,
+/// they are printed as `field: <invalid 0x3>`:
+///
+/// ```rust
+/// use memflow_pointer_debug::{PointerPrintConfig, is_canonical_x64};
+///
+/// let config = PointerPrintConfig::new()
+///     .valid_range(0x10000..0x8000_0000_0000)
+///     .valid_address(is_canonical_x64);
+/// ```
+///
+/// Corrupted structures can link to thousands of pointers before the depth limit is
+/// reached, a budget on the total amount read stops the traversal early:
+///
+/// ```rust
+/// use memflow_pointer_debug::PointerPrintConfig;
+///
+/// let config = PointerPrintConfig::new()
+///     .max_nodes(1000)
+///     .max_bytes(1024 * 1024);
+/// ```
+///
+/// When the process is at hand its memory map can be used instead, pointers outside of it
+/// are printed as `field: <unmapped 0x7ffe12340000>`:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{MappedMemory, PointerPrintConfig};
+///
+/// fn config(process: &mut impl Process) -> PointerPrintConfig {
+///     PointerPrintConfig::new().mapped_memory(MappedMemory::from_process(process))
+/// }
+/// ```
+///
+/// For logging, the compact format puts the whole structure on one line:
+///
+/// ```rust
+/// use memflow_pointer_debug::{
+///     OutputFormat, PointerDebugField, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+///     PointerTarget,
+/// };
+///
+/// let mut node = PointerDebugNode::new("Node");
+/// node.fields.push(PointerDebugField {
+///     name: "value".to_string(),
+///     type_name: "u32".to_string(),
+///     value: PointerDebugValue::Value("1".to_string()),
+/// });
+/// node.fields.push(PointerDebugField {
+///     name: "next".to_string(),
+///     type_name: "Pointer64".to_string(),
+///     value: PointerDebugValue::Pointer {
+///         address: 0x1000,
+///         target: PointerTarget::MaxDepth,
+///     },
+/// });
+///
+/// let config = PointerPrintConfig::new().format(OutputFormat::Compact);
+/// assert_eq!(node.display(&config).to_string(), "Node {value: 1, next -> {..}}\n");
+///
+/// let config = config.max_line_length(16);
+/// assert_eq!(node.display(&config).to_string(), "Node {value: ...\n");
+/// ```
+///
+/// The RON style format labels the nodes of a cycle instead of printing addresses:
+///
+/// ```rust
+/// use memflow_pointer_debug::{
+///     OutputFormat, PointerDebugField, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+///     PointerTarget,
+/// };
+///
+/// let pointer = |address, target| PointerDebugField {
+///     name: "next".to_string(),
+///     type_name: "Pointer64".to_string(),
+///     value: PointerDebugValue::Pointer { address, target },
+/// };
+///
+/// let mut second = PointerDebugNode::new("Node");
+/// second.address = Some(0x2000);
+/// second.fields.push(pointer(0x1000, PointerTarget::AlreadyVisited));
+/// let mut first = PointerDebugNode::new("Node");
+/// first.address = Some(0x1000);
+/// first.fields.push(pointer(0x2000, PointerTarget::Node(Box::new(second))));
+/// let mut root = PointerDebugNode::new("List");
+/// root.fields.push(pointer(0x1000, PointerTarget::Node(Box::new(first))));
+///
+/// let config = PointerPrintConfig::new().format(OutputFormat::Ron);
+/// assert_eq!(
+///     root.display(&config).to_string(),
+///     "List(\n  next: #1 Node(\n    next: Node(\n      next: -> #1,\n    ),\n  ),\n)\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerPrintConfig {
+    /// String repeated once per nesting level
+    pub indent: String,
+    /// Maximum recursion depth
+    pub max_depth: usize,
+    /// Maximum number of elements read for `array`, `follow_chain` and `list` fields and
+    /// pointers followed of embedded arrays of pointers, the limits given in the attributes
+    /// still apply
+    pub max_elements: Option<usize>,
+    /// Maximum number of elements printed per array, chain or list, the elements are
+    /// still read
+    pub max_printed_elements: Option<usize>,
+    /// Which elements are printed when there are more than
+    /// [`max_printed_elements`](Self::max_printed_elements)
+    pub element_truncation: ElementTruncation,
+    /// Maximum number of bytes read per `cstr` and `wstr` string, the limits given in the
+    /// attributes still apply
+    pub max_string_bytes: Option<usize>,
+    /// Appended to strings that were cut at their limit before their end
+    pub string_truncation_marker: String,
+    /// How the bytes of strings are turned into text
+    pub string_decoding: StringDecoding,
+    /// Number of digits printed after the decimal point of floats, as many as needed to
+    /// read them back if `None`
+    pub float_precision: Option<usize>,
+    /// Floats whose decimal exponent is at least this far from zero are printed in
+    /// scientific notation, e.g. `1.5e9`
+    pub scientific_threshold: Option<u32>,
+    /// Separates the digits of decimal integers with more than four digits into groups of
+    /// three
+    pub thousands_separator: Option<char>,
+    /// Radix integer fields are printed in
+    pub radix: Radix,
+    /// Print the declared type of value fields, e.g. `id: u64 = 5` instead of `id = 5`
+    pub show_type_names: bool,
+    /// How read errors are handled
+    pub error_policy: ErrorPolicy,
+    /// Print the target address of every pointer, e.g. `next-> Node @ 0x7ffe12340000`
+    pub show_addresses: bool,
+    /// Prefix every field with its byte offset and size inside the structure, e.g.
+    /// `+0x18 (8) health: f32 = 100.0`
+    pub show_offsets: bool,
+    /// Print the bytes every structure read through a pointer was read from as a hex dump
+    /// after its fields
+    pub show_raw_bytes: bool,
+    /// Print the fields of structures in ascending offset order instead of declaration order
+    pub sort_by_offset: bool,
+    /// Leave null pointers out of the output instead of printing `field: NULL`
+    pub hide_null: bool,
+    /// Number of times a target that was read already is read and printed again when
+    /// another pointer leads to it as the same type
+    pub max_revisits: usize,
+    /// Only addresses inside one of these ranges are read, all are read if empty
+    pub valid_ranges: Vec<Range<u64>>,
+    /// Only addresses accepted by the predicate are read
+    pub valid_address: Option<AddressPredicate>,
+    /// Only addresses inside a mapped region of the process are read
+    pub mapped_memory: Option<Arc<MappedMemory>>,
+    /// Field offsets replacing the compiled ones of the structures read
+    pub offsets: Option<Arc<Offsets>>,
+    /// Modules and exports vtable pointers are printed as
+    pub symbols: Option<Arc<Symbols>>,
+    /// Printers replacing how the values of their types are printed
+    pub printers: Option<Arc<PrinterRegistry>>,
+    /// Regions the targets of followed pointers are annotated with
+    pub regions: Option<Arc<MemoryRegions>>,
+    /// Memory views of other address spaces by name, read for the fields marked
+    /// `#[pointer_debug(view = "...")]`
+    pub views: HashMap<String, SharedView>,
+    /// Maximum number of nodes read in total, the traversal stops once it is reached
+    pub max_nodes: Option<usize>,
+    /// Maximum number of bytes read in total, the traversal stops once it is reached
+    pub max_bytes: Option<usize>,
+    /// Maximum number of lines printed, the traversal stops once about as many were collected
+    pub max_output_lines: Option<usize>,
+    /// Maximum number of bytes printed
+    pub max_output_bytes: Option<usize>,
+    /// Checked before every read, nothing is read after the token was cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// Invoked for every read issued
+    pub progress: Option<ProgressCallback>,
+    /// Receives the structures and values as they are collected
+    pub stream: Option<StreamCallback>,
+    /// Read the pointer targets of a structure with one batched read instead of one read
+    /// per pointer
+    pub batch_reads: bool,
+    /// How often failed reads are tried again
+    pub retry: Option<RetryPolicy>,
+    /// Byte order of the values in the target memory
+    pub byte_order: ByteOrder,
+    /// Layout of the output
+    pub format: OutputFormat,
+    /// Maximum length of a line in the compact format, longer lines are cut
+    pub max_line_length: Option<usize>,
+    /// Print the fields of the root first and the structures pointed to after it, level by
+    /// level, instead of nesting them below their pointers
+    pub breadth_first: bool,
+    /// Print the [`TraversalStats`](crate::TraversalStats) after the dump
+    pub show_stats: bool,
+    /// Print where the pointers led after the dump
+    pub health_summary: Option<HealthSummary>,
+    /// Names of the padding fields of derived structures, `*` matches any run of characters.
+    /// Fields containing `_pad` are padding if this is empty
+    pub padding_patterns: Vec<String>,
+    /// Globs of the field paths to collect, everything is collected if empty
+    pub include_paths: Vec<String>,
+    /// Globs of the field paths left out with everything below them
+    pub exclude_paths: Vec<String>,
+    /// Colorize the output with ANSI escape codes
+    #[cfg(feature = "color")]
+    pub color: bool,
+    /// Log printed output instead of writing it to stdout
+    #[cfg(feature = "log")]
+    pub log: Option<LogOutput>,
+}
+
+impl Default for PointerPrintConfig {
+    fn default() -> Self {
+        Self {
+            indent: "  ".to_string(),
+            max_depth: 5,
+            max_elements: None,
+            max_printed_elements: None,
+            element_truncation: ElementTruncation::Head,
+            max_string_bytes: None,
+            string_truncation_marker: "...".to_string(),
+            string_decoding: StringDecoding::Lossy,
+            float_precision: None,
+            scientific_threshold: None,
+            thousands_separator: None,
+            radix: Radix::Dec,
+            show_type_names: true,
+            error_policy: ErrorPolicy::Show,
+            show_addresses: false,
+            show_offsets: false,
+            show_raw_bytes: false,
+            sort_by_offset: false,
+            hide_null: false,
+            max_revisits: 0,
+            valid_ranges: Vec::new(),
+            valid_address: None,
+            mapped_memory: None,
+            offsets: None,
+            symbols: None,
+            printers: None,
+            regions: None,
+            views: HashMap::new(),
+            max_nodes: None,
+            max_bytes: None,
+            max_output_lines: None,
+            max_output_bytes: None,
+            cancellation: None,
+            progress: None,
+            stream: None,
+            batch_reads: true,
+            retry: None,
+            byte_order: ByteOrder::native(),
+            format: OutputFormat::Tree,
+            max_line_length: None,
+            breadth_first: false,
+            show_stats: false,
+            health_summary: None,
+            padding_patterns: Vec::new(),
+            include_paths: Vec::new(),
+            exclude_paths: Vec::new(),
+            #[cfg(feature = "color")]
+            color: false,
+            #[cfg(feature = "log")]
+            log: None,
+        }
+    }
+}
+
+impl PointerPrintConfig {
+    /// Create the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the string repeated once per nesting level
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    /// Set the maximum recursion depth
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Follow pointers at any depth until no new targets are found or `max_nodes` nodes
+    /// were read, instead of guessing a [`max_depth`](Self::max_depth) that neither cuts the
+    /// dump short nor explodes it.
+    ///
+    /// Every target is still read only once, so the traversal ends by itself once everything
+    /// reachable was read. Otherwise the remaining pointers are printed as
+    /// `field: <budget exhausted 0x...>`. The depth reached is part of the
+    /// [`TraversalStats`](crate::TraversalStats).
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Node {
+    ///     value: u64,
+    ///     next: Pointer64<Node>,
+    /// }
+    ///
+    /// fn example(mem: &mut impl MemoryView, head: &Node) {
+    ///     let config = PointerPrintConfig::new().auto_depth(1000);
+    ///     let (tree, stats) = head.pointer_collect_with_stats(mem, &config);
+    ///     println!("{}reached depth {}", tree, stats.depth);
+    /// }
+    /// ```
+    pub fn auto_depth(mut self, max_nodes: usize) -> Self {
+        self.max_depth = usize::MAX;
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Limit the number of elements read for `array`, `follow_chain` and `list` fields and
+    /// the number of pointers followed of embedded arrays like `[Pointer64<Part>; 16]`
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    /// Print at most `max` elements per array, chain or list, embedded arrays like
+    /// `[Pointer64<Handle>; 4096]` included. The elements left out are replaced by
+    /// `... (N more)`.
+    ///
+    /// This only shortens the output, use [`max_elements`](Self::max_elements) to read
+    /// fewer elements in the first place.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     ElementTruncation, PointerDebugField, PointerDebugNode, PointerDebugValue,
+    ///     PointerPrintConfig,
+    /// };
+    ///
+    /// let mut table = PointerDebugNode::new("[u32; 6]");
+    /// for i in 0..6 {
+    ///     table.fields.push(PointerDebugField {
+    ///         name: format!("[{}]", i),
+    ///         type_name: "u32".to_string(),
+    ///         value: PointerDebugValue::Value(i.to_string()),
+    ///     });
+    /// }
+    ///
+    /// let config = PointerPrintConfig::new()
+    ///     .show_type_names(false)
+    ///     .max_printed_elements(3)
+    ///     .element_truncation(ElementTruncation::HeadTail);
+    /// assert_eq!(
+    ///     table.display(&config).to_string(),
+    ///     "[u32; 6] {\n  [0] = 0\n  [1] = 1\n  ... (3 more)\n  [5] = 5\n}\n"
+    /// );
+    /// ```
+    pub fn max_printed_elements(mut self, max: usize) -> Self {
+        self.max_printed_elements = Some(max);
+        self
+    }
+
+    /// Set which elements are printed when there are more than
+    /// [`max_printed_elements`](Self::max_printed_elements)
+    pub fn element_truncation(mut self, element_truncation: ElementTruncation) -> Self {
+        self.element_truncation = element_truncation;
+        self
+    }
+
+    /// Set the maximum number of bytes read per string.
+    ///
+    /// A string without a NUL terminator is read up to this limit instead of up to the
+    /// `max_len` of its attribute, strings cut at their limit end with the
+    /// [`string_truncation_marker`](Self::string_truncation_marker).
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{PointerPrintConfig, StringDecoding};
+    ///
+    /// let config = PointerPrintConfig::new()
+    ///     .max_string_bytes(64)
+    ///     .string_truncation_marker(" [cut]")
+    ///     .string_decoding(StringDecoding::Escape);
+    /// assert_eq!(config.string_limit(256), 64);
+    /// assert_eq!(config.string_limit(16), 16);
+    /// ```
+    pub fn max_string_bytes(mut self, max: usize) -> Self {
+        self.max_string_bytes = Some(max);
+        self
+    }
+
+    /// Set the text appended to strings that were cut at their limit
+    pub fn string_truncation_marker(mut self, marker: impl Into<String>) -> Self {
+        self.string_truncation_marker = marker.into();
+        self
+    }
+
+    /// Set how the bytes of strings are turned into text
+    pub fn string_decoding(mut self, string_decoding: StringDecoding) -> Self {
+        self.string_decoding = string_decoding;
+        self
+    }
+
+    /// Print floats with `precision` digits after the decimal point
+    pub fn float_precision(mut self, precision: usize) -> Self {
+        self.float_precision = Some(precision);
+        self
+    }
+
+    /// Print floats in scientific notation if their decimal exponent is `threshold` or
+    /// more away from zero, e.g. `scientific_threshold(6)` prints `2500000000.0` as `2.5e9`
+    /// and `12345.5` unchanged
+    pub fn scientific_threshold(mut self, threshold: u32) -> Self {
+        self.scientific_threshold = Some(threshold);
+        self
+    }
+
+    /// Separate the digits of large decimal integers with `separator`, e.g. `1,234,567`
+    pub fn thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// Print integer fields in `radix`, [`Radix::Dec`] by default.
+    ///
+    /// These options apply to the integer and float fields of derived structures and the
+    /// targets of pointers to numbers. A `#[pointer_debug(format = "...")]` attribute takes
+    /// precedence, and so do `flags`, `enum_repr`, `timestamp` and `with`.
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig, Radix};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Entity {
+    ///     flags: u32,
+    ///     #[pointer_debug(format = "oct")]
+    ///     mode: u32,
+    /// }
+    ///
+    /// // Prints `flags: u32 = 0x40` and `mode: u32 = 0o00000000644`
+    /// fn example(mem: &mut impl MemoryView, entity: &Entity) {
+    ///     let config = PointerPrintConfig::new().radix(Radix::Hex);
+    ///     entity.pointer_print_with_config(mem, &config).unwrap();
+    /// }
+    /// ```
+    pub fn radix(mut self, radix: Radix) -> Self {
+        self.radix = radix;
+        self
+    }
+
+    /// The number of bytes read of a string whose attribute allows `max_len` bytes
+    pub fn string_limit(&self, max_len: usize) -> usize {
+        self.max_string_bytes
+            .map_or(max_len, |max| max.min(max_len))
+    }
+
+    /// Print or hide the declared type of value fields
+    pub fn show_type_names(mut self, show_type_names: bool) -> Self {
+        self.show_type_names = show_type_names;
+        self
+    }
+
+    /// Set how read errors are handled
+    pub fn error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Print or hide the target address of every pointer
+    pub fn show_addresses(mut self, show_addresses: bool) -> Self {
+        self.show_addresses = show_addresses;
+        self
+    }
+
+    /// Print or hide the offset and size of every field.
+    ///
+    /// The locations are recorded in [`PointerDebugNode::field_locations`](crate::PointerDebugNode::field_locations)
+    /// while collecting, from `offset_of!` for derived structures, the
+    /// [`offsets`](Self::offsets) replacing them, or the layout of runtime layouts.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+    /// };
+    ///
+    /// let mut player = PointerDebugNode::new("Player");
+    /// player.fields.push(PointerDebugField {
+    ///     name: "health".to_string(),
+    ///     type_name: "f32".to_string(),
+    ///     value: PointerDebugValue::Value("100.0".to_string()),
+    /// });
+    /// player.field_locations.insert("health".to_string(), (0x18, 4));
+    ///
+    /// let config = PointerPrintConfig::new().show_offsets(true);
+    /// assert_eq!(
+    ///     player.display(&config).to_string(),
+    ///     "Player {\n  +0x18 (4) health: f32 = 100.0\n}\n"
+    /// );
+    /// ```
+    pub fn show_offsets(mut self, show_offsets: bool) -> Self {
+        self.show_offsets = show_offsets;
+        self
+    }
+
+    /// Print or hide a hex dump of the bytes every structure was read from after its fields,
+    /// so fields at the wrong offset stand out next to the memory they were read from.
+    ///
+    /// The bytes are recorded in [`PointerDebugNode::raw_bytes`](crate::PointerDebugNode::raw_bytes)
+    /// while collecting, for the targets of pointers, the elements of `array`, `buckets`,
+    /// `follow_chain` and `list` fields and the structures of runtime layouts. A root value
+    /// passed in by the caller was not read by the traversal and has none, the root read by
+    /// [`read_and_print`](crate::read_and_print) has them.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+    /// };
+    ///
+    /// let mut player = PointerDebugNode::new("Player");
+    /// player.fields.push(PointerDebugField {
+    ///     name: "health".to_string(),
+    ///     type_name: "u32".to_string(),
+    ///     value: PointerDebugValue::Value("100".to_string()),
+    /// });
+    /// player.raw_bytes = Some(vec![0x64, 0, 0, 0]);
+    ///
+    /// let config = PointerPrintConfig::new().show_raw_bytes(true);
+    /// assert_eq!(
+    ///     player.display(&config).to_string(),
+    ///     "Player {\n  health: u32 = 100\n  raw (4 bytes):\n    \
+    ///      0000  64 00 00 00                                       |d...|\n}\n"
+    /// );
+    /// ```
+    pub fn show_raw_bytes(mut self, show_raw_bytes: bool) -> Self {
+        self.show_raw_bytes = show_raw_bytes;
+        self
+    }
+
+    /// Print the fields of structures in ascending order of their offset instead of their
+    /// declaration order, to compare a dump with a hex view or ReClass.
+    ///
+    /// The order differs once [`offsets`](Self::offsets) move fields or a runtime
+    /// [`Layout`](crate::Layout) declares them out of order. Fields without an offset, like
+    /// the `_tag` field of a tagged pointer, stay behind the field before them.
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{Offsets, PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Player {
+    ///     health: u32,
+    ///     armor: u32,
+    /// }
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Local {
+    ///     player: Pointer64<Player>,
+    /// }
+    ///
+    /// fn example(mem: &mut impl MemoryView, local: &Local) {
+    ///     // An update of the target swapped the fields, `armor` is printed first
+    ///     let offsets = Offsets::new()
+    ///         .offset("Player", "health", 0x4)
+    ///         .offset("Player", "armor", 0x0);
+    ///     let config = PointerPrintConfig::new()
+    ///         .offsets(offsets)
+    ///         .sort_by_offset(true);
+    ///     local.pointer_print_with_config(mem, &config).unwrap();
+    /// }
+    /// ```
+    pub fn sort_by_offset(mut self, sort_by_offset: bool) -> Self {
+        self.sort_by_offset = sort_by_offset;
+        self
+    }
+
+    /// Leave null pointers out of the output
+    pub fn hide_null(mut self, hide_null: bool) -> Self {
+        self.hide_null = hide_null;
+        self
+    }
+
+    /// Print targets shared by several pointers up to `max_revisits` more times before they
+    /// are printed as already visited.
+    ///
+    /// Targets are told apart by their address and the type they are read as, two pointers
+    /// interpreting the same address as different types always print both. Cycles are
+    /// still cut, after going around them `max_revisits` more times.
+    pub fn max_revisits(mut self, max_revisits: usize) -> Self {
+        self.max_revisits = max_revisits;
+        self
+    }
+
+    /// Add a range of addresses that may be read, addresses outside of every range given
+    /// are not read
+    pub fn valid_range(mut self, range: Range<u64>) -> Self {
+        self.valid_ranges.push(range);
+        self
+    }
+
+    /// Only read addresses accepted by `predicate`
+    pub fn valid_address(
+        mut self,
+        predicate: impl Fn(u64) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.valid_address = Some(AddressPredicate::new(predicate));
+        self
+    }
+
+    /// Only read addresses inside a region of `mapped_memory`, pointers to anything else are
+    /// printed as `field: <unmapped 0x...>`
+    pub fn mapped_memory(mut self, mapped_memory: MappedMemory) -> Self {
+        self.mapped_memory = Some(Arc::new(mapped_memory));
+        self
+    }
+
+    /// Print the vtable pointers inside a module of `symbols` as `module.dll+0x1234` with the
+    /// nearest export before them instead of following them.
+    ///
+    /// Pointer and address fields named like a vtable pointer, e.g. `vtable`, `__vfptr` or
+    /// `_vptr`, or marked `#[pointer_debug(vtable)]` are printed this way. Fields marked
+    /// `#[pointer_debug(function)]` and address fields pointing into executable memory are
+    /// printed as a function like `kernel32.dll!CreateFileW+0x0`:
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig, Symbols};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Entity {
+    ///     vtable: u64,
+    ///     health: u32,
+    ///     team: u32,
+    /// }
+    ///
+    /// fn example(process: &mut (impl Process + MemoryView), entity: &Entity) {
+    ///     let config = PointerPrintConfig::new().symbols(Symbols::from_process(process));
+    ///     // vtable: u64 = client.dll+0x1a2b30 (CreateInterface+0x40)
+    ///     entity.pointer_print_with_config(process, &config).unwrap();
+    /// }
+    /// ```
+    pub fn symbols(mut self, symbols: Symbols) -> Self {
+        self.symbols = Some(Arc::new(symbols));
+        self
+    }
+
+    /// Print the values of the types registered in `printers` with their printer, see
+    /// [`PrinterRegistry`]
+    pub fn printers(mut self, printers: PrinterRegistry) -> Self {
+        self.printers = Some(Arc::new(printers));
+        self
+    }
+
+    /// Annotate the target of every followed pointer with the region of `regions` it lies in,
+    /// e.g. `next-> Node [heap]` or `vtable-> VTable [client.dll+0x1a2b30]`
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{MemoryRegions, PointerPrintConfig};
+    ///
+    /// fn config(process: &mut impl Process) -> PointerPrintConfig {
+    ///     PointerPrintConfig::new().regions(MemoryRegions::from_process(process))
+    /// }
+    /// ```
+    pub fn regions(mut self, regions: MemoryRegions) -> Self {
+        self.regions = Some(Arc::new(regions));
+        self
+    }
+
+    /// Read the fields listed in `offsets` from their offset there instead of the compiled
+    /// one, see [`Offsets`]
+    pub fn offsets(mut self, offsets: Offsets) -> Self {
+        self.offsets = Some(Arc::new(offsets));
+        self
+    }
+
+    /// Read the fields marked `#[pointer_debug(view = "name")]` through `view`, e.g. the
+    /// kernel address space for pointers of a user mode structure into kernel memory.
+    ///
+    /// Everything reachable through such a field is read from `view` as well. Fields whose
+    /// view isn't registered are not followed and printed as `<not followed 0x...>`.
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct KernelObject {
+    ///     flags: u32,
+    ///     ref_count: u32,
+    /// }
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Handle {
+    ///     access: u32,
+    ///     index: u32,
+    ///     #[pointer_debug(view = "kernel")]
+    ///     object: Pointer64<KernelObject>,
+    /// }
+    ///
+    /// fn example(process: &mut impl MemoryView, kernel: impl MemoryView + 'static, handle: &Handle) {
+    ///     let config = PointerPrintConfig::new().view("kernel", kernel);
+    ///     handle.pointer_print_with_config(process, &config).unwrap();
+    /// }
+    /// ```
+    pub fn view(mut self, name: impl Into<String>, view: impl MemoryView + 'static) -> Self {
+        self.views.insert(name.into(), SharedView::new(view));
+        self
+    }
+
+    /// Read the fields marked `#[pointer_debug(phys)]` from the physical memory `phys`, e.g. the
+    /// connector, registered as the view [`PHYSICAL_VIEW`].
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct PageTable {
+    ///     entries: [u64; 512],
+    /// }
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct DmaDescriptor {
+    ///     length: u32,
+    ///     flags: u32,
+    ///     #[pointer_debug(phys, cstr(max_len = 32))]
+    ///     buffer: Pointer64<u8>,
+    ///     #[pointer_debug(phys, ptr_mask = 0x000f_ffff_ffff_f000)]
+    ///     table: Pointer64<PageTable>,
+    /// }
+    ///
+    /// fn example(
+    ///     mem: &mut impl MemoryView,
+    ///     phys: impl PhysicalMemory + 'static,
+    ///     descriptor: &DmaDescriptor,
+    /// ) {
+    ///     let config = PointerPrintConfig::new().physical_memory(phys);
+    ///     descriptor.pointer_print_with_config(mem, &config).unwrap();
+    /// }
+    /// ```
+    pub fn physical_memory(self, phys: impl PhysicalMemory + 'static) -> Self {
+        self.view(PHYSICAL_VIEW, phys.into_phys_view())
+    }
+
+    /// Stop the traversal after reading `max_nodes` nodes, the remaining pointers are printed
+    /// as `field: <budget exhausted 0x...>`
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Stop the traversal before reading more than `max_bytes` bytes in total
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Print at most `max_lines` lines, e.g. to keep a runaway dump within the scrollback of
+    /// a terminal.
+    ///
+    /// The traversal stops reading once it collected about as many lines, the remaining
+    /// pointers are [budget exhausted](Self::max_nodes). The output is cut after `max_lines`
+    /// lines and ends with `... output truncated after N lines (budget exhausted)` and the
+    /// number of lines and bytes left out.
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Node {
+    ///     value: u64,
+    ///     next: Pointer64<Node>,
+    /// }
+    ///
+    /// fn example(mem: &mut impl MemoryView, head: &Node) {
+    ///     let config = PointerPrintConfig::new()
+    ///         .auto_depth(100_000)
+    ///         .max_output_lines(500)
+    ///         .max_output_bytes(64 * 1024);
+    ///     head.pointer_print_with_config(mem, &config).unwrap();
+    /// }
+    /// ```
+    pub fn max_output_lines(mut self, max_lines: usize) -> Self {
+        self.max_output_lines = Some(max_lines);
+        self
+    }
+
+    /// Print at most `max_bytes` bytes, the output is cut before the first line that doesn't
+    /// fit like with [`max_output_lines`](Self::max_output_lines)
+    pub fn max_output_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_output_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Stop reading once `token` is cancelled, the remaining pointers are printed as
+    /// `field: <cancelled 0x...>`
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Invoke `callback` for every read issued, e.g. to drive a progress bar
+    pub fn progress(mut self, callback: impl FnMut(ProgressEvent) + Send + 'static) -> Self {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+
+    /// Send every structure, value and failed read to `sender` as soon as it was collected,
+    /// e.g. to render a huge dump while it is read, see [`PointerStreamEvent`].
+    ///
+    /// The traversal stops reading once the receiving end is gone, the pointers left are
+    /// printed as `field: <cancelled 0x...>`.
+    ///
+    /// [`PointerStreamEvent`]: crate::PointerStreamEvent
+    pub fn stream(mut self, sender: impl StreamSender + 'static) -> Self {
+        self.stream = Some(StreamCallback::new(sender));
+        self
+    }
+
+    /// Read the pointer targets of a structure with one batched read, enabled by default.
+    ///
+    /// This cuts down the number of round trips over DMA and network connectors, the
+    /// output is the same either way. Targets that overlap or follow each other in memory
+    /// are read as one range.
+    pub fn batch_reads(mut self, batch_reads: bool) -> Self {
+        self.batch_reads = batch_reads;
+        self
+    }
+
+    /// Try failed reads again with `policy`, for connectors that drop reads now and then.
+    ///
+    /// The retries are counted in [`TraversalStats::retries`]. Batched reads are split up
+    /// so only the requests that failed are issued again.
+    ///
+    /// [`TraversalStats::retries`]: crate::TraversalStats::retries
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Read scalars and pointer addresses in byte `order`, e.g. [`ByteOrder::Big`] for
+    /// console memory dumps. The byte order of the host by default.
+    ///
+    /// Values read by the traversal are converted before they are printed or followed, a
+    /// root value read by the caller is printed as it is passed in. Pass a pointer to it
+    /// or read it with [`read_and_print`](crate::read_and_print) to convert it as well.
+    pub fn byte_order(mut self, order: ByteOrder) -> Self {
+        self.byte_order = order;
+        self
+    }
+
+    /// Layout of the output, [`OutputFormat::Tree`] by default
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Cut lines of the [`OutputFormat::Compact`] output after `max` characters, the cut is
+    /// marked with `...`
+    pub fn max_line_length(mut self, max: usize) -> Self {
+        self.max_line_length = Some(max);
+        self
+    }
+
+    /// Print all fields of the root first, then the structures its pointers lead to, then
+    /// the ones those point to and so on, for an overview of wide structures whose sibling
+    /// fields would otherwise be buried below large subtrees.
+    ///
+    /// Every structure pointed to is printed as a block of its own, numbered in that order
+    /// and headed by the path of the pointer that leads to it. The pointers refer to the
+    /// blocks by number. This changes the [`OutputFormat::Tree`] output only, the targets
+    /// are read the same either way.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+    ///     PointerTarget,
+    /// };
+    ///
+    /// let value = |name: &str, value: &str| PointerDebugField {
+    ///     name: name.to_string(),
+    ///     type_name: "u64".to_string(),
+    ///     value: PointerDebugValue::Value(value.to_string()),
+    /// };
+    /// let pointer = |name: &str, address, target| PointerDebugField {
+    ///     name: name.to_string(),
+    ///     type_name: "Pointer64".to_string(),
+    ///     value: PointerDebugValue::Pointer { address, target },
+    /// };
+    ///
+    /// let mut team = PointerDebugNode::new("Team");
+    /// team.address = Some(0x2000);
+    /// team.fields.push(value("id", "7"));
+    /// let mut player = PointerDebugNode::new("Player");
+    /// player.address = Some(0x1000);
+    /// player.fields.push(pointer("team", 0x2000, PointerTarget::Node(Box::new(team))));
+    /// let mut world = PointerDebugNode::new("World");
+    /// world.fields.push(pointer("local", 0x1000, PointerTarget::Node(Box::new(player))));
+    /// world.fields.push(pointer("target", 0x1000, PointerTarget::AlreadyVisited));
+    /// world.fields.push(value("tick", "42"));
+    ///
+    /// let config = PointerPrintConfig::new().breadth_first(true);
+    /// assert_eq!(
+    ///     world.display(&config).to_string(),
+    ///     "World {
+    ///   local-> Player #1
+    ///   target → see Player #1 (0x1000)
+    ///   tick: u64 = 42
+    /// }
+    /// #1 local-> Player {
+    ///   team-> Team #2
+    /// }
+    /// #2 local.team-> Team {
+    ///   id: u64 = 7
+    /// }
+    /// "
+    /// );
+    /// ```
+    pub fn breadth_first(mut self, breadth_first: bool) -> Self {
+        self.breadth_first = breadth_first;
+        self
+    }
+
+    /// Print the totals of the traversal after the dump, e.g.
+    /// `... 12 node(s), 11 pointer(s) followed, 1536 byte(s) read, 0 read(s) failed, 2 cycle(s), depth 3, 1.21ms`
+    pub fn show_stats(mut self, show_stats: bool) -> Self {
+        self.show_stats = show_stats;
+        self
+    }
+
+    /// Print how many pointers were valid, null, out of range, unmapped, unreadable or
+    /// cyclic after the dump, in total or for every field path, see [`PointerHealth`].
+    ///
+    /// ```text
+    /// ... pointers: 41 valid, 3 null, 12 unreadable, 2 cyclic
+    /// ...   *.players[].team: 24 valid, 12 unreadable
+    /// ```
+    ///
+    /// [`PointerHealth`]: crate::PointerHealth
+    pub fn health_summary(mut self, summary: HealthSummary) -> Self {
+        self.health_summary = Some(summary);
+        self
+    }
+
+    /// Treat fields of derived structures whose name matches `pattern` as padding and leave
+    /// them out, `*` matches any run of characters.
+    ///
+    /// Once a pattern is given, fields containing `_pad` are no longer hidden just for their
+    /// name. Fields marked with `#[pointer_debug(pad)]` are always hidden.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::PointerPrintConfig;
+    ///
+    /// let config = PointerPrintConfig::new()
+    ///     .padding_pattern("pad_*")
+    ///     .padding_pattern("*_reserved");
+    /// assert!(config.is_padding("pad_0x18"));
+    /// assert!(!config.is_padding("scratch_pad"));
+    /// assert!(PointerPrintConfig::new().is_padding("scratch_pad"));
+    /// ```
+    pub fn padding_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.padding_patterns.push(pattern.into());
+        self
+    }
+
+    /// Collect only the fields whose dotted path matches the glob `pattern`, along with the
+    /// fields leading to them and everything below them. Can be given several times.
+    ///
+    /// Paths are the field names from the root joined with `.`, elements are appended as
+    /// `[i]`, e.g. `player.inventory[3].name`. `*` matches any run of characters inside one
+    /// field name and `**` any number of fields. The filters are checked while traversing,
+    /// the targets of fields that are left out are never read. Pointer targets are read one
+    /// at a time instead of [batched](Self::batch_reads) while filters are set.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::PointerPrintConfig;
+    ///
+    /// let config = PointerPrintConfig::new()
+    ///     .include_path("player.inventory.**")
+    ///     .exclude_path("**.render_*");
+    /// assert!(config.is_path_selected("player"));
+    /// assert!(config.is_path_selected("player.inventory"));
+    /// assert!(config.is_path_selected("player.inventory[3].name"));
+    /// assert!(!config.is_path_selected("player.health"));
+    /// assert!(!config.is_path_selected("player.inventory[3].render_mesh"));
+    /// assert!(!config.is_path_selected("world"));
+    /// ```
+    pub fn include_path(mut self, pattern: impl Into<String>) -> Self {
+        self.include_paths.push(pattern.into());
+        self
+    }
+
+    /// Leave out the fields whose dotted path matches the glob `pattern` and everything below
+    /// them, e.g. `*.render_*`. Can be given several times, see
+    /// [`include_path`](Self::include_path) for the syntax
+    pub fn exclude_path(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude_paths.push(pattern.into());
+        self
+    }
+
+    /// Colorize type names, field names, addresses and errors of the tree output, disabled
+    /// by default.
+    ///
+    /// Read failures are red, skipped targets yellow and targets that were not followed,
+    /// e.g. already visited ones, are dimmed. Only enable this when writing to a terminal.
+    #[cfg(feature = "color")]
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Log printed output through the `log` crate instead of writing it to stdout.
+    ///
+    /// Every line becomes one record with the given `target` and `level`, so dumps are
+    /// filtered and formatted along with the other logs of the application.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::PointerPrintConfig;
+    ///
+    /// let config = PointerPrintConfig::new().log("game::dump", log::Level::Debug);
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn log(mut self, target: impl Into<String>, level: log::Level) -> Self {
+        self.log = Some(LogOutput {
+            target: target.into(),
+            level,
+        });
+        self
+    }
+
+    /// Returns true if the traversal was cancelled through [`cancellation`](Self::cancellation)
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Returns true if `address` passes the range and predicate checks
+    pub fn is_valid_address(&self, address: u64) -> bool {
+        let in_range = self.valid_ranges.is_empty()
+            || self
+                .valid_ranges
+                .iter()
+                .any(|range| range.contains(&address));
+        in_range
+            && self
+                .valid_address
+                .as_ref()
+                .is_none_or(|predicate| predicate.check(address))
+    }
+
+    /// Returns true if the field `name` of a derived structure is padding, see
+    /// [`padding_pattern`](Self::padding_pattern)
+    pub fn is_padding(&self, name: &str) -> bool {
+        if self.padding_patterns.is_empty() {
+            return name.contains("_pad");
+        }
+        self.padding_patterns
+            .iter()
+            .any(|pattern| matches_pattern(pattern, name))
+    }
+
+    /// Returns true if the field at the dotted `path` is collected, see
+    /// [`include_path`](Self::include_path) and [`exclude_path`](Self::exclude_path)
+    pub fn is_path_selected(&self, path: &str) -> bool {
+        let included = self.include_paths.is_empty()
+            || self
+                .include_paths
+                .iter()
+                .any(|pattern| matches_path(pattern.as_bytes(), path.as_bytes(), true));
+        included
+            && !self
+                .exclude_paths
+                .iter()
+                .any(|pattern| matches_path(pattern.as_bytes(), path.as_bytes(), false))
+    }
+
+    /// Returns true if fields are filtered by their path
+    pub(crate) fn filters_paths(&self) -> bool {
+        !self.include_paths.is_empty() || !self.exclude_paths.is_empty()
+    }
+
+    /// Name of the module and export `address` points into, `None` if no symbols are set or
+    /// the address is outside every module
+    pub fn symbolize(&self, address: u64) -> Option<String> {
+        self.symbols.as_ref()?.resolve(address)
+    }
+
+    /// Name of the function `address` points to like `kernel32.dll!CreateFileW+0x0`, `None`
+    /// if no symbols are set or the address is outside every module
+    pub fn symbolize_function(&self, address: u64) -> Option<String> {
+        self.symbols.as_ref()?.resolve_function(address)
+    }
+
+    /// Name of the function `address` points to if it lies in executable memory of the
+    /// configured symbols, see [`Symbols::resolve_code`]
+    pub fn symbolize_code(&self, address: u64) -> Option<String> {
+        self.symbols.as_ref()?.resolve_code(address)
+    }
+
+    /// Region `address` lies in, `None` if no regions are set or the region is unknown
+    pub fn region(&self, address: u64) -> Option<Region> {
+        self.regions.as_ref()?.region(address)
+    }
+
+    /// Returns true if `address` is mapped or no memory map is set, an empty map is treated as
+    /// not knowing the mapped regions
+    pub fn is_mapped_address(&self, address: u64) -> bool {
+        self.mapped_memory
+            .as_ref()
+            .is_none_or(|mapped_memory| mapped_memory.is_empty() || mapped_memory.contains(address))
+    }
+}
+
+/// Returns true if the field path `path` or one of the fields it lies below matches
+/// `pattern`, where `*` matches inside one field name and `**` across fields. With `partial`
+/// a path leading to a field that could match is accepted as well
+fn matches_path(pattern: &[u8], path: &[u8], partial: bool) -> bool {
+    if let Some(rest) = pattern.strip_prefix(b"**") {
+        return (0..=path.len()).any(|i| matches_path(rest, &path[i..], partial));
+    }
+    if let Some(rest) = pattern.strip_prefix(b"*") {
+        let end = path.iter().position(|c| *c == b'.').unwrap_or(path.len());
+        return (0..=end).any(|i| matches_path(rest, &path[i..], partial));
+    }
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        // A trailing `.**` matches the field itself too
+        (Some(_), None) => pattern == b".**" || (partial && matches!(pattern[0], b'.' | b'[')),
+        // The path lies below a matching field
+        (None, Some(c)) => matches!(c, b'.' | b'['),
+        // Elements follow their array without a `.`, `.**` still covers them
+        (Some(b'.'), Some(b'[')) if pattern[1..].starts_with(b"**") => {
+            matches_path(&pattern[1..], path, partial)
+        }
+        (Some(p), Some(c)) => p == c && matches_path(&pattern[1..], &path[1..], partial),
+    }
+}
+
+/// Returns true if `name` matches `pattern`, where `*` matches any run of characters
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // Without a `*` the whole name has to match
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    if parts.peek().is_none() {
+        return rest.is_empty();
+    }
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
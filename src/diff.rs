@@ -0,0 +1,391 @@
+//! Differences between two collected trees of the same structure.
+
+use core::fmt;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::{HashSet, PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// Lines of context shown around every change of the unified diff
+const CONTEXT: usize = 3;
+
+/// How a field differs between two trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeKind {
+    /// The field only exists in the new tree, e.g. a pointer was followed that was null
+    Added,
+    /// The field only exists in the old tree
+    Removed,
+    /// The value of the field changed
+    Changed,
+    /// The pointer holds a different address
+    Retargeted,
+}
+
+/// A field that differs between two trees, see [`PointerDebugDiff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldChange {
+    /// Dotted path of the field starting at the root, e.g. `player.health`
+    pub path: String,
+    /// How the field differs
+    pub kind: ChangeKind,
+    /// Value in the old tree, `None` if the field was added
+    pub old: Option<String>,
+    /// Value in the new tree, `None` if the field was removed
+    pub new: Option<String>,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let old = self.old.as_deref().unwrap_or("<absent>");
+        let new = self.new.as_deref().unwrap_or("<absent>");
+        write!(f, "{}: {} → {}", self.path, old, new)
+    }
+}
+
+/// The differences between two trees collected from the same structure.
+///
+/// Both trees are flattened into one line per value, labeled by its dotted path. Pointers
+/// are listed with the address they hold followed by the values of their target, so a
+/// pointer that now leads elsewhere shows up as retargeted along with the fields that
+/// differ below it. `Display` renders a unified diff of the flattened trees.
+///
+/// ```rust
+/// use memflow_pointer_debug::{
+///     ChangeKind, PointerDebugField, PointerDebugNode, PointerDebugValue,
+/// };
+///
+/// let node = |health: &str| {
+///     let mut node = PointerDebugNode::new("Player");
+///     node.fields.push(PointerDebugField {
+///         name: "health".to_string(),
+///         type_name: "u32".to_string(),
+///         value: PointerDebugValue::Value(health.to_string()),
+///     });
+///     node
+/// };
+///
+/// let diff = node("100").diff(&node("75"));
+/// assert_eq!(diff.changes()[0].kind, ChangeKind::Changed);
+/// assert_eq!(
+///     diff.to_string(),
+///     "--- old\n+++ new\n@@ -1 +1 @@\n-health = 100\n+health = 75\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerDebugDiff {
+    changes: Vec<FieldChange>,
+    /// Every line of the unified diff, unchanged ones included
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+impl PointerDebugDiff {
+    /// Compare the `old` and `new` tree
+    pub fn new(old: &PointerDebugNode, new: &PointerDebugNode) -> Self {
+        Self::from_fields(&flatten(old), &flatten(new))
+    }
+
+    /// The fields that differ in the order of the trees
+    pub fn changes(&self) -> &[FieldChange] {
+        &self.changes
+    }
+
+    /// Take the fields that differ
+    pub fn into_changes(self) -> Vec<FieldChange> {
+        self.changes
+    }
+
+    /// Returns true if both trees are the same
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub(crate) fn from_fields(old: &[FlatField], new: &[FlatField]) -> Self {
+        let old_paths: HashSet<_> = old.iter().map(|field| &field.path).collect();
+        let new_paths: HashSet<_> = new.iter().map(|field| &field.path).collect();
+
+        let mut diff = Self::default();
+        let (mut i, mut j) = (0, 0);
+        // Both trees list their fields in the same order, fields only present in one of
+        // them are picked up as they come
+        while i < old.len() || j < new.len() {
+            match (old.get(i), new.get(j)) {
+                (Some(old), Some(new)) if old.path == new.path => {
+                    diff.compare(old, new);
+                    i += 1;
+                    j += 1;
+                }
+                (Some(old), _) if !new_paths.contains(&old.path) => {
+                    diff.remove(old);
+                    i += 1;
+                }
+                (_, Some(new)) if !old_paths.contains(&new.path) => {
+                    diff.add(new);
+                    j += 1;
+                }
+                // The field moved, it is shown as removed here and added where it is now
+                (Some(old), _) => {
+                    diff.remove(old);
+                    i += 1;
+                }
+                (None, Some(new)) => {
+                    diff.add(new);
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+        diff
+    }
+
+    fn compare(&mut self, old: &FlatField, new: &FlatField) {
+        if old.value == new.value {
+            self.lines.push(DiffLine::Same(old.line()));
+            return;
+        }
+
+        let kind = match (old.address, new.address) {
+            (Some(old), Some(new)) if old != new => ChangeKind::Retargeted,
+            _ => ChangeKind::Changed,
+        };
+        self.changes.push(FieldChange {
+            path: new.path.clone(),
+            kind,
+            old: Some(old.value.clone()),
+            new: Some(new.value.clone()),
+        });
+        self.lines.push(DiffLine::Removed(old.line()));
+        self.lines.push(DiffLine::Added(new.line()));
+    }
+
+    fn remove(&mut self, old: &FlatField) {
+        self.changes.push(FieldChange {
+            path: old.path.clone(),
+            kind: ChangeKind::Removed,
+            old: Some(old.value.clone()),
+            new: None,
+        });
+        self.lines.push(DiffLine::Removed(old.line()));
+    }
+
+    fn add(&mut self, new: &FlatField) {
+        self.changes.push(FieldChange {
+            path: new.path.clone(),
+            kind: ChangeKind::Added,
+            old: None,
+            new: Some(new.value.clone()),
+        });
+        self.lines.push(DiffLine::Added(new.line()));
+    }
+}
+
+impl PointerDebugNode {
+    /// Compare this tree with a `new` one collected from the same structure later on
+    pub fn diff(&self, new: &PointerDebugNode) -> PointerDebugDiff {
+        PointerDebugDiff::new(self, new)
+    }
+}
+
+impl fmt::Display for PointerDebugDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "--- old")?;
+        writeln!(f, "+++ new")?;
+
+        // Line numbers in the old and new listing at the start of every line
+        let mut numbers = Vec::with_capacity(self.lines.len());
+        let (mut old_line, mut new_line) = (1, 1);
+        for line in &self.lines {
+            numbers.push((old_line, new_line));
+            match line {
+                DiffLine::Same(_) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                DiffLine::Removed(_) => old_line += 1,
+                DiffLine::Added(_) => new_line += 1,
+            }
+        }
+
+        let changed: Vec<_> = (0..self.lines.len())
+            .filter(|&i| !matches!(self.lines[i], DiffLine::Same(_)))
+            .collect();
+
+        // Changes closer than twice the context share a hunk
+        let mut k = 0;
+        while k < changed.len() {
+            let start = changed[k].saturating_sub(CONTEXT);
+            let mut end = changed[k];
+            while k + 1 < changed.len() && changed[k + 1] <= end + 2 * CONTEXT + 1 {
+                k += 1;
+                end = changed[k];
+            }
+            let end = (end + CONTEXT).min(self.lines.len() - 1);
+            k += 1;
+
+            let hunk = &self.lines[start..=end];
+            let old_count = hunk
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Added(_)))
+                .count();
+            let new_count = hunk
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Removed(_)))
+                .count();
+            let (old_start, new_start) = numbers[start];
+            writeln!(
+                f,
+                "@@ -{} +{} @@",
+                range(old_start, old_count),
+                range(new_start, new_count)
+            )?;
+
+            for line in hunk {
+                match line {
+                    DiffLine::Same(text) => writeln!(f, " {}", text)?,
+                    DiffLine::Removed(text) => writeln!(f, "-{}", text)?,
+                    DiffLine::Added(text) => writeln!(f, "+{}", text)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Line range of a hunk header, `start,count` or just `start` for a single line
+fn range(start: usize, count: usize) -> String {
+    match count {
+        1 => start.to_string(),
+        // An empty range starts at the line before it
+        0 => format!("{},0", start - 1),
+        _ => format!("{},{}", start, count),
+    }
+}
+
+/// A single value of a flattened tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FlatField {
+    /// Dotted path starting at the root, elements are appended as `[i]`
+    pub path: String,
+    pub value: String,
+    /// Address held by a pointer field
+    pub address: Option<u64>,
+}
+
+impl FlatField {
+    fn line(&self) -> String {
+        format!("{} = {}", self.path, self.value)
+    }
+}
+
+/// Every value below `node` by its dotted path, pointers are listed with the address they
+/// hold followed by the values of their target
+pub(crate) fn flatten(node: &PointerDebugNode) -> Vec<FlatField> {
+    let mut fields = Vec::new();
+    flatten_node(&mut fields, "", node);
+    fields
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() || name.starts_with('[') {
+        format!("{}{}", prefix, name)
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+fn flatten_node(fields: &mut Vec<FlatField>, path: &str, node: &PointerDebugNode) {
+    if let Some(value) = &node.value {
+        fields.push(FlatField {
+            path: path.to_string(),
+            value: value.clone(),
+            address: None,
+        });
+    }
+    for field in &node.fields {
+        let path = join(path, &field.name);
+        match &field.value {
+            PointerDebugValue::Value(value) => fields.push(FlatField {
+                path,
+                value: value.clone(),
+                address: None,
+            }),
+            PointerDebugValue::Pointer { address, target } => {
+                flatten_target(fields, path, *address, target)
+            }
+            PointerDebugValue::Nested(node) => flatten_node(fields, &path, node),
+        }
+    }
+}
+
+fn flatten_target(fields: &mut Vec<FlatField>, path: String, address: u64, target: &PointerTarget) {
+    let pointer = |path: String, value: String| FlatField {
+        path,
+        value,
+        address: Some(address),
+    };
+
+    let items: Vec<(u64, &PointerTarget)> = match target {
+        PointerTarget::Node(node) => {
+            fields.push(pointer(path.clone(), format!("-> {:#x}", address)));
+            flatten_node(fields, &path, node);
+            return;
+        }
+        PointerTarget::Text(text) => {
+            fields.push(pointer(path, format!("-> {:#x} {:?}", address, text)));
+            return;
+        }
+        PointerTarget::Bytes(bytes) => {
+            let value = format!("-> {:#x} b\"{}\"", address, bytes.escape_ascii());
+            fields.push(pointer(path, value));
+            return;
+        }
+        PointerTarget::Array { items, .. } => items
+            .iter()
+            .map(|item| (item_address(item, address), item))
+            .collect(),
+        PointerTarget::Chain { items, .. } => chain_addresses(address, items)
+            .into_iter()
+            .zip(items)
+            .collect(),
+        PointerTarget::List { items, .. } => items
+            .iter()
+            .map(|(address, item)| (*address, item))
+            .collect(),
+        PointerTarget::Null => {
+            fields.push(pointer(path, "NULL".to_string()));
+            return;
+        }
+        PointerTarget::ReadError(e) => {
+            let value = format!("-> {:#x} <error reading: {}>", address, e);
+            fields.push(pointer(path, value));
+            return;
+        }
+        // Targets that weren't read only hold their address
+        _ => {
+            fields.push(pointer(path, format!("-> {:#x}", address)));
+            return;
+        }
+    };
+
+    fields.push(pointer(
+        path.clone(),
+        format!("-> {:#x} [{}]", address, items.len()),
+    ));
+    for (i, (address, item)) in items.into_iter().enumerate() {
+        flatten_target(fields, format!("{}[{}]", path, i), address, item);
+    }
+}
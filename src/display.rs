@@ -0,0 +1,85 @@
+//! Printing structures through the formatting machinery of `core`.
+
+use core::cell::RefCell;
+use core::fmt;
+
+use memflow::mem::MemoryView;
+
+use crate::retry::RetryView;
+use crate::{
+    DerefDebugPrint, PointerDebugReport, PointerPrintConfig, TraversalState, TraversalStats,
+    render_node, stats::Stopwatch,
+};
+
+/// A structure that is read and printed whenever it is formatted.
+///
+/// The output is the same as [`pointer_print_with_config`] prints, so dumps can be passed to
+/// `format!`, `write!`, `tracing::info!("{}", ...)` and anything else taking a [`Display`].
+/// `{:?}` prints the same as `{}`. Every formatting traverses the structure again.
+///
+/// Formatting can't return the failed reads, with [`ErrorPolicy::Fail`] they are printed in
+/// place like with [`ErrorPolicy::Show`].
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerDisplay, PointerPrintConfig};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// fn example(mem: &mut impl MemoryView, head: &Node) {
+///     let config = PointerPrintConfig::new().max_depth(3);
+///     let dump = format!("head = {}", PointerDisplay::new(head, mem, &config));
+///     assert!(dump.starts_with("head = Node {"));
+/// }
+/// ```
+///
+/// [`pointer_print_with_config`]: crate::PointerPrint::pointer_print_with_config
+/// [`Display`]: fmt::Display
+/// [`ErrorPolicy::Fail`]: crate::ErrorPolicy::Fail
+/// [`ErrorPolicy::Show`]: crate::ErrorPolicy::Show
+pub struct PointerDisplay<'a, T: ?Sized, M> {
+    value: &'a T,
+    /// Formatting only borrows the display, the view is borrowed mutably for the traversal
+    mem: RefCell<&'a mut M>,
+    config: PointerPrintConfig,
+}
+
+impl<'a, T: DerefDebugPrint + ?Sized, M: MemoryView> PointerDisplay<'a, T, M> {
+    /// Print `value` read from `mem` with the options of `config` when formatted
+    pub fn new(value: &'a T, mem: &'a mut M, config: &PointerPrintConfig) -> Self {
+        Self {
+            value,
+            mem: RefCell::new(mem),
+            config: config.clone(),
+        }
+    }
+}
+
+impl<T: DerefDebugPrint + ?Sized, M: MemoryView> fmt::Display for PointerDisplay<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stopwatch = Stopwatch::start();
+        let mut state = TraversalState::with_config(&self.config);
+        let mut mem = self.mem.borrow_mut();
+        let mut mem = RetryView::new(&mut **mem, &self.config);
+        let node = self.value.pointer_collect_internal(&mut mem, 0, &mut state);
+        state.finish_root(&node);
+
+        let mut stats = TraversalStats::from_node(&node);
+        stats.bytes = state.bytes_read;
+        stats.retries = mem.retries;
+        stats.elapsed = stopwatch.elapsed();
+        let report = PointerDebugReport::from_node(&node);
+        f.write_str(&render_node(&node, &stats, &report, &self.config))
+    }
+}
+
+impl<T: DerefDebugPrint + ?Sized, M: MemoryView> fmt::Debug for PointerDisplay<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,376 @@
+//! Graphviz DOT export of the pointer graph.
+//!
+//! The graph is built from a collected [`PointerDebugNode`] tree and can then be rendered
+//! with [`PointerGraph::to_dot`], [`PointerGraph::to_mermaid`] or
+//! [`PointerGraph::to_graphml`].
+
+use core::fmt::Write;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::{HashMap, HashSet, PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// Identifier of the node the traversal was started from.
+///
+/// The root value is passed in by reference so its address is not known.
+pub const ROOT_NODE: &str = "root";
+
+/// A recorded pointer graph.
+///
+/// There is one node per visited address, labeled by the type name of the pointer target,
+/// and one edge per followed pointer field, labeled by the field name. Pointers to addresses
+/// that were already visited still produce an edge back to the existing node.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerGraph {
+    nodes: Vec<(String, String)>,
+    edges: Vec<(String, String, String)>,
+    /// Type and values of the nodes built from a tree, by identifier
+    #[cfg_attr(feature = "serde", serde(default))]
+    details: HashMap<String, NodeDetails>,
+}
+
+/// What a node built from a tree records besides its label
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct NodeDetails {
+    type_name: String,
+    /// Path and value of every field that is printed as a value
+    values: Vec<(String, String)>,
+}
+
+impl PointerGraph {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the DOT node identifier used for the given address
+    pub fn node_id(address: u64) -> String {
+        format!("{:#x}", address)
+    }
+
+    /// Add a node with the given identifier and label
+    pub fn add_node(&mut self, id: impl Into<String>, label: impl Into<String>) {
+        self.nodes.push((id.into(), label.into()));
+    }
+
+    /// Add an edge between two nodes, labeled by the field it was followed through
+    pub fn add_edge(
+        &mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        label: impl Into<String>,
+    ) {
+        self.edges.push((from.into(), to.into(), label.into()));
+    }
+
+    /// Build the graph of a collected tree, `root` becomes the [`ROOT_NODE`]
+    pub fn from_node(root: &PointerDebugNode) -> Self {
+        let mut graph = Self::new();
+        graph.add_tree(ROOT_NODE, root);
+        graph
+    }
+
+    /// Add `node` under the identifier `id` along with everything reachable from it
+    fn add_tree(&mut self, id: &str, node: &PointerDebugNode) {
+        let label = match &node.value {
+            Some(value) => format!("{} = {}", node.type_name, value),
+            None => node.type_name.clone(),
+        };
+        self.add_node(id, label);
+        let mut values = Vec::new();
+        node_values(node, "", &mut values);
+        self.details.entry(id.to_string()).or_insert(NodeDetails {
+            type_name: node.type_name.clone(),
+            values,
+        });
+        self.add_fields(id, "", node);
+    }
+
+    /// Add the pointers of `node` as edges from `id`, labels are prefixed with `prefix`
+    fn add_fields(&mut self, id: &str, prefix: &str, node: &PointerDebugNode) {
+        for field in &node.fields {
+            let label = if prefix.is_empty() || field.name.starts_with('[') {
+                format!("{}{}", prefix, field.name)
+            } else {
+                format!("{}.{}", prefix, field.name)
+            };
+            match &field.value {
+                PointerDebugValue::Pointer { address, target } => {
+                    self.add_target(id, &label, *address, target);
+                }
+                // Embedded values are part of their owner's node
+                PointerDebugValue::Nested(nested) => self.add_fields(id, &label, nested),
+                PointerDebugValue::Value(_) => {}
+            }
+        }
+    }
+
+    /// Add the edge `from -> target` labeled `label` and the target node
+    fn add_target(&mut self, from: &str, label: &str, address: u64, target: &PointerTarget) {
+        let target_id = Self::node_id(address);
+
+        match target {
+            PointerTarget::Node(child) => self.add_tree(&target_id, child),
+            // Already visited addresses only get a back-edge to the existing node
+            PointerTarget::AlreadyVisited => {}
+            PointerTarget::MaxDepth => self.add_node(target_id.clone(), "<max depth>"),
+            PointerTarget::Invalid => self.add_node(target_id.clone(), "<invalid>"),
+            PointerTarget::Unmapped => self.add_node(target_id.clone(), "<unmapped>"),
+            PointerTarget::BudgetExhausted => {
+                self.add_node(target_id.clone(), "<budget exhausted>")
+            }
+            PointerTarget::Cancelled => self.add_node(target_id.clone(), "<cancelled>"),
+            PointerTarget::NotFollowed => self.add_node(target_id.clone(), "<not followed>"),
+            PointerTarget::Text(text) => self.add_node(target_id.clone(), format!("{:?}", text)),
+            PointerTarget::Bytes(bytes) => {
+                self.add_node(target_id.clone(), format!("{} bytes", bytes.len()))
+            }
+            PointerTarget::Array { items, .. } => {
+                // Elements are linked directly from the node holding the array pointer
+                for (i, item) in items.iter().enumerate() {
+                    let item_address = item_address(item, address);
+                    self.add_target(from, &format!("{}[{}]", label, i), item_address, item);
+                }
+                return;
+            }
+            PointerTarget::Chain { items, .. } => {
+                // Each element links to the next one like the pointers in memory do
+                let mut from = from.to_string();
+                for (item, item_address) in items.iter().zip(chain_addresses(address, items)) {
+                    self.add_target(&from, label, item_address, item);
+                    from = Self::node_id(item_address);
+                }
+                return;
+            }
+            PointerTarget::List { items, .. } => {
+                // Records are linked from the node holding the list head
+                for (i, (item_address, item)) in items.iter().enumerate() {
+                    self.add_target(from, &format!("{}[{}]", label, i), *item_address, item);
+                }
+                return;
+            }
+            // The link is drawn by the owner of the chain
+            PointerTarget::ChainLink => return,
+            // Null pointers don't point anywhere
+            PointerTarget::Null => return,
+            PointerTarget::ReadError(e) => {
+                self.add_node(target_id.clone(), format!("<error: {}>", e))
+            }
+        }
+
+        self.add_edge(from, target_id, label);
+    }
+
+    /// Render the graph in Graphviz DOT syntax
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph pointers {\n");
+
+        for (id, label) in &self.nodes {
+            let _ = writeln!(out, "  \"{}\" [label=\"{}\"];", escape(id), escape(label));
+        }
+
+        for (from, to, label) in &self.edges {
+            let _ = writeln!(
+                out,
+                "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape(from),
+                escape(to),
+                escape(label)
+            );
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the graph as a Mermaid `graph TD` diagram, e.g. for GitHub issues and wikis.
+    ///
+    /// Mermaid identifiers are restricted to plain names, nodes are numbered `n0`, `n1`, ...
+    /// in the order they were added and labeled like in the DOT output.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{PointerGraph, ROOT_NODE};
+    ///
+    /// let mut graph = PointerGraph::new();
+    /// graph.add_node(ROOT_NODE, "Node");
+    /// graph.add_node(PointerGraph::node_id(0x1000), "Node");
+    /// graph.add_edge(ROOT_NODE, PointerGraph::node_id(0x1000), "next");
+    ///
+    /// assert_eq!(
+    ///     graph.to_mermaid(),
+    ///     "graph TD\n  n0[\"Node\"]\n  n1[\"Node\"]\n  n0 -->|\"next\"| n1\n"
+    /// );
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph TD\n");
+        let mut ids = HashMap::new();
+
+        for (id, label) in &self.nodes {
+            let n = ids.len();
+            let n = *ids.entry(id.as_str()).or_insert(n);
+            let _ = writeln!(out, "  n{}[\"{}\"]", n, escape_mermaid(label));
+        }
+
+        for (from, to, label) in &self.edges {
+            // Edges may lead to nodes that were never added, they are labeled by their id
+            let from = mermaid_node(&mut ids, from, &mut out);
+            let to = mermaid_node(&mut ids, to, &mut out);
+            let _ = writeln!(
+                out,
+                "  n{} -->|\"{}\"| n{}",
+                from,
+                escape_mermaid(label),
+                to
+            );
+        }
+
+        out
+    }
+
+    /// Render the graph as GraphML, e.g. to lay out and analyze large object graphs in Gephi
+    /// or yEd.
+    ///
+    /// Every node carries its `label` like in the DOT output, its `address`, and for the
+    /// nodes of a collected tree the `type` and the scalar `fields` as `name: value` lines.
+    /// Edges carry the `field` they were followed through. A node added more than once is
+    /// written once, edges to nodes that were never added declare them labeled by their id.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{PointerGraph, ROOT_NODE};
+    ///
+    /// let mut graph = PointerGraph::new();
+    /// graph.add_node(ROOT_NODE, "Node");
+    /// graph.add_edge(ROOT_NODE, PointerGraph::node_id(0x1000), "next");
+    ///
+    /// let graphml = graph.to_graphml();
+    /// assert!(graphml.contains("<node id=\"0x1000\">"));
+    /// assert!(graphml.contains(
+    ///     "<edge id=\"e0\" source=\"root\" target=\"0x1000\">\n      <data key=\"field\">next</data>"
+    /// ));
+    /// ```
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n",
+            "  <key id=\"address\" for=\"node\" attr.name=\"address\" attr.type=\"string\"/>\n",
+            "  <key id=\"fields\" for=\"node\" attr.name=\"fields\" attr.type=\"string\"/>\n",
+            "  <key id=\"field\" for=\"edge\" attr.name=\"field\" attr.type=\"string\"/>\n",
+            "  <graph id=\"pointers\" edgedefault=\"directed\">\n",
+        ));
+
+        // GraphML identifiers must be unique, the first label of a node wins
+        let mut declared = HashSet::new();
+        let edge_ends = self.edges.iter().flat_map(|(from, to, _)| [from, to]);
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(id, label)| (id, label.as_str()))
+            .chain(edge_ends.map(|id| (id, id.as_str())));
+        for (id, label) in nodes {
+            if !declared.insert(id.as_str()) {
+                continue;
+            }
+            let _ = writeln!(out, "    <node id=\"{}\">", escape_xml(id));
+            graphml_data(&mut out, "label", label);
+            if let Some(details) = self.details.get(id) {
+                graphml_data(&mut out, "type", &details.type_name);
+            }
+            if id != ROOT_NODE {
+                graphml_data(&mut out, "address", id);
+            }
+            if let Some(details) = self
+                .details
+                .get(id)
+                .filter(|details| !details.values.is_empty())
+            {
+                let fields: Vec<String> = details
+                    .values
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect();
+                graphml_data(&mut out, "fields", &fields.join("\n"));
+            }
+            out.push_str("    </node>\n");
+        }
+
+        for (i, (from, to, label)) in self.edges.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">",
+                i,
+                escape_xml(from),
+                escape_xml(to)
+            );
+            graphml_data(&mut out, "field", label);
+            out.push_str("    </edge>\n");
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
+
+/// Push the path and value of every scalar field of `node` and the values embedded in it to
+/// `values`, paths are prefixed with `prefix`
+fn node_values(node: &PointerDebugNode, prefix: &str, values: &mut Vec<(String, String)>) {
+    for field in &node.fields {
+        let path = if prefix.is_empty() || field.name.starts_with('[') {
+            format!("{}{}", prefix, field.name)
+        } else {
+            format!("{}.{}", prefix, field.name)
+        };
+        match &field.value {
+            PointerDebugValue::Value(value) => values.push((path, value.clone())),
+            PointerDebugValue::Nested(nested) => node_values(nested, &path, values),
+            PointerDebugValue::Pointer { .. } => {}
+        }
+    }
+}
+
+/// Write a GraphML `<data>` element of a node or edge
+fn graphml_data(out: &mut String, key: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        "      <data key=\"{}\">{}</data>",
+        key,
+        escape_xml(value)
+    );
+}
+
+/// Escape a string for use in XML text and attribute values
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Number of the Mermaid node `id`, the node is declared if it wasn't yet
+fn mermaid_node<'a>(ids: &mut HashMap<&'a str, usize>, id: &'a str, out: &mut String) -> usize {
+    if let Some(n) = ids.get(id) {
+        return *n;
+    }
+    let n = ids.len();
+    ids.insert(id, n);
+    let _ = writeln!(out, "  n{}[\"{}\"]", n, escape_mermaid(id));
+    n
+}
+
+/// Escape a string for use inside a quoted Mermaid label
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', " ")
+}
+
+/// Escape a string for use inside a quoted DOT identifier
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
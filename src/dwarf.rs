@@ -0,0 +1,389 @@
+//! Runtime layouts built from the DWARF debug information of an ELF binary.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use gimli::{
+    AttributeValue, DebuggingInformationEntry, Dwarf, DwarfSections, EndianSlice, RunTimeEndian,
+    Unit, UnitOffset, constants,
+};
+use object::{Object, ObjectSection};
+
+use crate::{FieldType, Layout, LayoutField, Layouts};
+
+type Slice<'d> = EndianSlice<'d, RunTimeEndian>;
+
+/// A debugging information entry by the index of its unit and its offset in there
+type Entry = (usize, UnitOffset);
+
+impl Layouts {
+    /// Build the layouts of the structure `name` and every type it refers to from the DWARF
+    /// debug information of an ELF file.
+    ///
+    /// Works like [`from_pdb`](Layouts::from_pdb) for Linux kernels and userspace binaries:
+    /// members keep their names and offsets, base classes are embedded as a field named
+    /// after the class and pointers are followed into the layout of their target. Typedefs
+    /// are looked through, enums are printed as their underlying integer and bitfields as
+    /// the whole integer they are stored in. Pointers to `void` or functions are printed as
+    /// plain addresses, types that are only declared have a layout without fields.
+    ///
+    /// Fails if the file can't be read or holds no definition of `name`.
+    ///
+    /// ```rust,no_run
+    /// use memflow::prelude::v1::{Address, MemoryView};
+    /// use memflow_pointer_debug::{Layouts, PointerPrint};
+    ///
+    /// fn print(mem: &mut impl MemoryView, task: Address) -> std::io::Result<()> {
+    ///     let layouts = Layouts::from_dwarf("vmlinux", "task_struct")?;
+    ///     if let Ok(task) = layouts.read(mem, "task_struct", task) {
+    ///         task.pointer_print(mem);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_dwarf(path: impl AsRef<Path>, name: &str) -> io::Result<Self> {
+        let data = std::fs::read(path)?;
+        let file = object::File::parse(&*data).map_err(invalid_data)?;
+        let endian = if file.is_little_endian() {
+            RunTimeEndian::Little
+        } else {
+            RunTimeEndian::Big
+        };
+
+        let sections = DwarfSections::load(|id| -> Result<Cow<'_, [u8]>, object::Error> {
+            Ok(match file.section_by_name(id.name()) {
+                Some(section) => section.uncompressed_data()?,
+                None => Cow::Borrowed(&[]),
+            })
+        })
+        .map_err(invalid_data)?;
+        let dwarf = sections.borrow(|section| EndianSlice::new(section, endian));
+
+        let mut units = Vec::new();
+        let mut headers = dwarf.units();
+        while let Some(header) = headers.next().map_err(invalid_data)? {
+            units.push(dwarf.unit(header).map_err(invalid_data)?);
+        }
+
+        let mut builder = Builder {
+            dwarf: &dwarf,
+            units: &units,
+            definitions: HashMap::new(),
+            names: HashMap::new(),
+            pending: Vec::new(),
+            layouts: Layouts::new().pointer_size(if file.is_64() { 8 } else { 4 }),
+        };
+        builder.index().map_err(invalid_data)?;
+
+        let root = *builder.definitions.get(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no definition of `{}` in the debug information", name),
+            )
+        })?;
+        builder.structure(root).map_err(invalid_data)?;
+        while let Some((entry, name)) = builder.pending.pop() {
+            builder.fields(entry, &name).map_err(invalid_data)?;
+        }
+        Ok(builder.layouts)
+    }
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Collects the layouts of the structures reachable from the requested one
+struct Builder<'a, 'd> {
+    dwarf: &'a Dwarf<Slice<'d>>,
+    units: &'a [Unit<Slice<'d>>],
+    /// The complete definitions of structures, classes and unions by name
+    definitions: HashMap<String, Entry>,
+    /// The layout name of every structure seen so far
+    names: HashMap<Entry, String>,
+    /// Structures whose layout still has to be filled with fields
+    pending: Vec<(Entry, String)>,
+    layouts: Layouts,
+}
+
+impl<'d> Builder<'_, 'd> {
+    /// Find the definitions of all named structures
+    fn index(&mut self) -> gimli::Result<()> {
+        for (i, unit) in self.units.iter().enumerate() {
+            let mut entries = unit.entries();
+            while let Some((_, entry)) = entries.next_dfs()? {
+                if !is_structure(entry.tag()) || is_declaration(entry)? {
+                    continue;
+                }
+                if let Some(name) = self.name(i, entry)? {
+                    self.definitions.entry(name).or_insert((i, entry.offset()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The `DW_AT_name` of an entry
+    fn name(
+        &self,
+        unit: usize,
+        entry: &DebuggingInformationEntry<'_, '_, Slice<'d>>,
+    ) -> gimli::Result<Option<String>> {
+        let Some(value) = entry.attr_value(constants::DW_AT_name)? else {
+            return Ok(None);
+        };
+        let name = self.dwarf.attr_string(&self.units[unit], value)?;
+        Ok(Some(name.to_string_lossy().into_owned()))
+    }
+
+    /// The entry a reference attribute like `DW_AT_type` points to
+    fn reference(&self, unit: usize, value: AttributeValue<Slice<'d>>) -> Option<Entry> {
+        match value {
+            AttributeValue::UnitRef(offset) => Some((unit, offset)),
+            AttributeValue::DebugInfoRef(offset) => self
+                .units
+                .iter()
+                .enumerate()
+                .find_map(|(i, unit)| Some((i, offset.to_unit_offset(&unit.header)?))),
+            _ => None,
+        }
+    }
+
+    /// The type an entry refers to with `DW_AT_type`, `None` for `void`
+    fn type_of(
+        &self,
+        unit: usize,
+        entry: &DebuggingInformationEntry<'_, '_, Slice<'d>>,
+    ) -> gimli::Result<Option<Entry>> {
+        Ok(entry
+            .attr_value(constants::DW_AT_type)?
+            .and_then(|value| self.reference(unit, value)))
+    }
+
+    /// The layout name of the structure at `entry`.
+    ///
+    /// Named structures and declarations are resolved to the first definition of the name.
+    /// The first time a structure is seen
+    /// an empty layout of the right size is added, its fields are filled in later.
+    fn structure(&mut self, (unit, offset): Entry) -> gimli::Result<String> {
+        let entry = self.units[unit].entry(offset)?;
+        let name = self.name(unit, &entry)?;
+        let mut key = (unit, offset);
+        // Every unit including a header has a copy of its structures, use the same one
+        if let Some(name) = &name
+            && let Some(definition) = self.definitions.get(name)
+        {
+            key = *definition;
+        }
+        if let Some(name) = self.names.get(&key) {
+            return Ok(name.clone());
+        }
+
+        let entry = self.units[key.0].entry(key.1)?;
+        let size = udata(&entry, constants::DW_AT_byte_size)?.unwrap_or(0);
+        // Anonymous structures all have the same name
+        let name = match name {
+            Some(name) if self.layouts.get(&name).is_none() => name,
+            name => format!("{}#{:x}", name.as_deref().unwrap_or("<anonymous>"), key.1.0),
+        };
+        self.names.insert(key, name.clone());
+        self.layouts
+            .insert(Layout::new(name.clone(), size as usize));
+        self.pending.push((key, name.clone()));
+        Ok(name)
+    }
+
+    /// Add the members and base classes of the structure at `entry` to its layout
+    fn fields(&mut self, (unit, offset): Entry, name: &str) -> gimli::Result<()> {
+        let mut fields = Vec::new();
+        let mut tree = self.units[unit].entries_tree(Some(offset))?;
+        let mut children = tree.root()?.children();
+        while let Some(child) = children.next()? {
+            let entry = child.entry();
+            let Some(ty) = self.type_of(unit, entry)? else {
+                continue;
+            };
+            match entry.tag() {
+                constants::DW_TAG_member => {
+                    let Some(field_type) = self.field_type(ty)? else {
+                        continue;
+                    };
+                    let offset = match udata(entry, constants::DW_AT_data_member_location)? {
+                        Some(offset) => offset,
+                        // Bitfields start at the integer they are stored in, members of
+                        // unions have no location
+                        None => match udata(entry, constants::DW_AT_data_bit_offset)? {
+                            Some(bits) => {
+                                let size = field_type.size(&self.layouts).unwrap_or(1).max(1);
+                                bits / 8 / size as u64 * size as u64
+                            }
+                            None => 0,
+                        },
+                    };
+                    fields.push(LayoutField {
+                        name: self
+                            .name(unit, entry)?
+                            .unwrap_or_else(|| "<anonymous>".to_string()),
+                        offset: offset as usize,
+                        ty: field_type,
+                    });
+                }
+                constants::DW_TAG_inheritance => {
+                    let name = self.structure(ty)?;
+                    let offset = udata(entry, constants::DW_AT_data_member_location)?;
+                    fields.push(LayoutField {
+                        name: name.clone(),
+                        offset: offset.unwrap_or(0) as usize,
+                        ty: FieldType::named(name),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(layout) = self.layouts.get_mut(name) {
+            layout.fields = fields;
+        }
+        Ok(())
+    }
+
+    /// The type of a member, `None` for types that have no useful representation
+    fn field_type(&mut self, (unit, offset): Entry) -> gimli::Result<Option<FieldType>> {
+        let entry = self.units[unit].entry(offset)?;
+        let underlying = self.type_of(unit, &entry)?;
+        Ok(match entry.tag() {
+            constants::DW_TAG_base_type => base_type(&entry)?,
+            tag if is_structure(tag) => Some(FieldType::named(self.structure((unit, offset))?)),
+            constants::DW_TAG_pointer_type
+            | constants::DW_TAG_reference_type
+            | constants::DW_TAG_rvalue_reference_type => {
+                if let Some(size) = udata(&entry, constants::DW_AT_byte_size)? {
+                    self.layouts.pointer_size = size as usize;
+                }
+                let target = match underlying {
+                    Some(target) => self.field_type(target)?,
+                    None => None,
+                };
+                Some(target.map_or_else(|| self.address(), FieldType::pointer))
+            }
+            constants::DW_TAG_enumeration_type => match underlying {
+                Some(underlying) => self.field_type(underlying)?,
+                None => unsigned(udata(&entry, constants::DW_AT_byte_size)?.unwrap_or(4)),
+            },
+            constants::DW_TAG_array_type => {
+                let Some(mut ty) = (match underlying {
+                    Some(element) => self.field_type(element)?,
+                    None => None,
+                }) else {
+                    return Ok(None);
+                };
+                // `int a[2][3]` has the subranges 2 and 3, the last one is the innermost
+                let mut lens = Vec::new();
+                let mut tree = self.units[unit].entries_tree(Some(offset))?;
+                let mut children = tree.root()?.children();
+                while let Some(child) = children.next()? {
+                    let entry = child.entry();
+                    if entry.tag() != constants::DW_TAG_subrange_type {
+                        continue;
+                    }
+                    let len = match udata(entry, constants::DW_AT_count)? {
+                        Some(count) => count,
+                        None => udata(entry, constants::DW_AT_upper_bound)?.map_or(0, |n| n + 1),
+                    };
+                    lens.push(len as usize);
+                }
+                for len in lens.into_iter().rev() {
+                    ty = FieldType::array(ty, len);
+                }
+                Some(ty)
+            }
+            constants::DW_TAG_typedef
+            | constants::DW_TAG_const_type
+            | constants::DW_TAG_volatile_type
+            | constants::DW_TAG_restrict_type
+            | constants::DW_TAG_atomic_type => match underlying {
+                Some(underlying) => self.field_type(underlying)?,
+                None => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// An integer as large as a pointer, for addresses whose target can't be printed
+    fn address(&self) -> FieldType {
+        match self.layouts.pointer_size {
+            4 => FieldType::U32,
+            _ => FieldType::U64,
+        }
+    }
+}
+
+fn is_structure(tag: constants::DwTag) -> bool {
+    matches!(
+        tag,
+        constants::DW_TAG_structure_type
+            | constants::DW_TAG_class_type
+            | constants::DW_TAG_union_type
+    )
+}
+
+fn is_declaration(entry: &DebuggingInformationEntry<'_, '_, Slice<'_>>) -> gimli::Result<bool> {
+    Ok(matches!(
+        entry.attr_value(constants::DW_AT_declaration)?,
+        Some(AttributeValue::Flag(true))
+    ))
+}
+
+/// An attribute holding an unsigned constant
+fn udata(
+    entry: &DebuggingInformationEntry<'_, '_, Slice<'_>>,
+    name: constants::DwAt,
+) -> gimli::Result<Option<u64>> {
+    Ok(entry
+        .attr_value(name)?
+        .and_then(|value| value.udata_value()))
+}
+
+/// The scalar type of a `DW_TAG_base_type`, `None` for types without an equivalent
+fn base_type(
+    entry: &DebuggingInformationEntry<'_, '_, Slice<'_>>,
+) -> gimli::Result<Option<FieldType>> {
+    let size = udata(entry, constants::DW_AT_byte_size)?.unwrap_or(0);
+    let Some(AttributeValue::Encoding(encoding)) = entry.attr_value(constants::DW_AT_encoding)?
+    else {
+        return Ok(None);
+    };
+    Ok(match encoding {
+        constants::DW_ATE_boolean if size == 1 => Some(FieldType::Bool),
+        constants::DW_ATE_signed | constants::DW_ATE_signed_char => match size {
+            1 => Some(FieldType::I8),
+            2 => Some(FieldType::I16),
+            4 => Some(FieldType::I32),
+            8 => Some(FieldType::I64),
+            _ => None,
+        },
+        constants::DW_ATE_float => match size {
+            4 => Some(FieldType::F32),
+            8 => Some(FieldType::F64),
+            _ => None,
+        },
+        constants::DW_ATE_boolean
+        | constants::DW_ATE_unsigned
+        | constants::DW_ATE_unsigned_char
+        | constants::DW_ATE_UTF => unsigned(size),
+        _ => None,
+    })
+}
+
+/// The unsigned integer of `size` bytes
+fn unsigned(size: u64) -> Option<FieldType> {
+    match size {
+        1 => Some(FieldType::U8),
+        2 => Some(FieldType::U16),
+        4 => Some(FieldType::U32),
+        8 => Some(FieldType::U64),
+        _ => None,
+    }
+}
@@ -0,0 +1,72 @@
+//! Byte order of the memory a structure is read from.
+
+/// Byte order of the scalars and pointer addresses in the target memory, see
+/// [`PointerPrintConfig::byte_order`](crate::PointerPrintConfig::byte_order).
+///
+/// Values read by the traversal are converted to the byte order of the host before they
+/// are printed or followed. Fields marked `#[pointer_debug(endian = "big")]` or
+/// `endian = "little"` keep their own byte order, e.g. network-ordered fields of an
+/// otherwise little-endian structure.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{ByteOrder, DerefDebugPrint, PointerDerefDebugPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Packet {
+///     len: u32,
+///     #[pointer_debug(endian = "big")]
+///     port: u16,
+///     flags: [u8; 2],
+///     next: Pointer64<Packet>,
+/// }
+///
+/// // As read from little-endian memory
+/// let mut packet = Packet {
+///     len: 0x40,
+///     port: u16::from_be(0x1f90),
+///     flags: [1, 2],
+///     next: Pointer64::from(0x1000u64),
+/// };
+/// packet.pointer_to_host_order(ByteOrder::Little);
+/// assert_eq!((packet.len, packet.port, packet.flags), (0x40, 0x1f90, [1, 2]));
+/// assert_eq!(packet.next.address().to_umem(), 0x1000);
+///
+/// // The same structure read from big-endian memory
+/// let mut packet = Packet {
+///     len: u32::from_be(0x40),
+///     port: u16::from_be(0x1f90),
+///     flags: [1, 2],
+///     next: Pointer64::from(u64::from_be(0x1000)),
+/// };
+/// packet.pointer_to_host_order(ByteOrder::Big);
+/// assert_eq!((packet.len, packet.port, packet.flags), (0x40, 0x1f90, [1, 2]));
+/// assert_eq!(packet.next.address().to_umem(), 0x1000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ByteOrder {
+    /// Least significant byte first, like x86 and most ARM targets
+    Little,
+    /// Most significant byte first, like PowerPC consoles and network byte order
+    Big,
+}
+
+impl ByteOrder {
+    /// Byte order of the host the traversal runs on
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Self::Big
+        } else {
+            Self::Little
+        }
+    }
+}
+
+impl Default for ByteOrder {
+    /// The byte order of the host
+    fn default() -> Self {
+        Self::native()
+    }
+}
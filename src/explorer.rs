@@ -0,0 +1,415 @@
+//! Interactive terminal explorer built on ratatui.
+
+use std::collections::HashSet;
+use std::io;
+
+use memflow::mem::MemoryView;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::node::{chain_addresses, collect_path, item_address, splice_path, target_note};
+use crate::{
+    DerefDebugPrint, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget,
+};
+
+const HELP: &str = " ↑↓ move  → expand  ← collapse  r re-read  q quit ";
+
+/// A terminal UI for navigating a structure, pointers are read once they are expanded.
+///
+/// The arrow keys move the focus and expand or collapse the focused field and `q` or `Esc`
+/// quits. Expanding a pointer collects the fields on the path to it again, down to the
+/// fields of its target, nothing off that path is read again. `r` reads the target of the
+/// focused pointer again, together with the pointers expanded below it, on other rows it
+/// reads the target of the pointer they belong to or the root structure. The `max_depth`,
+/// `max_revisits` and `include_paths` of the configuration are ignored, every other option
+/// applies.
+///
+/// ```rust,no_run
+/// use memflow::prelude::v1::{MemoryView, Pod, Pointer64};
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerExplorer};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// fn explore(mem: &mut impl MemoryView, node: Node) -> std::io::Result<()> {
+///     PointerExplorer::new(node).run(mem)
+/// }
+/// ```
+pub struct PointerExplorer<T> {
+    value: T,
+    config: PointerPrintConfig,
+    tree: Option<PointerDebugNode>,
+    /// Paths of the expanded rows
+    expanded: HashSet<Vec<String>>,
+    /// Paths of the pointers read when they were expanded in that order, with the depth
+    /// their targets were collected to
+    reads: Vec<Read>,
+    /// Reads requested by keys since the last update
+    pending: Vec<Read>,
+    selected: usize,
+}
+
+/// Path of a pointer and the depth its target is collected to, the root structure for an
+/// empty path
+type Read = (Vec<String>, usize);
+
+/// A visible line of the explorer
+struct Row {
+    indent: usize,
+    path: Vec<String>,
+    spans: Vec<Span<'static>>,
+    kind: RowKind,
+    /// Read that collects the row again
+    read: Read,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+    Leaf,
+    Branch,
+    /// A pointer that wasn't read yet, expanding it needs the tree collected to `depth`
+    Unread {
+        depth: usize,
+    },
+}
+
+impl<T: DerefDebugPrint> PointerExplorer<T> {
+    /// Explore `value` with the default options
+    pub fn new(value: T) -> Self {
+        Self::with_config(value, PointerPrintConfig::new())
+    }
+
+    /// Explore `value` with the given options
+    pub fn with_config(value: T, config: PointerPrintConfig) -> Self {
+        Self {
+            value,
+            config,
+            tree: None,
+            expanded: HashSet::new(),
+            reads: Vec::new(),
+            pending: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Take over the terminal and run the explorer until it is quit
+    pub fn run<M: MemoryView>(&mut self, mem: &mut M) -> io::Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal, mem);
+        // The terminal is restored even if drawing failed
+        ratatui::restore();
+        result
+    }
+
+    fn event_loop<M: MemoryView>(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        mem: &mut M,
+    ) -> io::Result<()> {
+        loop {
+            self.update(mem);
+            terminal.draw(|frame| self.render(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key.code)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Read what the keys since the last update expanded or asked to read again
+    pub fn update<M: MemoryView>(&mut self, mem: &mut M) {
+        if self.tree.is_none() {
+            self.read(mem, &[], 1);
+        }
+        for (path, depth) in core::mem::take(&mut self.pending) {
+            self.read(mem, &path, depth);
+        }
+    }
+
+    /// Collect the target of the pointer at `path` down to `depth` and the pointers that were
+    /// expanded below it, and put them into the tree
+    fn read<M: MemoryView>(&mut self, mem: &mut M, path: &[String], depth: usize) {
+        let below = self
+            .reads
+            .iter()
+            .filter(|(read, _)| read.len() > path.len() && read.starts_with(path));
+        let reads: Vec<Read> = [(path.to_vec(), depth)]
+            .into_iter()
+            .chain(below.cloned())
+            .collect();
+        for (path, depth) in reads {
+            let read = collect_path(&self.value, mem, &self.config, &path, depth);
+            match &mut self.tree {
+                // The path may not lead to a pointer anymore once the memory changed
+                Some(tree) => splice_path(tree, read, &path),
+                None => self.tree = Some(read),
+            }
+        }
+    }
+
+    /// Apply a key press, returns false if the explorer should quit
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        let rows = self.rows();
+        let Some(row) = rows.get(self.selected) else {
+            return !matches!(key, KeyCode::Char('q') | KeyCode::Esc);
+        };
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(rows.len() - 1),
+            KeyCode::Right | KeyCode::Enter => match row.kind {
+                RowKind::Branch => {
+                    self.expanded.insert(row.path.clone());
+                }
+                RowKind::Unread { depth } => {
+                    self.expanded.insert(row.path.clone());
+                    let read = (row.path.clone(), depth);
+                    if !self.reads.contains(&read) {
+                        self.reads.push(read.clone());
+                        self.pending.push(read);
+                    }
+                }
+                RowKind::Leaf => {}
+            },
+            KeyCode::Left => {
+                if row.kind == RowKind::Branch && self.expanded.remove(&row.path) {
+                    return true;
+                }
+                // Collapsed rows move the focus to their parent
+                if let Some(parent) = rows[..self.selected]
+                    .iter()
+                    .rposition(|parent| parent.indent < row.indent)
+                {
+                    self.selected = parent;
+                }
+            }
+            KeyCode::Char('r') => self.pending.push(row.read.clone()),
+            _ => {}
+        }
+        true
+    }
+
+    /// Draw the explorer, [`update`](Self::update) has to be called first
+    pub fn render(&mut self, frame: &mut Frame) {
+        let rows = self.rows();
+        self.selected = self.selected.min(rows.len().saturating_sub(1));
+
+        let items: Vec<_> = rows
+            .into_iter()
+            .map(|row| {
+                let marker = match row.kind {
+                    RowKind::Leaf => "  ",
+                    _ if self.expanded.contains(&row.path) => "▾ ",
+                    _ => "▸ ",
+                };
+                let mut spans = vec![Span::raw("  ".repeat(row.indent)), Span::raw(marker)];
+                spans.extend(row.spans);
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let title = self
+            .tree
+            .as_ref()
+            .map_or_else(String::new, |tree| format!(" {} ", tree.type_name));
+        let list = List::new(items)
+            .block(Block::bordered().title(title).title_bottom(HELP))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, frame.area(), &mut state);
+    }
+
+    /// The visible rows of the current tree
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        if let Some(tree) = &self.tree {
+            RowBuilder {
+                rows: &mut rows,
+                expanded: &self.expanded,
+                read: (Vec::new(), 1),
+            }
+            .fields(tree, &[], 0, 0);
+        }
+        rows
+    }
+}
+
+struct RowBuilder<'a> {
+    rows: &'a mut Vec<Row>,
+    expanded: &'a HashSet<Vec<String>>,
+    /// Read that collected the rows added
+    read: Read,
+}
+
+impl RowBuilder<'_> {
+    /// Add the fields of `node` collected at `depth`, indented by `indent`
+    fn fields(&mut self, node: &PointerDebugNode, path: &[String], indent: usize, depth: usize) {
+        for field in &node.fields {
+            let path = child_path(path, &field.name);
+            let name = field_span(&field.name);
+            match &field.value {
+                PointerDebugValue::Value(value) => {
+                    let spans = vec![
+                        name,
+                        Span::raw(": "),
+                        type_span(&field.type_name),
+                        Span::raw(format!(" = {}", value)),
+                    ];
+                    self.push(indent, path, spans, RowKind::Leaf);
+                }
+                PointerDebugValue::Pointer { address, target } => {
+                    self.target(name, *address, target, path, indent, depth)
+                }
+                PointerDebugValue::Nested(nested) => {
+                    let spans = vec![name, Span::raw(": "), type_span(&nested.type_name)];
+                    if self.push(indent, path.clone(), spans, RowKind::Branch) {
+                        self.fields(nested, &path, indent + 1, depth);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add the target of a pointer to `address` held by a node at `depth`
+    fn target(
+        &mut self,
+        name: Span<'static>,
+        address: u64,
+        target: &PointerTarget,
+        path: Vec<String>,
+        indent: usize,
+        depth: usize,
+    ) {
+        let at = Span::styled(
+            format!(" @ {:#x}", address),
+            Style::new().fg(Color::Magenta),
+        );
+        if let Some(note) = target_note(target, address) {
+            let separator = if note.leads { " → " } else { ": " };
+            let text = Span::styled(
+                format!("{}{}", separator, note.text),
+                Style::new().add_modifier(Modifier::DIM),
+            );
+            self.push(indent, path, vec![name, text], RowKind::Leaf);
+            return;
+        }
+
+        let items: Vec<(u64, &PointerTarget)> = match target {
+            PointerTarget::Node(node) => {
+                let spans = vec![name, Span::raw(" → "), type_span(&node.type_name), at];
+                let outer = self.enter(&path, depth);
+                if self.push(indent, path.clone(), spans, RowKind::Branch) {
+                    self.fields(node, &path, indent + 1, depth + 1);
+                }
+                self.read = outer;
+                return;
+            }
+            PointerTarget::MaxDepth => {
+                let spans = vec![name, Span::raw(" →"), at];
+                let kind = RowKind::Unread { depth: depth + 2 };
+                self.push(indent, path, spans, kind);
+                return;
+            }
+            PointerTarget::Array { items, .. } => items
+                .iter()
+                .map(|item| (item_address(item, address), item))
+                .collect(),
+            PointerTarget::Chain { items, .. } => chain_addresses(address, items)
+                .into_iter()
+                .zip(items)
+                .collect(),
+            PointerTarget::List { items, .. } => items
+                .iter()
+                .map(|(address, item)| (*address, item))
+                .collect(),
+            PointerTarget::Text(text) => {
+                let spans = vec![name, Span::raw(format!(" → {:?}", text))];
+                self.push(indent, path, spans, RowKind::Leaf);
+                return;
+            }
+            PointerTarget::Bytes(bytes) => {
+                let text = format!(" → b\"{}\"", bytes.escape_ascii());
+                let spans = vec![name, Span::raw(text)];
+                self.push(indent, path, spans, RowKind::Leaf);
+                return;
+            }
+            PointerTarget::ReadError(e) => {
+                let error = format!(" → error reading {:#x}: {}", address, e);
+                let spans = vec![name, Span::styled(error, Style::new().fg(Color::Red))];
+                self.push(indent, path, spans, RowKind::Leaf);
+                return;
+            }
+            // Described by the note above
+            _ => return,
+        };
+
+        // Elements are collected one level below the node holding the pointer
+        let spans = vec![name, Span::raw(format!(" → [{}]", items.len())), at];
+        let outer = self.enter(&path, depth);
+        if self.push(indent, path.clone(), spans, RowKind::Branch) {
+            for (i, (address, item)) in items.into_iter().enumerate() {
+                let label = format!("[{}]", i);
+                let item_path = child_path(&path, &label);
+                self.target(
+                    field_span(&label),
+                    address,
+                    item,
+                    item_path,
+                    indent + 1,
+                    depth,
+                );
+            }
+        }
+        self.read = outer;
+    }
+
+    /// Start adding the rows of the target of the pointer at `path` held by a node at
+    /// `depth`, reading them again reads that target. Returns the read to restore after.
+    fn enter(&mut self, path: &[String], depth: usize) -> Read {
+        core::mem::replace(&mut self.read, (path.to_vec(), depth + 2))
+    }
+
+    /// Add a row, returns true if it is an expanded branch
+    fn push(
+        &mut self,
+        indent: usize,
+        path: Vec<String>,
+        spans: Vec<Span<'static>>,
+        kind: RowKind,
+    ) -> bool {
+        let open = kind == RowKind::Branch && self.expanded.contains(&path);
+        self.rows.push(Row {
+            indent,
+            path,
+            spans,
+            kind,
+            read: self.read.clone(),
+        });
+        open
+    }
+}
+
+/// `path` extended by the field name or index `segment`
+fn child_path(path: &[String], segment: &str) -> Vec<String> {
+    let mut path = path.to_vec();
+    path.push(segment.to_string());
+    path
+}
+
+fn field_span(name: &str) -> Span<'static> {
+    Span::styled(name.to_string(), Style::new().fg(Color::Cyan))
+}
+
+fn type_span(name: &str) -> Span<'static> {
+    Span::styled(name.to_string(), Style::new().fg(Color::Green))
+}
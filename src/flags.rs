@@ -0,0 +1,36 @@
+//! Names of the set bits of flag fields.
+
+use crate::prelude::*;
+
+/// Format `value` as the names of its set bits followed by the value, e.g. `READ | EXEC (0x5)`.
+///
+/// Flags are matched in order, a flag is printed if all of its bits are set and at least one
+/// of them was not printed by an earlier flag. Bits without a name are printed as a number,
+/// a value without any bits set is printed as `0x0`. This is used by the
+/// `#[pointer_debug(flags(...))]` attribute.
+///
+/// ```rust
+/// use memflow_pointer_debug::format_flags;
+///
+/// let flags = [("READ", 0x1), ("WRITE", 0x2), ("EXEC", 0x4)];
+/// assert_eq!(format_flags(0x5, &flags), "READ | EXEC (0x5)");
+/// assert_eq!(format_flags(0x13, &flags), "READ | WRITE | 0x10 (0x13)");
+/// assert_eq!(format_flags(0, &flags), "0x0");
+/// ```
+pub fn format_flags(value: u64, flags: &[(&str, u64)]) -> String {
+    let mut names = Vec::new();
+    let mut rest = value;
+    for &(name, bits) in flags {
+        if bits != 0 && value & bits == bits && rest & bits != 0 {
+            names.push(name.to_string());
+            rest &= !bits;
+        }
+    }
+    if names.is_empty() {
+        return format!("{:#x}", value);
+    }
+    if rest != 0 {
+        names.push(format!("{:#x}", rest));
+    }
+    format!("{} ({:#x})", names.join(" | "), value)
+}
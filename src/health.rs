@@ -0,0 +1,246 @@
+//! Summary of where the pointers of a traversal led.
+
+use core::fmt;
+
+use crate::prelude::*;
+use crate::{HashMap, PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// Which pointer health summary is printed after the dump, see
+/// [`PointerPrintConfig::health_summary`](crate::PointerPrintConfig::health_summary)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HealthSummary {
+    /// One line with the totals of the whole traversal
+    Totals,
+    /// The totals followed by a line for every field path holding a pointer
+    ByPath,
+}
+
+/// Number of pointers per outcome, see [`PointerHealth`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerHealthCounts {
+    /// Pointers whose target was read
+    pub valid: usize,
+    /// Null pointers
+    pub null: usize,
+    /// Pointers rejected by the configured address checks
+    pub out_of_range: usize,
+    /// Pointers outside of the configured memory map
+    pub unmapped: usize,
+    /// Pointers whose target failed to read
+    pub unreadable: usize,
+    /// Pointers to a target visited before
+    pub cyclic: usize,
+    /// Pointers that weren't followed because of the maximum depth, the read budget, a
+    /// cancellation or a `deref_if` condition
+    pub not_followed: usize,
+}
+
+impl PointerHealthCounts {
+    /// Total number of pointers
+    pub fn total(&self) -> usize {
+        self.valid
+            + self.null
+            + self.out_of_range
+            + self.unmapped
+            + self.unreadable
+            + self.cyclic
+            + self.not_followed
+    }
+
+    /// Returns true if no pointer was out of range, unmapped or unreadable. Null pointers,
+    /// cycles and pointers that weren't followed are a normal part of a dump
+    pub fn is_healthy(&self) -> bool {
+        self.out_of_range + self.unmapped + self.unreadable == 0
+    }
+
+    /// Count a pointer to `target`, returns false for targets that aren't the outcome of
+    /// a single pointer
+    fn add(&mut self, target: &PointerTarget) -> bool {
+        let count = match target {
+            PointerTarget::Node(_)
+            | PointerTarget::Text(_)
+            | PointerTarget::Bytes(_)
+            | PointerTarget::Array { .. } => &mut self.valid,
+            PointerTarget::Null => &mut self.null,
+            PointerTarget::Invalid => &mut self.out_of_range,
+            PointerTarget::Unmapped => &mut self.unmapped,
+            PointerTarget::ReadError(_) => &mut self.unreadable,
+            PointerTarget::AlreadyVisited => &mut self.cyclic,
+            PointerTarget::MaxDepth
+            | PointerTarget::BudgetExhausted
+            | PointerTarget::Cancelled
+            | PointerTarget::NotFollowed => &mut self.not_followed,
+            PointerTarget::Chain { .. } | PointerTarget::List { .. } | PointerTarget::ChainLink => {
+                return false;
+            }
+        };
+        *count += 1;
+        true
+    }
+}
+
+/// Prints the non-zero counts like `3 null, 1 unreadable`, the valid pointers first
+impl fmt::Display for PointerHealthCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = [
+            (self.valid, "valid"),
+            (self.null, "null"),
+            (self.out_of_range, "out of range"),
+            (self.unmapped, "unmapped"),
+            (self.unreadable, "unreadable"),
+            (self.cyclic, "cyclic"),
+            (self.not_followed, "not followed"),
+        ];
+        let mut first = true;
+        for (count, name) in counts.into_iter().filter(|(count, _)| *count > 0) {
+            write!(f, "{}{} {}", if first { "" } else { ", " }, count, name)?;
+            first = false;
+        }
+        if first {
+            f.write_str("none")?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the pointers of a collected tree led, in total and for every field path.
+///
+/// A wrong base address shows up as most pointers being out of range, unmapped or
+/// unreadable across all paths, a stale field as the failures piling up under one path.
+/// The elements of arrays, chains and lists are grouped under one path with `[]` in place
+/// of their index, so `players[].team` counts the `team` pointer of every player. The
+/// elements of chains and lists count as a pointer each.
+///
+/// ```rust
+/// use memflow_pointer_debug::{
+///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerHealth, PointerTarget,
+/// };
+///
+/// let pointer = |name: &str, address, target| PointerDebugField {
+///     name: name.to_string(),
+///     type_name: "Team".to_string(),
+///     value: PointerDebugValue::Pointer { address, target },
+/// };
+/// let mut root = PointerDebugNode::new("Player");
+/// root.fields.push(pointer("team", 0x2000, PointerTarget::Node(Box::new(PointerDebugNode::new("Team")))));
+/// root.fields.push(pointer("rival", 0, PointerTarget::Null));
+/// root.fields.push(pointer("guild", 0x10, PointerTarget::Invalid));
+///
+/// let health = PointerHealth::from_node(&root);
+/// assert_eq!(health.total.to_string(), "1 valid, 1 null, 1 out of range");
+/// assert_eq!(health.path("rival").unwrap().null, 1);
+/// assert!(!health.total.is_healthy());
+/// assert!(health.path("team").unwrap().is_healthy());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerHealth {
+    /// Counts of every pointer of the tree
+    pub total: PointerHealthCounts,
+    /// Counts of the pointers at each field path, in the order the paths were first seen
+    pub paths: Vec<(String, PointerHealthCounts)>,
+}
+
+impl PointerHealth {
+    /// Count the pointers of a collected tree
+    pub fn from_node(root: &PointerDebugNode) -> Self {
+        let mut builder = Builder {
+            health: Self::default(),
+            indices: HashMap::new(),
+        };
+        builder.add_node("", root);
+        builder.health
+    }
+
+    /// Counts of the pointers at `path`, `None` if there is no pointer at the path
+    pub fn path(&self, path: &str) -> Option<&PointerHealthCounts> {
+        self.paths
+            .iter()
+            .find(|(name, _)| name == path)
+            .map(|(_, counts)| counts)
+    }
+
+    /// Write the summary printed after the dump, every line starts with `... `
+    pub(crate) fn render(&self, summary: HealthSummary) -> String {
+        let mut out = format!("... pointers: {}\n", self.total);
+        if summary == HealthSummary::ByPath {
+            for (path, counts) in &self.paths {
+                out += &format!("...   {}: {}\n", path, counts);
+            }
+        }
+        out
+    }
+}
+
+/// Walks a tree and counts its pointers
+struct Builder {
+    health: PointerHealth,
+    /// Position of each path in `health.paths`
+    indices: HashMap<String, usize>,
+}
+
+impl Builder {
+    fn add_node(&mut self, prefix: &str, node: &PointerDebugNode) {
+        for field in &node.fields {
+            // Elements of embedded arrays are grouped like array pointers
+            let path = if field.name.starts_with('[') {
+                format!("{}[]", prefix)
+            } else if prefix.is_empty() {
+                field.name.clone()
+            } else {
+                format!("{}.{}", prefix, field.name)
+            };
+            match &field.value {
+                PointerDebugValue::Pointer { target, .. } => self.add_pointer(&path, target),
+                PointerDebugValue::Nested(nested) => self.add_node(&path, nested),
+                PointerDebugValue::Value(_) => {}
+            }
+        }
+    }
+
+    /// Count a pointer at `path` leading to `target` and the pointers behind it
+    fn add_pointer(&mut self, path: &str, target: &PointerTarget) {
+        if self.health.total.add(target) {
+            self.count(path).add(target);
+        }
+        self.add_target(path, target);
+    }
+
+    /// Count the pointers behind `target` at `path`
+    fn add_target(&mut self, path: &str, target: &PointerTarget) {
+        match target {
+            PointerTarget::Node(node) => self.add_node(path, node),
+            PointerTarget::Array { items, .. } => {
+                let path = format!("{}[]", path);
+                for item in items {
+                    self.add_target(&path, item);
+                }
+            }
+            PointerTarget::Chain { items, .. } => {
+                let path = format!("{}[]", path);
+                for item in items {
+                    self.add_pointer(&path, item);
+                }
+            }
+            PointerTarget::List { items, .. } => {
+                let path = format!("{}[]", path);
+                for (_, item) in items {
+                    self.add_pointer(&path, item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The counts of `path`, added the first time it is seen
+    fn count(&mut self, path: &str) -> &mut PointerHealthCounts {
+        let paths = &mut self.health.paths;
+        let index = *self.indices.entry(path.to_string()).or_insert_with(|| {
+            paths.push((path.to_string(), PointerHealthCounts::default()));
+            paths.len() - 1
+        });
+        &mut paths[index].1
+    }
+}
@@ -0,0 +1,49 @@
+//! Hex dumps of raw pointer targets.
+
+use core::fmt::Write;
+
+use crate::prelude::*;
+
+/// Number of bytes on each line of a hex dump
+const LINE_BYTES: usize = 16;
+
+/// Format `bytes` as a classic hex dump with one line per 16 bytes: the offset, the bytes
+/// in hex and the printable ASCII characters, used for fields marked
+/// `#[pointer_debug(hexdump = 64)]`.
+///
+/// ```rust
+/// use memflow_pointer_debug::format_hexdump;
+///
+/// assert_eq!(
+///     format_hexdump(b"Hello, world!\0\0\0\x01\x02"),
+///     "0000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 00 00  |Hello, world!...|\n\
+///      0010  01 02                                             |..|"
+/// );
+/// ```
+pub fn format_hexdump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(LINE_BYTES).enumerate() {
+        if line > 0 {
+            out.push('\n');
+        }
+        write!(out, "{:04x} ", line * LINE_BYTES).unwrap();
+        for i in 0..LINE_BYTES {
+            // The two halves of a line are separated by an extra space
+            if i % 8 == 0 {
+                out.push(' ');
+            }
+            match chunk.get(i) {
+                Some(byte) => write!(out, "{:02x} ", byte).unwrap(),
+                None => out.push_str("   "),
+            }
+        }
+        out.push(' ');
+        out.push('|');
+        out.extend(chunk.iter().map(|&byte| match byte {
+            0x20..=0x7e => byte as char,
+            _ => '.',
+        }));
+        out.push('|');
+    }
+    out
+}
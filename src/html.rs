@@ -0,0 +1,270 @@
+//! HTML export of a collected tree.
+//!
+//! [`PointerDebugNode::to_html`] writes a single self-contained page, every structure is a
+//! collapsible `<details>` element and a search box opens and highlights the matches.
+
+use core::fmt::Write;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::{PointerDebugNode, PointerDebugValue, PointerTarget, format_hexdump};
+
+const STYLE: &str = "\
+body { font-family: monospace; margin: 1em; }
+#search { width: 40em; margin-bottom: 1em; }
+ul { list-style: none; margin: 0; padding-left: 1.5em; }
+summary { cursor: pointer; }
+.field { color: #00749e; }
+.type { color: #2e7d32; }
+.address { color: #8e24aa; }
+.error { color: #c62828; }
+.skipped { color: #ef6c00; }
+.dim { color: #888; }
+.match { background: #fff59d; }
+";
+
+const SCRIPT: &str = "\
+const search = document.getElementById('search');
+const count = document.getElementById('count');
+search.addEventListener('input', () => {
+  const needle = search.value.toLowerCase();
+  let matches = 0;
+  for (const item of document.querySelectorAll('li, details')) {
+    const label = item.tagName === 'DETAILS' ? item.querySelector('summary') : item;
+    const own = item.tagName === 'LI' && item.querySelector('details') ? null : label;
+    const hit = needle !== '' && own !== null && own.textContent.toLowerCase().includes(needle);
+    item.classList.toggle('match', hit);
+    if (hit) {
+      matches++;
+      for (let parent = item.parentElement; parent; parent = parent.parentElement) {
+        if (parent.tagName === 'DETAILS') parent.open = true;
+      }
+    }
+  }
+  count.textContent = needle === '' ? '' : matches + ' match(es)';
+});
+";
+
+impl PointerDebugNode {
+    /// Render this tree as a self-contained HTML page.
+    ///
+    /// Every structure, array, chain and list is a collapsible `<details>` element showing
+    /// the address it was read from, pointers to already visited addresses link to the
+    /// first occurrence. The search box at the top highlights the fields, types, values and
+    /// addresses containing the search term and expands everything leading to them.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>\n{}</style>\n</head>\n<body>\n\
+             <input id=\"search\" type=\"search\" placeholder=\"Search fields, types, values and addresses\"> \
+             <span id=\"count\"></span>\n",
+            escape(&self.type_name),
+            STYLE
+        );
+
+        out.push_str("<details open>\n<summary>");
+        write_summary(&mut out, None, self);
+        out.push_str("</summary>\n");
+        write_fields(&mut out, self);
+        out.push_str("</details>\n");
+
+        let _ = write!(out, "<script>\n{}</script>\n</body>\n</html>\n", SCRIPT);
+        out
+    }
+}
+
+/// Write `label Type @ 0x...` of a node, scalars include their value
+fn write_summary(out: &mut String, label: Option<&str>, node: &PointerDebugNode) {
+    if let Some(label) = label {
+        let _ = write!(out, "<span class=\"field\">{}</span> → ", escape(label));
+    }
+    let _ = write!(
+        out,
+        "<span class=\"type\">{}</span>",
+        escape(&node.type_name)
+    );
+    if node.misaligned {
+        out.push_str(" <span class=\"error\">⚠ misaligned</span>");
+    }
+    if let Some(address) = node.address {
+        write_address(out, address);
+    }
+    if let Some(value) = &node.value {
+        let _ = write!(out, " = {}", escape(value));
+    }
+}
+
+fn write_address(out: &mut String, address: u64) {
+    let _ = write!(out, " <span class=\"address\">@ {:#x}</span>", address);
+}
+
+/// Write the fields of `node` as a list
+fn write_fields(out: &mut String, node: &PointerDebugNode) {
+    out.push_str("<ul>\n");
+    for field in &node.fields {
+        let name = escape(&field.name);
+        match &field.value {
+            PointerDebugValue::Value(value) => {
+                let _ = writeln!(
+                    out,
+                    "<li><span class=\"field\">{}</span>: <span class=\"type\">{}</span> = {}</li>",
+                    name,
+                    escape(&field.type_name),
+                    escape(value)
+                );
+            }
+            PointerDebugValue::Pointer { address, target } => {
+                write_target(out, &field.name, *address, target)
+            }
+            PointerDebugValue::Nested(node) => {
+                let _ = write!(
+                    out,
+                    "<li><details>\n<summary><span class=\"field\">{}</span>: ",
+                    name
+                );
+                write_summary(out, None, node);
+                out.push_str("</summary>\n");
+                write_fields(out, node);
+                out.push_str("</details></li>\n");
+            }
+        }
+    }
+    out.push_str("</ul>\n");
+}
+
+/// Write the target of a pointer to `address` labeled `label` as a list item
+fn write_target(out: &mut String, label: &str, address: u64, target: &PointerTarget) {
+    let name = escape(label);
+    let line = |out: &mut String, class: &str, text: &str| {
+        let _ = writeln!(
+            out,
+            "<li><span class=\"field\">{}</span>: <span class=\"{}\">{} {:#x}</span></li>",
+            name, class, text, address
+        );
+    };
+
+    match target {
+        PointerTarget::Node(node) => {
+            let _ = write!(out, "<li><details id=\"{:#x}\">\n<summary>", address);
+            write_summary(out, Some(label), node);
+            out.push_str("</summary>\n");
+            write_fields(out, node);
+            out.push_str("</details></li>\n");
+        }
+        PointerTarget::AlreadyVisited => {
+            let _ = writeln!(
+                out,
+                "<li><span class=\"field\">{}</span> → <a class=\"dim\" href=\"#{:#x}\">already visited {:#x}</a></li>",
+                name, address, address
+            );
+        }
+        PointerTarget::Text(text) => {
+            let _ = write!(
+                out,
+                "<li><span class=\"field\">{}</span> → {}",
+                name,
+                escape(&format!("{:?}", text))
+            );
+            write_address(out, address);
+            out.push_str("</li>\n");
+        }
+        PointerTarget::Bytes(bytes) => {
+            let _ = write!(
+                out,
+                "<li><span class=\"field\">{}</span> → {} bytes",
+                name,
+                bytes.len()
+            );
+            write_address(out, address);
+            let _ = writeln!(out, "<pre>{}</pre></li>", escape(&format_hexdump(bytes)));
+        }
+        PointerTarget::Array { len, items } => {
+            let items: Vec<_> = items
+                .iter()
+                .map(|item| (item_address(item, address), item))
+                .collect();
+            let more = len.saturating_sub(items.len());
+            let rest = (more > 0).then(|| format!("... ({} more)", more));
+            write_items(out, label, &format!("[{}]", len), address, items, rest);
+        }
+        PointerTarget::Chain { items, more } => {
+            let items: Vec<_> = chain_addresses(address, items)
+                .into_iter()
+                .zip(items)
+                .collect();
+            let rest = more.then(|| "... (chain continues)".to_string());
+            write_items(out, label, "chain", address, items, rest);
+        }
+        PointerTarget::List { items, more } => {
+            let items = items
+                .iter()
+                .map(|(address, item)| (*address, item))
+                .collect();
+            let rest = more.then(|| "... (list continues)".to_string());
+            write_items(out, label, "list", address, items, rest);
+        }
+        PointerTarget::Null => {
+            let _ = writeln!(
+                out,
+                "<li><span class=\"field\">{}</span>: <span class=\"dim\">NULL</span></li>",
+                name
+            );
+        }
+        PointerTarget::MaxDepth => line(out, "dim", "max depth reached at"),
+        PointerTarget::ChainLink => line(out, "dim", "next in chain"),
+        PointerTarget::Invalid => line(out, "skipped", "invalid"),
+        PointerTarget::Unmapped => line(out, "skipped", "unmapped"),
+        PointerTarget::BudgetExhausted => line(out, "skipped", "budget exhausted"),
+        PointerTarget::Cancelled => line(out, "skipped", "cancelled"),
+        PointerTarget::NotFollowed => line(out, "skipped", "not followed"),
+        PointerTarget::ReadError(e) => line(
+            out,
+            "error",
+            &format!("error reading: {} at", escape(&e.to_string())),
+        ),
+    }
+}
+
+/// Write the elements of an array, chain or list at `address` as a collapsible list
+fn write_items(
+    out: &mut String,
+    label: &str,
+    kind: &str,
+    address: u64,
+    items: Vec<(u64, &PointerTarget)>,
+    rest: Option<String>,
+) {
+    let _ = write!(
+        out,
+        "<li><details>\n<summary><span class=\"field\">{}</span> → {}",
+        escape(label),
+        kind
+    );
+    write_address(out, address);
+    out.push_str("</summary>\n<ul>\n");
+    for (i, (address, item)) in items.into_iter().enumerate() {
+        write_target(out, &format!("[{}]", i), address, item);
+    }
+    if let Some(rest) = rest {
+        let _ = writeln!(out, "<li class=\"dim\">{}</li>", rest);
+    }
+    out.push_str("</ul>\n</details></li>\n");
+}
+
+/// Escape text for use in HTML content and attribute values
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
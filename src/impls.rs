@@ -0,0 +1,354 @@
+//! [`DerefDebugPrint`] implementations for pointers, std containers and scalar types.
+
+use memflow::dataview::{Pod, PodMethods};
+use memflow::mem::MemoryView;
+use memflow::types::{Address, Pointer, PrimitiveAddress};
+
+use crate::prelude::*;
+use crate::{
+    ByteOrder, DerefDebugPrint, FormatNumber, PointerDebugField, PointerDebugNode,
+    PointerDebugValue, PointerPrintConfig,
+};
+use crate::{PointerLike, TraversalState};
+
+/// Name of a type without module paths, e.g. `Vec<Pointer<u64, Node>>`
+pub(crate) fn short_type_name<T: ?Sized>() -> String {
+    let name = core::any::type_name::<T>();
+    let mut out = String::with_capacity(name.len());
+
+    // Every path ends at a delimiter, only its last segment is kept
+    let mut path_start = 0;
+    for (i, c) in name.char_indices() {
+        if !(c.is_alphanumeric() || c == '_' || c == ':') {
+            out.push_str(last_segment(&name[path_start..i]));
+            out.push(c);
+            path_start = i + c.len_utf8();
+        }
+    }
+    out.push_str(last_segment(&name[path_start..]));
+
+    out
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Collect `items` as the fields of a node named `type_name`
+fn collect_items<'a, T, M, I>(
+    type_name: String,
+    items: I,
+    mem: &mut M,
+    depth: usize,
+    state: &mut TraversalState,
+) -> PointerDebugNode
+where
+    T: DerefDebugPrint + 'a,
+    M: MemoryView,
+    I: IntoIterator<Item = (String, &'a T)>,
+{
+    let mut node = PointerDebugNode::new(type_name);
+    for (name, item) in items {
+        state.enter_field(&name);
+        let value = item.pointer_collect_value(mem, depth, state);
+        state.leave_field();
+        node.fields.push(PointerDebugField {
+            name,
+            type_name: short_type_name::<T>(),
+            value,
+        });
+    }
+    node
+}
+
+impl<U: PrimitiveAddress, T: DerefDebugPrint + Pod> DerefDebugPrint for Pointer<U, T> {
+    /// A pointer printed on its own is shown as a node with its target as the only field
+    fn pointer_collect_internal<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        let mut node = PointerDebugNode::new(short_type_name::<Self>());
+        state.enter_field("*");
+        let value = self.pointer_collect_value(mem, depth, state);
+        state.leave_field();
+        node.fields.push(PointerDebugField {
+            name: "*".to_string(),
+            type_name: short_type_name::<T>(),
+            value,
+        });
+        node
+    }
+
+    fn pointer_collect_value<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugValue
+    where
+        M: MemoryView,
+    {
+        self.collect_target(mem, depth, state)
+    }
+
+    fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+        targets.push(TraversalState::pointer_target(self));
+    }
+
+    fn pointer_to_host_order(&mut self, order: ByteOrder) {
+        PointerLike::to_host_order(self, order);
+    }
+}
+
+impl<T: DerefDebugPrint> DerefDebugPrint for [T] {
+    fn pointer_collect_internal<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        state.prefetch_targets(self, mem, depth);
+        let items = self
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (format!("[{}]", i), item));
+        collect_items(short_type_name::<Self>(), items, mem, depth, state)
+    }
+
+    fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+        for item in self {
+            item.pointer_targets(targets);
+        }
+    }
+
+    fn pointer_to_host_order(&mut self, order: ByteOrder) {
+        for item in self {
+            item.pointer_to_host_order(order);
+        }
+    }
+}
+
+impl<T: DerefDebugPrint, const N: usize> DerefDebugPrint for [T; N] {
+    fn pointer_collect_internal<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        let mut node = self.as_slice().pointer_collect_internal(mem, depth, state);
+        node.type_name = short_type_name::<Self>();
+        node
+    }
+
+    fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+        self.as_slice().pointer_targets(targets);
+    }
+
+    fn pointer_to_host_order(&mut self, order: ByteOrder) {
+        self.as_mut_slice().pointer_to_host_order(order);
+    }
+}
+
+impl<T: DerefDebugPrint> DerefDebugPrint for Vec<T> {
+    fn pointer_collect_internal<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        let mut node = self.as_slice().pointer_collect_internal(mem, depth, state);
+        node.type_name = short_type_name::<Self>();
+        node
+    }
+
+    fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+        self.as_slice().pointer_targets(targets);
+    }
+}
+
+/// `Some` values are collected like the contained value, `None` is printed as is
+impl<T: DerefDebugPrint> DerefDebugPrint for Option<T> {
+    fn pointer_collect_internal<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        match self {
+            Some(value) => value.pointer_collect_internal(mem, depth, state),
+            None => PointerDebugNode::new("None"),
+        }
+    }
+
+    fn pointer_collect_value<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugValue
+    where
+        M: MemoryView,
+    {
+        match self {
+            Some(value) => value.pointer_collect_value(mem, depth, state),
+            None => PointerDebugValue::Value("None".to_string()),
+        }
+    }
+
+    fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+        if let Some(value) = self {
+            value.pointer_targets(targets);
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: DerefDebugPrint),+> DerefDebugPrint for ($($name,)+) {
+            fn pointer_collect_internal<M>(
+                &self,
+                mem: &mut M,
+                depth: usize,
+                state: &mut TraversalState,
+            ) -> PointerDebugNode
+            where
+                M: MemoryView,
+            {
+                let mut node = PointerDebugNode::new(short_type_name::<Self>());
+                state.prefetch_targets(self, mem, depth);
+                $(
+                    state.enter_field(stringify!($index));
+                    let value = self.$index.pointer_collect_value(mem, depth, state);
+                    state.leave_field();
+                    node.fields.push(PointerDebugField {
+                        name: stringify!($index).to_string(),
+                        type_name: short_type_name::<$name>(),
+                        value,
+                    });
+                )+
+                node
+            }
+
+            fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+                $(self.$index.pointer_targets(targets);)+
+            }
+
+            fn pointer_to_host_order(&mut self, order: ByteOrder) {
+                $(self.$index.pointer_to_host_order(order);)+
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0);
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+macro_rules! impl_scalar {
+    (@impl $ty:ty, $text:path { $($host_order:tt)* }) => {
+        impl DerefDebugPrint for $ty {
+            fn pointer_collect_internal<M>(
+                &self,
+                _mem: &mut M,
+                _depth: usize,
+                state: &mut TraversalState,
+            ) -> PointerDebugNode
+            where
+                M: MemoryView,
+            {
+                PointerDebugNode::scalar(stringify!($ty), $text(self, &state.config))
+            }
+
+            fn pointer_collect_value<M>(
+                &self,
+                _mem: &mut M,
+                _depth: usize,
+                state: &mut TraversalState,
+            ) -> PointerDebugValue
+            where
+                M: MemoryView,
+            {
+                PointerDebugValue::Value($text(self, &state.config))
+            }
+
+            $($host_order)*
+        }
+    };
+    ($($ty:ty),+) => {
+        $(
+            impl_scalar!(@impl $ty, FormatNumber::format_number {
+                fn pointer_to_host_order(&mut self, order: ByteOrder) {
+                    if order != ByteOrder::native() {
+                        self.as_bytes_mut().reverse();
+                    }
+                }
+            });
+        )+
+    };
+}
+
+impl_scalar!(u8, u16, u32, u64, u128, usize);
+impl_scalar!(i8, i16, i32, i64, i128, isize);
+impl_scalar!(f32, f64);
+// A single byte has no byte order
+impl_scalar!(@impl bool, bool_text {});
+
+/// Booleans print as `true` and `false`, the numeric options don't apply to them
+fn bool_text(value: &bool, _config: &PointerPrintConfig) -> String {
+    value.to_string()
+}
+
+/// Addresses are printed in hex with a `0x` prefix
+impl DerefDebugPrint for Address {
+    fn pointer_collect_internal<M>(
+        &self,
+        _mem: &mut M,
+        _depth: usize,
+        _state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        PointerDebugNode::scalar("Address", format!("{:#x}", self.to_umem()))
+    }
+
+    fn pointer_collect_value<M>(
+        &self,
+        _mem: &mut M,
+        _depth: usize,
+        _state: &mut TraversalState,
+    ) -> PointerDebugValue
+    where
+        M: MemoryView,
+    {
+        PointerDebugValue::Value(format!("{:#x}", self.to_umem()))
+    }
+
+    fn pointer_to_host_order(&mut self, order: ByteOrder) {
+        if order != ByteOrder::native() {
+            *self = Address::from(self.to_umem().swap_bytes());
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! Repeated dumps of one structure that only read and print what changed.
+
+use core::fmt;
+
+use memflow::error::Result;
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+
+use crate::prelude::*;
+use crate::read::read_each;
+use crate::retry::RetryView;
+use crate::{
+    DerefDebugPrint, HashMap, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+    PointerTarget, TraversalState,
+};
+
+/// Every read of a pass by address and length, `None` if it failed
+type Reads = HashMap<(u64, usize), Option<Vec<u8>>>;
+
+/// Dumps of a structure that is read again and again, e.g. once per frame.
+///
+/// The first [`update`](Self::update) collects the whole tree and remembers the bytes of
+/// every read. The next ones read all of those again in one batch and compare them byte by
+/// byte: if nothing changed, the pass ends there without traversing the structure. Otherwise
+/// the structure is traversed again, the unchanged memory is served from the reads that were
+/// just made and only new targets are read one by one. The pass returns the topmost nodes
+/// whose memory changed, see [`ChangedSubtree`].
+///
+/// Values passed by reference aren't read again, only what their pointers lead to. To track
+/// a structure itself, track a pointer to it.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{IncrementalDump, PointerDerefDebugPrint, PointerPrintConfig};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     _pad: u32,
+///     team: Pointer64<Team>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Team {
+///     id: u64,
+/// }
+///
+/// fn poll(mem: &mut impl MemoryView, player: Pointer64<Player>) {
+///     let config = PointerPrintConfig::new();
+///     let mut dump = IncrementalDump::new(&player, &config);
+///     for _frame in 0..1000 {
+///         // The first frame prints the whole player, the later ones print
+///         // `*.team-> Team { ... }` whenever the team changed
+///         for changed in dump.update(mem) {
+///             print!("{}", changed.display(&config));
+///         }
+///     }
+/// }
+/// ```
+pub struct IncrementalDump<'a, T: ?Sized> {
+    value: &'a T,
+    config: PointerPrintConfig,
+    /// Reads of the last pass
+    reads: Reads,
+    /// Tree of the last pass that traversed the structure
+    tree: Option<PointerDebugNode>,
+}
+
+impl<'a, T: DerefDebugPrint + ?Sized> IncrementalDump<'a, T> {
+    /// Track `value` collected with the options of `config`, nothing is read yet
+    pub fn new(value: &'a T, config: &PointerPrintConfig) -> Self {
+        Self {
+            value,
+            config: config.clone(),
+            reads: Reads::new(),
+            tree: None,
+        }
+    }
+
+    /// The tree of the latest pass that read something new, `None` before the first
+    /// [`update`](Self::update)
+    pub fn tree(&self) -> Option<&PointerDebugNode> {
+        self.tree.as_ref()
+    }
+
+    /// Read the structure again, returns the subtrees whose memory changed since the last
+    /// pass in the order they are printed. The first pass returns the whole tree with an
+    /// empty path
+    pub fn update<M: MemoryView>(&mut self, mem: &mut M) -> Vec<ChangedSubtree> {
+        if self.tree.is_none() {
+            let node = self.traverse(mem, &Reads::new());
+            self.tree = Some(node.clone());
+            return vec![ChangedSubtree {
+                path: String::new(),
+                node,
+            }];
+        }
+
+        let current = refresh(mem, &self.reads);
+        let changed: Vec<(u64, usize)> = current
+            .iter()
+            .filter(|(key, bytes)| self.reads.get(*key) != Some(*bytes))
+            .map(|(key, _)| *key)
+            .collect();
+        if changed.is_empty() {
+            return Vec::new();
+        }
+
+        let node = self.traverse(mem, &current);
+        let is_changed = |address: u64| {
+            changed
+                .iter()
+                .any(|(start, len)| (*start..*start + *len as u64).contains(&address))
+        };
+        let mut subtrees = Vec::new();
+        changed_subtrees(&node, "", &is_changed, &mut subtrees);
+        self.tree = Some(node);
+        subtrees
+    }
+
+    /// Print the subtrees returned by [`update`](Self::update) like
+    /// [`pointer_print_with_config`](crate::PointerPrint::pointer_print_with_config), nothing
+    /// is printed if nothing changed
+    #[cfg(feature = "std")]
+    pub fn print<M: MemoryView>(&mut self, mem: &mut M) {
+        let output: String = self
+            .update(mem)
+            .iter()
+            .map(|changed| changed.display(&self.config).to_string())
+            .collect();
+        if !output.is_empty() {
+            crate::write_output(&output, &self.config);
+        }
+    }
+
+    /// Collect the structure, the reads found in `known` are served from there and
+    /// everything read is kept for the next pass
+    fn traverse<M: MemoryView>(&mut self, mem: &mut M, known: &Reads) -> PointerDebugNode {
+        let mut reads = Reads::new();
+        let mut mem = RetryView::new(mem, &self.config);
+        let mut view = PassView {
+            mem: &mut mem,
+            known,
+            reads: &mut reads,
+        };
+        let mut state = TraversalState::with_config(&self.config);
+        let node = self
+            .value
+            .pointer_collect_internal(&mut view, 0, &mut state);
+        self.reads = reads;
+        node
+    }
+}
+
+/// A subtree returned by [`IncrementalDump::update`], the memory of its node changed.
+///
+/// Nodes whose own bytes changed are returned, and the nodes holding a string, array or
+/// hex dump that changed. Their whole subtree is read again, the changed nodes below them
+/// are not returned on their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedSubtree {
+    /// Path of the pointer leading to the node like `*.team` or `players[2]`, empty for
+    /// the root
+    pub path: String,
+    /// The node as it was read now
+    pub node: PointerDebugNode,
+}
+
+impl ChangedSubtree {
+    /// Render the subtree with the options of `config` as `path-> Type { ... }`
+    pub fn display<'a>(&'a self, config: &'a PointerPrintConfig) -> ChangedSubtreeDisplay<'a> {
+        ChangedSubtreeDisplay {
+            changed: self,
+            config,
+        }
+    }
+}
+
+/// A [`ChangedSubtree`] rendered with a [`PointerPrintConfig`], see
+/// [`ChangedSubtree::display`]
+#[derive(Debug, Clone, Copy)]
+pub struct ChangedSubtreeDisplay<'a> {
+    changed: &'a ChangedSubtree,
+    config: &'a PointerPrintConfig,
+}
+
+impl fmt::Display for ChangedSubtreeDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.changed.path.is_empty() {
+            write!(f, "{}-> ", self.changed.path)?;
+        }
+        write!(f, "{}", self.changed.node.display(self.config))
+    }
+}
+
+/// Read everything of `reads` again, the reads that succeeded last time in one batch
+fn refresh<M: MemoryView>(mem: &mut M, reads: &Reads) -> Reads {
+    let mut buffers: Vec<((u64, usize), Vec<u8>)> = reads
+        .iter()
+        .filter(|(_, bytes)| bytes.is_some())
+        .map(|(key, _)| (*key, vec![0u8; key.1]))
+        .collect();
+    let committed = {
+        let mut batcher = mem.batcher();
+        for ((address, _), buffer) in buffers.iter_mut() {
+            batcher.read_raw_into((*address).into(), buffer);
+        }
+        batcher.commit_rw().is_ok()
+    };
+
+    let mut current = Reads::new();
+    if committed {
+        current.extend(buffers.into_iter().map(|(key, bytes)| (key, Some(bytes))));
+    }
+    // Reads that failed are tried one by one, so is everything if the batch failed
+    for &(address, len) in reads.keys() {
+        current.entry((address, len)).or_insert_with(|| {
+            let mut buffer = vec![0u8; len];
+            mem.read_raw_into(address.into(), &mut buffer)
+                .ok()
+                .map(|_| buffer)
+        });
+    }
+    current
+}
+
+/// Push the topmost nodes below `node`, reached through `path`, whose memory changed
+fn changed_subtrees(
+    node: &PointerDebugNode,
+    path: &str,
+    is_changed: &impl Fn(u64) -> bool,
+    out: &mut Vec<ChangedSubtree>,
+) {
+    if node.address.is_some_and(is_changed) || holds_changed(node, is_changed) {
+        out.push(ChangedSubtree {
+            path: path.to_string(),
+            node: node.clone(),
+        });
+        return;
+    }
+    for_each_pointer(node, path, &mut |path, _, target| {
+        changed_targets(target, path, is_changed, out)
+    });
+}
+
+/// Push the topmost changed nodes of `target`, reached through `path`
+fn changed_targets(
+    target: &PointerTarget,
+    path: String,
+    is_changed: &impl Fn(u64) -> bool,
+    out: &mut Vec<ChangedSubtree>,
+) {
+    match target {
+        PointerTarget::Node(node) => changed_subtrees(node, &path, is_changed, out),
+        PointerTarget::Array { items, .. } | PointerTarget::Chain { items, .. } => {
+            for (i, item) in items.iter().enumerate() {
+                changed_targets(item, format!("{}[{}]", path, i), is_changed, out);
+            }
+        }
+        PointerTarget::List { items, .. } => {
+            for (i, (_, item)) in items.iter().enumerate() {
+                changed_targets(item, format!("{}[{}]", path, i), is_changed, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns true if a pointer of `node` leads to changed memory that isn't a node of its
+/// own: a string, a hex dump, the elements of an array read in one go or a failed read
+fn holds_changed(node: &PointerDebugNode, is_changed: &impl Fn(u64) -> bool) -> bool {
+    let mut changed = false;
+    for_each_pointer(node, "", &mut |_, address, target| {
+        changed |= match target {
+            PointerTarget::Node(_) | PointerTarget::Chain { .. } | PointerTarget::List { .. } => {
+                false
+            }
+            _ => is_changed(address),
+        };
+    });
+    changed
+}
+
+/// Call `f` with the path, address and target of every pointer field of `node` and of the
+/// structures embedded in it, `node` is reached through `path`
+fn for_each_pointer(
+    node: &PointerDebugNode,
+    path: &str,
+    f: &mut impl FnMut(String, u64, &PointerTarget),
+) {
+    for field in &node.fields {
+        let path = if path.is_empty() || field.name.starts_with('[') {
+            format!("{}{}", path, field.name)
+        } else {
+            format!("{}.{}", path, field.name)
+        };
+        match &field.value {
+            PointerDebugValue::Pointer { address, target } => f(path, *address, target),
+            PointerDebugValue::Nested(nested) => for_each_pointer(nested, &path, f),
+            PointerDebugValue::Value(_) => {}
+        }
+    }
+}
+
+/// The memory of one pass, reads made before are served from `known`
+struct PassView<'a, M> {
+    mem: &'a mut M,
+    known: &'a Reads,
+    reads: &'a mut Reads,
+}
+
+impl<M: MemoryView> MemoryView for PassView<'_, M> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        read_each(data, |address, buf| {
+            let key = (address.to_umem(), buf.len());
+            let bytes = match self.known.get(&key) {
+                Some(bytes) => bytes.clone(),
+                None => self
+                    .mem
+                    .read_raw_into(address, buf)
+                    .ok()
+                    .map(|_| buf.to_vec()),
+            };
+            if let Some(bytes) = &bytes {
+                buf.copy_from_slice(bytes);
+            }
+            let read = bytes.is_some();
+            self.reads.insert(key, bytes);
+            read
+        })
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.mem.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.mem.metadata()
+    }
+}
@@ -0,0 +1,265 @@
+//! egui widget exploring a structure with lazily read pointers.
+
+use egui::{CollapsingHeader, Color32, RichText, Ui};
+use memflow::mem::MemoryView;
+
+use crate::node::{chain_addresses, collect_path, item_address, splice_path, target_note};
+use crate::{
+    DerefDebugPrint, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget,
+    format_hexdump,
+};
+
+/// An egui widget showing the fields of a structure as a tree of collapsible nodes.
+///
+/// Only the root structure is read at first, a pointer is read once it is expanded: the
+/// fields on the path to it are collected again down to the fields of its target, which
+/// is then added to the tree, nothing off that path is read again. The `max_depth`,
+/// `max_revisits` and `include_paths` of the configuration are ignored, every other option
+/// applies.
+///
+/// ```rust,no_run
+/// use memflow::prelude::v1::{MemoryView, Pod, Pointer64};
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerInspector};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// fn inspect(ui: &mut egui::Ui, inspector: &mut PointerInspector<Node>, mem: &mut impl MemoryView) {
+///     inspector.show(ui, mem);
+/// }
+/// ```
+pub struct PointerInspector<T> {
+    value: T,
+    config: PointerPrintConfig,
+    /// Paths of the expanded pointers in the order they were read, with the depth their
+    /// targets were collected to
+    expanded: Vec<(Vec<String>, usize)>,
+    tree: Option<PointerDebugNode>,
+}
+
+impl<T: DerefDebugPrint> PointerInspector<T> {
+    /// Inspect `value` with the default options
+    pub fn new(value: T) -> Self {
+        Self::with_config(value, PointerPrintConfig::new())
+    }
+
+    /// Inspect `value` with the given options
+    pub fn with_config(value: T, config: PointerPrintConfig) -> Self {
+        Self {
+            value,
+            config,
+            expanded: Vec::new(),
+            tree: None,
+        }
+    }
+
+    /// Replace the inspected value, e.g. after reading it again, the expanded nodes stay open
+    pub fn set_value(&mut self, value: T) {
+        self.value = value;
+        self.tree = None;
+    }
+
+    /// Read every expanded pointer again the next time the widget is shown
+    pub fn refresh(&mut self) {
+        self.tree = None;
+    }
+
+    /// Show the widget, the pointers that were just expanded are read from `mem`
+    pub fn show<M: MemoryView>(&mut self, ui: &mut Ui, mem: &mut M) {
+        let mut tree = match self.tree.take() {
+            Some(tree) => tree,
+            None => {
+                let mut tree = collect_path(&self.value, mem, &self.config, &[], 1);
+                for (path, depth) in &self.expanded {
+                    self.read_path(&mut tree, mem, path, *depth);
+                }
+                tree
+            }
+        };
+
+        let mut view = TreeView {
+            path: Vec::new(),
+            opened: Vec::new(),
+        };
+        let id = ui.id().with("pointer_inspector");
+        CollapsingHeader::new(type_text(&tree.type_name))
+            .id_salt(id)
+            .default_open(true)
+            .show(ui, |ui| view.fields(ui, id, &tree, 0));
+
+        // Pointers expanded for the first time are shown on the next frame
+        if !view.opened.is_empty() {
+            for (path, depth) in view.opened {
+                // A path that doesn't lead to a pointer anymore stays unread
+                if !self.expanded.iter().any(|(expanded, _)| *expanded == path) {
+                    self.read_path(&mut tree, mem, &path, depth);
+                    self.expanded.push((path, depth));
+                }
+            }
+            ui.ctx().request_repaint();
+        }
+        self.tree = Some(tree);
+    }
+
+    /// Read the target of the pointer at `path` down to `depth` and put it into `tree`
+    fn read_path<M: MemoryView>(
+        &self,
+        tree: &mut PointerDebugNode,
+        mem: &mut M,
+        path: &[String],
+        depth: usize,
+    ) {
+        let read = collect_path(&self.value, mem, &self.config, path, depth);
+        splice_path(tree, read, path);
+    }
+}
+
+/// Renders one frame of the tree and records the pointers that were expanded
+struct TreeView {
+    /// Field names and indices leading to the field shown
+    path: Vec<String>,
+    /// Paths of the unread pointers that were expanded, with the depth their targets have
+    /// to be collected to
+    opened: Vec<(Vec<String>, usize)>,
+}
+
+impl TreeView {
+    /// Show the fields of `node`, which was collected at `depth`
+    fn fields(&mut self, ui: &mut Ui, id: egui::Id, node: &PointerDebugNode, depth: usize) {
+        if let Some(value) = &node.value {
+            ui.label(value);
+        }
+        for field in &node.fields {
+            let id = id.with(&field.name);
+            self.path.push(field.name.clone());
+            match &field.value {
+                PointerDebugValue::Value(value) => {
+                    ui.horizontal(|ui| {
+                        ui.label(field_text(&field.name));
+                        ui.label(type_text(&field.type_name));
+                        ui.label(format!("= {}", value));
+                    });
+                }
+                PointerDebugValue::Pointer { address, target } => {
+                    self.target(ui, id, &field.name, *address, target, depth)
+                }
+                PointerDebugValue::Nested(nested) => {
+                    CollapsingHeader::new(format!("{}: {}", field.name, nested.type_name))
+                        .id_salt(id)
+                        .show(ui, |ui| self.fields(ui, id, nested, depth));
+                }
+            }
+            self.path.pop();
+        }
+    }
+
+    /// Show the target of a pointer to `address` held by a node at `depth`
+    fn target(
+        &mut self,
+        ui: &mut Ui,
+        id: egui::Id,
+        label: &str,
+        address: u64,
+        target: &PointerTarget,
+        depth: usize,
+    ) {
+        if let Some(note) = target_note(target, address) {
+            // Targets shown elsewhere are pointed at like the ones shown here
+            let text = if note.leads {
+                format!("→ {}", note.text)
+            } else {
+                note.text
+            };
+            ui.horizontal(|ui| {
+                ui.label(field_text(label));
+                ui.weak(text);
+            });
+            return;
+        }
+        match target {
+            PointerTarget::Node(node) => {
+                let title = format!("{} → {} @ {:#x}", label, node.type_name, address);
+                CollapsingHeader::new(title)
+                    .id_salt(id)
+                    .show(ui, |ui| self.fields(ui, id, node, depth + 1));
+            }
+            PointerTarget::MaxDepth => {
+                // Opening the header asks for the next level to be read
+                let title = format!("{} → {:#x}", label, address);
+                let opened = CollapsingHeader::new(title)
+                    .id_salt(id)
+                    .show(ui, |ui| ui.weak("reading..."))
+                    .body_returned
+                    .is_some();
+                if opened {
+                    self.opened.push((self.path.clone(), depth + 2));
+                }
+            }
+            PointerTarget::Array { items, .. } => {
+                let items = items.iter().map(|item| (item_address(item, address), item));
+                self.items(ui, id, label, address, items.collect(), depth);
+            }
+            PointerTarget::Chain { items, .. } => {
+                let items = chain_addresses(address, items).into_iter().zip(items);
+                self.items(ui, id, label, address, items.collect(), depth);
+            }
+            PointerTarget::List { items, .. } => {
+                let items = items.iter().map(|(address, item)| (*address, item));
+                self.items(ui, id, label, address, items.collect(), depth);
+            }
+            PointerTarget::Text(text) => {
+                ui.horizontal(|ui| {
+                    ui.label(field_text(label));
+                    ui.label(format!("→ {:?}", text));
+                });
+            }
+            PointerTarget::Bytes(bytes) => {
+                CollapsingHeader::new(field_text(label))
+                    .id_salt(id)
+                    .show(ui, |ui| ui.monospace(format_hexdump(bytes)));
+            }
+            PointerTarget::ReadError(e) => {
+                ui.horizontal(|ui| {
+                    ui.label(field_text(label));
+                    ui.colored_label(Color32::RED, format!("error reading {:#x}: {}", address, e));
+                });
+            }
+            // Described by the note above
+            _ => {}
+        }
+    }
+
+    /// Show the elements of an array, chain or list, they were collected one level below
+    /// the node at `depth`
+    fn items(
+        &mut self,
+        ui: &mut Ui,
+        id: egui::Id,
+        label: &str,
+        address: u64,
+        items: Vec<(u64, &PointerTarget)>,
+        depth: usize,
+    ) {
+        let title = format!("{} → [{}] @ {:#x}", label, items.len(), address);
+        CollapsingHeader::new(title).id_salt(id).show(ui, |ui| {
+            for (i, (address, item)) in items.into_iter().enumerate() {
+                let label = format!("[{}]", i);
+                self.path.push(label.clone());
+                self.target(ui, id.with(i), &label, address, item, depth);
+                self.path.pop();
+            }
+        });
+    }
+}
+
+fn field_text(name: &str) -> RichText {
+    RichText::new(name).color(Color32::from_rgb(0x00, 0x74, 0x9e))
+}
+
+fn type_text(name: &str) -> RichText {
+    RichText::new(name).color(Color32::from_rgb(0x2e, 0x7d, 0x32))
+}
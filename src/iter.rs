@@ -0,0 +1,250 @@
+//! Iterating the fields of a collected tree as path and value pairs.
+
+use core::fmt;
+
+use memflow::error::Error;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::{PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// Dotted path of a field starting at the root, e.g. `peb.ldr`.
+///
+/// Elements of arrays, chains and lists are appended as `[i]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldPath(String);
+
+impl FieldPath {
+    /// The path as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Take the path as a string
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// The path of the field `name` below this one
+    fn join(&self, name: &str) -> Self {
+        if self.0.is_empty() || name.starts_with('[') {
+            Self(format!("{}{}", self.0, name))
+        } else {
+            Self(format!("{}.{}", self.0, name))
+        }
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for FieldPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for FieldPath {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for FieldPath {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Value of a field yielded by [`PointerIter`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FieldValue {
+    /// A plain value, or a scalar read through a pointer like `Pointer64<u64>`
+    Scalar {
+        /// Declared type of the field
+        type_name: String,
+        /// The value formatted with its `Debug` implementation
+        value: String,
+    },
+    /// A pointer that was followed, the fields of its target come next
+    Pointer {
+        /// The address stored in the pointer
+        address: u64,
+    },
+    /// A pointer that was read as a string
+    Text {
+        /// The address stored in the pointer
+        address: u64,
+        /// The string that was read
+        text: String,
+    },
+    /// A pointer whose target was read as raw bytes
+    Bytes {
+        /// The address stored in the pointer
+        address: u64,
+        /// The bytes that were read
+        bytes: Vec<u8>,
+    },
+    /// Reading the target of a pointer failed
+    ReadError {
+        /// The address stored in the pointer
+        address: u64,
+        /// The error returned by memflow
+        #[cfg_attr(feature = "serde", serde(with = "crate::snapshot::error_code"))]
+        error: Error,
+    },
+    /// A pointer whose target was not read, e.g. because it is null or was already visited
+    Unread {
+        /// The address stored in the pointer
+        address: u64,
+        /// Why the target was not read, one of the variants of [`PointerTarget`] without data
+        target: PointerTarget,
+    },
+}
+
+/// The fields of a collected tree as `(path, value)` pairs in traversal order.
+///
+/// Created by [`PointerDebugNode::into_entries`] and
+/// [`PointerPrint::pointer_iter`](crate::PointerPrint::pointer_iter), both iterate a tree
+/// that was collected in full. Structures embedded by value don't have an entry of their
+/// own, their fields are listed by their path.
+///
+/// ```rust
+/// use memflow_pointer_debug::{
+///     FieldValue, PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget,
+/// };
+///
+/// let mut node = PointerDebugNode::new("Player");
+/// node.fields.push(PointerDebugField {
+///     name: "health".to_string(),
+///     type_name: "u32".to_string(),
+///     value: PointerDebugValue::Value("100".to_string()),
+/// });
+/// node.fields.push(PointerDebugField {
+///     name: "target".to_string(),
+///     type_name: "Pointer64".to_string(),
+///     value: PointerDebugValue::Pointer {
+///         address: 0,
+///         target: PointerTarget::Null,
+///     },
+/// });
+///
+/// let mut entries = node.into_entries();
+/// let (path, value) = entries.next().unwrap();
+/// assert_eq!(path, "health");
+/// assert!(matches!(value, FieldValue::Scalar { .. }));
+///
+/// let nulls: Vec<_> = entries
+///     .filter(|(_, value)| matches!(value, FieldValue::Unread { address: 0, .. }))
+///     .map(|(path, _)| path.into_string())
+///     .collect();
+/// assert_eq!(nulls, ["target"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PointerIter {
+    /// What is left to visit, the next entry comes from the top
+    stack: Vec<(FieldPath, Pending)>,
+}
+
+#[derive(Debug, Clone)]
+enum Pending {
+    Node(PointerDebugNode),
+    Field(PointerDebugField),
+    Target(u64, PointerTarget),
+}
+
+impl PointerDebugNode {
+    /// Iterate the fields of this tree as `(path, value)` pairs, see [`PointerIter`]
+    pub fn into_entries(self) -> PointerIter {
+        PointerIter {
+            stack: vec![(FieldPath(String::new()), Pending::Node(self))],
+        }
+    }
+}
+
+impl PointerIter {
+    /// Push the fields of `node` so they come out in declaration order
+    fn push_node(&mut self, path: FieldPath, node: PointerDebugNode) {
+        for field in node.fields.into_iter().rev() {
+            self.stack
+                .push((path.join(&field.name), Pending::Field(field)));
+        }
+    }
+
+    /// Push the elements of an array, chain or list at `path`
+    fn push_items(&mut self, path: &FieldPath, items: Vec<(u64, PointerTarget)>) {
+        for (i, (address, item)) in items.into_iter().enumerate().rev() {
+            let path = path.join(&format!("[{}]", i));
+            self.stack.push((path, Pending::Target(address, item)));
+        }
+    }
+}
+
+impl Iterator for PointerIter {
+    type Item = (FieldPath, FieldValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, pending) = self.stack.pop()?;
+            let (address, target) = match pending {
+                Pending::Node(node) => {
+                    // Scalars read through a pointer have a value instead of fields
+                    if let Some(value) = node.value {
+                        let type_name = node.type_name;
+                        return Some((path, FieldValue::Scalar { type_name, value }));
+                    }
+                    self.push_node(path, node);
+                    continue;
+                }
+                Pending::Field(field) => match field.value {
+                    PointerDebugValue::Value(value) => {
+                        let type_name = field.type_name;
+                        return Some((path, FieldValue::Scalar { type_name, value }));
+                    }
+                    PointerDebugValue::Pointer { address, target } => (address, target),
+                    PointerDebugValue::Nested(node) => {
+                        self.push_node(path, *node);
+                        continue;
+                    }
+                },
+                Pending::Target(address, target) => (address, target),
+            };
+
+            let items = match target {
+                PointerTarget::Node(node) => {
+                    self.stack.push((path.clone(), Pending::Node(*node)));
+                    return Some((path, FieldValue::Pointer { address }));
+                }
+                PointerTarget::Text(text) => {
+                    return Some((path, FieldValue::Text { address, text }));
+                }
+                PointerTarget::Bytes(bytes) => {
+                    return Some((path, FieldValue::Bytes { address, bytes }));
+                }
+                PointerTarget::ReadError(error) => {
+                    return Some((path, FieldValue::ReadError { address, error }));
+                }
+                PointerTarget::Array { items, .. } => {
+                    let addresses: Vec<_> = items
+                        .iter()
+                        .map(|item| item_address(item, address))
+                        .collect();
+                    addresses.into_iter().zip(items).collect()
+                }
+                PointerTarget::Chain { items, .. } => chain_addresses(address, &items)
+                    .into_iter()
+                    .zip(items)
+                    .collect(),
+                PointerTarget::List { items, .. } => items,
+                target => return Some((path, FieldValue::Unread { address, target })),
+            };
+            self.push_items(&path, items);
+            return Some((path, FieldValue::Pointer { address }));
+        }
+    }
+}
@@ -0,0 +1,625 @@
+//! Structure layouts described at runtime instead of with the derive macro.
+
+use core::error;
+use core::fmt;
+use core::str::FromStr;
+
+use memflow::error::{Error, ErrorKind, ErrorOrigin};
+use memflow::mem::MemoryView;
+use memflow::types::Address;
+
+use crate::prelude::*;
+use crate::{DerefDebugPrint, HashMap, PointerDebugField, PointerDebugNode, PointerDebugValue};
+use crate::{PointerTarget, TraversalState};
+
+/// Type of a field of a runtime [`Layout`].
+///
+/// Types are written the way they are parsed with [`FromStr`]: scalars by their Rust name
+/// like `u32` or `bool`, `*T` for a pointer to `T`, `[T; N]` for an embedded array and any
+/// other name for a structure of the [`Layouts`] it is used with.
+///
+/// ```rust
+/// use memflow_pointer_debug::FieldType;
+///
+/// let ty: FieldType = "[*Player; 4]".parse().unwrap();
+/// assert_eq!(
+///     ty,
+///     FieldType::array(FieldType::pointer(FieldType::named("Player")), 4)
+/// );
+/// assert_eq!(ty.to_string(), "[*Player; 4]");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub enum FieldType {
+    /// `bool`
+    Bool,
+    /// `u8`
+    U8,
+    /// `u16`
+    U16,
+    /// `u32`
+    U32,
+    /// `u64`
+    U64,
+    /// `i8`
+    I8,
+    /// `i16`
+    I16,
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// A pointer to the given type, its size is the pointer size of the [`Layouts`]
+    Pointer(Box<FieldType>),
+    /// `N` consecutive elements of the given type
+    Array(Box<FieldType>, usize),
+    /// A structure looked up by name in the [`Layouts`]
+    Struct(String),
+}
+
+impl FieldType {
+    /// A pointer to `target`
+    pub fn pointer(target: FieldType) -> Self {
+        Self::Pointer(Box::new(target))
+    }
+
+    /// An embedded array of `len` elements
+    pub fn array(element: FieldType, len: usize) -> Self {
+        Self::Array(Box::new(element), len)
+    }
+
+    /// The structure called `name`
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::Struct(name.into())
+    }
+
+    /// Size of a value of this type in bytes, `None` for structures that aren't part of
+    /// `layouts`
+    pub fn size(&self, layouts: &Layouts) -> Option<usize> {
+        Some(match self {
+            Self::Bool | Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+            Self::Pointer(_) => layouts.pointer_size,
+            Self::Array(element, len) => element.size(layouts)?.checked_mul(*len)?,
+            Self::Struct(name) => layouts.get(name)?.size,
+        })
+    }
+
+    /// Returns true if values of this type are collected with fields of their own instead
+    /// of being formatted as a whole
+    fn has_fields(&self) -> bool {
+        match self {
+            Self::Pointer(_) | Self::Struct(_) => true,
+            Self::Array(element, _) => element.has_fields(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Bool => "bool",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::Pointer(target) => return write!(f, "*{}", target),
+            Self::Array(element, len) => return write!(f, "[{}; {}]", element, len),
+            Self::Struct(name) => name,
+        };
+        f.write_str(name)
+    }
+}
+
+/// A type description that could not be parsed, see [`FieldType`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFieldTypeError(String);
+
+impl fmt::Display for ParseFieldTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid field type `{}`", self.0)
+    }
+}
+
+impl error::Error for ParseFieldTypeError {}
+
+impl FromStr for FieldType {
+    type Err = ParseFieldTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseFieldTypeError(s.to_string());
+        let s = s.trim();
+
+        if let Some(target) = s.strip_prefix('*') {
+            return Ok(Self::pointer(target.parse().map_err(|_| invalid())?));
+        }
+        if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            // The length follows the last `;`, the element type may contain arrays itself
+            let (element, len) = inner.rsplit_once(';').ok_or_else(invalid)?;
+            let len = len.trim().parse().map_err(|_| invalid())?;
+            return Ok(Self::array(element.parse().map_err(|_| invalid())?, len));
+        }
+
+        Ok(match s {
+            "bool" => Self::Bool,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            // Names from debug information can hold templates like `List<Entry *>`
+            name if !name.is_empty() => Self::named(name),
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+impl TryFrom<String> for FieldType {
+    type Error = ParseFieldTypeError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<FieldType> for String {
+    fn from(ty: FieldType) -> Self {
+        ty.to_string()
+    }
+}
+
+/// A field of a runtime [`Layout`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutField {
+    /// Name the field is printed with
+    pub name: String,
+    /// Offset of the field from the start of the structure in bytes
+    pub offset: usize,
+    /// Type of the field
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: FieldType,
+}
+
+/// The layout of a structure, described at runtime.
+///
+/// Fields are printed in the order they were added, bytes not covered by a field are
+/// skipped just like padding fields of derived structures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout {
+    /// Name of the structure
+    pub name: String,
+    /// Size of the structure in bytes
+    pub size: usize,
+    /// The fields of the structure
+    pub fields: Vec<LayoutField>,
+}
+
+impl Layout {
+    /// Create a layout without fields
+    pub fn new(name: impl Into<String>, size: usize) -> Self {
+        Self {
+            name: name.into(),
+            size,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a field of type `ty` at `offset`
+    pub fn field(mut self, name: impl Into<String>, offset: usize, ty: FieldType) -> Self {
+        self.fields.push(LayoutField {
+            name: name.into(),
+            offset,
+            ty,
+        });
+        self
+    }
+}
+
+/// A set of runtime [`Layout`]s that refer to each other by name.
+///
+/// Reading a structure with [`read`](Layouts::read) returns a [`LayoutValue`], which is
+/// printed with the [`PointerPrint`](crate::PointerPrint) methods like a derived structure.
+/// Pointers are followed according to the layout of their target type, so the layouts
+/// can be updated for a new build of the target without recompiling. With the `serde`
+/// feature the layouts are read from a JSON file with [`load`](Layouts::load).
+///
+/// ```rust,no_run
+/// use memflow::prelude::v1::{Address, MemoryView};
+/// use memflow_pointer_debug::{FieldType, Layout, Layouts, PointerPrint};
+///
+/// fn print(mem: &mut impl MemoryView, address: Address) -> memflow::error::Result<()> {
+///     let layouts = Layouts::new()
+///         .layout(
+///             Layout::new("Player", 0x20)
+///                 .field("health", 0x0, FieldType::U32)
+///                 .field("team", 0x8, FieldType::pointer(FieldType::named("Team"))),
+///         )
+///         .layout(Layout::new("Team", 0x10).field("id", 0x0, FieldType::U64));
+///
+///     layouts.read(mem, "Player", address)?.pointer_print(mem);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layouts {
+    /// Size of a pointer in bytes, 8 by default
+    #[cfg_attr(feature = "serde", serde(default = "default_pointer_size"))]
+    pub pointer_size: usize,
+    /// The layouts by name
+    #[cfg_attr(feature = "serde", serde(with = "layout_list"))]
+    pub layouts: HashMap<String, Layout>,
+}
+
+#[cfg(feature = "serde")]
+fn default_pointer_size() -> usize {
+    8
+}
+
+impl Default for Layouts {
+    fn default() -> Self {
+        Self {
+            pointer_size: 8,
+            layouts: HashMap::new(),
+        }
+    }
+}
+
+impl Layouts {
+    /// Create an empty set of layouts for 64-bit pointers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Size of a pointer in bytes, e.g. 4 for 32-bit targets
+    pub fn pointer_size(mut self, pointer_size: usize) -> Self {
+        self.pointer_size = pointer_size;
+        self
+    }
+
+    /// Add a layout, an existing layout of the same name is replaced
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.insert(layout);
+        self
+    }
+
+    /// Add a layout, returns the layout of the same name that was replaced
+    pub fn insert(&mut self, layout: Layout) -> Option<Layout> {
+        self.layouts.insert(layout.name.clone(), layout)
+    }
+
+    /// Look up a layout by name
+    pub fn get(&self, name: &str) -> Option<&Layout> {
+        self.layouts.get(name)
+    }
+
+    /// Look up a layout by name for modification
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Layout> {
+        self.layouts.get_mut(name)
+    }
+
+    /// Read the structure `name` at `address`.
+    ///
+    /// Fails if there is no layout called `name` or reading it failed, the structures its
+    /// pointers lead to are read while printing.
+    pub fn read<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        name: &str,
+        address: impl Into<Address>,
+    ) -> Result<LayoutValue<'_>, Error> {
+        let layout = self
+            .get(name)
+            .ok_or(Error(ErrorOrigin::Other, ErrorKind::NotFound))?;
+        let mut bytes = vec![0u8; layout.size];
+        mem.read_raw_into(address.into(), &mut bytes)?;
+        Ok(LayoutValue {
+            layouts: self,
+            ty: FieldType::named(name),
+            bytes,
+        })
+    }
+
+    /// Wrap `bytes` read elsewhere as the structure `name`, `None` if there is no such
+    /// layout
+    pub fn value(&self, name: &str, bytes: Vec<u8>) -> Option<LayoutValue<'_>> {
+        self.get(name)?;
+        Some(LayoutValue {
+            layouts: self,
+            ty: FieldType::named(name),
+            bytes,
+        })
+    }
+
+    /// Read layouts from a JSON file.
+    ///
+    /// The file holds an object with the `pointer_size` and a list of `layouts`, each with
+    /// a `name`, `size` and `fields` made up of a `name`, `offset` and `type`:
+    ///
+    /// ```json
+    /// {
+    ///   "pointer_size": 8,
+    ///   "layouts": [
+    ///     {
+    ///       "name": "Player",
+    ///       "size": 32,
+    ///       "fields": [
+    ///         { "name": "health", "offset": 0, "type": "u32" },
+    ///         { "name": "team", "offset": 8, "type": "*Team" }
+    ///       ]
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// Layouts are stored as a list, their names are the keys
+#[cfg(feature = "serde")]
+mod layout_list {
+    use crate::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Layout;
+
+    pub fn serialize<S: Serializer>(
+        layouts: &HashMap<String, Layout>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut list: Vec<_> = layouts.values().collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<String, Layout>, D::Error> {
+        let list = Vec::<Layout>::deserialize(deserializer)?;
+        Ok(list
+            .into_iter()
+            .map(|layout| (layout.name.clone(), layout))
+            .collect())
+    }
+}
+
+/// A value read according to a runtime [`Layout`], see [`Layouts::read`]
+#[derive(Debug, Clone)]
+pub struct LayoutValue<'a> {
+    layouts: &'a Layouts,
+    ty: FieldType,
+    bytes: Vec<u8>,
+}
+
+impl LayoutValue<'_> {
+    /// The bytes of the value
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl DerefDebugPrint for LayoutValue<'_> {
+    fn pointer_collect_internal<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        Collector {
+            layouts: self.layouts,
+            mem,
+            state,
+        }
+        .node(&self.ty, &self.bytes, depth)
+    }
+
+    fn pointer_type_name(&self) -> String {
+        self.ty.to_string()
+    }
+}
+
+/// Collects values of runtime layouts
+struct Collector<'a, 's, M> {
+    layouts: &'a Layouts,
+    mem: &'a mut M,
+    state: &'s mut TraversalState,
+}
+
+impl<M: MemoryView> Collector<'_, '_, M> {
+    /// Collect a value of type `ty` as a node of its own, e.g. the target of a pointer
+    fn node(&mut self, ty: &FieldType, bytes: &[u8], depth: usize) -> PointerDebugNode {
+        match ty {
+            FieldType::Struct(name) => {
+                let mut node = PointerDebugNode::new(name.clone());
+                if self.state.config.show_raw_bytes {
+                    node.raw_bytes = Some(bytes.to_vec());
+                }
+                let Some(layout) = self.layouts.get(name) else {
+                    node.value = Some(format!("<unknown type {}>", name));
+                    return node;
+                };
+                for field in &layout.fields {
+                    if !self.state.is_selected(&field.name) {
+                        continue;
+                    }
+                    self.state.enter_field(&field.name);
+                    let value = self.value(
+                        &field.ty,
+                        slice(bytes, field.offset, &field.ty, self.layouts),
+                        depth,
+                    );
+                    self.state.leave_field();
+                    if self.state.config.show_offsets || self.state.config.sort_by_offset {
+                        let size = field.ty.size(self.layouts).unwrap_or(0);
+                        node.field_locations
+                            .insert(field.name.clone(), (field.offset, size));
+                    }
+                    node.fields.push(PointerDebugField {
+                        name: field.name.clone(),
+                        type_name: field.ty.to_string(),
+                        value,
+                    });
+                }
+                if self.state.config.sort_by_offset {
+                    node.sort_fields_by_offset();
+                }
+                node
+            }
+            FieldType::Array(element, len) if element.has_fields() => {
+                let mut node = PointerDebugNode::new(ty.to_string());
+                let size = element.size(self.layouts).unwrap_or(0);
+                for i in 0..*len {
+                    let name = format!("[{}]", i);
+                    self.state.enter_field(&name);
+                    let value = self.value(
+                        element,
+                        slice(bytes, i * size, element, self.layouts),
+                        depth,
+                    );
+                    self.state.leave_field();
+                    node.fields.push(PointerDebugField {
+                        name,
+                        type_name: element.to_string(),
+                        value,
+                    });
+                }
+                node
+            }
+            FieldType::Pointer(_) => {
+                // A pointer printed on its own has its target as the only field
+                let mut node = PointerDebugNode::new(ty.to_string());
+                self.state.enter_field("*");
+                let value = self.value(ty, bytes, depth);
+                self.state.leave_field();
+                node.fields.push(PointerDebugField {
+                    name: "*".to_string(),
+                    type_name: ty.to_string(),
+                    value,
+                });
+                node
+            }
+            _ => PointerDebugNode::scalar(ty.to_string(), format_scalar(ty, bytes, self.layouts)),
+        }
+    }
+
+    /// Collect a value of type `ty` held by a structure at `depth`
+    fn value(&mut self, ty: &FieldType, bytes: &[u8], depth: usize) -> PointerDebugValue {
+        match ty {
+            FieldType::Pointer(target) => {
+                let address = read_uint(bytes);
+                let target = self.target(address, target, depth);
+                PointerDebugValue::Pointer { address, target }
+            }
+            FieldType::Struct(_) => {
+                PointerDebugValue::Nested(Box::new(self.node(ty, bytes, depth)))
+            }
+            FieldType::Array(element, _) if element.has_fields() => {
+                PointerDebugValue::Nested(Box::new(self.node(ty, bytes, depth)))
+            }
+            _ => PointerDebugValue::Value(format_scalar(ty, bytes, self.layouts)),
+        }
+    }
+
+    /// Follow a pointer to `address` held by a structure at `depth`, like a `Pointer<U, T>`
+    /// field of a derived structure
+    fn target(&mut self, address: u64, ty: &FieldType, depth: usize) -> PointerTarget {
+        let Some(size) = ty.size(self.layouts) else {
+            return PointerTarget::ReadError(Error(ErrorOrigin::Other, ErrorKind::NotFound));
+        };
+
+        let type_name = ty.to_string();
+        if let Some(target) = self.state.skip_target(address) {
+            target
+        } else if self.state.is_visited(address, &type_name) {
+            PointerTarget::AlreadyVisited
+        } else if depth + 1 >= self.state.max_depth {
+            PointerTarget::MaxDepth
+        } else if !self.state.take_budget(address, depth, 1, size) {
+            PointerTarget::BudgetExhausted
+        } else {
+            self.state.visit(address, &type_name);
+
+            let mut bytes = vec![0u8; size];
+            match self.mem.read_raw_into(address.into(), &mut bytes) {
+                Ok(()) => {
+                    let mut node = self.node(ty, &bytes, depth + 1);
+                    node.address = Some(address);
+                    PointerTarget::Node(Box::new(node))
+                }
+                Err(e) => PointerTarget::ReadError(e.into()),
+            }
+        }
+    }
+}
+
+/// The bytes of a value of type `ty` at `offset`, shorter if `bytes` ends before
+fn slice<'b>(bytes: &'b [u8], offset: usize, ty: &FieldType, layouts: &Layouts) -> &'b [u8] {
+    let start = offset.min(bytes.len());
+    let end = offset
+        .saturating_add(ty.size(layouts).unwrap_or(0))
+        .min(bytes.len());
+    &bytes[start..end]
+}
+
+/// A little-endian unsigned integer of up to 8 bytes, missing bytes are 0
+fn read_uint(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    let len = bytes.len().min(8);
+    buf[..len].copy_from_slice(&bytes[..len]);
+    u64::from_le_bytes(buf)
+}
+
+/// Format a value without pointers like its `Debug` implementation would
+fn format_scalar(ty: &FieldType, bytes: &[u8], layouts: &Layouts) -> String {
+    let uint = read_uint(bytes);
+    match ty {
+        FieldType::Bool => format!("{:?}", uint != 0),
+        FieldType::U8 | FieldType::U16 | FieldType::U32 | FieldType::U64 => uint.to_string(),
+        FieldType::I8 => (uint as i8).to_string(),
+        FieldType::I16 => (uint as i16).to_string(),
+        FieldType::I32 => (uint as i32).to_string(),
+        FieldType::I64 => (uint as i64).to_string(),
+        FieldType::F32 => format!("{:?}", f32::from_bits(uint as u32)),
+        FieldType::F64 => format!("{:?}", f64::from_bits(uint)),
+        FieldType::Pointer(_) => format!("{:#x}", uint),
+        FieldType::Array(element, len) => {
+            let size = element.size(layouts).unwrap_or(0);
+            let items: Vec<_> = (0..*len)
+                .map(|i| format_scalar(element, slice(bytes, i * size, element, layouts), layouts))
+                .collect();
+            format!("[{}]", items.join(", "))
+        }
+        FieldType::Struct(name) => format!("<{}>", name),
+    }
+}
@@ -5,23 +5,336 @@
 //!
 //! This crate works with the `memflow` memory introspection framework to follow pointers
 //! through memory and display their contents.
+//!
+//! Without the default `std` feature the crate is `no_std` and only needs `alloc`, the
+//! printing functions are left out and dumps are written to any [`core::fmt::Write`] through
+//! [`PointerDisplay`] instead.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// The Windows structures are derived, the generated code names this crate by its path
+#[cfg(feature = "win-types")]
+extern crate self as memflow_pointer_debug;
 
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use memflow::dataview::Pod;
 use memflow::mem::MemoryView;
-use std::collections::HashSet;
+#[cfg(feature = "std")]
+use memflow::types::Address;
+
+mod addrmap;
+mod cache;
+#[cfg(feature = "cparse")]
+mod cheader;
+mod cheattable;
+mod compact;
+mod config;
+mod diff;
+mod display;
+mod dot;
+#[cfg(feature = "dwarf")]
+mod dwarf;
+mod endian;
+#[cfg(feature = "tui")]
+mod explorer;
+mod flags;
+mod health;
+mod hexdump;
+mod html;
+mod impls;
+mod incremental;
+#[cfg(feature = "egui")]
+mod inspector;
+mod iter;
+mod layout;
+mod list;
+mod memmap;
+mod node;
+mod number;
+mod offsets;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "pdb")]
+mod pdb;
+mod pointer;
+mod printers;
+mod progress;
+mod query;
+mod read;
+mod reclass;
+mod regions;
+#[cfg(feature = "repl")]
+mod repl;
+#[cfg(feature = "serde")]
+mod replay;
+mod report;
+mod retry;
+mod ron;
+mod schema;
+mod session;
+#[cfg(feature = "serde")]
+mod snapshot;
+mod state;
+mod stats;
+mod stream;
+mod style;
+mod symbols;
+mod sync;
+#[cfg(feature = "testing")]
+mod testing;
+mod timestamp;
+mod trace;
+mod views;
+mod visit;
+#[cfg(feature = "std")]
+mod watch;
+#[cfg(feature = "win-types")]
+mod win;
+mod yaml;
+
+pub use addrmap::{AddressEntry, AddressMap};
+pub use cache::CachedView;
+#[cfg(feature = "cparse")]
+pub use cheader::CHeaderError;
+pub use cheattable::{CheatEntry, CheatTable};
+#[cfg(feature = "log")]
+pub use config::LogOutput;
+pub use config::{
+    AddressPredicate, CancellationToken, ElementTruncation, ErrorPolicy, OutputFormat,
+    PointerPrintConfig, is_canonical_x64,
+};
+pub use diff::{ChangeKind, FieldChange, PointerDebugDiff};
+pub use display::PointerDisplay;
+pub use dot::{PointerGraph, ROOT_NODE};
+pub use endian::ByteOrder;
+#[cfg(feature = "tui")]
+pub use explorer::PointerExplorer;
+pub use flags::format_flags;
+pub use health::{HealthSummary, PointerHealth, PointerHealthCounts};
+pub use hexdump::format_hexdump;
+pub use incremental::{ChangedSubtree, ChangedSubtreeDisplay, IncrementalDump};
+#[cfg(feature = "egui")]
+pub use inspector::PointerInspector;
+pub use iter::{FieldPath, FieldValue, PointerIter};
+pub use layout::{FieldType, Layout, LayoutField, LayoutValue, Layouts, ParseFieldTypeError};
+pub use list::{ListEntry64, ListWalker, containing_record, read_list};
+pub use memmap::MappedMemory;
+pub use node::{
+    PointerDebugDisplay, PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget,
+};
+pub use number::{FormatNumber, Radix};
+pub use offsets::{Offsets, ParseOffsetsError};
+pub use pointer::PointerLike;
+pub use printers::{PrinterMemory, PrinterRegistry};
+pub use progress::{ProgressCallback, ProgressEvent};
+pub use read::{StringDecoding, read_array, read_cstr_bytes, read_utf16_lossy, read_utf16_units};
+pub use reclass::ReClassProject;
+pub use regions::{MemoryRegions, Region};
+#[cfg(feature = "repl")]
+pub use repl::PointerRepl;
+#[cfg(feature = "serde")]
+pub use replay::{RecordingView, ReplayView};
+pub use report::{PointerDebugReport, PointerReadFailure};
+pub use retry::RetryPolicy;
+use retry::RetryView;
+pub use schema::{FieldSchema, TypeSchema};
+pub use session::PointerDebugSession;
+#[cfg(feature = "serde")]
+pub use snapshot::{Snapshot, SnapshotMemory, SnapshotRegion};
+pub use state::{TraversalState, VisitedSet};
+use stats::Stopwatch;
+pub use stats::TraversalStats;
+pub use stream::{PointerStreamEvent, StreamCallback, StreamSender};
+pub use symbols::Symbols;
+#[cfg(feature = "testing")]
+pub use testing::FakeMemory;
+pub use timestamp::{TimestampFormat, format_timestamp};
+pub use trace::DerefSpan;
+pub use views::{PHYSICAL_VIEW, SharedView};
+pub use visit::PointerVisitor;
+#[cfg(feature = "std")]
+pub use watch::PointerWatch;
+#[cfg(feature = "win-types")]
+pub use win::{LdrDataTableEntry64, Peb64, PebLdrData64, RtlBalancedNode64, UnicodeString64};
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap, HashSet};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{HashMap, HashSet};
+
+/// The items of the `std` prelude that come from `alloc`, imported by every module so the
+/// crate builds without `std`
+mod prelude {
+    pub(crate) use alloc::boxed::Box;
+    pub(crate) use alloc::format;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec;
+    pub(crate) use alloc::vec::Vec;
+}
+
+use prelude::*;
+
+/// Items used by the code generated by the derive macro, which can't rely on the prelude of
+/// `no_std` crates
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::string::String;
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+    #[cfg(not(feature = "std"))]
+    pub use hashbrown::HashSet;
+    #[cfg(feature = "std")]
+    pub use std::collections::HashSet;
+}
+
+/// Collects the fields at the given indices of a structure, see
+/// [`DerefDebugPrint::pointer_collect_fields_with`]
+pub type FieldCollector<'a, M> =
+    dyn FnMut(&[usize], &mut M, &mut TraversalState, bool) -> Vec<Option<PointerDebugField>> + 'a;
 
 /// Internal trait that handles the details of pointer dereferencing and printing.
 ///
 /// This trait is marked as `#[doc(hidden)]` because it's not intended to be used directly.
 /// Instead, use the [`PointerPrint`] trait which provides a more user-friendly interface
 pub trait DerefDebugPrint {
-    fn pointer_debug_internal<M>(
+    /// Collect this value and everything reachable through its pointers into a tree.
+    fn pointer_collect_internal<M>(
         &self,
         mem: &mut M,
         depth: usize,
-        max_depth: usize,
-        visited_addresses: &mut ::std::collections::HashSet<u64>,
-    ) where
+        state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
         M: memflow::mem::MemoryView;
+
+    /// Collect this value as the value of a field at the given depth.
+    ///
+    /// Structures are embedded as a [`PointerDebugValue::Nested`] node by default, pointers
+    /// override this to follow their target.
+    fn pointer_collect_value<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugValue
+    where
+        M: memflow::mem::MemoryView,
+    {
+        PointerDebugValue::Nested(Box::new(self.pointer_collect_internal(mem, depth, state)))
+    }
+
+    /// Name of the node collected for this value
+    fn pointer_type_name(&self) -> String {
+        impls::short_type_name::<Self>()
+    }
+
+    /// Number of fields collected by [`pointer_collect_field`](Self::pointer_collect_field),
+    /// 0 for values that are not collected field by field
+    fn pointer_field_count(&self) -> usize {
+        0
+    }
+
+    /// Collect the field `index` of this value, `None` if there is no such field or it is
+    /// [padding](PointerPrintConfig::is_padding).
+    ///
+    /// `chain_element` is set if this value is an element of a followed chain. Fields can
+    /// be collected independently of each other, which allows traversing them in parallel.
+    fn pointer_collect_field<M>(
+        &self,
+        index: usize,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+        chain_element: bool,
+    ) -> Option<PointerDebugField>
+    where
+        M: memflow::mem::MemoryView,
+    {
+        let _ = (index, mem, depth, state, chain_element);
+        None
+    }
+
+    /// Collect this value like [`pointer_collect_internal`](Self::pointer_collect_internal),
+    /// with `collect` collecting the fields at the given indices and `chain_element`.
+    ///
+    /// The structure itself builds the node around the fields, `collect` only decides how
+    /// they are read, the parallel traversal collects each of them as its own branch.
+    /// Values that are not collected field by field never call `collect`.
+    fn pointer_collect_fields_with<M>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+        collect: &mut FieldCollector<'_, M>,
+    ) -> PointerDebugNode
+    where
+        M: memflow::mem::MemoryView,
+    {
+        let _ = collect;
+        self.pointer_collect_internal(mem, depth, state)
+    }
+
+    /// Add the address and size of every pointer target this value reads directly.
+    ///
+    /// Used by [`TraversalState::prefetch_targets`] to batch the reads of sibling pointers,
+    /// values without pointers don't add anything.
+    fn pointer_targets(&self, targets: &mut Vec<(u64, usize)>) {
+        let _ = targets;
+    }
+
+    /// Convert the scalars and pointer addresses of this value, read from memory in byte
+    /// `order`, to the byte order of the host.
+    ///
+    /// Called by [`TraversalState::to_host_order`] on every value read. Derived structures
+    /// convert their integer, float and pointer fields, arrays of them and the fields that
+    /// are followed, other fields are kept as they are. Enums and unions are kept as well,
+    /// their variant can't be told before the conversion.
+    fn pointer_to_host_order(&mut self, order: ByteOrder) {
+        let _ = order;
+    }
+
+    /// Offset and size of the field `name`, `None` if there is no such field.
+    ///
+    /// Used by [`TraversalState::apply_offsets`] to move fields to the offsets given in
+    /// [`PointerPrintConfig::offsets`].
+    fn pointer_field_location(name: &str) -> Option<(usize, usize)>
+    where
+        Self: Sized,
+    {
+        let _ = name;
+        None
+    }
+
+    /// [`pointer_field_location`](Self::pointer_field_location) of this value, available for
+    /// unsized values as well.
+    ///
+    /// Used by [`TraversalState::record_locations`] for
+    /// [`PointerPrintConfig::show_offsets`].
+    fn pointer_field_offset(&self, name: &str) -> Option<(usize, usize)> {
+        let _ = name;
+        None
+    }
+
+    /// Description of the fields of this type, without reading any memory.
+    ///
+    /// Derived types list every declared field, other types are described without fields.
+    fn pointer_debug_schema() -> TypeSchema
+    where
+        Self: Sized,
+    {
+        TypeSchema::new(
+            impls::short_type_name::<Self>(),
+            core::mem::size_of::<Self>(),
+        )
+    }
 }
 
 /// High-level trait for printing data structures with automatic pointer dereferencing.
@@ -33,12 +346,13 @@ pub trait DerefDebugPrint {
 /// # Example
 ///
 /// ```rust
-/// use memflow_pointer_debug::{PointerPrint, PointerDerefDebugPrint};
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerPrint, PointerDerefDebugPrint, PointerPrintConfig};
 ///
-/// #[derive(PointerDerefDebugPrint)]
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
 /// struct MyStruct {
-///     id: u32,
-///     name: String,
+///     id: u64,
 ///     next: Pointer64<MyStruct>,
 /// }
 ///
@@ -48,6 +362,21 @@ pub trait DerefDebugPrint {
 ///
 ///     // With custom depth
 ///     my_struct.pointer_print_with_depth(mem, 10);
+///
+///     // Find out whether any pointer failed to read
+///     if let Err(report) = my_struct.try_pointer_print(mem) {
+///         eprintln!("{}", report);
+///     }
+///
+///     // Tune the output
+///     let config = PointerPrintConfig::new().max_depth(3).show_type_names(false);
+///     my_struct.pointer_print_with_config(mem, &config).ok();
+///
+///     // Collect the tree and inspect it yourself
+///     let node = my_struct.pointer_collect(mem, 5);
+///     if let Some(next) = node.field("next") {
+///         println!("{:?}", next.value);
+///     }
 /// }
 /// ```
 pub trait PointerPrint {
@@ -59,6 +388,7 @@ pub trait PointerPrint {
     /// # Parameters
     ///
     /// * `mem` - The memory view to read from
+    #[cfg(feature = "std")]
     fn pointer_print<M: MemoryView>(&self, mem: &mut M);
 
     /// Print this structure with pointer dereferencing using a custom max depth.
@@ -70,23 +400,744 @@ pub trait PointerPrint {
     ///
     /// * `mem` - The memory view to read from
     /// * `max_depth` - Maximum recursion depth
+    #[cfg(feature = "std")]
     fn pointer_print_with_depth<M: MemoryView>(&self, mem: &mut M, max_depth: usize);
+
+    /// Walk this structure and render the pointer graph in Graphviz DOT syntax.
+    ///
+    /// Every visited address becomes a node labeled by its type name and every followed
+    /// pointer field becomes an edge labeled by the field name. Pointers back to already
+    /// visited addresses are emitted as back-edges to the existing node.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    fn pointer_dot<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String;
+
+    /// Walk this structure and render the pointer graph as a Mermaid `graph TD` diagram,
+    /// which GitHub and most wikis render inline.
+    ///
+    /// The graph is the same as the one of [`PointerPrint::pointer_dot`].
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    fn pointer_mermaid<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String;
+
+    /// Walk this structure and render the pointer graph as GraphML for Gephi or yEd, see
+    /// [`PointerGraph::to_graphml`].
+    ///
+    /// The graph is the same as the one of [`PointerPrint::pointer_dot`], nodes carry their
+    /// type, address and scalar fields as well.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    fn pointer_graphml<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String;
+
+    /// Walk this structure and render it as a YAML document, see
+    /// [`PointerDebugNode::to_yaml`] for the layout.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    fn pointer_yaml<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String;
+
+    /// Walk this structure and render it as a self-contained HTML page with collapsible
+    /// nodes and a search box, see [`PointerDebugNode::to_html`].
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    fn pointer_html<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String;
+
+    /// Walk this structure and collect it into a [`PointerDebugNode`] tree.
+    ///
+    /// The tree contains every field, value, followed pointer address and read error
+    /// and can be inspected or rendered without touching memory again. Printing the
+    /// returned node with `{}` produces the same output as [`PointerPrint::pointer_print`].
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    fn pointer_collect<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> PointerDebugNode;
+
+    /// Walk this structure with the given options and collect it into a [`PointerDebugNode`] tree.
+    ///
+    /// The traversal options of the configuration apply, render the tree with
+    /// [`PointerDebugNode::display`] to apply the output options as well.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `config` - Traversal and output options
+    fn pointer_collect_with_config<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> PointerDebugNode;
+
+    /// Walk this structure like [`PointerPrint::pointer_collect_with_config`] and return
+    /// the totals of the traversal along with the tree.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `config` - Traversal and output options
+    fn pointer_collect_with_stats<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> (PointerDebugNode, TraversalStats);
+
+    /// Print this structure with the given options.
+    ///
+    /// Failed reads are handled according to [`PointerPrintConfig::error_policy`], with
+    /// [`ErrorPolicy::Fail`] nothing is printed if a read failed and the failures are
+    /// returned instead. With [`ErrorPolicy::Abort`] the structure is printed up to the
+    /// failed read and the failure is returned. Otherwise this always returns `Ok`. If the
+    /// read budget ran out a summary of the skipped pointers is printed after the structure,
+    /// the same goes for cancelled traversals. With [`PointerPrintConfig::show_stats`] the
+    /// [`TraversalStats`] are printed last.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `config` - Traversal and output options
+    #[cfg(feature = "std")]
+    fn pointer_print_with_config<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> Result<(), PointerDebugReport>;
+
+    /// Look up the value at a dotted path like `world.players[3].name`, only the pointers
+    /// leading to it are followed.
+    ///
+    /// Arrays on the way are read in full, the pointers inside the elements that aren't
+    /// picked are not followed. The maximum depth of the configuration is raised to reach
+    /// the end of the path, see [`PointerDebugNode::get_path`] for the path syntax.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `path` - Dotted path of the field, elements are picked with `[i]`
+    /// * `config` - Traversal options
+    fn pointer_get<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        path: &str,
+        config: &PointerPrintConfig,
+    ) -> Option<PointerDebugValue>;
+
+    /// Walk this structure with the given options and iterate its fields as `(path, value)`
+    /// pairs, see [`PointerIter`].
+    ///
+    /// The iterator is not lazy: the whole structure is collected before the first entry is
+    /// returned, so `take(n)` or breaking out of the loop doesn't read any less. Limit what
+    /// is read with the options of `config`, e.g. [`max_depth`](PointerPrintConfig::max_depth)
+    /// or [`max_nodes`](PointerPrintConfig::max_nodes), or look up a single field with
+    /// [`pointer_get`](Self::pointer_get).
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `config` - Traversal options
+    fn pointer_iter<M: MemoryView>(&self, mem: &mut M, config: &PointerPrintConfig) -> PointerIter;
+
+    /// Walk this structure with the given options and invoke the callbacks of `visitor` for
+    /// every structure, value and pointer, see [`PointerVisitor`].
+    ///
+    /// The structure is collected in full first and the visitor walks the collected tree
+    /// like [`PointerDebugNode::visit`], the callbacks can't change what is read. Use
+    /// [`pointer_stream`](Self::pointer_stream) to receive the parts while they are read.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `visitor` - Receives the visited fields in traversal order
+    /// * `config` - Traversal options
+    fn pointer_visit<M: MemoryView, V: PointerVisitor + ?Sized>(
+        &self,
+        mem: &mut M,
+        visitor: &mut V,
+        config: &PointerPrintConfig,
+    );
+
+    /// Walk this structure with the given options and send its structures, values and
+    /// failed reads to `sender` as they are read, see [`PointerStreamEvent`]. A structure is
+    /// sent after the targets of its pointers, the root comes last.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `sender` - Receives the events, e.g. a channel read by a UI thread
+    /// * `config` - Traversal options
+    fn pointer_stream<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        sender: impl StreamSender + 'static,
+        config: &PointerPrintConfig,
+    ) -> PointerDebugNode;
+
+    /// Read this structure every `interval` and print the fields that changed since the
+    /// last read as `path: old → new`.
+    ///
+    /// This runs until the [`cancellation`](PointerPrintConfig::cancellation) token of the
+    /// configuration is cancelled, see [`PointerWatch`] for iterating the changes instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `interval` - Time to wait between two reads
+    /// * `config` - Traversal and output options
+    #[cfg(feature = "std")]
+    fn pointer_watch<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        interval: Duration,
+        config: &PointerPrintConfig,
+    );
+
+    /// Collect this structure, run `between` and collect it again, e.g. to see what a
+    /// triggered action changed.
+    ///
+    /// Values passed by reference aren't read again, only what their pointers lead to, call
+    /// this on a pointer to the structure to compare the structure itself.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from, it is handed to `between`
+    /// * `config` - Traversal options
+    /// * `between` - Runs between the two reads
+    fn pointer_diff<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+        between: impl FnOnce(&mut M),
+    ) -> PointerDebugDiff;
+
+    /// Collect this structure with the given options and record the memory it was read
+    /// from, see [`Snapshot`] for saving it to disk.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `config` - Traversal options
+    #[cfg(feature = "serde")]
+    fn pointer_snapshot<M: MemoryView>(&self, mem: &mut M, config: &PointerPrintConfig)
+    -> Snapshot;
+
+    /// Walk this structure with the given options, traversing its fields in parallel.
+    ///
+    /// Every field of the structure is collected as its own branch on the rayon thread pool
+    /// with a clone of `mem`, the result is the same tree
+    /// [`pointer_collect_with_config`](PointerPrint::pointer_collect_with_config) returns.
+    /// A target reachable from several fields is expanded by the first of them and
+    /// [`PointerTarget::AlreadyVisited`] everywhere else, the fields that looked it up
+    /// later are collected again after the branches finished. With a read budget or a
+    /// progress callback configured the traversal is sequential.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from, cloned once per branch
+    /// * `config` - Traversal and output options
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Player {
+    ///     health: u64,
+    /// }
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// #[pointer_debug(expected_size = 0x20)]
+    /// struct World {
+    ///     local: Pointer64<Player>,
+    ///     focus: Pointer64<Player>,
+    ///     players: [Pointer64<Player>; 2],
+    /// }
+    ///
+    /// # let mut bytes = vec![0u8; 0x100];
+    /// # bytes[0x10..0x18].copy_from_slice(&100u64.to_le_bytes());
+    /// # bytes[0x20..0x28].copy_from_slice(&50u64.to_le_bytes());
+    /// # let mut mem = memflow::connector::FileIoMemory::new(std::io::Cursor::new(bytes))
+    /// #     .unwrap()
+    /// #     .into_phys_view();
+    /// let world = World {
+    ///     local: Pointer64::from(0x10u64),
+    ///     focus: Pointer64::from(0x20u64),
+    ///     players: [Pointer64::from(0x10u64), Pointer64::from(0x20u64)],
+    /// };
+    ///
+    /// // `local` and `focus` expand the players, `players` refers back to them
+    /// let config = PointerPrintConfig::new();
+    /// assert_eq!(
+    ///     world.pointer_collect_parallel(&mut mem, &config),
+    ///     world.pointer_collect_with_config(&mut mem, &config)
+    /// );
+    /// ```
+    #[cfg(feature = "parallel")]
+    fn pointer_collect_parallel<M>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView + Clone + Send,
+        Self: Sync;
+
+    /// Walk this structure like [`PointerPrint::pointer_collect_parallel`] and return the
+    /// totals of the traversal along with the tree.
+    ///
+    /// The totals are the ones of a sequential traversal, the reads of a field that is
+    /// collected again after the branches finished are only counted once.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from, cloned once per branch
+    /// * `config` - Traversal and output options
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, PointerPrintConfig};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Player {
+    ///     health: u64,
+    /// }
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct World {
+    ///     local: Pointer64<Player>,
+    ///     focus: Pointer64<Player>,
+    /// }
+    ///
+    /// # let mut bytes = vec![0u8; 0x100];
+    /// # bytes[0x10..0x18].copy_from_slice(&100u64.to_le_bytes());
+    /// # let mut mem = memflow::connector::FileIoMemory::new(std::io::Cursor::new(bytes))
+    /// #     .unwrap()
+    /// #     .into_phys_view();
+    /// // Both fields point at the same player, it is read once
+    /// let world = World {
+    ///     local: Pointer64::from(0x10u64),
+    ///     focus: Pointer64::from(0x10u64),
+    /// };
+    ///
+    /// let config = PointerPrintConfig::new();
+    /// let (_, mut parallel) = world.pointer_collect_parallel_with_stats(&mut mem, &config);
+    /// let (_, mut sequential) = world.pointer_collect_with_stats(&mut mem, &config);
+    /// assert_eq!(parallel.bytes, 8);
+    /// parallel.elapsed = Default::default();
+    /// sequential.elapsed = Default::default();
+    /// assert_eq!(parallel, sequential);
+    /// ```
+    #[cfg(feature = "parallel")]
+    fn pointer_collect_parallel_with_stats<M>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> (PointerDebugNode, TraversalStats)
+    where
+        M: MemoryView + Clone + Send,
+        Self: Sync;
+
+    /// Print this structure with the given options, traversing its fields in parallel.
+    ///
+    /// See [`pointer_collect_parallel`](PointerPrint::pointer_collect_parallel) and
+    /// [`pointer_print_with_config`](PointerPrint::pointer_print_with_config).
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from, cloned once per branch
+    /// * `config` - Traversal and output options
+    #[cfg(feature = "parallel")]
+    fn pointer_print_parallel<M>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> Result<(), PointerDebugReport>
+    where
+        M: MemoryView + Clone + Send,
+        Self: Sync;
+
+    /// Print this structure like [`PointerPrint::pointer_print`] and report failed reads.
+    ///
+    /// The structure is printed in full either way, afterwards every pointer that could not
+    /// be read is returned with its field path, address and the memflow error so tooling
+    /// can react to partial failures.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    #[cfg(feature = "std")]
+    fn try_pointer_print<M: MemoryView>(&self, mem: &mut M) -> Result<(), PointerDebugReport>;
+
+    /// Print this structure with a custom max depth and report failed reads.
+    ///
+    /// See [`PointerPrint::try_pointer_print`].
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    /// * `max_depth` - Maximum recursion depth
+    #[cfg(feature = "std")]
+    fn try_pointer_print_with_depth<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        max_depth: usize,
+    ) -> Result<(), PointerDebugReport>;
 }
 
 /// Implement PointerPrint for any type that implements DerefDebugPrint
 impl<T: DerefDebugPrint> PointerPrint for T {
+    #[cfg(feature = "std")]
     fn pointer_print<M: MemoryView>(&self, mem: &mut M) {
         // Use default max depth of 5
         self.pointer_print_with_depth(mem, 5);
     }
 
+    #[cfg(feature = "std")]
     fn pointer_print_with_depth<M: MemoryView>(&self, mem: &mut M, max_depth: usize) {
-        // Create a new HashSet to track visited addresses
-        let mut visited_addresses = HashSet::new();
+        // Printing is just rendering the collected tree
+        print!("{}", self.pointer_collect(mem, max_depth));
+    }
+
+    fn pointer_dot<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String {
+        PointerGraph::from_node(&self.pointer_collect(mem, max_depth)).to_dot()
+    }
+
+    fn pointer_mermaid<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String {
+        PointerGraph::from_node(&self.pointer_collect(mem, max_depth)).to_mermaid()
+    }
+
+    fn pointer_graphml<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String {
+        PointerGraph::from_node(&self.pointer_collect(mem, max_depth)).to_graphml()
+    }
+
+    fn pointer_yaml<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String {
+        self.pointer_collect(mem, max_depth).to_yaml()
+    }
+
+    fn pointer_html<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> String {
+        self.pointer_collect(mem, max_depth).to_html()
+    }
+
+    fn pointer_collect<M: MemoryView>(&self, mem: &mut M, max_depth: usize) -> PointerDebugNode {
+        self.pointer_collect_with_config(mem, &PointerPrintConfig::new().max_depth(max_depth))
+    }
+
+    fn pointer_collect_with_config<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> PointerDebugNode {
+        self.pointer_collect_with_stats(mem, config).0
+    }
+
+    fn pointer_collect_with_stats<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> (PointerDebugNode, TraversalStats) {
+        let stopwatch = Stopwatch::start();
+
+        // Create a new state to track visited addresses
+        let mut state = TraversalState::with_config(config);
+        let mut mem = RetryView::new(mem, config);
 
         // Call the internal method with initial depth 0
-        self.pointer_debug_internal(mem, 0, max_depth, &mut visited_addresses);
+        let node = self.pointer_collect_internal(&mut mem, 0, &mut state);
+        state.finish_root(&node);
+
+        let mut stats = TraversalStats::from_node(&node);
+        stats.bytes = state.bytes_read;
+        stats.retries = mem.retries;
+        stats.elapsed = stopwatch.elapsed();
+        (node, stats)
     }
+
+    #[cfg(feature = "std")]
+    fn pointer_print_with_config<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> Result<(), PointerDebugReport> {
+        let (node, stats) = self.pointer_collect_with_stats(mem, config);
+        print_node(&node, &stats, config)
+    }
+
+    fn pointer_get<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        path: &str,
+        config: &PointerPrintConfig,
+    ) -> Option<PointerDebugValue> {
+        query::get(self, mem, path, config)
+    }
+
+    fn pointer_iter<M: MemoryView>(&self, mem: &mut M, config: &PointerPrintConfig) -> PointerIter {
+        self.pointer_collect_with_config(mem, config).into_entries()
+    }
+
+    fn pointer_visit<M: MemoryView, V: PointerVisitor + ?Sized>(
+        &self,
+        mem: &mut M,
+        visitor: &mut V,
+        config: &PointerPrintConfig,
+    ) {
+        self.pointer_collect_with_config(mem, config).visit(visitor);
+    }
+
+    fn pointer_stream<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        sender: impl StreamSender + 'static,
+        config: &PointerPrintConfig,
+    ) -> PointerDebugNode {
+        self.pointer_collect_with_config(mem, &config.clone().stream(sender))
+    }
+
+    #[cfg(feature = "std")]
+    fn pointer_watch<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        interval: Duration,
+        config: &PointerPrintConfig,
+    ) {
+        watch::print_watch(self, mem, interval, config)
+    }
+
+    fn pointer_diff<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+        between: impl FnOnce(&mut M),
+    ) -> PointerDebugDiff {
+        let old = self.pointer_collect_with_config(mem, config);
+        between(mem);
+        old.diff(&self.pointer_collect_with_config(mem, config))
+    }
+
+    #[cfg(feature = "serde")]
+    fn pointer_snapshot<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> Snapshot {
+        Snapshot::capture(self, mem, config)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn pointer_collect_parallel<M>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView + Clone + Send,
+        Self: Sync,
+    {
+        self.pointer_collect_parallel_with_stats(mem, config).0
+    }
+
+    #[cfg(feature = "parallel")]
+    fn pointer_collect_parallel_with_stats<M>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> (PointerDebugNode, TraversalStats)
+    where
+        M: MemoryView + Clone + Send,
+        Self: Sync,
+    {
+        let stopwatch = Stopwatch::start();
+        let (node, bytes, retries) = parallel::collect_parallel(self, mem, config);
+        let mut stats = TraversalStats::from_node(&node);
+        stats.bytes = bytes;
+        stats.retries = retries;
+        stats.elapsed = stopwatch.elapsed();
+        (node, stats)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn pointer_print_parallel<M>(
+        &self,
+        mem: &mut M,
+        config: &PointerPrintConfig,
+    ) -> Result<(), PointerDebugReport>
+    where
+        M: MemoryView + Clone + Send,
+        Self: Sync,
+    {
+        let (node, stats) = self.pointer_collect_parallel_with_stats(mem, config);
+        print_node(&node, &stats, config)
+    }
+
+    #[cfg(feature = "std")]
+    fn try_pointer_print<M: MemoryView>(&self, mem: &mut M) -> Result<(), PointerDebugReport> {
+        self.try_pointer_print_with_depth(mem, 5)
+    }
+
+    #[cfg(feature = "std")]
+    fn try_pointer_print_with_depth<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        max_depth: usize,
+    ) -> Result<(), PointerDebugReport> {
+        let node = self.pointer_collect(mem, max_depth);
+        print!("{}", node);
+
+        let report = PointerDebugReport::from_node(&node);
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err(report)
+        }
+    }
+}
+
+/// Print a collected tree with the given options, see
+/// [`PointerPrint::pointer_print_with_config`]
+#[cfg(feature = "std")]
+fn print_node(
+    node: &PointerDebugNode,
+    stats: &TraversalStats,
+    config: &PointerPrintConfig,
+) -> Result<(), PointerDebugReport> {
+    print_rendered(node, stats, config, |node| node.display(config).to_string())
+}
+
+/// Print a collected tree rendered by `render` with the given options, see
+/// [`PointerPrint::pointer_print_with_config`]
+#[cfg(feature = "std")]
+pub(crate) fn print_rendered(
+    node: &PointerDebugNode,
+    stats: &TraversalStats,
+    config: &PointerPrintConfig,
+    render: impl FnOnce(&PointerDebugNode) -> String,
+) -> Result<(), PointerDebugReport> {
+    let report = PointerDebugReport::from_node(node);
+
+    if config.error_policy == ErrorPolicy::Fail && !report.is_empty() {
+        return Err(report);
+    }
+
+    let output = finish_output(render(node), node, stats, &report, config);
+    write_output(&output, config);
+
+    let aborted = config.error_policy == ErrorPolicy::Abort && !report.is_empty();
+    if aborted { Err(report) } else { Ok(()) }
+}
+
+/// Render a collected tree with the given options followed by the summaries printed by
+/// [`PointerPrint::pointer_print_with_config`]
+pub(crate) fn render_node(
+    node: &PointerDebugNode,
+    stats: &TraversalStats,
+    report: &PointerDebugReport,
+    config: &PointerPrintConfig,
+) -> String {
+    finish_output(
+        node.display(config).to_string(),
+        node,
+        stats,
+        report,
+        config,
+    )
+}
+
+/// Cut a rendered tree to the output limits of the configuration and append the summaries
+/// printed by [`PointerPrint::pointer_print_with_config`]
+pub(crate) fn finish_output(
+    mut output: String,
+    node: &PointerDebugNode,
+    stats: &TraversalStats,
+    report: &PointerDebugReport,
+    config: &PointerPrintConfig,
+) -> String {
+    if let Some(cut) = output_cut(&output, config) {
+        let rest = &output[cut..];
+        let marker = format!(
+            "... output truncated after {} lines (budget exhausted): {} line(s), {} byte(s) not printed\n",
+            output[..cut].lines().count(),
+            rest.lines().count(),
+            rest.len()
+        );
+        output.truncate(cut);
+        output += &marker;
+    }
+    if report.skipped > 0 {
+        output += &format!(
+            "... traversal stopped, read budget exhausted: {} pointer(s) skipped\n",
+            report.skipped
+        );
+    }
+    // The pointers left after an aborted read are cancelled
+    let aborted = config.error_policy == ErrorPolicy::Abort && !report.is_empty();
+    if let Some(failure) = report.failures.first().filter(|_| aborted) {
+        output += &format!(
+            "... traversal aborted, {} @ {:#x}: {}\n",
+            failure.path, failure.address, failure.error
+        );
+    } else if report.cancelled {
+        output += "... traversal cancelled\n";
+    }
+    if config.show_stats {
+        output += &format!("... {}\n", stats);
+    }
+    if let Some(summary) = config.health_summary {
+        output += &PointerHealth::from_node(node).render(summary);
+    }
+    output
+}
+
+/// Offset of the first line of `output` beyond [`PointerPrintConfig::max_output_lines`] or
+/// [`PointerPrintConfig::max_output_bytes`], `None` if all of it fits
+fn output_cut(output: &str, config: &PointerPrintConfig) -> Option<usize> {
+    let mut end = 0;
+    for (i, line) in output.split_inclusive('\n').enumerate() {
+        if config.max_output_lines.is_some_and(|max| i >= max)
+            || config
+                .max_output_bytes
+                .is_some_and(|max| end + line.len() > max)
+        {
+            return Some(end);
+        }
+        end += line.len();
+    }
+    None
+}
+
+/// Write printed output to stdout, or to the log if the configuration asks for it
+#[cfg(feature = "std")]
+pub(crate) fn write_output(output: &str, config: &PointerPrintConfig) {
+    #[cfg(feature = "log")]
+    if let Some(log) = &config.log {
+        for line in output.lines() {
+            log::log!(target: &log.target, log.level, "{}", line);
+        }
+        return;
+    }
+    #[cfg(not(feature = "log"))]
+    let _ = config;
+    print!("{}", output);
 }
 
 /// Convenience function for printing any value that implements DerefDebugPrint.
@@ -102,9 +1153,10 @@ impl<T: DerefDebugPrint> PointerPrint for T {
 /// # Example
 ///
 /// ```rust
+/// use memflow::prelude::v1::*;
 /// use memflow_pointer_debug::{print_with_pointer_reading, PointerDerefDebugPrint};
 ///
-/// #[derive(PointerDerefDebugPrint)]
+/// #[derive(Debug, PointerDerefDebugPrint)]
 /// struct MyStruct {
 ///     // fields...
 /// }
@@ -113,32 +1165,635 @@ impl<T: DerefDebugPrint> PointerPrint for T {
 ///     print_with_pointer_reading(my_struct, mem);
 /// }
 /// ```
+#[cfg(feature = "std")]
+pub fn print_with_pointer_reading<T: DerefDebugPrint, M: MemoryView>(value: &T, mem: &mut M) {
+    value.pointer_print(mem);
+}
+
+/// Read a `T` at `address` and print it with the given options.
+///
+/// This saves reading the root structure by hand before printing it. Pointers back to
+/// `address` are printed as already visited. If the structure itself can't be read nothing
+/// is printed and the failed read is returned, otherwise this behaves like
+/// [`PointerPrint::pointer_print_with_config`].
+///
+/// # Parameters
+///
+/// * `mem` - The memory view to read from
+/// * `address` - Address of the structure
+/// * `config` - Traversal and output options
+///
+/// # Example
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrintConfig, read_and_print};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct MyStruct {
+///     id: u64,
+///     next: Pointer64<MyStruct>,
+/// }
+///
+/// fn example(mem: &mut impl MemoryView, address: Address) {
+///     let config = PointerPrintConfig::new();
+///     if let Err(report) = read_and_print::<MyStruct, _>(mem, address, &config) {
+///         eprintln!("{}", report);
+///     }
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn read_and_print<T, M>(
+    mem: &mut M,
+    address: impl Into<Address>,
+    config: &PointerPrintConfig,
+) -> Result<(), PointerDebugReport>
+where
+    T: DerefDebugPrint + Pod,
+    M: MemoryView,
+{
+    let stopwatch = Stopwatch::start();
+    let address = address.into();
+    let mut mem = RetryView::new(mem, config);
+    let mut value: T = mem.read(address).map_err(|error| PointerDebugReport {
+        failures: vec![PointerReadFailure {
+            path: String::new(),
+            address: address.to_umem(),
+            error: error.into(),
+        }],
+        ..PointerDebugReport::default()
+    })?;
+
+    let mut state = TraversalState::with_config(config);
+    let raw_bytes = state.raw_bytes(&value);
+    state.apply_offsets(&mut value, address.to_umem(), &mut mem);
+    state.to_host_order(&mut value);
+    state.visit(address.to_umem(), std::any::type_name::<T>());
+    let mut node = value.pointer_collect_internal(&mut mem, 0, &mut state);
+    node.set_address(address.to_umem(), std::mem::align_of::<T>());
+    node.raw_bytes = raw_bytes;
+    state.finish_root(&node);
+
+    let mut stats = TraversalStats::from_node(&node);
+    stats.bytes = std::mem::size_of::<T>() + state.bytes_read;
+    stats.retries = mem.retries;
+    stats.elapsed = stopwatch.elapsed();
+    print_node(&node, &stats, config)
+}
 
 /// Re-export of the derive macro for implementing DerefDebugPrint.
 ///
-/// This derive macro automatically implements the DerefDebugPrint trait for structs,
-/// handling all the complexity of traversing fields, checking for pointers, and
+/// This derive macro automatically implements the DerefDebugPrint trait for structs, enums
+/// and unions, handling all the complexity of traversing fields, checking for pointers, and
 /// preventing infinite recursion due to circular references.
 ///
 /// # Example
 ///
 /// ```rust
+/// use memflow::prelude::v1::*;
 /// use memflow_pointer_debug::PointerDerefDebugPrint;
 ///
-/// #[derive(Debug, PointerDerefDebugPrint)]
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
 /// struct MyStruct {
-///     id: u32,
-///     name: String,
-///     next_ptr: Pointer<MyStruct>,
+///     id: u64,
+///     next_ptr: Pointer64<MyStruct>,
 /// }
 /// ```
 ///
-/// Fields containing "_pad" in their name will not be printed.
-/// This crate is designed to be used with the `offsetter` crate
-/// If you choose to manually pad just ensure your padding fields
-/// contain `_pad``
-pub fn print_with_pointer_reading<T: DerefDebugPrint, M: MemoryView>(value: &T, mem: &mut M) {
-    value.pointer_print(mem);
-}
-
+/// Padding fields are not printed. Mark them with `#[pointer_debug(pad)]` or name them with
+/// [`PointerPrintConfig::padding_pattern`], without a pattern fields containing `_pad` in
+/// their name are treated as padding, like the ones generated by the `offsetter` crate.
+///
+/// # Tuple structs and enums
+///
+/// Fields of tuple structs are labeled by their index, e.g. `0-> Node` for
+/// `struct Head(Pointer64<Node>);`. Attributes referring to a sibling field name it by its
+/// index as well, e.g. `array(len = "0")`.
+///
+/// For enums the active variant is printed as `Name::Variant`, the fields of tuple and
+/// struct variants are printed and followed like struct fields. `follow_chain` is not
+/// supported inside enum variants.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Object {
+///     id: u64,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct ObjectRef(Pointer64<Object>);
+///
+/// #[derive(Debug, PointerDerefDebugPrint)]
+/// enum Handle {
+///     Closed,
+///     Index(u32),
+///     Object {
+///         object: Pointer64<Object>,
+///         #[pointer_debug(format = "hex")]
+///         access: u32,
+///     },
+/// }
+/// ```
+///
+/// # Containers
+///
+/// Fields holding pointers inside arrays, slices, `Vec`, `Option` or tuples, e.g.
+/// `[Pointer64<Item>; 8]` or `Option<Pointer64<Item>>`, are collected through the
+/// [`DerefDebugPrint`] implementations of those containers. Elements are labeled `[0]`, `[1]`
+/// for arrays and `0`, `1` for tuples, `Some` values are shown like the contained value.
+/// Fixed-size arrays of pointers follow only the first
+/// [`max_elements`](PointerPrintConfig::max_elements) elements, the rest are printed as
+/// `[9]: <not followed 0x...>`.
+///
+/// The integer, float and `bool` primitives and memflow's `Address` implement
+/// [`DerefDebugPrint`] as well, so pointers to them like `Pointer64<u64>` are followed and
+/// printed as `count-> u64 = 42`.
+///
+/// # Generic types
+///
+/// Type parameters of a derived type are printed with `Debug`. A parameter named in a field
+/// that is followed, e.g. the `T` of `next: Pointer64<Node<T>>`, is bound by
+/// `T: Pod + DerefDebugPrint` instead, so generic containers are traversed for any
+/// printable `T`. Parameters only reached through `nested` fields are bound by
+/// `DerefDebugPrint` alone. Lifetimes and const parameters are kept as declared, e.g.
+/// `struct Buf<const N: usize> { data: [u8; N], next: Pointer64<Self> }`.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, PointerDerefDebugPrint)]
+/// struct Node<T> {
+///     value: T,
+///     next: Pointer64<Node<T>>,
+/// }
+///
+/// unsafe impl<T: Pod> Pod for Node<T> {}
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, PointerDerefDebugPrint)]
+/// #[pointer_debug(expected_size = 0x18)]
+/// struct Buf<const N: usize> {
+///     data: [u8; N],
+///     next: Pointer64<Self>,
+/// }
+///
+/// unsafe impl<const N: usize> Pod for Buf<N> {}
+///
+/// fn example(mem: &mut impl MemoryView, head: Pointer64<Node<u64>>, buf: Pointer64<Buf<16>>) {
+///     head.pointer_print(mem);
+///     buf.pointer_print(mem);
+/// }
+/// ```
+///
+/// # 32-bit targets
+///
+/// `Pointer32<T>` fields are followed like `Pointer64<T>` fields, with every attribute taking
+/// a pointer, so the structures of 32-bit processes and of the 32-bit side of WOW64
+/// processes are derived with their native pointer width. Structures on the 64-bit side that
+/// hold a 32-bit address in a 64-bit field, whose upper half may be garbage, read only its
+/// low bits with `addr_width = 32`.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Peb32 {
+///     flags: u32,
+///     ldr: Pointer32<u32>,
+///     #[pointer_debug(cstr)]
+///     image_name: Pointer32<u8>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, Pod, PointerDerefDebugPrint)]
+/// struct WowThread {
+///     #[pointer_debug(addr_width = 32)]
+///     peb32: Pointer64<Peb32>,
+/// }
+/// ```
+///
+/// # Packed structures
+///
+/// Structures declared `#[repr(C, packed)]` or with `packed(N)` are supported as well, their
+/// fields are copied before they are collected because they may be unaligned. All their
+/// fields have to be `Copy`, like those of any `Pod` structure.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C, packed)]
+/// #[derive(Clone, Copy, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Entry {
+///     kind: u8,
+///     next: Pointer64<Entry>,
+///     #[pointer_debug(cstr)]
+///     name: Pointer64<u8>,
+/// }
+/// ```
+///
+/// # Unions
+///
+/// Unions whose fields are all `Pod` can be derived once the union implements `Pod` itself.
+/// Every field is read from a copy of the bytes of the union, so only interpretations that
+/// are valid for any bytes can be printed. By default all of them are printed side by side,
+/// `#[pointer_debug(active = "raw")]` on the union prints only the `raw` field.
+///
+/// A structure embedding a union picks the field from a sibling with
+/// `#[pointer_debug(select_by = "kind")]`, the field of the union marked `when = 1` is printed
+/// when `kind` is 1. Values without a `when` field fall back to `active` or all fields.
+/// Pointers inside unions are followed like the fields of structures, but not read ahead with
+/// [`PointerPrintConfig::batch_reads`].
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Circle {
+///     radius: f32,
+///     segments: u32,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, PointerDerefDebugPrint)]
+/// #[pointer_debug(active = "raw")]
+/// union Body {
+///     #[pointer_debug(when = 1)]
+///     circle: Circle,
+///     #[pointer_debug(when = 2)]
+///     mesh: Pointer64<Circle>,
+///     #[pointer_debug(format = "hex")]
+///     raw: u64,
+/// }
+///
+/// unsafe impl Pod for Body {}
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, PointerDerefDebugPrint)]
+/// struct Shape {
+///     kind: u32,
+///     flags: u32,
+///     #[pointer_debug(select_by = "kind")]
+///     body: Body,
+/// }
+///
+/// fn example(mem: &mut impl MemoryView, shape: &Shape) {
+///     // Only `body.circle` is printed for `kind: 1`
+///     shape.pointer_print(mem);
+/// }
+/// ```
+///
+/// # Schema
+///
+/// The derive also describes the type through
+/// [`pointer_debug_schema`](DerefDebugPrint::pointer_debug_schema): the name, declared type,
+/// offset and size of every field and the target type of the pointers, see [`TypeSchema`].
+///
+/// # Type attributes
+///
+/// * `expected_size = 0x248` - fail the build if the size of the type is not `0x248`, so a
+///   missing or mistyped field can't silently shift all the fields after it. Types with type
+///   or const parameters are checked once they are collected. The size is printed in the
+///   header of the dump, e.g. `Player (0x248 bytes) {`
+/// * `active = "raw"` - print only the field `raw` of a union, see [Unions](#unions)
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// #[pointer_debug(expected_size = 0x10)]
+/// struct Player {
+///     id: u64,
+///     health: f32,
+///     armor: f32,
+/// }
+///
+/// # let mut mem = memflow::connector::FileIoMemory::new(std::io::Cursor::new(vec![0u8; 0x100]))
+/// #     .unwrap()
+/// #     .into_phys_view();
+/// let player = Player { id: 1, health: 100.0, armor: 50.0 };
+/// assert_eq!(player.pointer_collect(&mut mem, 5).size, Some(0x10));
+/// ```
+///
+/// ```rust,compile_fail
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// #[pointer_debug(expected_size = 0x18)]
+/// struct Player {
+///     id: u64,
+///     health: f32,
+///     armor: f32,
+/// }
+/// ```
+///
+/// # Field attributes
+///
+/// Individual fields can be configured with `#[pointer_debug(...)]`:
+///
+/// * `skip` - exclude the field from the output without renaming it
+/// * `pad` - the field is padding and never printed, whatever its name
+/// * `rename = "m_vOrigin"` - print the field under another name, e.g. the one a reversing
+///   tool shows. Paths, [`PointerDebugNode::field`] and [`Offsets`] use this name as well
+/// * `nested` - collect a structure embedded by value through its [`DerefDebugPrint`]
+///   implementation so its pointers are followed too, instead of printing it with `Debug`.
+///   This also works for containers of such structures like `Vec<Inner>`
+/// * `pointer` - follow a field of a pointer type implementing [`PointerLike`] whose name
+///   doesn't contain `Pointer`, e.g. a `RemotePtr<T>` newtype. Every attribute taking a
+///   pointer field works with it
+/// * `max_depth = 1` - follow at most this many levels below the field, replacing the
+///   remaining global depth for everything reachable through it. `max_depth = 0` shows the
+///   pointer without reading its target
+/// * `format = "hex" | "bin" | "oct"` - print an integer field with a `0x`/`0b`/`0o` prefix,
+///   zero padded to the width of its type
+/// * `flags(READ = 0x1, WRITE = 0x2, EXEC = 0x4)` - print an integer field as the names of its
+///   set bits, e.g. `READ | EXEC (0x5)`, see [`format_flags`]. `flags = "Type"` takes the
+///   names from a type declared with `bitflags!` (version 2) instead
+/// * `enum_repr = "ObjectType"` - print an integer field as the variant of a fieldless enum
+///   it converts to with `TryFrom`, e.g. derived with `num_enum::TryFromPrimitive`. The enum
+///   is printed with `Debug`, values that don't convert as `Unknown(42)`
+/// * `timestamp = "filetime" | "unix_secs" | "unix_millis"` - print an integer field as a UTC
+///   date and time followed by the raw value, e.g. `2023-11-14 22:13:20 UTC (1700000000)`,
+///   see [`format_timestamp`]
+/// * `cstr` / `cstr(max_len = 64)` - read a `Pointer64<u8>` style field as a NUL-terminated
+///   string instead of following it, at most `max_len` bytes (default 256) are read
+/// * `wstr` / `wstr(len_field = "length", max_len = 128)` - read a `Pointer64<u16>` style field
+///   as a UTF-16 string. Without `len_field` the string ends at the first NUL, with it the
+///   named sibling field holds the length in bytes like `UNICODE_STRING::Length`. At most
+///   `max_len` code units (default 256) are read. Both limits are clamped to
+///   [`PointerPrintConfig::max_string_bytes`] and decoded with
+///   [`PointerPrintConfig::string_decoding`]
+/// * `array(len = "count", max = 64)` - read `count` elements starting at the pointer with a
+///   single read and collect each of them, `count` names a sibling field. At most `max`
+///   elements (default 64) are read
+/// * `slice(len = "num_entities")` / `slice` / `slice(max = 16)` - the same for a pointer to
+///   the first element of a run whose length is held elsewhere or not known at all. Without
+///   `len` exactly `max` elements (default 64) are read, so the read fails if the run ends
+///   close to unmapped memory
+/// * `ptr_array_null_terminated` / `ptr_array_null_terminated(max = 256)` - read the
+///   pointers of an `argv` style array at a `Pointer64<Pointer64<T>>` field up to the first
+///   NULL entry and follow each of them. The targets are listed by index with their addresses
+///   like list records, at most `max` entries (default 256) are read
+/// * `buckets(count = "bucket_count", next = "next", max = 256, max_chain = 32)` - walk a
+///   hash table or handle table through a `Pointer64<Pointer64<Entry>>` field pointing to its
+///   bucket heads, `bucket_count` names the sibling field holding the number of buckets and
+///   `next` the field of `Entry` linking the entries of a bucket. Buckets are listed by index
+///   with their entries below them, empty buckets are null and can be left out with
+///   [`PointerPrintConfig::hide_null`]. Entries reached again, from their predecessor or
+///   another bucket, refer back to where they were printed. At most `max` buckets
+///   (default 256) and `max_chain` entries per bucket (default 32) are read
+/// * `hexdump = 64` - read this many bytes at a pointer whose target type isn't known yet and
+///   print them as an offset/hex/ASCII dump, see [`format_hexdump`]
+/// * `follow_chain` / `follow_chain(max = 32)` - iterate a linked list through a
+///   `next: Pointer64<Self>` style field. The elements are listed by index one level below
+///   the owner instead of nesting, so long lists don't run into the depth limit. The chain
+///   ends at a null link, a loop back to a visited element or after `max` elements (default 32)
+/// * `list(entry = "Process", field = "links", max = 32)` - walk the `LIST_ENTRY` list headed
+///   by an embedded [`ListEntry64`] field. Each entry is mapped back to the containing
+///   `Process` using the offset of its `links` field, `offset = 0x448` can be given instead of
+///   `field`. At most `max` records (default 32) are read
+/// * `with = "path::to::fmt"` - print the field with a `fn(&T) -> String`, e.g. for handles,
+///   GUIDs or fixed-point values. `with_mem = "path::to::fmt"` passes the memory view as a
+///   second argument, `fn<M: MemoryView>(&T, &mut M) -> String`. Pointer fields formatted
+///   this way are not followed
+/// * `deref_as = "Type"` - treat an integer field holding an address like a `Pointer64<Type>`,
+///   the `Type` there is read and followed with the usual null, cycle and depth checks
+/// * `ptr_mask = 0x0000_ffff_ffff_fff8` / `tag_bits = 3` - clear the address bits of a tagged
+///   pointer outside the mask, or its lowest `tag_bits` bits, before it is followed. The
+///   cleared bits are printed as a `<field>_tag` field right after it. Works together with
+///   `cstr`, `wstr`, `array` and `max_depth`
+/// * `addr_width = 32` - follow only the low 32 bits of the address of a pointer field, for
+///   32-bit addresses stored in 64-bit fields. The other bits are ignored and not printed,
+///   see [32-bit targets](#32-bit-targets)
+/// * `view = "kernel"` - read the target of a pointer into another address space through the
+///   memory view registered under that name with [`PointerPrintConfig::view`], everything
+///   reachable through it is read there too. Also works with `deref_as`
+/// * `phys` - read the target of a pointer or `deref_as` field holding a physical address,
+///   e.g. of a DMA descriptor or a page table entry, through the physical memory set with
+///   [`PointerPrintConfig::physical_memory`]. Physical addresses packed with other bits are
+///   unpacked by combining it with `ptr_mask`
+/// * `vtable` - print a pointer or address field as the module and export it points into,
+///   e.g. `client.dll+0x1a2b30 (CreateInterface+0x40)`, when the address is inside a module
+///   of [`PointerPrintConfig::symbols`]. Fields named like a vtable pointer, e.g. `vtable`,
+///   `__vfptr` or `_vptr`, don't need it
+/// * `function` - print a pointer or address field as the export of the function it points
+///   to, e.g. `kernel32.dll!CreateFileW+0x0`, when the address is inside a module of
+///   [`PointerPrintConfig::symbols`]. Address fields pointing into executable memory of a
+///   module, e.g. the entries of a callback table, don't need it
+/// * `redact` / `redact(hash)` - print `<redacted>` instead of the value of a field holding a
+///   token or credential, e.g. for dumps attached to bug reports. The field is still listed,
+///   its pointer isn't followed. With `hash` the value is collected with the other attributes
+///   of the field and printed as a hash, see [`PointerDebugValue::redacted_hash`]
+/// * `deref_if = "self.is_loaded != 0"` - follow a pointer field only while a condition on the
+///   fields of the structure holds, `self.0` names tuple fields. Otherwise the pointer is
+///   shown as [`PointerTarget::NotFollowed`] without reading it or marking it visited
+/// * `dyn_type(by = "class_id", map(1 = "Player", 2 = "Npc"))` - follow a pointer to a base
+///   type as the concrete type picked by the sibling integer field `class_id`, e.g. a
+///   `Pointer64<Entity>` is read as a `Player` when `class_id` is 1. Ids that are not mapped
+///   are read as the declared target type. `map(Player = 1, Npc = 2)` works as well
+/// * `select_by = "kind"` - collect an embedded union printing only its field picked by the
+///   value of the sibling integer field `kind`, see [Unions](#unions)
+/// * `when = 1` - on a field of a union, print the field when the selector of
+///   `select_by` is 1
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Desktop {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// #[derive(Debug, PointerDerefDebugPrint)]
+/// struct Session {
+///     #[pointer_debug(rename = "m_nUserId")]
+///     user_id: u64,
+///     #[pointer_debug(format = "hex")]
+///     flags: u32,
+///     #[pointer_debug(flags(READ = 0x1, WRITE = 0x2, EXEC = 0x4))]
+///     protection: u32,
+///     #[pointer_debug(timestamp = "filetime")]
+///     logon_time: u64,
+///     #[pointer_debug(skip)]
+///     token: [u8; 32],
+///     #[pointer_debug(cstr(max_len = 64))]
+///     user_name: Pointer64<u8>,
+///     machine_name_length: u16,
+///     #[pointer_debug(wstr(len_field = "machine_name_length"))]
+///     machine_name: Pointer64<u16>,
+///     #[pointer_debug(deref_as = "Desktop")]
+///     desktop: u64,
+///     #[pointer_debug(tag_bits = 3)]
+///     previous_desktop: Pointer64<Desktop>,
+///     is_locked: u8,
+///     #[pointer_debug(deref_if = "self.is_locked == 0")]
+///     input_desktop: Pointer64<Desktop>,
+///     #[pointer_debug(ptr_array_null_terminated(max = 16))]
+///     desktops: Pointer64<Pointer64<Desktop>>,
+/// }
+/// ```
+///
+/// A handle table hashing its entries into buckets:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct HandleEntry {
+///     handle: u64,
+///     object: u64,
+///     next: Pointer64<HandleEntry>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct HandleTable {
+///     bucket_count: u64,
+///     #[pointer_debug(buckets(count = "bucket_count", next = "next"))]
+///     buckets: Pointer64<Pointer64<HandleEntry>>,
+/// }
+/// ```
+///
+/// A pointer whose target type depends on a type id, the common layout of game object
+/// systems:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::PointerDerefDebugPrint;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Entity {
+///     class_id: u32,
+///     health: u32,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     #[pointer_debug(nested)]
+///     base: Entity,
+///     #[pointer_debug(cstr)]
+///     name: Pointer64<u8>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Npc {
+///     #[pointer_debug(nested)]
+///     base: Entity,
+///     target: Pointer64<Entity>,
+/// }
+///
+/// #[derive(Debug, PointerDerefDebugPrint)]
+/// struct EntitySlot {
+///     class_id: u32,
+///     #[pointer_debug(dyn_type(by = "class_id", map(1 = "Player", 2 = "Npc")))]
+///     entity: Pointer64<Entity>,
+/// }
+/// ```
+///
+/// A formatter given with `with` or `with_mem`:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDebugValue, PointerDerefDebugPrint, PointerPrint};
+///
+/// fn fixed_16_16(value: &i32) -> String {
+///     format!("{:.3}", *value as f64 / 65536.0)
+/// }
+///
+/// fn first_byte<M: MemoryView>(pointer: &Pointer64<u8>, mem: &mut M) -> String {
+///     match pointer.read(mem) {
+///         Ok(byte) => format!("starts with {:#04x}", byte),
+///         Err(_) => "unreadable".to_string(),
+///     }
+/// }
+///
+/// #[derive(Debug, PointerDerefDebugPrint)]
+/// struct Body {
+///     #[pointer_debug(with = "fixed_16_16")]
+///     mass: i32,
+///     #[pointer_debug(with_mem = "first_byte")]
+///     name: Pointer64<u8>,
+/// }
+///
+/// # let mut mem = memflow::connector::FileIoMemory::new(std::io::Cursor::new(vec![0u8; 0x100]))
+/// #     .unwrap()
+/// #     .into_phys_view();
+/// let body = Body {
+///     mass: 0x18000,
+///     name: Pointer64::null(),
+/// };
+/// let node = body.pointer_collect(&mut mem, 5);
+/// assert_eq!(node.fields[0].value, PointerDebugValue::Value("1.500".to_string()));
+/// assert_eq!(node.fields[1].value, PointerDebugValue::Value("starts with 0x00".to_string()));
+/// ```
+///
+/// A discriminant printed with `enum_repr`:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDebugValue, PointerDerefDebugPrint, PointerPrint};
+///
+/// #[derive(Debug)]
+/// #[repr(u32)]
+/// enum ObjectType {
+///     Process = 7,
+///     Thread = 8,
+/// }
+///
+/// impl TryFrom<u32> for ObjectType {
+///     type Error = u32;
+///
+///     fn try_from(value: u32) -> std::result::Result<Self, u32> {
+///         match value {
+///             7 => Ok(Self::Process),
+///             8 => Ok(Self::Thread),
+///             other => Err(other),
+///         }
+///     }
+/// }
+///
+/// #[derive(Debug, PointerDerefDebugPrint)]
+/// struct ObjectHeader {
+///     #[pointer_debug(enum_repr = "ObjectType")]
+///     kind: u32,
+///     #[pointer_debug(enum_repr = "ObjectType")]
+///     other_kind: u32,
+/// }
+///
+/// # let mut mem = memflow::connector::FileIoMemory::new(std::io::Cursor::new(vec![0u8; 0x100]))
+/// #     .unwrap()
+/// #     .into_phys_view();
+/// let header = ObjectHeader {
+///     kind: 8,
+///     other_kind: 42,
+/// };
+/// let node = header.pointer_collect(&mut mem, 5);
+/// assert_eq!(node.fields[0].value, PointerDebugValue::Value("Thread".to_string()));
+/// assert_eq!(node.fields[1].value, PointerDebugValue::Value("Unknown(42)".to_string()));
+/// ```
 pub use memflow_pointer_debug_derive::PointerDerefDebugPrint;
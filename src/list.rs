@@ -0,0 +1,163 @@
+//! Walking doubly-linked `LIST_ENTRY` lists.
+//!
+//! Windows kernel structures link their elements through a `LIST_ENTRY` embedded at some
+//! offset inside the containing structure. The list head is a `LIST_ENTRY` as well, the last
+//! element links back to it.
+
+use core::fmt;
+
+use memflow::dataview::Pod;
+use memflow::error::{Error, PartialResultExt};
+use memflow::mem::MemoryView;
+use memflow::types::Address;
+
+use crate::HashSet;
+use crate::prelude::*;
+
+/// A 64-bit `LIST_ENTRY`
+#[repr(C)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Pod)]
+pub struct ListEntry64 {
+    /// Address of the next entry
+    pub flink: u64,
+    /// Address of the previous entry
+    pub blink: u64,
+}
+
+impl fmt::Debug for ListEntry64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListEntry64")
+            .field("flink", &format_args!("{:#x}", self.flink))
+            .field("blink", &format_args!("{:#x}", self.blink))
+            .finish()
+    }
+}
+
+/// Iterates the entries of a list starting at its head.
+///
+/// The walk stops when it gets back to the head, at a null link or when the address of
+/// the list head is read again. Loops that skip the head are not detected here, callers
+/// have to keep track of the entries they have seen.
+#[derive(Debug, Clone)]
+pub struct ListWalker {
+    head: ListEntry64,
+    next: u64,
+    done: bool,
+}
+
+impl ListWalker {
+    /// Start walking the list owning `head`
+    pub fn new(head: ListEntry64) -> Self {
+        Self {
+            head,
+            next: head.flink,
+            done: false,
+        }
+    }
+
+    /// Address of the `LIST_ENTRY` read by the next call to [`next_entry`](Self::next_entry)
+    pub fn next_address(&self) -> Address {
+        self.next.into()
+    }
+
+    /// Read the next link and return the address of its `LIST_ENTRY`.
+    ///
+    /// Returns `Ok(None)` once the list is exhausted. After an error the walk is over.
+    ///
+    /// # Parameters
+    ///
+    /// * `mem` - The memory view to read from
+    pub fn next_entry<M: MemoryView>(&mut self, mem: &mut M) -> Result<Option<Address>, Error> {
+        if self.done || self.next == 0 {
+            return Ok(None);
+        }
+
+        let address = self.next;
+        let entry: ListEntry64 = match mem.read(address.into()) {
+            Ok(entry) => entry,
+            Err(e) => {
+                self.done = true;
+                return Err(e.into());
+            }
+        };
+
+        // The address of the head is not known, but reading it yields the head again
+        if entry == self.head {
+            self.done = true;
+            return Ok(None);
+        }
+
+        // The head points back to the last entry
+        self.done = address == self.head.blink;
+        self.next = entry.flink;
+
+        Ok(Some(address.into()))
+    }
+}
+
+/// Address of the structure containing a `LIST_ENTRY` at `offset`, like `CONTAINING_RECORD`.
+///
+/// # Parameters
+///
+/// * `entry` - Address of the embedded `LIST_ENTRY`
+/// * `offset` - Offset of the `LIST_ENTRY` inside the containing structure
+pub fn containing_record(entry: Address, offset: usize) -> Address {
+    entry - offset
+}
+
+/// Read every structure of a list.
+///
+/// Returns the address and value of each containing structure in list order, at most
+/// `max` of them are read.
+///
+/// # Parameters
+///
+/// * `mem` - The memory view to read from
+/// * `head` - The list head
+/// * `offset` - Offset of the `LIST_ENTRY` inside `T`
+/// * `max` - Maximum number of entries read
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{ListEntry64, read_list};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Pod)]
+/// struct Process {
+///     pid: u64,
+///     links: ListEntry64,
+/// }
+///
+/// fn pids(mem: &mut impl MemoryView, head: ListEntry64) -> Result<Vec<u64>> {
+///     let offset = core::mem::offset_of!(Process, links);
+///     let processes = read_list::<Process, _>(mem, head, offset, 1024)?;
+///     Ok(processes.into_iter().map(|(_, process)| process.pid).collect())
+/// }
+/// ```
+pub fn read_list<T: Pod, M: MemoryView>(
+    mem: &mut M,
+    head: ListEntry64,
+    offset: usize,
+    max: usize,
+) -> Result<Vec<(Address, T)>, Error> {
+    let mut walker = ListWalker::new(head);
+    let mut seen = HashSet::new();
+    let mut records = Vec::new();
+
+    while records.len() < max {
+        let Some(entry) = walker.next_entry(mem)? else {
+            break;
+        };
+        // Corrupted lists may loop without getting back to the head
+        if !seen.insert(entry.to_umem()) {
+            break;
+        }
+
+        let address = containing_record(entry, offset);
+        let mut record: T = memflow::dataview::zeroed();
+        mem.read_into(address, &mut record).data_part()?;
+        records.push((address, record));
+    }
+
+    Ok(records)
+}
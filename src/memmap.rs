@@ -0,0 +1,73 @@
+//! Mapped memory regions of a process.
+
+use core::ops::Range;
+
+use memflow::os::Process;
+use memflow::types::Address;
+
+use crate::prelude::*;
+
+/// The mapped memory regions of a process.
+///
+/// Set on a [`PointerPrintConfig`](crate::PointerPrintConfig) with
+/// [`mapped_memory`](crate::PointerPrintConfig::mapped_memory), pointers outside of every
+/// region are printed as `field: <unmapped 0x...>` instead of issuing a read that is bound
+/// to fail. Reads of such pointers are slow on DMA connectors so this is worth doing for
+/// large or corrupted structures.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MappedMemory {
+    /// Sorted, non-overlapping regions
+    ranges: Vec<Range<u64>>,
+}
+
+impl MappedMemory {
+    /// Create the map from a list of regions, they may overlap and don't need to be sorted
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<u64>>) -> Self {
+        let mut sorted: Vec<Range<u64>> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        sorted.sort_by_key(|range| range.start);
+
+        // Merge adjacent and overlapping regions so lookups can use a binary search
+        let mut ranges: Vec<Range<u64>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Query the mapped regions of a process.
+    ///
+    /// # Parameters
+    ///
+    /// * `process` - The process whose memory map is read
+    pub fn from_process<P: Process>(process: &mut P) -> Self {
+        let regions = process.mapped_mem_range_vec(0, Address::null(), Address::invalid());
+        Self::from_ranges(regions.into_iter().map(|region| {
+            let start = region.0.to_umem();
+            start..start.saturating_add(region.1)
+        }))
+    }
+
+    /// Returns true if `address` lies inside a mapped region
+    pub fn contains(&self, address: u64) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= address);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&address))
+    }
+
+    /// Returns true if no region is mapped, e.g. because the OS layer doesn't provide a
+    /// memory map
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The mapped regions in ascending order
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+}
@@ -0,0 +1,1371 @@
+//! Structured tree model produced by the pointer traversal.
+//!
+//! [`PointerPrint::pointer_collect`](crate::PointerPrint::pointer_collect) walks a structure
+//! and returns a [`PointerDebugNode`] instead of printing directly. The tree can then be
+//! inspected, filtered or rendered, the [`Display`](core::fmt::Display) implementation
+//! produces the same output as [`PointerPrint::pointer_print`](crate::PointerPrint::pointer_print).
+//!
+//! With the `serde` feature the tree implements `Serialize` and `Deserialize`, read errors
+//! are stored by their memflow error code.
+
+use core::fmt;
+
+use memflow::error::Error;
+
+use crate::compact::to_compact;
+use crate::prelude::*;
+use crate::ron::to_ron;
+use crate::style::{Style, paint};
+use crate::{
+    ElementTruncation, ErrorPolicy, HashMap, HashSet, OutputFormat, PointerPrintConfig,
+    StringDecoding, format_hexdump,
+};
+
+/// A structure visited during the traversal
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerDebugNode {
+    /// Name of the structure's type
+    pub type_name: String,
+    /// Address the structure was read from, `None` for the root value
+    pub address: Option<u64>,
+    /// Fields of the structure in declaration order, padding fields are not included
+    pub fields: Vec<PointerDebugField>,
+    /// Formatted value of scalar types like `u64`, they don't have fields
+    pub value: Option<String>,
+    /// Size of the structure in bytes, printed in the header. Recorded for derived
+    /// structures with `#[pointer_debug(expected_size = ...)]`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub size: Option<usize>,
+    /// Byte offset and size of the fields inside the structure by name, recorded when
+    /// [`PointerPrintConfig::show_offsets`] or [`PointerPrintConfig::sort_by_offset`] is set
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "HashMap::is_empty")
+    )]
+    pub field_locations: HashMap<String, (usize, usize)>,
+    /// Bytes the structure was read from, printed as a hex dump after its fields. Recorded
+    /// for pointer targets when [`PointerPrintConfig::show_raw_bytes`] is set
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub raw_bytes: Option<Vec<u8>>,
+    /// Set if the address isn't aligned to the alignment of the type, printed as
+    /// `⚠ misaligned` after the type name and counted in
+    /// [`TraversalStats::misaligned`](crate::TraversalStats::misaligned). The layout of the
+    /// structure holding the pointer is likely off by a few bytes
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "core::ops::Not::not")
+    )]
+    pub misaligned: bool,
+}
+
+/// A single field of a [`PointerDebugNode`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerDebugField {
+    /// Name of the field
+    pub name: String,
+    /// Declared type of the field, without generic arguments or module path
+    pub type_name: String,
+    /// The collected value
+    pub value: PointerDebugValue,
+}
+
+/// Value of a [`PointerDebugField`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerDebugValue {
+    /// A plain value formatted with its `Debug` implementation
+    Value(String),
+    /// A pointer field and the result of following it
+    Pointer {
+        /// The address stored in the pointer
+        address: u64,
+        /// What was found at that address
+        target: PointerTarget,
+    },
+    /// A value embedded in the structure that has fields of its own, e.g. an array of
+    /// pointers or a nested structure
+    Nested(Box<PointerDebugNode>),
+}
+
+/// Result of following a pointer field
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerTarget {
+    /// The target was read and collected
+    Node(Box<PointerDebugNode>),
+    /// The address was already visited earlier in the traversal
+    AlreadyVisited,
+    /// The target was not read because the maximum depth was reached
+    MaxDepth,
+    /// The target was read as a string
+    Text(String),
+    /// The target was read as raw bytes, printed as a hex dump
+    Bytes(Vec<u8>),
+    /// A run of elements was read starting at the target
+    Array {
+        /// Number of elements declared by the length field
+        len: usize,
+        /// The collected elements, may be fewer than `len` when capped
+        items: Vec<PointerTarget>,
+    },
+    /// A linked list was iterated starting at the target
+    Chain {
+        /// The collected elements in chain order, ends with `AlreadyVisited` when the
+        /// chain loops back to an earlier address
+        items: Vec<PointerTarget>,
+        /// Set when the chain continues beyond the element cap
+        more: bool,
+    },
+    /// The link to the next element of a chain that is iterated by its owner
+    ChainLink,
+    /// The structures linked through an embedded `LIST_ENTRY` list head, the targets of a
+    /// NULL-terminated pointer array or the buckets of a hash table, each holding the list of
+    /// its entries
+    List {
+        /// Address and contents of each containing structure in list order, ends with
+        /// `AlreadyVisited` when the list loops without getting back to the head
+        items: Vec<(u64, PointerTarget)>,
+        /// Set when the list continues beyond the element cap
+        more: bool,
+    },
+    /// The pointer is null, nothing was read
+    Null,
+    /// The address was rejected by the configured address checks, nothing was read
+    Invalid,
+    /// The address lies outside of the configured memory map, nothing was read
+    Unmapped,
+    /// The read budget of the traversal was exhausted, nothing was read
+    BudgetExhausted,
+    /// The traversal was cancelled, nothing was read
+    Cancelled,
+    /// The `deref_if` condition of the field didn't hold or the view of the field isn't
+    /// registered, nothing was read
+    NotFollowed,
+    /// Reading the target failed
+    ReadError(#[cfg_attr(feature = "serde", serde(with = "crate::snapshot::error_code"))] Error),
+}
+
+impl PointerDebugValue {
+    /// `<redacted>`, printed for fields marked `#[pointer_debug(redact)]` instead of their
+    /// value
+    pub fn redacted() -> Self {
+        Self::Value(String::from("<redacted>"))
+    }
+
+    /// `<redacted 9e3f4c1a2b7d8e05>` with a hash of this value, printed for fields marked
+    /// `#[pointer_debug(redact(hash))]`.
+    ///
+    /// Values and strings are hashed by their text and hex dumps by their bytes, anything
+    /// else by its `Debug` representation. The hash is the 64-bit FNV-1a, it tells whether
+    /// two dumps hold the same secret but short secrets can be guessed from it.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::PointerDebugValue;
+    ///
+    /// let token = PointerDebugValue::Value("\"hunter2\"".to_string());
+    /// let redacted = token.redacted_hash();
+    /// assert_eq!(redacted, token.clone().redacted_hash());
+    /// assert_ne!(redacted, PointerDebugValue::Value("\"hunter3\"".to_string()).redacted_hash());
+    /// assert_eq!(PointerDebugValue::redacted(), PointerDebugValue::Value("<redacted>".to_string()));
+    /// ```
+    pub fn redacted_hash(&self) -> Self {
+        let hash = match self {
+            Self::Value(text)
+            | Self::Pointer {
+                target: PointerTarget::Text(text),
+                ..
+            } => fnv1a(text.as_bytes()),
+            Self::Pointer {
+                target: PointerTarget::Bytes(bytes),
+                ..
+            } => fnv1a(bytes),
+            other => fnv1a(format!("{:?}", other).as_bytes()),
+        };
+        Self::Value(format!("<redacted {:016x}>", hash))
+    }
+}
+
+/// 64-bit FNV-1a hash of `bytes`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl PointerDebugNode {
+    /// Create an empty node for the given type
+    pub fn new(type_name: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            address: None,
+            fields: Vec::new(),
+            value: None,
+            size: None,
+            field_locations: HashMap::new(),
+            raw_bytes: None,
+            misaligned: false,
+        }
+    }
+
+    /// Create a node for a scalar value
+    pub fn scalar(type_name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            value: Some(value.into()),
+            ..Self::new(type_name)
+        }
+    }
+
+    /// Record that the node was read from `address`, it is flagged as
+    /// [`misaligned`](Self::misaligned) unless the address is a multiple of `align`
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::PointerDebugNode;
+    ///
+    /// let mut team = PointerDebugNode::new("Team");
+    /// team.set_address(0x2004, 8);
+    /// assert!(team.misaligned);
+    /// assert_eq!(team.to_string(), "Team ⚠ misaligned {\n}\n");
+    /// ```
+    pub fn set_address(&mut self, address: u64, align: usize) {
+        self.address = Some(address);
+        self.misaligned = !address.is_multiple_of(align as u64);
+    }
+
+    /// Look up a field of this node by name
+    pub fn field(&self, name: &str) -> Option<&PointerDebugField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Order the fields by their offset in [`field_locations`](Self::field_locations).
+    ///
+    /// Fields without a location stay right after the field before them, like the `_tag`
+    /// field of a tagged pointer, fields at the same offset keep their order.
+    pub(crate) fn sort_fields_by_offset(&mut self) {
+        let mut offset = 0;
+        let mut fields: Vec<_> = core::mem::take(&mut self.fields)
+            .into_iter()
+            .map(|field| {
+                if let Some((field_offset, _)) = self.field_locations.get(&field.name) {
+                    offset = *field_offset;
+                }
+                (offset, field)
+            })
+            .collect();
+        fields.sort_by_key(|(offset, _)| *offset);
+        self.fields = fields.into_iter().map(|(_, field)| field).collect();
+    }
+
+    /// Render this node with the given options instead of the defaults used by `Display`.
+    ///
+    /// Nodes that are pointed to again after they were printed get a label like `#1`, the
+    /// later pointers refer back to it:
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerPrintConfig,
+    ///     PointerTarget,
+    /// };
+    ///
+    /// let pointer = |name: &str, target| PointerDebugField {
+    ///     name: name.to_string(),
+    ///     type_name: "Pointer64".to_string(),
+    ///     value: PointerDebugValue::Pointer {
+    ///         address: 0x1000,
+    ///         target,
+    ///     },
+    /// };
+    ///
+    /// let mut shared = PointerDebugNode::new("Node");
+    /// shared.address = Some(0x1000);
+    /// let mut root = PointerDebugNode::new("Pair");
+    /// root.fields.push(pointer("left", PointerTarget::Node(Box::new(shared))));
+    /// root.fields.push(pointer("right", PointerTarget::AlreadyVisited));
+    ///
+    /// assert_eq!(
+    ///     root.display(&PointerPrintConfig::new()).to_string(),
+    ///     "Pair {\n  left-> Node #1\n  }\n  right → see Node #1 (0x1000)\n}\n"
+    /// );
+    /// ```
+    pub fn display<'a>(&'a self, config: &'a PointerPrintConfig) -> PointerDebugDisplay<'a> {
+        PointerDebugDisplay {
+            node: self,
+            config,
+            earlier: None,
+        }
+    }
+
+    /// Write this node at the given depth, the root is written at depth 0
+    fn fmt_depth(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+        refs: &Refs<'_>,
+        config: &PointerPrintConfig,
+    ) -> fmt::Result {
+        let indent = config.indent.repeat(depth);
+        let inner = config.indent.repeat(depth + 1);
+        let mut type_name = paint(&self.type_name, Style::TypeName, config);
+
+        let at = match self.address {
+            Some(address) => address_suffix(address, config),
+            None => String::new(),
+        };
+
+        // Blocks of the breadth-first output are numbered, the others only carry a label if
+        // they are pointed to again later
+        let block = refs.blocks.get(&(self as *const Self));
+        if block.is_none()
+            && let Some(label) = node_label(self, &refs.labels)
+        {
+            type_name = format!("{} #{}", type_name, label);
+        }
+        if let Some(size) = self.size {
+            type_name = format!("{} ({:#x} bytes)", type_name, size);
+        }
+        if self.misaligned {
+            type_name = format!(
+                "{} {}",
+                type_name,
+                paint("⚠ misaligned", Style::Error, config)
+            );
+        }
+
+        // Scalars fit on a single line
+        if let Some(value) = &self.value {
+            if depth > 0 {
+                return writeln!(f, " {}{} = {}", type_name, at, value);
+            }
+            return writeln!(f, "{}{} = {}", indent, type_name, value);
+        }
+
+        // Nested nodes continue the `field->` line of their parent
+        if depth > 0 {
+            writeln!(f, " {}{}", type_name, at)?;
+        } else if let Some((number, path)) = block {
+            let path = paint(path, Style::FieldName, config);
+            writeln!(f, "#{} {}-> {}{} {{", number, path, type_name, at)?;
+        } else {
+            writeln!(f, "{}{} {{", indent, type_name)?;
+        }
+
+        for shown in self.shown_fields(config) {
+            let field = match shown {
+                Shown::Item(_, field) => field,
+                Shown::Gap(more) => {
+                    writeln!(f, "{}... ({} more)", inner, more)?;
+                    continue;
+                }
+            };
+            let location = match self.field_locations.get(&field.name) {
+                Some((offset, size)) if config.show_offsets => paint(
+                    format_args!("+{:#x} ({}) ", offset, size),
+                    Style::Dim,
+                    config,
+                ),
+                _ => String::new(),
+            };
+            let inner = format!("{}{}", inner, location);
+            let name = paint(&field.name, Style::FieldName, config);
+            match &field.value {
+                PointerDebugValue::Value(value) if config.show_type_names => {
+                    let type_name = paint(&field.type_name, Style::TypeName, config);
+                    writeln!(f, "{}{}: {} = {}", inner, name, type_name, value)?;
+                }
+                PointerDebugValue::Value(value) => {
+                    writeln!(f, "{}{} = {}", inner, name, value)?;
+                }
+                PointerDebugValue::Pointer { address, target } => {
+                    if !is_hidden(target, config) {
+                        let label = format!("{}{}", location, field.name);
+                        fmt_target(f, depth, &label, *address, target, refs, config)?;
+                    }
+                }
+                PointerDebugValue::Nested(node) => {
+                    write!(f, "{}{}:", inner, name)?;
+                    node.fmt_depth(f, depth + 1, refs, config)?;
+                }
+            }
+        }
+
+        // The raw bytes come last so the parsed fields can be compared with them
+        if let Some(bytes) = &self.raw_bytes {
+            let raw = paint(
+                format_args!("raw ({} bytes):", bytes.len()),
+                Style::Dim,
+                config,
+            );
+            writeln!(f, "{}{}", inner, raw)?;
+            for line in format_hexdump(bytes).lines() {
+                writeln!(
+                    f,
+                    "{}{}{}",
+                    inner,
+                    config.indent,
+                    paint(line, Style::Dim, config)
+                )?;
+            }
+        }
+
+        writeln!(f, "{}}}", indent)
+    }
+
+    /// Fields of this node to print, the elements of an embedded array are shortened by
+    /// [`PointerPrintConfig::max_printed_elements`]
+    pub(crate) fn shown_fields(
+        &self,
+        config: &PointerPrintConfig,
+    ) -> Vec<Shown<'_, PointerDebugField>> {
+        // Elements are the only fields labeled by their index
+        let elements =
+            !self.fields.is_empty() && self.fields.iter().all(|field| field.name.starts_with('['));
+        if elements {
+            shown_elements(&self.fields, 0, config)
+        } else {
+            self.fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| Shown::Item(i, field))
+                .collect()
+        }
+    }
+}
+
+/// An element to print or a run of elements left out
+pub(crate) enum Shown<'a, T> {
+    /// The element at the index
+    Item(usize, &'a T),
+    /// This many elements are left out
+    Gap(usize),
+}
+
+/// The elements of `items` to print with [`PointerPrintConfig::max_printed_elements`] and
+/// the gaps left between them, `unread` elements following `items` that were not read
+/// are left out at the end
+pub(crate) fn shown_elements<'a, T>(
+    items: &'a [T],
+    unread: usize,
+    config: &PointerPrintConfig,
+) -> Vec<Shown<'a, T>> {
+    let len = items.len();
+    let (head, tail) = match config.max_printed_elements {
+        Some(max) if max < len => match config.element_truncation {
+            ElementTruncation::Head => (max, 0),
+            ElementTruncation::HeadTail => (max - max / 2, max / 2),
+        },
+        _ => (len, 0),
+    };
+
+    let mut shown: Vec<_> = items[..head]
+        .iter()
+        .enumerate()
+        .map(|(i, item)| Shown::Item(i, item))
+        .collect();
+    let skipped = len - head - tail;
+    if tail > 0 {
+        shown.push(Shown::Gap(skipped));
+        shown.extend(
+            items[len - tail..]
+                .iter()
+                .enumerate()
+                .map(|(i, item)| Shown::Item(len - tail + i, item)),
+        );
+        if unread > 0 {
+            shown.push(Shown::Gap(unread));
+        }
+    } else if skipped + unread > 0 {
+        shown.push(Shown::Gap(skipped + unread));
+    }
+    shown
+}
+
+/// Returns true if `target` is left out of the output by the error policy or `hide_null`
+pub(crate) fn is_hidden(target: &PointerTarget, config: &PointerPrintConfig) -> bool {
+    match target {
+        PointerTarget::ReadError(_) => config.error_policy == ErrorPolicy::Hide,
+        PointerTarget::Null => config.hide_null,
+        _ => false,
+    }
+}
+
+/// The text of a string target as printed, it is quoted unless the
+/// [`string_decoding`](PointerPrintConfig::string_decoding) stored it quoted already
+pub(crate) fn quoted_text(text: &str, config: &PointerPrintConfig) -> String {
+    match config.string_decoding {
+        StringDecoding::Lossy => format!("{:?}", text),
+        StringDecoding::Escape | StringDecoding::Hex => text.to_string(),
+    }
+}
+
+/// Write a pointer target labeled `label` inside a node at the given depth
+fn fmt_target(
+    f: &mut fmt::Formatter<'_>,
+    depth: usize,
+    label: &str,
+    address: u64,
+    target: &PointerTarget,
+    refs: &Refs<'_>,
+    config: &PointerPrintConfig,
+) -> fmt::Result {
+    let indent = config.indent.repeat(depth + 1);
+    let inner = config.indent.repeat(depth + 2);
+    let at = address_suffix(address, config);
+    let label = paint(label, Style::FieldName, config);
+    let hex = paint(format_args!("{:#x}", address), Style::Address, config);
+
+    match target {
+        PointerTarget::Node(node) => match refs.blocks.get(&(&**node as *const PointerDebugNode)) {
+            // The breadth-first output writes the node as a block of its own later
+            Some((number, _)) => {
+                let type_name = paint(&node.type_name, Style::TypeName, config);
+                writeln!(f, "{}{}-> {} #{}", indent, label, type_name, number)
+            }
+            None => {
+                write!(f, "{}{}->", indent, label)?;
+                node.fmt_depth(f, depth + 1, refs, config)
+            }
+        },
+        PointerTarget::AlreadyVisited => {
+            fmt_visited(f, &indent, &label, address, None, refs, config)
+        }
+        PointerTarget::MaxDepth => {
+            let message = paint("→ Max depth reached", Style::Dim, config);
+            writeln!(f, "{}{}{} {}", indent, label, at, message)
+        }
+        PointerTarget::Text(text) => {
+            writeln!(
+                f,
+                "{}{}-> {}{}",
+                indent,
+                label,
+                quoted_text(text, config),
+                at
+            )
+        }
+        PointerTarget::Bytes(bytes) => {
+            writeln!(f, "{}{}-> {} bytes{}", indent, label, bytes.len(), at)?;
+            for line in format_hexdump(bytes).lines() {
+                writeln!(f, "{}{}", inner, line)?;
+            }
+            Ok(())
+        }
+        PointerTarget::Array { len, items } => {
+            writeln!(f, "{}{}-> [{}]{}", indent, label, len, at)?;
+            let items_with_address = items
+                .iter()
+                .map(|item| (item_address(item, address), item))
+                .collect();
+            let unread = len.saturating_sub(items.len());
+            fmt_items(f, depth, items_with_address, unread, refs, config)?;
+            writeln!(f, "{}]", indent)
+        }
+        PointerTarget::Chain { items, more } => {
+            writeln!(f, "{}{}-> chain{}", indent, label, at)?;
+            let items_with_address = chain_addresses(address, items)
+                .into_iter()
+                .zip(items)
+                .collect();
+            fmt_items(f, depth, items_with_address, 0, refs, config)?;
+            if *more {
+                writeln!(f, "{}... (chain continues)", inner)?;
+            }
+            writeln!(f, "{}]", indent)
+        }
+        PointerTarget::List { items, more } => {
+            writeln!(f, "{}{}-> list{}", indent, label, at)?;
+            let items_with_address = items
+                .iter()
+                .map(|(address, item)| (*address, item))
+                .collect();
+            fmt_items(f, depth, items_with_address, 0, refs, config)?;
+            if *more {
+                writeln!(f, "{}... (list continues)", inner)?;
+            }
+            writeln!(f, "{}]", indent)
+        }
+        PointerTarget::Null => {
+            writeln!(
+                f,
+                "{}{}: {}",
+                indent,
+                label,
+                paint("NULL", Style::Dim, config)
+            )
+        }
+        PointerTarget::Invalid => fmt_skipped(f, &indent, &label, "invalid", &hex, config),
+        PointerTarget::Unmapped => fmt_skipped(f, &indent, &label, "unmapped", &hex, config),
+        PointerTarget::BudgetExhausted => {
+            fmt_skipped(f, &indent, &label, "budget exhausted", &hex, config)
+        }
+        PointerTarget::Cancelled => fmt_skipped(f, &indent, &label, "cancelled", &hex, config),
+        PointerTarget::NotFollowed => fmt_skipped(f, &indent, &label, "not followed", &hex, config),
+        PointerTarget::ChainLink => {
+            let message = paint("→ Next in chain", Style::Dim, config);
+            writeln!(f, "{}{} {} {}", indent, label, message, hex)
+        }
+        PointerTarget::ReadError(_) if config.error_policy == ErrorPolicy::Placeholder => {
+            let message = paint("<unreadable>", Style::Dim, config);
+            writeln!(f, "{}{}: {}", indent, label, message)
+        }
+        PointerTarget::ReadError(e) => {
+            let message = paint(format_args!("→ Error reading: {}", e), Style::Error, config);
+            writeln!(f, "{}{}{} {}", indent, label, at, message)
+        }
+    }
+}
+
+/// Write a target that was skipped without reading it as `label: <reason 0x...>`
+fn fmt_skipped(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    label: &str,
+    reason: &str,
+    address: &str,
+    config: &PointerPrintConfig,
+) -> fmt::Result {
+    let open = paint(format_args!("<{}", reason), Style::Skipped, config);
+    let close = paint(">", Style::Skipped, config);
+    writeln!(f, "{}{}: {} {}{}", indent, label, open, address, close)
+}
+
+/// ` @ 0x...` if addresses are shown, followed by ` [region]` if the regions are known,
+/// otherwise empty
+fn address_suffix(address: u64, config: &PointerPrintConfig) -> String {
+    let mut suffix = if config.show_addresses {
+        format!(
+            " @ {}",
+            paint(format_args!("{:#x}", address), Style::Address, config)
+        )
+    } else {
+        String::new()
+    };
+    if let Some(region) = config.region(address) {
+        let region = paint(format_args!("[{}]", region), Style::Dim, config);
+        suffix = format!("{} {}", suffix, region);
+    }
+    suffix
+}
+
+/// Write a pointer labeled `label` to the already visited `address`, `type_name` is the
+/// type of its target if known
+fn fmt_visited(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    label: &str,
+    address: u64,
+    type_name: Option<&str>,
+    refs: &Refs<'_>,
+    config: &PointerPrintConfig,
+) -> fmt::Result {
+    let earlier = refs
+        .earlier
+        .and_then(|printed| printed.referred(address, type_name));
+    let message = match (refs.labels.referred(address, type_name), earlier) {
+        (Some((number, type_name)), _) => paint(
+            format_args!("→ see {} #{} ({:#x})", type_name, number, address),
+            Style::Dim,
+            config,
+        ),
+        (None, Some((Some(number), type_name))) => paint(
+            format_args!("→ see {} #{} ({:#x})", type_name, number, address),
+            Style::Dim,
+            config,
+        ),
+        // Printed by an earlier dump of the session without being pointed to again
+        (None, Some((None, type_name))) => paint(
+            format_args!("→ see {} above ({:#x})", type_name, address),
+            Style::Dim,
+            config,
+        ),
+        // The root was not read from memory or the type of the target is not known, there
+        // is no node to match
+        (None, None) => paint(
+            format_args!("→ Already visited address {:#x}", address),
+            Style::Dim,
+            config,
+        ),
+    };
+    writeln!(f, "{}{} {}", indent, label, message)
+}
+
+/// Write the elements of an array, chain or list labeled by their index, followed by
+/// `... (N more)` for the `unread` elements after them
+fn fmt_items(
+    f: &mut fmt::Formatter<'_>,
+    depth: usize,
+    items: Vec<(u64, &PointerTarget)>,
+    unread: usize,
+    refs: &Refs<'_>,
+    config: &PointerPrintConfig,
+) -> fmt::Result {
+    let element = element_type(&items);
+    for shown in shown_elements(&items, unread, config) {
+        match shown {
+            Shown::Item(i, (address, item)) => {
+                let label = format!("[{}]", i);
+                if **item == PointerTarget::AlreadyVisited {
+                    let indent = config.indent.repeat(depth + 2);
+                    let label = paint(&label, Style::FieldName, config);
+                    fmt_visited(f, &indent, &label, *address, element, refs, config)?;
+                } else if !is_hidden(item, config) {
+                    fmt_target(f, depth + 1, &label, *address, item, refs, config)?;
+                }
+            }
+            Shown::Gap(more) => {
+                writeln!(f, "{}... ({} more)", config.indent.repeat(depth + 2), more)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Type name of the elements of an array, chain or list, `None` if none of them was read
+pub(crate) fn element_type<'a>(items: &[(u64, &'a PointerTarget)]) -> Option<&'a str> {
+    items.iter().find_map(|(_, item)| match item {
+        PointerTarget::Node(node) => Some(node.type_name.as_str()),
+        _ => None,
+    })
+}
+
+/// Label numbers of the nodes that are pointed to again after being printed.
+///
+/// The same address can be read as different types, a label belongs to the node of one
+/// type like the visited set.
+pub(crate) struct Labels<'a> {
+    /// Label number and type name of the labeled nodes, by address
+    numbers: HashMap<u64, Vec<(usize, &'a str)>>,
+    /// Type names read from every address pointed to again, `None` for a target that
+    /// failed to read
+    types: HashMap<u64, Vec<Option<&'a str>>>,
+}
+
+impl<'a> Labels<'a> {
+    /// No labels yet, the targets read from the `shared` addresses below `root` are
+    /// recorded to match the pointers to them
+    fn new(root: &'a PointerDebugNode, shared: &HashSet<u64>) -> Self {
+        let mut types: HashMap<u64, Vec<Option<&'a str>>> = HashMap::new();
+        let mut add = |address, type_name| {
+            let read = types.entry(address).or_default();
+            if !read.contains(&type_name) {
+                read.push(type_name);
+            }
+        };
+        if let Some(address) = root.address.filter(|address| shared.contains(address)) {
+            add(address, Some(root.type_name.as_str()));
+        }
+        visit_targets(root, &mut |address, target| match target {
+            PointerTarget::Node(node) => {
+                if let Some(address) = node.address.filter(|address| shared.contains(address)) {
+                    add(address, Some(node.type_name.as_str()));
+                }
+            }
+            PointerTarget::ReadError(_) if shared.contains(&address) => add(address, None),
+            _ => {}
+        });
+        Self {
+            numbers: HashMap::new(),
+            types,
+        }
+    }
+
+    /// Label `node` with `number` unless it has a label already
+    fn insert(&mut self, node: &'a PointerDebugNode, number: usize) {
+        let Some(address) = node.address else {
+            return;
+        };
+        let labeled = self.numbers.entry(address).or_default();
+        if !labeled
+            .iter()
+            .any(|(_, type_name)| *type_name == node.type_name)
+        {
+            labeled.push((number, node.type_name.as_str()));
+        }
+    }
+
+    /// Number of labeled nodes
+    fn len(&self) -> usize {
+        self.numbers.values().map(Vec::len).sum()
+    }
+
+    fn numbers(&self) -> impl Iterator<Item = usize> + '_ {
+        self.numbers.values().flatten().map(|(number, _)| *number)
+    }
+
+    fn numbers_mut(&mut self) -> impl Iterator<Item = &mut usize> + '_ {
+        self.numbers
+            .values_mut()
+            .flatten()
+            .map(|(number, _)| number)
+    }
+
+    /// Label number of the node read from `address` as `type_name`
+    fn get(&self, address: u64, type_name: &str) -> Option<usize> {
+        let labeled = self.numbers.get(&address)?;
+        labeled
+            .iter()
+            .find(|(_, labeled)| *labeled == type_name)
+            .map(|(number, _)| *number)
+    }
+
+    /// Label number and type name of the node an already visited pointer to `address`
+    /// refers to. Without the `type_name` of the target the label is only used if every
+    /// target read from the address has the same type
+    pub(crate) fn referred(
+        &self,
+        address: u64,
+        type_name: Option<&str>,
+    ) -> Option<(usize, &'a str)> {
+        let type_name = match (type_name, self.types.get(&address).map(Vec::as_slice)) {
+            (Some(type_name), _) => type_name,
+            (None, Some([Some(type_name)])) => *type_name,
+            (None, _) => return None,
+        };
+        let labeled = self.numbers.get(&address)?;
+        labeled
+            .iter()
+            .find(|(_, labeled)| *labeled == type_name)
+            .copied()
+    }
+}
+
+/// Label the nodes below `root` that later pointers refer back to, numbered in the order
+/// they are printed
+pub(crate) fn shared_labels(root: &PointerDebugNode) -> Labels<'_> {
+    let shared = shared_addresses(root);
+    let mut labels = Labels::new(root, &shared);
+    if root
+        .address
+        .is_some_and(|address| shared.contains(&address))
+    {
+        labels.insert(root, 1);
+    }
+    visit_targets(root, &mut |_, target| {
+        if let PointerTarget::Node(node) = target
+            && node
+                .address
+                .is_some_and(|address| shared.contains(&address))
+        {
+            let next = labels.len() + 1;
+            labels.insert(node, next);
+        }
+    });
+    labels
+}
+
+/// Addresses of the nodes below `root` that are referenced again after being printed
+fn shared_addresses(root: &PointerDebugNode) -> HashSet<u64> {
+    let mut shared = HashSet::new();
+    visit_targets(root, &mut |address, target| {
+        if *target == PointerTarget::AlreadyVisited {
+            shared.insert(address);
+        }
+    });
+    shared
+}
+
+/// How the nodes of a tree are referred to while it is written
+pub(crate) struct Refs<'a> {
+    /// Labels of the nodes pointed to again after they were printed
+    labels: Labels<'a>,
+    /// Number and path of the nodes written as blocks of their own after the root
+    blocks: HashMap<*const PointerDebugNode, (usize, String)>,
+    /// Nodes printed by the earlier dumps of a session
+    earlier: Option<&'a PrintedNodes>,
+}
+
+impl<'a> Refs<'a> {
+    /// Every target is written in place below the pointer leading to it
+    fn depth_first(root: &'a PointerDebugNode) -> Self {
+        Self {
+            labels: shared_labels(root),
+            blocks: HashMap::new(),
+            earlier: None,
+        }
+    }
+
+    /// Refer to the nodes of `root` as the configured tree output does. Returns the blocks
+    /// of the breadth-first output in order
+    fn tree(
+        root: &'a PointerDebugNode,
+        config: &PointerPrintConfig,
+    ) -> (Self, Vec<&'a PointerDebugNode>) {
+        if config.breadth_first {
+            Self::breadth_first(root, config)
+        } else {
+            (Self::depth_first(root), Vec::new())
+        }
+    }
+
+    /// Continue the numbering of the earlier dumps of a session, pointers to the nodes they
+    /// printed refer back to them
+    fn after(mut self, printed: &'a PrintedNodes) -> Self {
+        for number in self.labels.numbers_mut() {
+            *number += printed.labels;
+        }
+        for (number, _) in self.blocks.values_mut() {
+            *number += printed.labels;
+        }
+        self.earlier = Some(printed);
+        self
+    }
+
+    /// Highest number handed out, `0` if there is none
+    fn last_number(&self) -> usize {
+        let labels = self.labels.numbers();
+        let blocks = self.blocks.values().map(|(number, _)| *number);
+        labels.chain(blocks).max().unwrap_or(0)
+    }
+
+    /// The structures pointed to are written as numbered blocks after the root, level by
+    /// level, and their pointers refer to them by number. Returns the blocks in order
+    fn breadth_first(
+        root: &'a PointerDebugNode,
+        config: &PointerPrintConfig,
+    ) -> (Self, Vec<&'a PointerDebugNode>) {
+        let shared = shared_addresses(root);
+        let mut labels = Labels::new(root, &shared);
+        if root
+            .address
+            .is_some_and(|address| shared.contains(&address))
+        {
+            labels.insert(root, 1);
+        }
+        let mut queue = BlockQueue {
+            config,
+            shared,
+            order: Vec::new(),
+        };
+        queue.push_fields(root, "");
+        let mut next = 0;
+        while let Some((node, path)) = queue.order.get(next).cloned() {
+            queue.push_fields(node, &path);
+            next += 1;
+        }
+
+        let first = labels.len() + 1;
+        let mut blocks = HashMap::new();
+        for (i, (node, path)) in queue.order.iter().enumerate() {
+            labels.insert(node, first + i);
+            // Scalars are numbered to be referred back to but stay in place
+            if node.value.is_none() {
+                blocks.insert(*node as *const PointerDebugNode, (first + i, path.clone()));
+            }
+        }
+        let order = queue
+            .order
+            .into_iter()
+            .map(|(node, _)| node)
+            .filter(|node| node.value.is_none())
+            .collect();
+        let refs = Self {
+            labels,
+            blocks,
+            earlier: None,
+        };
+        (refs, order)
+    }
+}
+
+/// The nodes numbered by the breadth-first output in the order they are printed
+struct BlockQueue<'a, 'c> {
+    config: &'c PointerPrintConfig,
+    /// Addresses of the nodes pointed to again after they were printed
+    shared: HashSet<u64>,
+    /// Every structure pointed to and the scalars pointed to again, with the path of the
+    /// pointer leading to them
+    order: Vec<(&'a PointerDebugNode, String)>,
+}
+
+impl<'a> BlockQueue<'a, '_> {
+    /// Queue the nodes the printed pointers of `node` lead to, `node` is reached through
+    /// `path`
+    fn push_fields(&mut self, node: &'a PointerDebugNode, path: &str) {
+        for shown in node.shown_fields(self.config) {
+            let Shown::Item(_, field) = shown else {
+                continue;
+            };
+            let path = if path.is_empty() || field.name.starts_with('[') {
+                format!("{}{}", path, field.name)
+            } else {
+                format!("{}.{}", path, field.name)
+            };
+            match &field.value {
+                PointerDebugValue::Pointer { target, .. } => self.push_target(target, path),
+                PointerDebugValue::Nested(nested) => self.push_fields(nested, &path),
+                PointerDebugValue::Value(_) => {}
+            }
+        }
+    }
+
+    /// Queue the nodes of `target` reached through `path`
+    fn push_target(&mut self, target: &'a PointerTarget, path: String) {
+        if is_hidden(target, self.config) {
+            return;
+        }
+        let items: Vec<_> = match target {
+            PointerTarget::Node(node) => {
+                let shared = node
+                    .address
+                    .is_some_and(|address| self.shared.contains(&address));
+                if node.value.is_none() || shared {
+                    self.order.push((node, path));
+                }
+                return;
+            }
+            PointerTarget::Array { items, .. } | PointerTarget::Chain { items, .. } => {
+                items.iter().collect()
+            }
+            PointerTarget::List { items, .. } => items.iter().map(|(_, item)| item).collect(),
+            _ => return,
+        };
+        for shown in shown_elements(&items, 0, self.config) {
+            if let Shown::Item(i, item) = shown {
+                self.push_target(item, format!("{}[{}]", path, i));
+            }
+        }
+    }
+}
+
+/// The nodes printed by the earlier dumps of a
+/// [`PointerDebugSession`](crate::PointerDebugSession)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PrintedNodes {
+    /// Label number, if the node got one, and type name of every node printed, by address
+    nodes: HashMap<u64, Vec<(Option<usize>, String)>>,
+    /// Numbers handed out to labels and blocks so far
+    labels: usize,
+}
+
+impl PrintedNodes {
+    /// Render `root` like [`PointerDebugNode::display`] after the earlier dumps and record
+    /// its nodes for the next ones. Only the tree output refers back to earlier dumps
+    pub(crate) fn display(
+        &mut self,
+        root: &PointerDebugNode,
+        config: &PointerPrintConfig,
+    ) -> String {
+        let output = match config.format {
+            OutputFormat::Tree => PointerDebugDisplay {
+                node: root,
+                config,
+                earlier: Some(self),
+            }
+            .to_string(),
+            OutputFormat::Compact | OutputFormat::Ron => root.display(config).to_string(),
+        };
+
+        let (refs, _) = Refs::tree(root, config);
+        let refs = refs.after(self);
+        let mut nodes = Vec::new();
+        if let Some(address) = root.address {
+            nodes.push((
+                address,
+                node_label(root, &refs.labels),
+                root.type_name.clone(),
+            ));
+        }
+        visit_targets(root, &mut |_, target| {
+            if let PointerTarget::Node(node) = target
+                && let Some(address) = node.address
+            {
+                nodes.push((
+                    address,
+                    node_label(node, &refs.labels),
+                    node.type_name.clone(),
+                ));
+            }
+        });
+        let last = refs.last_number();
+        drop(refs);
+        self.labels = self.labels.max(last);
+        for (address, label, type_name) in nodes {
+            let printed = self.nodes.entry(address).or_default();
+            if !printed.iter().any(|(_, printed)| *printed == type_name) {
+                printed.push((label, type_name));
+            }
+        }
+        output
+    }
+
+    /// Label number and type name of the node printed from `address` an already visited
+    /// pointer refers to, like [`Labels::referred`]
+    fn referred(&self, address: u64, type_name: Option<&str>) -> Option<(Option<usize>, &str)> {
+        let printed = self.nodes.get(&address)?;
+        let found = match type_name {
+            Some(type_name) => printed.iter().find(|(_, printed)| printed == type_name),
+            None if printed.len() == 1 => printed.first(),
+            None => None,
+        };
+        found.map(|(label, type_name)| (*label, type_name.as_str()))
+    }
+
+    /// Forget every node printed so far, numbering starts over
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Label of `node` if later pointers refer back to it.
+///
+/// Another node read from the same address as a different type doesn't share the label.
+pub(crate) fn node_label(node: &PointerDebugNode, labels: &Labels<'_>) -> Option<usize> {
+    labels.get(node.address?, &node.type_name)
+}
+
+/// Call `f` with every pointer target below `node` in the order they are printed
+fn visit_targets<'a>(node: &'a PointerDebugNode, f: &mut impl FnMut(u64, &'a PointerTarget)) {
+    for field in &node.fields {
+        match &field.value {
+            PointerDebugValue::Pointer { address, target } => visit_target(*address, target, f),
+            PointerDebugValue::Nested(node) => visit_targets(node, f),
+            PointerDebugValue::Value(_) => {}
+        }
+    }
+}
+
+fn visit_target<'a>(
+    address: u64,
+    target: &'a PointerTarget,
+    f: &mut impl FnMut(u64, &'a PointerTarget),
+) {
+    f(address, target);
+    match target {
+        PointerTarget::Node(node) => visit_targets(node, f),
+        PointerTarget::Array { items, .. } => {
+            for item in items {
+                visit_target(item_address(item, address), item, f);
+            }
+        }
+        PointerTarget::Chain { items, .. } => {
+            for (item_address, item) in chain_addresses(address, items).into_iter().zip(items) {
+                visit_target(item_address, item, f);
+            }
+        }
+        PointerTarget::List { items, .. } => {
+            for (item_address, item) in items {
+                visit_target(*item_address, item, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Address of an array or chain element, falls back to the address of the pointer
+pub(crate) fn item_address(item: &PointerTarget, address: u64) -> u64 {
+    match item {
+        PointerTarget::Node(node) => node.address.unwrap_or(address),
+        _ => address,
+    }
+}
+
+/// Addresses of the elements of a chain starting at `address`.
+///
+/// Every element after the first one is found through the link field of its predecessor.
+pub(crate) fn chain_addresses(address: u64, items: &[PointerTarget]) -> Vec<u64> {
+    let mut next = address;
+    items
+        .iter()
+        .map(|item| {
+            let item_address = item_address(item, next);
+            if let PointerTarget::Node(node) = item {
+                next = node
+                    .fields
+                    .iter()
+                    .find_map(|field| match field.value {
+                        PointerDebugValue::Pointer {
+                            address,
+                            target: PointerTarget::ChainLink,
+                        } => Some(address),
+                        _ => None,
+                    })
+                    .unwrap_or(next);
+            }
+            item_address
+        })
+        .collect()
+}
+
+impl fmt::Display for PointerDebugNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_depth(
+            f,
+            0,
+            &Refs::depth_first(self),
+            &PointerPrintConfig::default(),
+        )
+    }
+}
+
+/// A [`PointerDebugNode`] rendered with a [`PointerPrintConfig`], see
+/// [`PointerDebugNode::display`]
+#[derive(Debug, Clone, Copy)]
+pub struct PointerDebugDisplay<'a> {
+    node: &'a PointerDebugNode,
+    config: &'a PointerPrintConfig,
+    /// Nodes printed by the earlier dumps of a session
+    earlier: Option<&'a PrintedNodes>,
+}
+
+impl fmt::Display for PointerDebugDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.config.format {
+            OutputFormat::Tree => {
+                let (mut refs, blocks) = Refs::tree(self.node, self.config);
+                if let Some(earlier) = self.earlier {
+                    refs = refs.after(earlier);
+                }
+                self.node.fmt_depth(f, 0, &refs, self.config)?;
+                for block in blocks {
+                    block.fmt_depth(f, 0, &refs, self.config)?;
+                }
+                Ok(())
+            }
+            OutputFormat::Compact => writeln!(f, "{}", to_compact(self.node, self.config)),
+            OutputFormat::Ron => f.write_str(&to_ron(self.node, self.config)),
+        }
+    }
+}
+
+/// Description of a target without contents to show, like `already visited 0x10` or
+/// `NULL`, as listed by the interactive browsers
+#[cfg(any(feature = "egui", feature = "tui", feature = "repl"))]
+pub(crate) struct TargetNote {
+    pub(crate) text: String,
+    /// Set if the pointer leads to a target shown elsewhere, otherwise the note is about
+    /// the pointer itself
+    pub(crate) leads: bool,
+}
+
+/// The [`TargetNote`] of a pointer to `address`, `None` for targets that are shown with
+/// their contents or an error
+#[cfg(any(feature = "egui", feature = "tui", feature = "repl"))]
+pub(crate) fn target_note(target: &PointerTarget, address: u64) -> Option<TargetNote> {
+    let (text, leads) = match target {
+        PointerTarget::AlreadyVisited => (format!("already visited {:#x}", address), true),
+        PointerTarget::ChainLink => (format!("next in chain {:#x}", address), true),
+        PointerTarget::Null => ("NULL".to_string(), false),
+        PointerTarget::Invalid => (format!("<invalid {:#x}>", address), false),
+        PointerTarget::Unmapped => (format!("<unmapped {:#x}>", address), false),
+        PointerTarget::BudgetExhausted => (format!("<budget exhausted {:#x}>", address), false),
+        PointerTarget::Cancelled => (format!("<cancelled {:#x}>", address), false),
+        PointerTarget::NotFollowed => (format!("<not followed {:#x}>", address), false),
+        PointerTarget::Node(_)
+        | PointerTarget::MaxDepth
+        | PointerTarget::Text(_)
+        | PointerTarget::Bytes(_)
+        | PointerTarget::Array { .. }
+        | PointerTarget::Chain { .. }
+        | PointerTarget::List { .. }
+        | PointerTarget::ReadError(_) => return None,
+    };
+    Some(TargetNote { text, leads })
+}
+
+/// Field names and indices of `path` joined like `players[2].team`
+#[cfg(any(feature = "egui", feature = "tui", feature = "repl"))]
+pub(crate) fn dotted(path: &[String]) -> String {
+    let mut dotted = String::new();
+    for segment in path {
+        if !dotted.is_empty() && !segment.starts_with('[') {
+            dotted.push('.');
+        }
+        dotted.push_str(segment);
+    }
+    dotted
+}
+
+/// Collect `value` only along the field `path` and below it, down to `max_depth`.
+///
+/// The segments of `path` are field names and element indices like `[2]`. Only a single
+/// path is collected, so it can lead back to targets read before, the `max_revisits` and
+/// `include_paths` of `config` are ignored.
+#[cfg(any(feature = "egui", feature = "tui", feature = "repl"))]
+pub(crate) fn collect_path<T, M>(
+    value: &T,
+    mem: &mut M,
+    config: &PointerPrintConfig,
+    path: &[String],
+    max_depth: usize,
+) -> PointerDebugNode
+where
+    T: crate::DerefDebugPrint,
+    M: memflow::mem::MemoryView,
+{
+    use crate::PointerPrint;
+
+    let mut config = config.clone().max_depth(max_depth);
+    config.max_revisits = usize::MAX;
+    config.include_paths = if path.is_empty() {
+        Vec::new()
+    } else {
+        vec![format!("{}.**", dotted(path))]
+    };
+    value.pointer_collect_with_config(mem, &config)
+}
+
+/// Replace the target at the end of `path` in `tree` with the one in `read`, a tree
+/// collected for that path. An empty path replaces the whole tree, nothing is replaced if
+/// the path does not lead to a pointer or element in both trees.
+#[cfg(any(feature = "egui", feature = "tui"))]
+pub(crate) fn splice_path(
+    tree: &mut PointerDebugNode,
+    mut read: PointerDebugNode,
+    path: &[String],
+) {
+    if path.is_empty() {
+        *tree = read;
+    } else if let (Some(target), Some(read)) =
+        (target_at_mut(tree, path), target_at_mut(&mut read, path))
+    {
+        *target = core::mem::replace(read, PointerTarget::MaxDepth);
+    }
+}
+
+/// Target of the pointer or element at the end of the field `path` below `node`, `None` if
+/// the path does not lead to one
+#[cfg(any(feature = "egui", feature = "tui"))]
+fn target_at_mut<'a>(
+    node: &'a mut PointerDebugNode,
+    path: &[String],
+) -> Option<&'a mut PointerTarget> {
+    let (segment, rest) = path.split_first()?;
+    let field = node
+        .fields
+        .iter_mut()
+        .find(|field| field.name == *segment)?;
+    match &mut field.value {
+        PointerDebugValue::Nested(nested) => target_at_mut(nested, rest),
+        PointerDebugValue::Pointer { target, .. } => item_at_mut(target, rest),
+        PointerDebugValue::Value(_) => None,
+    }
+}
+
+/// The element at the end of `path` below `target`, `target` itself for an empty path
+#[cfg(any(feature = "egui", feature = "tui"))]
+fn item_at_mut<'a>(
+    target: &'a mut PointerTarget,
+    path: &[String],
+) -> Option<&'a mut PointerTarget> {
+    let Some((segment, rest)) = path.split_first() else {
+        return Some(target);
+    };
+    let index = segment
+        .strip_prefix('[')
+        .and_then(|index| index.strip_suffix(']'))
+        .and_then(|index| index.parse::<usize>().ok());
+    match target {
+        PointerTarget::Node(node) => target_at_mut(node, path),
+        PointerTarget::Array { items, .. } | PointerTarget::Chain { items, .. } => {
+            item_at_mut(items.get_mut(index?)?, rest)
+        }
+        PointerTarget::List { items, .. } => item_at_mut(&mut items.get_mut(index?)?.1, rest),
+        _ => None,
+    }
+}
@@ -0,0 +1,115 @@
+//! Numeric display options of integer and float fields.
+
+use crate::PointerPrintConfig;
+use crate::prelude::*;
+
+/// Radix integer fields are printed in, see [`PointerPrintConfig::radix`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Radix {
+    /// Decimal like `42`
+    #[default]
+    Dec,
+    /// Hexadecimal like `0x2a`
+    Hex,
+    /// Binary like `0b101010`
+    Bin,
+    /// Octal like `0o52`
+    Oct,
+}
+
+/// Integers and floats printed with the numeric options of a [`PointerPrintConfig`].
+///
+/// This is how derived structures print their integer and float fields unless the field has
+/// a `format` attribute, and how the targets of pointers like `Pointer64<f32>` are printed.
+///
+/// ```rust
+/// use memflow_pointer_debug::{FormatNumber, PointerPrintConfig, Radix};
+///
+/// let config = PointerPrintConfig::new()
+///     .float_precision(2)
+///     .scientific_threshold(6)
+///     .thousands_separator('_');
+/// assert_eq!(1_234_567u64.format_number(&config), "1_234_567");
+/// assert_eq!(1000u32.format_number(&config), "1000");
+/// assert_eq!(3.14159f32.format_number(&config), "3.14");
+/// assert_eq!(2.5e9f64.format_number(&config), "2.50e9");
+///
+/// let config = PointerPrintConfig::new().radix(Radix::Hex);
+/// assert_eq!(255u8.format_number(&config), "0xff");
+/// ```
+pub trait FormatNumber {
+    /// Format the number with the options of `config`
+    fn format_number(&self, config: &PointerPrintConfig) -> String;
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty),+) => {
+        $(
+            impl FormatNumber for $ty {
+                fn format_number(&self, config: &PointerPrintConfig) -> String {
+                    match config.radix {
+                        Radix::Dec => match config.thousands_separator {
+                            Some(separator) => group_digits(&self.to_string(), separator),
+                            None => self.to_string(),
+                        },
+                        Radix::Hex => format!("{:#x}", self),
+                        Radix::Bin => format!("{:#b}", self),
+                        Radix::Oct => format!("{:#o}", self),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize);
+impl_integer!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_float {
+    ($($ty:ty),+) => {
+        $(
+            impl FormatNumber for $ty {
+                fn format_number(&self, config: &PointerPrintConfig) -> String {
+                    let scientific = config.scientific_threshold.is_some_and(|threshold| {
+                        self.is_finite() && decimal_exponent(&format!("{:e}", self)).unsigned_abs() >= threshold
+                    });
+                    match (scientific, config.float_precision) {
+                        (true, Some(precision)) => format!("{:.*e}", precision, self),
+                        (true, None) => format!("{:e}", self),
+                        (false, Some(precision)) => format!("{:.*}", precision, self),
+                        (false, None) => format!("{:?}", self),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_float!(f32, f64);
+
+/// Exponent of a float formatted with `{:e}`, e.g. `-3` of `1.5e-3`
+fn decimal_exponent(scientific: &str) -> i32 {
+    scientific
+        .rsplit('e')
+        .next()
+        .and_then(|exponent| exponent.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Separate the digits of a decimal integer with more than four digits into groups of
+/// three, e.g. `-1,234,567`
+fn group_digits(decimal: &str, separator: char) -> String {
+    let (sign, digits) = decimal.split_at(usize::from(decimal.starts_with('-')));
+    if digits.len() <= 4 {
+        return decimal.to_string();
+    }
+    let mut out = sign.to_string();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            out.push(separator);
+        }
+        out.push(digit);
+    }
+    out
+}
@@ -0,0 +1,149 @@
+//! Field offsets loaded at runtime.
+
+use core::error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::HashMap;
+use crate::prelude::*;
+
+/// Offsets of structure fields that replace the ones compiled into derived structures.
+///
+/// Set on a [`PointerPrintConfig`](crate::PointerPrintConfig) with
+/// [`offsets`](crate::PointerPrintConfig::offsets). The listed fields of a structure that is
+/// read through a pointer or a chain, or by [`read_and_print`](crate::read_and_print), are
+/// then read from their offset here. A dump stays correct after an update of the target
+/// moved some fields without recompiling, as long as their types didn't change.
+///
+/// Offsets are given by the type name as printed and the field name, a file has one
+/// `Type.field = offset` per line:
+///
+/// ```rust
+/// use memflow_pointer_debug::Offsets;
+///
+/// let offsets = Offsets::parse(
+///     "# build 22631
+///     _EPROCESS.peb = 0x550
+///     _EPROCESS.image_file_name = 0x5a8",
+/// )
+/// .unwrap();
+/// assert_eq!(offsets.get("_EPROCESS", "peb"), Some(0x550));
+/// assert_eq!(offsets.get("_EPROCESS", "pcb"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Offsets {
+    /// Field offsets by type name and field name
+    types: HashMap<String, HashMap<String, usize>>,
+}
+
+/// A line of an offsets file that could not be parsed, see [`Offsets::parse`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOffsetsError {
+    /// The line, starting at 1
+    pub line: usize,
+    /// Text of the line
+    pub text: String,
+}
+
+impl fmt::Display for ParseOffsetsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected `Type.field = offset`, found `{}`",
+            self.line, self.text
+        )
+    }
+}
+
+impl error::Error for ParseOffsetsError {}
+
+impl Offsets {
+    /// Create an empty set of offsets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the field `field` of `type_name` at `offset`
+    pub fn offset(mut self, type_name: &str, field: &str, offset: usize) -> Self {
+        self.insert(type_name, field, offset);
+        self
+    }
+
+    /// Read the field `field` of `type_name` at `offset`, returns the offset it replaced
+    pub fn insert(&mut self, type_name: &str, field: &str, offset: usize) -> Option<usize> {
+        self.types
+            .entry(type_name.to_string())
+            .or_default()
+            .insert(field.to_string(), offset)
+    }
+
+    /// The offset of the field `field` of `type_name`
+    pub fn get(&self, type_name: &str, field: &str) -> Option<usize> {
+        self.types.get(type_name)?.get(field).copied()
+    }
+
+    /// The fields of `type_name` with their offsets
+    pub fn fields(&self, type_name: &str) -> impl Iterator<Item = (&str, usize)> {
+        self.types
+            .get(type_name)
+            .into_iter()
+            .flatten()
+            .map(|(field, offset)| (field.as_str(), *offset))
+    }
+
+    /// Returns true if no offsets are set
+    pub fn is_empty(&self) -> bool {
+        self.types.values().all(HashMap::is_empty)
+    }
+
+    /// Parse `Type.field = offset` lines, offsets are decimal or hex with a `0x` prefix.
+    ///
+    /// `:` may be used instead of `=`, empty lines and lines starting with `#` or `//` are
+    /// skipped.
+    pub fn parse(text: &str) -> Result<Self, ParseOffsetsError> {
+        let mut offsets = Self::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+
+            let error = || ParseOffsetsError {
+                line: i + 1,
+                text: line.to_string(),
+            };
+            let (name, offset) = line.split_once(['=', ':']).ok_or_else(error)?;
+            let (type_name, field) = name.trim().rsplit_once('.').ok_or_else(error)?;
+            let offset = offset.trim().trim_end_matches([';', ',']);
+            let offset = match offset.strip_prefix("0x").or(offset.strip_prefix("0X")) {
+                Some(hex) => usize::from_str_radix(hex, 16),
+                None => offset.parse(),
+            }
+            .map_err(|_| error())?;
+            offsets.insert(type_name.trim(), field.trim(), offset);
+        }
+        Ok(offsets)
+    }
+
+    /// Read offsets from a file in the format of [`parse`](Self::parse).
+    ///
+    /// With the `serde` feature a JSON file holding an object of types with an object of
+    /// field offsets each, like `{ "_EPROCESS": { "peb": 1360 } }`, is read as well.
+    #[cfg(feature = "std")]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        #[cfg(feature = "serde")]
+        if text.trim_start().starts_with('{') {
+            return Ok(serde_json::from_str(&text)?);
+        }
+        Self::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
@@ -0,0 +1,100 @@
+//! Parallel traversal of the fields of the root structure.
+
+use memflow::mem::MemoryView;
+use rayon::prelude::*;
+
+use crate::prelude::*;
+use crate::retry::RetryView;
+use crate::{DerefDebugPrint, PointerDebugField, PointerDebugNode};
+use crate::{ErrorPolicy, PointerPrintConfig, TraversalState};
+
+/// Collect `value`, every field of it is traversed as its own branch on the rayon pool.
+///
+/// The branches read through their own clone of `mem` and record the targets they visit
+/// in their own copy of the visited set. The fields are then merged back in declaration
+/// order: a branch that looked up a target visited by an earlier field is collected again
+/// after it, so the target is expanded by the lowest field index and
+/// [`AlreadyVisited`](crate::PointerTarget::AlreadyVisited) everywhere else, exactly like
+/// in a sequential traversal. Budgets and progress counters need a single order of reads,
+/// with one of them or [`ErrorPolicy::Abort`] configured the traversal is sequential.
+///
+/// Returns the tree and the number of bytes read and of reads retried, as counted by a
+/// sequential traversal.
+pub(crate) fn collect_parallel<T, M>(
+    value: &T,
+    mem: &mut M,
+    config: &PointerPrintConfig,
+) -> (PointerDebugNode, usize, usize)
+where
+    T: DerefDebugPrint + Sync + ?Sized,
+    M: MemoryView + Clone + Send,
+{
+    let mut state = TraversalState::with_config(config);
+    let sequential = state.max_depth == 0
+        || config.max_nodes.is_some()
+        || config.max_bytes.is_some()
+        || config.max_output_lines.is_some()
+        || config.progress.is_some()
+        || config.error_policy == ErrorPolicy::Abort;
+
+    let source = mem.clone();
+    let mut branch_retries = 0;
+    let mut root_mem = RetryView::new(mem, config);
+    let node = value.pointer_collect_fields_with(
+        &mut root_mem,
+        0,
+        &mut state,
+        &mut |indices, mem, state, chain_element| {
+            if sequential || indices.len() < 2 {
+                return indices
+                    .iter()
+                    .map(|&index| value.pointer_collect_field(index, mem, 0, state, chain_element))
+                    .collect();
+            }
+
+            let base = state.visited_addresses.snapshot();
+            let (bytes_read, nodes_read) = (state.bytes_read, state.nodes_read);
+            let branches: Vec<(usize, M, TraversalState)> = indices
+                .iter()
+                .map(|&index| {
+                    let mut branch = state.clone();
+                    branch.visited_addresses = base.branch();
+                    (index, source.clone(), branch)
+                })
+                .collect();
+            let collected: Vec<(Option<PointerDebugField>, TraversalState, usize)> = branches
+                .into_par_iter()
+                .map(|(index, mut mem, mut branch)| {
+                    let mut mem = RetryView::new(&mut mem, config);
+                    let field =
+                        value.pointer_collect_field(index, &mut mem, 0, &mut branch, chain_element);
+                    (field, branch, mem.retries)
+                })
+                .collect();
+
+            let mut fields = Vec::with_capacity(indices.len());
+            for (&index, (field, branch, retries)) in indices.iter().zip(collected) {
+                if state
+                    .visited_addresses
+                    .conflicts(&branch.visited_addresses, &base)
+                {
+                    // An earlier field expanded a target this branch looked up, what the
+                    // branch read is not counted
+                    fields.push(value.pointer_collect_field(index, mem, 0, state, chain_element));
+                } else {
+                    state
+                        .visited_addresses
+                        .merge(&branch.visited_addresses, &base);
+                    state.bytes_read += branch.bytes_read - bytes_read;
+                    state.nodes_read += branch.nodes_read - nodes_read;
+                    branch_retries += retries;
+                    fields.push(field);
+                }
+            }
+            fields
+        },
+    );
+    state.finish_root(&node);
+
+    (node, state.bytes_read, root_mem.retries + branch_retries)
+}
@@ -0,0 +1,257 @@
+//! Runtime layouts built from the type information of a Windows PDB.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use ::pdb::{
+    FallibleIterator, Indirection, PDB, PrimitiveKind, PrimitiveType, TypeData, TypeFinder,
+    TypeIndex,
+};
+
+use crate::{FieldType, Layout, LayoutField, Layouts};
+
+impl Layouts {
+    /// Build the layouts of the structure `name` and every type it refers to from a PDB.
+    ///
+    /// Members keep their names and offsets, base classes are embedded as a field named
+    /// after the class and pointers are followed into the layout of their target, so
+    /// kernel structures like `_EPROCESS` can be printed without defining them in Rust.
+    /// Enums are printed as their underlying integer and bitfields as the whole integer
+    /// they are stored in. Pointers to `void` or functions are printed as plain addresses,
+    /// types that are only declared in the PDB have a layout without fields.
+    ///
+    /// Fails if the file can't be read or holds no definition of `name`.
+    ///
+    /// ```rust,no_run
+    /// use memflow::prelude::v1::{Address, MemoryView};
+    /// use memflow_pointer_debug::{Layouts, PointerPrint};
+    ///
+    /// fn print(mem: &mut impl MemoryView, eprocess: Address) -> std::io::Result<()> {
+    ///     let layouts = Layouts::from_pdb("ntkrnlmp.pdb", "_EPROCESS")?;
+    ///     if let Ok(process) = layouts.read(mem, "_EPROCESS", eprocess) {
+    ///         process.pointer_print(mem);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_pdb(path: impl AsRef<Path>, name: &str) -> io::Result<Self> {
+        let mut pdb = PDB::open(File::open(path)?).map_err(invalid_data)?;
+        let info = pdb.type_information().map_err(invalid_data)?;
+
+        let mut finder = info.finder();
+        let mut definitions = HashMap::new();
+        let mut types = info.iter();
+        while let Some(item) = types.next().map_err(invalid_data)? {
+            finder.update(&types);
+            // Records of kinds the crate doesn't know can't be part of a layout anyway
+            let definition = match item.parse() {
+                Ok(TypeData::Class(class)) if !class.properties.forward_reference() => class.name,
+                Ok(TypeData::Union(union)) if !union.properties.forward_reference() => union.name,
+                _ => continue,
+            };
+            definitions
+                .entry(definition.to_string().into_owned())
+                .or_insert(item.index());
+        }
+
+        let root = *definitions.get(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no definition of `{}` in the PDB", name),
+            )
+        })?;
+
+        let mut builder = Builder {
+            finder,
+            definitions,
+            names: HashMap::new(),
+            pending: Vec::new(),
+            layouts: Layouts::new(),
+        };
+        builder.structure(root).map_err(invalid_data)?;
+        while let Some((index, name)) = builder.pending.pop() {
+            builder.fields(index, &name).map_err(invalid_data)?;
+        }
+        Ok(builder.layouts)
+    }
+}
+
+fn invalid_data(error: ::pdb::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Collects the layouts of the structures reachable from the requested one
+struct Builder<'t> {
+    finder: TypeFinder<'t>,
+    /// The complete definitions of classes and unions by name
+    definitions: HashMap<String, TypeIndex>,
+    /// The layout name of every class or union seen so far
+    names: HashMap<TypeIndex, String>,
+    /// Structures whose layout still has to be filled with fields
+    pending: Vec<(TypeIndex, String)>,
+    layouts: Layouts,
+}
+
+impl Builder<'_> {
+    /// The layout name of the class or union at `index`.
+    ///
+    /// Forward references are resolved to their definition. The first time a structure is
+    /// seen an empty layout of the right size is added, its fields are filled in later.
+    fn structure(&mut self, index: TypeIndex) -> ::pdb::Result<String> {
+        let (name, size, forward) = match self.finder.find(index)?.parse()? {
+            TypeData::Class(class) => (class.name, class.size, class.properties),
+            TypeData::Union(union) => (union.name, union.size, union.properties),
+            _ => return Err(::pdb::Error::TypeNotFound(index.0)),
+        };
+        let name = name.to_string().into_owned();
+        let index = match self.definitions.get(&name) {
+            Some(definition) if forward.forward_reference() => *definition,
+            _ => index,
+        };
+        if let Some(name) = self.names.get(&index) {
+            return Ok(name.clone());
+        }
+
+        // Unnamed structures like `<unnamed-tag>` share their name
+        let name = if self.layouts.get(&name).is_some() {
+            format!("{}#{}", name, index.0)
+        } else {
+            name
+        };
+        self.names.insert(index, name.clone());
+        self.layouts
+            .insert(Layout::new(name.clone(), size as usize));
+        self.pending.push((index, name.clone()));
+        Ok(name)
+    }
+
+    /// Add the members and base classes of the structure at `index` to its layout
+    fn fields(&mut self, index: TypeIndex, name: &str) -> ::pdb::Result<()> {
+        // Structures that are only declared have no fields
+        let mut list = match self.finder.find(index)?.parse()? {
+            TypeData::Class(class) if !class.properties.forward_reference() => class.fields,
+            TypeData::Union(union) if !union.properties.forward_reference() => Some(union.fields),
+            _ => None,
+        };
+
+        let mut fields = Vec::new();
+        while let Some(index) = list {
+            let TypeData::FieldList(field_list) = self.finder.find(index)?.parse()? else {
+                break;
+            };
+            for field in field_list.fields {
+                match field {
+                    TypeData::Member(member) => {
+                        if let Some(ty) = self.field_type(member.field_type)? {
+                            fields.push(LayoutField {
+                                name: member.name.to_string().into_owned(),
+                                offset: member.offset as usize,
+                                ty,
+                            });
+                        }
+                    }
+                    TypeData::BaseClass(base) => {
+                        let name = self.structure(base.base_class)?;
+                        fields.push(LayoutField {
+                            name: name.clone(),
+                            offset: base.offset as usize,
+                            ty: FieldType::named(name),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            list = field_list.continuation;
+        }
+
+        if let Some(layout) = self.layouts.get_mut(name) {
+            layout.fields = fields;
+        }
+        Ok(())
+    }
+
+    /// The type of a member, `None` for types that have no useful representation
+    fn field_type(&mut self, index: TypeIndex) -> ::pdb::Result<Option<FieldType>> {
+        Ok(match self.finder.find(index)?.parse()? {
+            TypeData::Primitive(primitive) => self.primitive(primitive),
+            TypeData::Class(_) | TypeData::Union(_) => {
+                Some(FieldType::named(self.structure(index)?))
+            }
+            TypeData::Pointer(pointer) => {
+                let size = pointer.attributes.size() as usize;
+                if size != 0 {
+                    self.layouts.pointer_size = size;
+                }
+                match self.field_type(pointer.underlying_type)? {
+                    Some(target) => Some(FieldType::pointer(target)),
+                    None => Some(self.address()),
+                }
+            }
+            TypeData::Modifier(modifier) => self.field_type(modifier.underlying_type)?,
+            TypeData::Enumeration(enumeration) => self.field_type(enumeration.underlying_type)?,
+            TypeData::Bitfield(bitfield) => self.field_type(bitfield.underlying_type)?,
+            TypeData::Array(array) => {
+                let Some(mut ty) = self.field_type(array.element_type)? else {
+                    return Ok(None);
+                };
+                // Every dimension is the size of the array up to it in bytes
+                for dimension in array.dimensions {
+                    let len = match ty.size(&self.layouts) {
+                        Some(size) if size > 0 => dimension as usize / size,
+                        _ => 0,
+                    };
+                    ty = FieldType::array(ty, len);
+                }
+                Some(ty)
+            }
+            _ => None,
+        })
+    }
+
+    /// The type of a builtin type, pointers to `void` are plain addresses
+    fn primitive(&mut self, primitive: PrimitiveType) -> Option<FieldType> {
+        if let Some(indirection) = primitive.indirection {
+            self.layouts.pointer_size = match indirection {
+                Indirection::Near16 | Indirection::Far16 | Indirection::Huge16 => 2,
+                Indirection::Near32 | Indirection::Far32 => 4,
+                Indirection::Near64 => 8,
+                Indirection::Near128 => 16,
+            };
+            let target = primitive_scalar(primitive.kind);
+            return Some(target.map_or_else(|| self.address(), FieldType::pointer));
+        }
+        primitive_scalar(primitive.kind)
+    }
+
+    /// An integer as large as a pointer, for addresses whose target can't be printed
+    fn address(&self) -> FieldType {
+        match self.layouts.pointer_size {
+            4 => FieldType::U32,
+            _ => FieldType::U64,
+        }
+    }
+}
+
+/// The scalar type of a builtin type, `None` for `void` and types without an equivalent
+fn primitive_scalar(kind: PrimitiveKind) -> Option<FieldType> {
+    Some(match kind {
+        PrimitiveKind::Char | PrimitiveKind::RChar | PrimitiveKind::I8 => FieldType::I8,
+        PrimitiveKind::UChar | PrimitiveKind::U8 => FieldType::U8,
+        PrimitiveKind::Short | PrimitiveKind::I16 => FieldType::I16,
+        PrimitiveKind::UShort | PrimitiveKind::U16 => FieldType::U16,
+        PrimitiveKind::WChar | PrimitiveKind::RChar16 => FieldType::U16,
+        PrimitiveKind::Long | PrimitiveKind::I32 | PrimitiveKind::HRESULT => FieldType::I32,
+        PrimitiveKind::ULong | PrimitiveKind::U32 | PrimitiveKind::RChar32 => FieldType::U32,
+        PrimitiveKind::Quad | PrimitiveKind::I64 => FieldType::I64,
+        PrimitiveKind::UQuad | PrimitiveKind::U64 => FieldType::U64,
+        PrimitiveKind::F32 => FieldType::F32,
+        PrimitiveKind::F64 => FieldType::F64,
+        PrimitiveKind::Bool8 => FieldType::Bool,
+        PrimitiveKind::Bool16 => FieldType::U16,
+        PrimitiveKind::Bool32 => FieldType::U32,
+        PrimitiveKind::Bool64 => FieldType::U64,
+        _ => return None,
+    })
+}
@@ -0,0 +1,194 @@
+//! Pointer types followed by the traversal.
+
+use memflow::dataview::Pod;
+use memflow::error::{Error, PartialResult};
+use memflow::mem::MemoryView;
+use memflow::types::{Address, Pointer, Pointer64, PrimitiveAddress};
+
+use crate::prelude::*;
+use crate::{
+    ByteOrder, DerefDebugPrint, PointerDebugValue, PointerTarget, TraversalState, read_array,
+};
+
+/// A typed pointer into the memory of the target.
+///
+/// The derive follows the fields of every type implementing this trait, memflow's
+/// `Pointer<U, T>` implements it. Pointer newtypes of a project implement it by giving the
+/// address they hold, fields of types whose name doesn't contain `Pointer` are marked with
+/// `#[pointer_debug(pointer)]`:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerLike};
+///
+/// #[repr(transparent)]
+/// #[derive(Clone, Copy, Debug)]
+/// struct RemotePtr<T: 'static>(Pointer64<T>);
+///
+/// unsafe impl<T: 'static> Pod for RemotePtr<T> {}
+///
+/// impl<T: 'static> PointerLike for RemotePtr<T> {
+///     type Target = T;
+///
+///     fn pointer_address(&self) -> u64 {
+///         self.0.inner
+///     }
+///
+///     fn with_address(&self, address: u64) -> Self {
+///         Self(Pointer64::from(address))
+///     }
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     #[pointer_debug(pointer)]
+///     next: RemotePtr<Node>,
+/// }
+/// ```
+///
+/// Pointers inside containers like `Option<RemotePtr<Node>>` are collected through the
+/// [`DerefDebugPrint`] implementation of the pointer type, which can forward
+/// [`pointer_collect_value`](DerefDebugPrint::pointer_collect_value) to
+/// [`collect_target`](Self::collect_target).
+pub trait PointerLike {
+    /// Type of the value pointed to
+    type Target;
+
+    /// Address the pointer holds
+    fn pointer_address(&self) -> u64;
+
+    /// Pointer of the same type to `address`, used to clear the tag bits of tagged pointers
+    fn with_address(&self, address: u64) -> Self
+    where
+        Self: Sized;
+
+    /// Swap the bytes of the address if `order`, the byte order of the memory the pointer
+    /// was read from, isn't the byte order of the host
+    fn to_host_order(&mut self, order: ByteOrder)
+    where
+        Self: Sized,
+    {
+        if order == ByteOrder::native() {
+            return;
+        }
+        let address = self.pointer_address();
+        let address = match core::mem::size_of::<Self>() {
+            4 => u64::from((address as u32).swap_bytes()),
+            _ => address.swap_bytes(),
+        };
+        *self = self.with_address(address);
+    }
+
+    /// Read the value pointed to
+    fn read_target<M: MemoryView>(&self, mem: &mut M) -> PartialResult<Self::Target>
+    where
+        Self::Target: Pod,
+    {
+        mem.read(Address::from(self.pointer_address()))
+    }
+
+    /// Read `len` consecutive values starting at the pointer with a single read, see
+    /// [`read_array`]
+    fn read_targets<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        len: usize,
+    ) -> Result<Vec<Self::Target>, Error>
+    where
+        Self::Target: Pod,
+    {
+        read_array(
+            mem,
+            Pointer64::<Self::Target>::from(self.pointer_address()),
+            len,
+        )
+    }
+
+    /// Follow the pointer from a structure at `depth`, reading and collecting its target
+    /// unless it is null, invalid, already visited or beyond the limits of the traversal
+    fn collect_target<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerDebugValue
+    where
+        Self: Sized,
+        Self::Target: DerefDebugPrint + Pod,
+    {
+        PointerDebugValue::Pointer {
+            address: self.pointer_address(),
+            target: self.collect_pointee(mem, depth, state),
+        }
+    }
+
+    /// The target [`collect_target`](Self::collect_target) shows for the pointer, used for
+    /// pointers that are listed without a field of their own
+    fn collect_pointee<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        depth: usize,
+        state: &mut TraversalState,
+    ) -> PointerTarget
+    where
+        Self: Sized,
+        Self::Target: DerefDebugPrint + Pod,
+    {
+        // Get the address to check if we've seen it before
+        let address = self.pointer_address();
+        let type_name = core::any::type_name::<Self::Target>();
+
+        if let Some(target) = state.skip_target(address) {
+            // Null and invalid pointers are never read
+            target
+        } else if state.is_visited(address, type_name) {
+            PointerTarget::AlreadyVisited
+        } else if depth + 1 >= state.max_depth {
+            // Don't issue a read for a target that would not be collected
+            PointerTarget::MaxDepth
+        } else if !state.take_pointer_budget(self, depth, 1) {
+            PointerTarget::BudgetExhausted
+        } else {
+            // Add this target to our visited set
+            state.visit(address, type_name);
+
+            // Read the pointer value using the memory view unless it was fetched already
+            let span = state.deref_span(address, depth);
+            let value = match state.take_prefetched::<Self::Target>(address) {
+                Some(value) => Ok(value),
+                None => self.read_target(mem),
+            };
+            span.read_done();
+            let target = match value {
+                Ok(mut value) => {
+                    // The bytes as read, before the offsets move fields around
+                    let raw_bytes = state.raw_bytes(&value);
+                    state.apply_offsets(&mut value, address, mem);
+                    state.to_host_order(&mut value);
+                    // Call the recursive method, the target provides its own type name
+                    let mut node = state.collect_node(&value, mem, depth + 1);
+                    node.set_address(address, core::mem::align_of::<Self::Target>());
+                    node.raw_bytes = raw_bytes;
+                    PointerTarget::Node(Box::new(node))
+                }
+                Err(e) => PointerTarget::ReadError(e.into()),
+            };
+            state.finish_deref(span, &target);
+            target
+        }
+    }
+}
+
+impl<U: PrimitiveAddress, T> PointerLike for Pointer<U, T> {
+    type Target = T;
+
+    fn pointer_address(&self) -> u64 {
+        self.address().to_umem()
+    }
+
+    fn with_address(&self, address: u64) -> Self {
+        Pointer::from(U::from_umem(address))
+    }
+}
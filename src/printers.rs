@@ -0,0 +1,138 @@
+//! Printers installed at runtime for types that can't be annotated.
+
+use alloc::sync::Arc;
+use core::any::{Any, TypeId};
+use core::fmt;
+
+use memflow::error::Result;
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+
+use crate::HashMap;
+use crate::prelude::*;
+use crate::views::RawMemoryView;
+
+/// The memory a registered printer reads from, the view the traversal reads the value from
+pub struct PrinterMemory<'a>(&'a mut dyn RawMemoryView);
+
+impl MemoryView for PrinterMemory<'_> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        self.0.read_raw_iter(data)
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.0.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.0.metadata()
+    }
+}
+
+type Printer<T> = Box<dyn Fn(&T, &mut PrinterMemory<'_>) -> String + Send + Sync>;
+
+/// Functions printing the values of a type in place of their [`DerefDebugPrint`]
+/// implementation or their `Debug` output.
+///
+/// Set on a [`PointerPrintConfig`](crate::PointerPrintConfig) with
+/// [`printers`](crate::PointerPrintConfig::printers). A value of a registered type is printed
+/// as the string its printer returns wherever it is reached: through a pointer, as an
+/// element of an `array`, `follow_chain`, `list` or `buckets` field, or embedded in a
+/// derived structure through a field whose type names no type parameter or lifetime and that
+/// has no attribute changing how it is printed. Its own pointers are not followed, the printer reads what it needs itself.
+///
+/// This specializes types of other crates that can't be annotated with the derive
+/// attributes, or changes how the structures of a shared crate print in one tool.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{
+///     PointerDerefDebugPrint, PointerPrint, PointerPrintConfig, PrinterRegistry,
+///     read_utf16_lossy,
+/// };
+///
+/// // From a crate shared by several tools
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, Pod, PointerDerefDebugPrint)]
+/// struct UnicodeString {
+///     length: u16,
+///     maximum_length: u16,
+///     _pad: u32,
+///     buffer: Pointer64<u16>,
+/// }
+///
+/// fn example(mem: &mut impl MemoryView, name: Pointer64<UnicodeString>) {
+///     let printers = PrinterRegistry::new().register::<UnicodeString>(|name, mem| {
+///         let len = usize::from(name.length);
+///         match read_utf16_lossy(mem, name.buffer.address(), Some(len), 256) {
+///             Ok(text) => format!("{:?}", text),
+///             Err(e) => format!("<{}>", e),
+///         }
+///     });
+///     let config = PointerPrintConfig::new().printers(printers);
+///     name.pointer_print_with_config(mem, &config).unwrap();
+/// }
+/// ```
+///
+/// [`DerefDebugPrint`]: crate::DerefDebugPrint
+#[derive(Clone, Default)]
+pub struct PrinterRegistry {
+    /// The printer of each type, a `Printer<T>` behind `Any`, with the name of the type
+    printers: HashMap<TypeId, (&'static str, Arc<dyn Any + Send + Sync>)>,
+}
+
+impl PrinterRegistry {
+    /// Create a registry without printers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print the values of `T` with `printer`, replacing a printer registered for `T` before.
+    ///
+    /// The printer gets the value and the memory it was read from.
+    pub fn register<T: 'static>(
+        mut self,
+        printer: impl Fn(&T, &mut PrinterMemory<'_>) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let printer: Printer<T> = Box::new(printer);
+        self.printers.insert(
+            TypeId::of::<T>(),
+            (core::any::type_name::<T>(), Arc::new(printer)),
+        );
+        self
+    }
+
+    /// Returns true if no printer is registered
+    pub fn is_empty(&self) -> bool {
+        self.printers.is_empty()
+    }
+
+    /// Print `value` with the printer registered for `T`, `None` if there is none
+    pub fn print<T: 'static, M: MemoryView>(&self, value: &T, mem: &mut M) -> Option<String> {
+        let (_, printer) = self.printers.get(&TypeId::of::<T>())?;
+        let printer = printer.downcast_ref::<Printer<T>>()?;
+        Some(printer(value, &mut PrinterMemory(mem)))
+    }
+}
+
+impl fmt::Debug for PrinterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut types: Vec<_> = self.printers.values().map(|(name, _)| *name).collect();
+        types.sort_unstable();
+        f.debug_tuple("PrinterRegistry").field(&types).finish()
+    }
+}
+
+/// Registries are equal if they hold the same printers, clones of each other's
+impl PartialEq for PrinterRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.printers.len() == other.printers.len()
+            && self.printers.iter().all(|(id, (_, printer))| {
+                other
+                    .printers
+                    .get(id)
+                    .is_some_and(|(_, other)| Arc::ptr_eq(printer, other))
+            })
+    }
+}
+
+impl Eq for PrinterRegistry {}
@@ -0,0 +1,63 @@
+//! Progress reporting for long traversals.
+
+use alloc::sync::Arc;
+use core::fmt;
+
+use crate::prelude::*;
+use crate::sync::Mutex;
+
+/// A read issued by the traversal, passed to the callback set with
+/// [`PointerPrintConfig::progress`](crate::PointerPrintConfig::progress)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgressEvent {
+    /// Depth of the structure holding the pointer, the root is at depth 0
+    pub depth: usize,
+    /// Dotted path of the pointer field starting at the root, e.g. `peb.ldr`
+    pub path: String,
+    /// Address that is read
+    pub address: u64,
+    /// Number of nodes this read yields, 0 for strings
+    pub nodes: usize,
+    /// Size of this read in bytes
+    pub bytes: usize,
+    /// Number of nodes read so far, including this read
+    pub nodes_read: usize,
+    /// Number of bytes read so far, including this read
+    pub bytes_read: usize,
+}
+
+/// Callback invoked for every read of a traversal.
+///
+/// The callback is shared by clones of the configuration it was set on.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<Mutex<dyn FnMut(ProgressEvent) + Send>>);
+
+impl ProgressCallback {
+    /// Wrap a callback
+    pub fn new(callback: impl FnMut(ProgressEvent) + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(callback)))
+    }
+
+    /// Invoke the callback with `event`
+    pub fn call(&self, event: ProgressEvent) {
+        // Later events are still delivered after a callback panicked
+        let mut callback = self.0.lock();
+        callback(event)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback(..)")
+    }
+}
+
+/// Callbacks are only equal to clones of themselves
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressCallback {}
@@ -0,0 +1,131 @@
+//! Looking up a single field by its path.
+
+use memflow::mem::MemoryView;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::retry::RetryView;
+use crate::{
+    DerefDebugPrint, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget,
+    TraversalState,
+};
+
+impl PointerDebugNode {
+    /// Look up the value at a dotted path like `world.players[3].name`.
+    ///
+    /// Pointers are followed into their target, `[i]` picks an element of an array, chain,
+    /// list or embedded array. The target of a pointer printed on its own is its field `*`.
+    /// Returns `None` if there is no such field or it is behind a pointer that wasn't read.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{PointerDebugField, PointerDebugNode, PointerDebugValue};
+    ///
+    /// let mut player = PointerDebugNode::new("Player");
+    /// player.fields.push(PointerDebugField {
+    ///     name: "health".to_string(),
+    ///     type_name: "u32".to_string(),
+    ///     value: PointerDebugValue::Value("100".to_string()),
+    /// });
+    /// let mut world = PointerDebugNode::new("World");
+    /// world.fields.push(PointerDebugField {
+    ///     name: "player".to_string(),
+    ///     type_name: "Player".to_string(),
+    ///     value: PointerDebugValue::Nested(Box::new(player)),
+    /// });
+    ///
+    /// assert_eq!(
+    ///     world.get_path("player.health"),
+    ///     Some(PointerDebugValue::Value("100".to_string()))
+    /// );
+    /// assert_eq!(world.get_path("player.armor"), None);
+    /// ```
+    pub fn get_path(&self, path: &str) -> Option<PointerDebugValue> {
+        lookup(self, &parse(path))
+    }
+}
+
+/// Split a dotted path into field names and `[i]` indices, the same way
+/// [`TraversalState::path`] is tracked
+pub(crate) fn parse(path: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let mut rest = part;
+        if let Some(start) = rest.find('[') {
+            segments.push(rest[..start].to_string());
+            rest = &rest[start..];
+            while let Some(end) = rest.find(']') {
+                segments.push(rest[..=end].to_string());
+                rest = &rest[end + 1..];
+            }
+        }
+        segments.push(rest.to_string());
+    }
+    segments.retain(|segment| !segment.is_empty());
+    segments
+}
+
+/// Collect only the pointers leading to `path` below `value` and look it up
+pub(crate) fn get<T, M>(
+    value: &T,
+    mem: &mut M,
+    path: &str,
+    config: &PointerPrintConfig,
+) -> Option<PointerDebugValue>
+where
+    T: DerefDebugPrint + ?Sized,
+    M: MemoryView,
+{
+    let segments = parse(path);
+    // Batched reads would fetch the targets of every sibling on the way
+    let mut state = TraversalState::with_config(&config.clone().batch_reads(false));
+    // Every segment can be behind a pointer
+    state.max_depth = state.max_depth.max(segments.len() + 1);
+    state.query = segments.clone();
+
+    let node = value.pointer_collect_internal(&mut RetryView::new(mem, config), 0, &mut state);
+    lookup(&node, &segments)
+}
+
+fn lookup(node: &PointerDebugNode, segments: &[String]) -> Option<PointerDebugValue> {
+    let (name, rest) = segments.split_first()?;
+    let field = node.field(name)?;
+    resolve(field.value.clone(), rest)
+}
+
+fn resolve(value: PointerDebugValue, segments: &[String]) -> Option<PointerDebugValue> {
+    let Some(segment) = segments.first() else {
+        return Some(value);
+    };
+
+    let (address, target) = match value {
+        PointerDebugValue::Value(_) => return None,
+        PointerDebugValue::Nested(node) => return lookup(&node, segments),
+        PointerDebugValue::Pointer { address, target } => (address, target),
+    };
+
+    let index = |items: usize| {
+        let index: usize = segment.strip_prefix('[')?.strip_suffix(']')?.parse().ok()?;
+        (index < items).then_some(index)
+    };
+    let (address, item) = match target {
+        PointerTarget::Node(node) => return lookup(&node, segments),
+        PointerTarget::Array { mut items, .. } => {
+            let item = items.swap_remove(index(items.len())?);
+            (item_address(&item, address), item)
+        }
+        PointerTarget::Chain { mut items, .. } => {
+            let i = index(items.len())?;
+            let address = chain_addresses(address, &items)[i];
+            (address, items.swap_remove(i))
+        }
+        PointerTarget::List { mut items, .. } => items.swap_remove(index(items.len())?),
+        _ => return None,
+    };
+    resolve(
+        PointerDebugValue::Pointer {
+            address,
+            target: item,
+        },
+        &segments[1..],
+    )
+}
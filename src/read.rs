@@ -0,0 +1,230 @@
+//! Memory reading helpers used by the field attributes.
+
+use core::fmt::Write;
+
+use memflow::cglue::{CTup2, CTup3};
+use memflow::dataview::Pod;
+use memflow::error::{Error, PartialResultExt};
+use memflow::mem::{MemOps, MemoryView, ReadRawMemOps, opt_call};
+use memflow::types::{Address, Pointer, PrimitiveAddress};
+
+use crate::prelude::*;
+
+/// Serve every request of `data` on its own with `read`, which fills the buffer of the
+/// request at an address and returns false if that failed. Used by the memory views that
+/// need to know which requests of a batch succeeded
+pub(crate) fn read_each(
+    MemOps {
+        inp,
+        mut out,
+        mut out_fail,
+    }: ReadRawMemOps,
+    mut read: impl FnMut(Address, &mut [u8]) -> bool,
+) -> memflow::error::Result<()> {
+    for CTup3(address, meta, buf) in inp {
+        let buf: &mut [u8] = buf.into();
+        if read(address, buf) {
+            opt_call(out.as_deref_mut(), CTup2(meta, buf.into()));
+        } else {
+            opt_call(out_fail.as_deref_mut(), CTup2(meta, buf.into()));
+        }
+    }
+    Ok(())
+}
+
+/// Read `len` consecutive elements starting at `pointer` with a single read.
+///
+/// Partial reads are allowed, elements that could not be read are zeroed.
+///
+/// # Parameters
+///
+/// * `mem` - The memory view to read from
+/// * `pointer` - Pointer to the first element
+/// * `len` - Number of elements to read
+pub fn read_array<U: PrimitiveAddress, T: Pod, M: MemoryView>(
+    mem: &mut M,
+    pointer: Pointer<U, T>,
+    len: usize,
+) -> Result<Vec<T>, Error> {
+    let mut values: Vec<T> = (0..len).map(|_| memflow::dataview::zeroed()).collect();
+    mem.read_into(pointer.address(), values.as_mut_slice())
+        .data_part()?;
+    Ok(values)
+}
+
+/// Read a NUL-terminated string of up to `max_len` bytes.
+///
+/// Returns the bytes before the NUL and whether the string was cut at `max_len` without
+/// reaching one. Partial reads are allowed, bytes that could not be read are 0.
+///
+/// # Parameters
+///
+/// * `mem` - The memory view to read from
+/// * `address` - Address of the first byte
+/// * `max_len` - Maximum number of bytes to read
+pub fn read_cstr_bytes<M: MemoryView>(
+    mem: &mut M,
+    address: Address,
+    max_len: usize,
+) -> Result<(Vec<u8>, bool), Error> {
+    let mut bytes = vec![0u8; max_len];
+    mem.read_raw_into(address, &mut bytes).data_part()?;
+
+    match bytes.iter().position(|&b| b == 0) {
+        Some(end) => {
+            bytes.truncate(end);
+            Ok((bytes, false))
+        }
+        None => Ok((bytes, true)),
+    }
+}
+
+/// Read the code units of a UTF-16 string of up to `max_chars` code units.
+///
+/// If `len_bytes` is given exactly that many bytes are read (clamped to `max_chars`),
+/// this matches the `Length` member of `UNICODE_STRING`. Otherwise the string ends at the
+/// first NUL code unit. Returns the code units and whether the string was cut at
+/// `max_chars`.
+///
+/// # Parameters
+///
+/// * `mem` - The memory view to read from
+/// * `address` - Address of the first code unit
+/// * `len_bytes` - Optional length of the string in bytes
+/// * `max_chars` - Maximum number of code units to read
+pub fn read_utf16_units<M: MemoryView>(
+    mem: &mut M,
+    address: Address,
+    len_bytes: Option<usize>,
+    max_chars: usize,
+) -> Result<(Vec<u16>, bool), Error> {
+    let chars = len_bytes.map_or(max_chars, |len| (len / 2).min(max_chars));
+
+    // Partial reads are allowed like in `MemoryView::read_utf8_lossy`, missing bytes are 0
+    let mut buf = vec![0u8; chars * 2];
+    mem.read_raw_into(address, &mut buf).data_part()?;
+
+    let mut units: Vec<u16> = buf
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+
+    // Only unbounded strings are terminated, explicit lengths may contain NULs
+    let truncated = match len_bytes {
+        Some(len) => len / 2 > max_chars,
+        None => match units.iter().position(|&c| c == 0) {
+            Some(end) => {
+                units.truncate(end);
+                false
+            }
+            None => true,
+        },
+    };
+    Ok((units, truncated))
+}
+
+/// Read a UTF-16 string of up to `max_chars` code units.
+///
+/// The string is read like [`read_utf16_units`], invalid sequences are replaced with
+/// U+FFFD.
+///
+/// # Parameters
+///
+/// * `mem` - The memory view to read from
+/// * `address` - Address of the first code unit
+/// * `len_bytes` - Optional length of the string in bytes
+/// * `max_chars` - Maximum number of code units to read
+pub fn read_utf16_lossy<M: MemoryView>(
+    mem: &mut M,
+    address: Address,
+    len_bytes: Option<usize>,
+    max_chars: usize,
+) -> Result<String, Error> {
+    let (units, _) = read_utf16_units(mem, address, len_bytes, max_chars)?;
+    Ok(String::from_utf16_lossy(&units))
+}
+
+/// How the bytes of `cstr` and `wstr` strings are turned into text, see
+/// [`PointerPrintConfig::string_decoding`](crate::PointerPrintConfig::string_decoding)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StringDecoding {
+    /// Invalid sequences are replaced with U+FFFD, the text is quoted when printed
+    #[default]
+    Lossy,
+    /// Invalid bytes are kept as `\xff` and code units as `\u{d800}` escapes, other
+    /// non-printable characters are escaped like in `Debug`. The text is stored quoted
+    Escape,
+    /// Strings that are not valid or contain control characters other than whitespace are
+    /// stored as their bytes, e.g. `<hex 41 42 ff>`, all others quoted
+    Hex,
+}
+
+impl StringDecoding {
+    /// Decode the bytes of a UTF-8 string.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::StringDecoding;
+    ///
+    /// assert_eq!(StringDecoding::Lossy.decode_utf8(b"ab\xff"), "ab\u{fffd}");
+    /// assert_eq!(StringDecoding::Escape.decode_utf8(b"ab\xff\n"), r#""ab\xff\n""#);
+    /// assert_eq!(StringDecoding::Hex.decode_utf8(b"ab\xff"), "<hex 61 62 ff>");
+    /// assert_eq!(StringDecoding::Hex.decode_utf8(b"ab"), r#""ab""#);
+    /// ```
+    pub fn decode_utf8(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Lossy => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Escape => {
+                let mut out = String::from('"');
+                for chunk in bytes.utf8_chunks() {
+                    out.extend(chunk.valid().escape_debug());
+                    for byte in chunk.invalid() {
+                        let _ = write!(out, "\\x{:02x}", byte);
+                    }
+                }
+                out.push('"');
+                out
+            }
+            Self::Hex => match core::str::from_utf8(bytes) {
+                Ok(text) if is_printable(text) => format!("{:?}", text),
+                _ => hex(bytes.iter().map(|byte| format!("{:02x}", byte))),
+            },
+        }
+    }
+
+    /// Decode the code units of a UTF-16 string
+    pub fn decode_utf16(self, units: &[u16]) -> String {
+        match self {
+            Self::Lossy => String::from_utf16_lossy(units),
+            Self::Escape => {
+                let mut out = String::from('"');
+                for c in char::decode_utf16(units.iter().copied()) {
+                    match c {
+                        Ok(c) => out.extend(c.escape_debug()),
+                        Err(e) => {
+                            let _ = write!(out, "\\u{{{:x}}}", e.unpaired_surrogate());
+                        }
+                    }
+                }
+                out.push('"');
+                out
+            }
+            Self::Hex => match String::from_utf16(units) {
+                Ok(text) if is_printable(&text) => format!("{:?}", text),
+                _ => hex(units.iter().map(|unit| format!("{:04x}", unit))),
+            },
+        }
+    }
+}
+
+/// Returns true if `text` has no control characters other than whitespace
+fn is_printable(text: &str) -> bool {
+    text.chars()
+        .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+}
+
+/// `<hex ..>` listing the given digits
+fn hex(digits: impl Iterator<Item = String>) -> String {
+    let digits: Vec<_> = digits.collect();
+    format!("<hex {}>", digits.join(" "))
+}
@@ -0,0 +1,479 @@
+//! Class definitions for ReClass.NET built from the schemas of derived types.
+
+use core::fmt::Write;
+
+use crate::dot::escape_xml;
+use crate::prelude::*;
+use crate::{AddressEntry, AddressMap, DerefDebugPrint, FieldSchema, HashMap, TypeSchema};
+
+/// Version of the ReClass.NET file format written, 1.1
+const FILE_VERSION: u32 = 0x0001_0001;
+
+/// Name of the project inside an `.rcnet` archive
+const DATA_FILE: &str = "Data.xml";
+
+/// A ReClass.NET project with a class for every derived type and for the targets of their
+/// pointers.
+///
+/// Add the types with [`class`](Self::class), the schema of each becomes a class with a node
+/// for every field at its offset: integers, floats and `bool` as nodes of the same type,
+/// structures added to the project as class instances, arrays as array nodes and pointers as
+/// pointers to the class of their target. Pointers to `u8` and `u16` become UTF-8 and UTF-16
+/// text pointers. Everything else, the gaps between the fields and enums are filled with hex
+/// nodes.
+///
+/// A live dump passed to [`dump`](Self::dump) sets the address of each class to the node of
+/// its type nearest to the root. Pointer targets and types of the dump without a schema get a
+/// class of hex nodes the size the dump read them as, so every pointer has a class to open.
+///
+/// [`to_rcnet`](Self::to_rcnet) writes a `.rcnet` file ReClass.NET opens directly,
+/// [`to_xml`](Self::to_xml) the project inside it. Classes are written for 64-bit targets,
+/// pointers that aren't 8 bytes are written as hex nodes.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{AddressMap, PointerDerefDebugPrint, PointerPrintConfig, ReClassProject};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     _pad: u32,
+///     team: Pointer64<Team>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Team {
+///     id: u64,
+/// }
+///
+/// let xml = ReClassProject::new()
+///     .class::<Player>()
+///     .class::<Team>()
+///     .to_xml();
+/// assert!(xml.contains(r#"<node type="UInt32Node" name="health" comment="u32" hidden="false" />"#));
+/// assert!(xml.contains(r#"<node type="PointerNode" name="team" comment="Pointer64&lt;Team&gt;" hidden="false">"#));
+///
+/// fn export(mem: &mut impl MemoryView, player: Pointer64<Player>) -> std::io::Result<()> {
+///     let map = AddressMap::collect(&player, mem, &PointerPrintConfig::new());
+///     let project = ReClassProject::new().class::<Player>().dump(&map);
+///     // `Team` has no schema, its class is 8 bytes of hex read from the team of the player
+///     std::fs::write("player.rcnet", project.to_rcnet())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReClassProject {
+    /// Schemas of the classes in the order they were added
+    schemas: Vec<TypeSchema>,
+    /// Nodes of the live dump
+    dump: Vec<AddressEntry>,
+}
+
+impl ReClassProject {
+    /// Create a project without classes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a class for `T` built from its [`pointer_debug_schema`](DerefDebugPrint::pointer_debug_schema)
+    pub fn class<T: DerefDebugPrint>(self) -> Self {
+        self.schema(T::pointer_debug_schema())
+    }
+
+    /// Add a class built from `schema`, replacing a class of the same name added before
+    pub fn schema(mut self, schema: TypeSchema) -> Self {
+        match self.schemas.iter_mut().find(|s| s.name == schema.name) {
+            Some(existing) => *existing = schema,
+            None => self.schemas.push(schema),
+        }
+        self
+    }
+
+    /// Take the addresses of the classes from a live dump, and the sizes of the classes of
+    /// the types that have no schema
+    pub fn dump(mut self, map: &AddressMap) -> Self {
+        self.dump.extend(map.entries.iter().cloned());
+        self
+    }
+
+    /// Write the `.rcnet` file of the project, a ZIP archive holding [`to_xml`](Self::to_xml)
+    pub fn to_rcnet(&self) -> Vec<u8> {
+        zip_stored(DATA_FILE, self.to_xml().as_bytes())
+    }
+
+    /// Write the project as the XML document of an `.rcnet` file
+    pub fn to_xml(&self) -> String {
+        let classes = self.classes();
+        let uuids: HashMap<&str, String> = classes
+            .iter()
+            .map(|class| (class.name.as_str(), uuid(&class.name)))
+            .collect();
+        let writer = Writer {
+            schemas: &self.schemas,
+            uuids: &uuids,
+        };
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        let _ = writeln!(out, "<reclass version=\"{}\" type=\"x64\">", FILE_VERSION);
+        out.push_str("  <custom_data />\n  <enums />\n  <classes>\n");
+        for class in &classes {
+            let _ = writeln!(
+                out,
+                "    <class uuid=\"{}\" name=\"{}\" comment=\"\" address=\"{}\">",
+                uuids[class.name.as_str()],
+                escape_xml(&class.name),
+                class
+                    .address
+                    .map(|a| format!("{:X}", a))
+                    .unwrap_or_default()
+            );
+            match class.schema {
+                Some(schema) => writer.class_nodes(schema, &mut out),
+                None => hex_nodes("", class.size, 6, &mut out),
+            }
+            out.push_str("    </class>\n");
+        }
+        out.push_str("  </classes>\n</reclass>\n");
+        out
+    }
+
+    /// The classes of the project: the schemas, then the pointer targets without a schema,
+    /// then the types of the dump without a schema
+    fn classes(&self) -> Vec<Class<'_>> {
+        let mut classes: Vec<Class<'_>> = self
+            .schemas
+            .iter()
+            .map(|schema| Class {
+                name: schema.name.clone(),
+                size: schema.size,
+                address: None,
+                schema: Some(schema),
+            })
+            .collect();
+
+        let targets = self
+            .schemas
+            .iter()
+            .flat_map(|schema| schema.pointers())
+            .filter_map(|field| field.target.as_deref())
+            .filter(|target| class_target(target))
+            .map(base_name);
+        let dumped = self
+            .dump
+            .iter()
+            .map(|entry| entry.type_name.as_str())
+            .filter(|name| class_target(name))
+            .map(base_name);
+        for name in targets.chain(dumped) {
+            if !classes.iter().any(|class| class.name == name) {
+                classes.push(Class {
+                    name: name.to_string(),
+                    size: 0,
+                    address: None,
+                    schema: None,
+                });
+            }
+        }
+
+        for class in &mut classes {
+            // The node nearest to the root is the one the class is opened on
+            let entry = self
+                .dump
+                .iter()
+                .filter(|entry| base_name(&entry.type_name) == class.name)
+                .min_by_key(|entry| entry.path.len());
+            class.address = entry.map(|entry| entry.address);
+            if class.schema.is_none() {
+                class.size = entry.and_then(|entry| entry.size).unwrap_or(8);
+            }
+        }
+        classes
+    }
+}
+
+/// A class of the project
+struct Class<'a> {
+    name: String,
+    size: usize,
+    address: Option<u64>,
+    /// `None` for classes of hex nodes
+    schema: Option<&'a TypeSchema>,
+}
+
+/// A ReClass.NET node of a field
+enum Node<'a> {
+    /// A node without attributes of its own like `UInt32Node`
+    Plain(&'static str),
+    /// An instance of the class of a schema
+    Instance(&'a str),
+    /// A pointer to a node, `None` for `void *`
+    Pointer(Option<Box<Node<'a>>>),
+    /// `count` nodes one after another
+    Array(usize, Box<Node<'a>>),
+}
+
+/// Writes the nodes of the classes
+struct Writer<'a> {
+    schemas: &'a [TypeSchema],
+    /// Uuid of every class by name
+    uuids: &'a HashMap<&'a str, String>,
+}
+
+impl Writer<'_> {
+    /// Write the fields of `schema` in the order of their offsets, filling the gaps with hex
+    /// nodes. Fields overlapping the ones before, like the other fields of a union, are
+    /// left out
+    fn class_nodes(&self, schema: &TypeSchema, out: &mut String) {
+        let mut fields: Vec<(usize, &FieldSchema)> = schema
+            .fields
+            .iter()
+            .filter(|field| field.size > 0)
+            .filter_map(|field| Some((field.offset?, field)))
+            .collect();
+        fields.sort_by_key(|(offset, _)| *offset);
+
+        let mut end = 0;
+        for (offset, field) in fields {
+            if offset < end || offset + field.size > schema.size {
+                continue;
+            }
+            hex_nodes("", offset - end, 6, out);
+            match self.field_node(field) {
+                Some(node) => self.write_node(&node, &field.name, &field.ty, 6, out),
+                None => hex_nodes(&field.name, field.size, 6, out),
+            }
+            end = offset + field.size;
+        }
+        hex_nodes("", schema.size.saturating_sub(end), 6, out);
+    }
+
+    /// The node of `field`, `None` if it is written as hex nodes
+    fn field_node<'s>(&'s self, field: &'s FieldSchema) -> Option<Node<'s>> {
+        if !field.pointer {
+            return self.value_node(&field.ty, field.size);
+        }
+        if field.size != 8 {
+            return None;
+        }
+        let target = field.target.as_deref().map(base_name).unwrap_or("c_void");
+        Some(match target {
+            "u8" | "i8" | "c_char" => Node::Plain("Utf8TextPtrNode"),
+            "u16" => Node::Plain("Utf16TextPtrNode"),
+            target if !class_target(target) => {
+                Node::Pointer(scalar(target).map(|(node, _)| Box::new(Node::Plain(node))))
+            }
+            target => Node::Pointer(Some(Box::new(Node::Instance(target)))),
+        })
+    }
+
+    /// The node of a value of type `ty` taking up `size` bytes
+    fn value_node<'s>(&'s self, ty: &'s str, size: usize) -> Option<Node<'s>> {
+        if let Some(array) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+            let (item, count) = array.rsplit_once("; ")?;
+            let count: usize = count.parse().ok()?;
+            if count == 0 || !size.is_multiple_of(count) {
+                return None;
+            }
+            let item = self.value_node(item, size / count)?;
+            return Some(Node::Array(count, Box::new(item)));
+        }
+        if let Some((node, node_size)) = scalar(ty) {
+            return (node_size == size).then_some(Node::Plain(node));
+        }
+        let name = base_name(ty);
+        self.schemas
+            .iter()
+            .any(|schema| schema.name == name && schema.size == size)
+            .then_some(Node::Instance(name))
+    }
+
+    fn write_node(
+        &self,
+        node: &Node<'_>,
+        name: &str,
+        comment: &str,
+        indent: usize,
+        out: &mut String,
+    ) {
+        let (ty, attributes, inner) = match node {
+            Node::Plain(ty) => (*ty, String::new(), None),
+            Node::Instance(class) => (
+                "ClassInstanceNode",
+                format!(" reference=\"{}\"", self.uuids[class]),
+                None,
+            ),
+            Node::Pointer(inner) => ("PointerNode", String::new(), inner.as_deref()),
+            Node::Array(count, inner) => {
+                ("ArrayNode", format!(" count=\"{}\"", count), Some(&**inner))
+            }
+        };
+        let _ = write!(
+            out,
+            "{:indent$}<node type=\"{}\" name=\"{}\" comment=\"{}\" hidden=\"false\"{}",
+            "",
+            ty,
+            escape_xml(name),
+            escape_xml(comment),
+            attributes,
+            indent = indent
+        );
+        match inner {
+            Some(inner) => {
+                out.push_str(">\n");
+                self.write_node(inner, "", "", indent + 2, out);
+                let _ = writeln!(out, "{:indent$}</node>", "", indent = indent);
+            }
+            None => out.push_str(" />\n"),
+        }
+    }
+}
+
+/// Write `size` bytes of hex nodes, the first one named `name`
+fn hex_nodes(name: &str, mut size: usize, indent: usize, out: &mut String) {
+    let mut name = name;
+    while size > 0 {
+        let chunk = [8, 4, 2, 1]
+            .into_iter()
+            .find(|chunk| *chunk <= size)
+            .unwrap_or(1);
+        let _ = writeln!(
+            out,
+            "{:indent$}<node type=\"Hex{}Node\" name=\"{}\" comment=\"\" hidden=\"false\" />",
+            "",
+            chunk * 8,
+            escape_xml(name),
+            indent = indent
+        );
+        name = "";
+        size -= chunk;
+    }
+}
+
+/// The node type and size of a primitive type
+fn scalar(ty: &str) -> Option<(&'static str, usize)> {
+    Some(match ty {
+        "u8" => ("UInt8Node", 1),
+        "u16" => ("UInt16Node", 2),
+        "u32" => ("UInt32Node", 4),
+        "u64" | "umem" => ("UInt64Node", 8),
+        "i8" => ("Int8Node", 1),
+        "i16" => ("Int16Node", 2),
+        "i32" => ("Int32Node", 4),
+        "i64" => ("Int64Node", 8),
+        "usize" => ("NUIntNode", 8),
+        "isize" => ("NIntNode", 8),
+        "f32" => ("FloatNode", 4),
+        "f64" => ("DoubleNode", 8),
+        "bool" => ("BoolNode", 1),
+        _ => return None,
+    })
+}
+
+/// Returns true if a pointer to `target` needs a class: it isn't a primitive, `void` or
+/// another pointer
+fn class_target(target: &str) -> bool {
+    let name = base_name(target);
+    scalar(name).is_none()
+        && !matches!(
+            name,
+            "c_void" | "()" | "c_char" | "Pointer32" | "Pointer64" | ""
+        )
+}
+
+/// Name of a type without its module path and generic arguments, e.g. `Node` for
+/// `crate::Node<u32>`
+fn base_name(ty: &str) -> &str {
+    let ty = ty.split('<').next().unwrap_or(ty);
+    ty.rsplit("::").next().unwrap_or(ty).trim()
+}
+
+/// Uuid of a class as ReClass.NET writes them, the 16 bytes in base64. It is derived from
+/// the name so exports of the same types refer to the same classes
+fn uuid(name: &str) -> String {
+    let mut bytes = [0u8; 16];
+    for (half, seed) in [0xcbf2_9ce4_8422_2325u64, 0x8422_2325_cbf2_9ce4]
+        .into_iter()
+        .enumerate()
+    {
+        let hash = name.bytes().fold(seed, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        });
+        bytes[half * 8..half * 8 + 8].copy_from_slice(&hash.to_le_bytes());
+    }
+    base64(&bytes)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// A ZIP archive holding `data` uncompressed as the file `name`
+fn zip_stored(name: &str, data: &[u8]) -> Vec<u8> {
+    let crc = crc32(data);
+    let mut out = Vec::with_capacity(data.len() + 2 * name.len() + 98);
+    let u16s = |out: &mut Vec<u8>, values: &[u16]| {
+        values
+            .iter()
+            .for_each(|v| out.extend_from_slice(&v.to_le_bytes()))
+    };
+    let u32s = |out: &mut Vec<u8>, values: &[u32]| {
+        values
+            .iter()
+            .for_each(|v| out.extend_from_slice(&v.to_le_bytes()))
+    };
+    // Version 2.0, no flags, stored, 1980-01-01 00:00
+    let header = |out: &mut Vec<u8>| {
+        u16s(out, &[20, 0, 0, 0, 0x21]);
+        u32s(out, &[crc, data.len() as u32, data.len() as u32]);
+        u16s(out, &[name.len() as u16, 0]);
+    };
+
+    u32s(&mut out, &[0x0403_4b50]);
+    header(&mut out);
+    out.extend_from_slice(name.as_bytes());
+    out.extend_from_slice(data);
+
+    let directory = out.len() as u32;
+    u32s(&mut out, &[0x0201_4b50]);
+    u16s(&mut out, &[20]);
+    header(&mut out);
+    u16s(&mut out, &[0, 0, 0, 0, 0]);
+    u32s(&mut out, &[0]);
+    out.extend_from_slice(name.as_bytes());
+
+    let directory_size = out.len() as u32 - directory;
+    u32s(&mut out, &[0x0605_4b50]);
+    u16s(&mut out, &[0, 0, 1, 1]);
+    u32s(&mut out, &[directory_size, directory]);
+    u16s(&mut out, &[0]);
+    out
+}
+
+/// CRC-32 of ZIP archives
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, b| {
+        (0..8).fold(crc ^ u32::from(*b), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
@@ -0,0 +1,157 @@
+//! Kinds of memory pointers lead into.
+
+use core::fmt;
+use core::ops::Range;
+
+use memflow::os::Process;
+
+use crate::MappedMemory;
+use crate::prelude::*;
+
+/// What the memory at an address is used for, see [`MemoryRegions::region`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Region {
+    /// Inside the image of a module, at `offset` from its base
+    Module {
+        /// Name of the module
+        name: String,
+        /// Offset from the base of the module, the RVA on Windows
+        offset: u64,
+    },
+    /// Mapped memory outside every module and stack
+    Heap,
+    /// Inside a thread stack
+    Stack,
+    /// Not mapped at all
+    Unmapped,
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Region::Module { name, offset } => write!(f, "{}+{:#x}", name, offset),
+            Region::Heap => f.write_str("heap"),
+            Region::Stack => f.write_str("stack"),
+            Region::Unmapped => f.write_str("unmapped"),
+        }
+    }
+}
+
+/// The modules, stacks and mapped memory of a process.
+///
+/// Set on a [`PointerPrintConfig`](crate::PointerPrintConfig) with
+/// [`regions`](crate::PointerPrintConfig::regions), the target of every followed pointer is
+/// annotated with its [`Region`], so pointers into the wrong kind of memory stand out:
+///
+/// ```text
+/// Player {
+///   name-> "player" [heap]
+///   vtable-> VTable [client.dll+0x1a2b30]
+///   next-> Player [unmapped]
+/// }
+/// ```
+///
+/// ```rust
+/// use memflow_pointer_debug::{MappedMemory, MemoryRegions, Region};
+///
+/// let regions = MemoryRegions::new()
+///     .module("client.dll", 0x7ff6_0000_0000..0x7ff6_0020_0000)
+///     .stack(0x5000_0000..0x5010_0000)
+///     .mapped_memory(MappedMemory::from_ranges([
+///         0x1000_0000..0x2000_0000,
+///         0x5000_0000..0x5010_0000,
+///         0x7ff6_0000_0000..0x7ff6_0020_0000,
+///     ]));
+///
+/// assert_eq!(regions.region(0x7ff6_001a_2b30).unwrap().to_string(), "client.dll+0x1a2b30");
+/// assert_eq!(regions.region(0x5000_8000), Some(Region::Stack));
+/// assert_eq!(regions.region(0x1234_5678), Some(Region::Heap));
+/// assert_eq!(regions.region(0x4000_0000), Some(Region::Unmapped));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryRegions {
+    /// Modules sorted by their base address
+    modules: Vec<(Range<u64>, String)>,
+    /// Thread stacks
+    stacks: Vec<Range<u64>>,
+    /// Everything that is mapped, unknown if empty
+    mapped: MappedMemory,
+}
+
+impl MemoryRegions {
+    /// Create an empty map, every address is of an unknown region
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the module `name` loaded at `range`
+    pub fn module(mut self, name: impl Into<String>, range: Range<u64>) -> Self {
+        let index = self
+            .modules
+            .partition_point(|(module, _)| module.start <= range.start);
+        self.modules.insert(index, (range, name.into()));
+        self
+    }
+
+    /// Add the thread stack at `range`
+    pub fn stack(mut self, range: Range<u64>) -> Self {
+        self.stacks.push(range);
+        self
+    }
+
+    /// Set the mapped memory, addresses outside of it are [`Region::Unmapped`] and the ones
+    /// inside it but outside every module and stack are [`Region::Heap`]
+    pub fn mapped_memory(mut self, mapped: MappedMemory) -> Self {
+        self.mapped = mapped;
+        self
+    }
+
+    /// Query the modules and mapped memory of a process.
+    ///
+    /// memflow doesn't list the threads of a process, their stacks have to be added with
+    /// [`stack`](Self::stack) to be told apart from the heap.
+    ///
+    /// # Parameters
+    ///
+    /// * `process` - The process whose modules and memory map are read
+    pub fn from_process<P: Process>(process: &mut P) -> Self {
+        let mut regions = Self::new().mapped_memory(MappedMemory::from_process(process));
+        for module in process.module_list().unwrap_or_default() {
+            let base = module.base.to_umem();
+            regions = regions.module(
+                module.name.to_string(),
+                base..base.saturating_add(module.size),
+            );
+        }
+        regions
+    }
+
+    /// The region `address` lies in, `None` if it isn't inside a module or stack and the
+    /// mapped memory isn't known
+    pub fn region(&self, address: u64) -> Option<Region> {
+        let index = self
+            .modules
+            .partition_point(|(module, _)| module.start <= address);
+        if let Some((range, name)) = self.modules[..index]
+            .iter()
+            .rev()
+            .find(|(module, _)| module.contains(&address))
+        {
+            return Some(Region::Module {
+                name: name.clone(),
+                offset: address - range.start,
+            });
+        }
+        if self.stacks.iter().any(|stack| stack.contains(&address)) {
+            Some(Region::Stack)
+        } else if self.mapped.is_empty() {
+            None
+        } else if self.mapped.contains(address) {
+            Some(Region::Heap)
+        } else {
+            Some(Region::Unmapped)
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Read–eval loop for browsing a structure from the command line.
+
+use std::io::{self, BufRead, Write};
+
+use memflow::mem::MemoryView;
+
+use crate::node::{chain_addresses, collect_path, dotted, item_address, quoted_text, target_note};
+use crate::{
+    DerefDebugPrint, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget,
+    format_hexdump,
+};
+
+const HELP: &str = "\
+ls              list the fields of the current structure
+cd <path>       enter a field, e.g. `cd next`, `cd players[2]`, `cd ..` or `cd /`
+pwd             print the current path
+read            read the memory again and list the fields
+hexdump [len]   dump `len` bytes (default 64) at the current structure
+help            print this help
+quit            leave the loop";
+
+/// A read–eval loop navigating a structure like a file system, pointers are read once they
+/// are entered.
+///
+/// `ls` lists the fields of the current structure, `cd next` and `cd players[2]` follow a
+/// pointer or enter an element, `cd ..` goes back up and `cd /` to the root. `read` reads the
+/// memory again, `hexdump 64` dumps the bytes at the current structure.
+///
+/// Only the pointers on the current path are read, down to the fields of the current
+/// structure. Targets can be entered again through another path or a cycle, e.g.
+/// `cd next.prev.next`. The `max_depth`, `max_revisits` and `include_paths` of the
+/// configuration are ignored and every other option applies.
+///
+/// [`run`](Self::run) reads the commands from stdin, [`run_with`](Self::run_with) from a
+/// script:
+///
+/// ```rust
+/// use memflow::connector::FileIoMemory;
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerRepl};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// // Two nodes pointing at each other
+/// let mut bytes = vec![0u8; 0x100];
+/// let first = Node { value: 1, next: Pointer64::from(0x20u64) };
+/// let second = Node { value: 2, next: Pointer64::from(0x10u64) };
+/// bytes[0x10..0x20].copy_from_slice(first.as_bytes());
+/// bytes[0x20..0x30].copy_from_slice(second.as_bytes());
+/// let mut mem = FileIoMemory::new(std::io::Cursor::new(bytes)).unwrap().into_phys_view();
+///
+/// let root = Node { value: 0, next: Pointer64::from(0x10u64) };
+/// let script = "cd next.next\nls\ncd ..\nls\ncd /\nls\n";
+/// let mut out = Vec::new();
+/// PointerRepl::new(root).run_with(&mut mem, script.as_bytes(), &mut out).unwrap();
+/// let expected = "\
+/// Node> Node.next.next> value: u64 = 2
+/// next -> {..} @ 0x10
+/// Node.next.next> Node.next> value: u64 = 1
+/// next -> {..} @ 0x20
+/// Node.next> Node> value: u64 = 0
+/// next -> {..} @ 0x10
+/// Node> ";
+/// assert_eq!(String::from_utf8(out).unwrap(), expected);
+/// ```
+pub struct PointerRepl<T> {
+    value: T,
+    config: PointerPrintConfig,
+    /// The tree collected for a path
+    tree: Option<(Vec<String>, PointerDebugNode)>,
+    /// Field names and `[i]` indices from the root to the current structure
+    path: Vec<String>,
+}
+
+/// What a path leads to
+enum Location<'a> {
+    /// A structure
+    Node(&'a PointerDebugNode),
+    /// The elements of an array, chain or list starting at `address`
+    Items {
+        address: u64,
+        items: Vec<(u64, &'a PointerTarget)>,
+    },
+}
+
+impl<T: DerefDebugPrint> PointerRepl<T> {
+    /// Browse `value` with the default options
+    pub fn new(value: T) -> Self {
+        Self::with_config(value, PointerPrintConfig::new())
+    }
+
+    /// Browse `value` with the given options
+    pub fn with_config(value: T, config: PointerPrintConfig) -> Self {
+        Self {
+            value,
+            config,
+            tree: None,
+            path: Vec::new(),
+        }
+    }
+
+    /// Read commands from stdin until `quit` or the end of the input
+    pub fn run<M: MemoryView>(&mut self, mem: &mut M) -> io::Result<()> {
+        self.run_with(mem, io::stdin().lock(), io::stdout())
+    }
+
+    /// Read commands from `input` and write the prompts and the output to `out` until `quit`
+    /// or the end of the input
+    pub fn run_with<M, R, W>(&mut self, mem: &mut M, mut input: R, mut out: W) -> io::Result<()>
+    where
+        M: MemoryView,
+        R: BufRead,
+        W: Write,
+    {
+        let mut line = String::new();
+        loop {
+            write!(out, "{}> ", self.prompt())?;
+            out.flush()?;
+            line.clear();
+            if input.read_line(&mut line)? == 0 || !self.execute(line.trim(), mem, &mut out)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Run a single command and write its output to `out`, returns false for `quit`
+    pub fn execute<M: MemoryView, W: Write>(
+        &mut self,
+        command: &str,
+        mem: &mut M,
+        out: &mut W,
+    ) -> io::Result<bool> {
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, argument)| (name, argument.trim()));
+        match name {
+            "" => {}
+            "ls" => self.list(mem, out)?,
+            "cd" => {
+                let path = self.entered(argument);
+                self.collect(&path, mem);
+                match self.locate(&path) {
+                    Ok(_) => self.path = path,
+                    Err(error) => writeln!(out, "{}", error)?,
+                }
+            }
+            "pwd" => writeln!(out, "{}", self.prompt())?,
+            "read" => {
+                self.tree = None;
+                self.list(mem, out)?;
+            }
+            "hexdump" => match argument {
+                "" => self.hexdump(64, mem, out)?,
+                len => match len.parse() {
+                    Ok(len) => self.hexdump(len, mem, out)?,
+                    Err(_) => writeln!(out, "expected a number of bytes, got `{}`", len)?,
+                },
+            },
+            "help" => writeln!(out, "{}", HELP)?,
+            "quit" | "exit" | "q" => return Ok(false),
+            name => writeln!(out, "unknown command `{}`, try `help`", name)?,
+        }
+        Ok(true)
+    }
+
+    /// The current path like `Player.team.members[2]`
+    pub fn prompt(&self) -> String {
+        let name = self.value.pointer_type_name();
+        match self.path.first() {
+            Some(first) if !first.starts_with('[') => format!("{}.{}", name, dotted(&self.path)),
+            _ => format!("{}{}", name, dotted(&self.path)),
+        }
+    }
+
+    fn list<M: MemoryView, W: Write>(&mut self, mem: &mut M, out: &mut W) -> io::Result<()> {
+        self.collect(&self.path.clone(), mem);
+        let config = &self.config;
+        match self.locate(&self.path) {
+            Ok(Location::Node(node)) => {
+                if let Some(value) = &node.value {
+                    writeln!(out, "{} = {}", node.type_name, value)?;
+                }
+                for field in &node.fields {
+                    let line = match &field.value {
+                        PointerDebugValue::Value(value) => {
+                            format!("{}: {} = {}", field.name, field.type_name, value)
+                        }
+                        PointerDebugValue::Nested(nested) => {
+                            format!("{}: {} {{..}}", field.name, nested.type_name)
+                        }
+                        PointerDebugValue::Pointer { address, target } => {
+                            describe(&field.name, *address, target, config)
+                        }
+                    };
+                    writeln!(out, "{}", line)?;
+                }
+            }
+            Ok(Location::Items { items, .. }) => {
+                for (i, (address, item)) in items.into_iter().enumerate() {
+                    writeln!(
+                        out,
+                        "{}",
+                        describe(&format!("[{}]", i), address, item, config)
+                    )?;
+                }
+            }
+            Err(error) => writeln!(out, "{}", error)?,
+        }
+        Ok(())
+    }
+
+    fn hexdump<M: MemoryView, W: Write>(
+        &mut self,
+        len: usize,
+        mem: &mut M,
+        out: &mut W,
+    ) -> io::Result<()> {
+        self.collect(&self.path.clone(), mem);
+        let address = match self.locate(&self.path) {
+            Ok(Location::Node(node)) => node.address,
+            Ok(Location::Items { address, .. }) => Some(address),
+            Err(error) => return writeln!(out, "{}", error),
+        };
+        let Some(address) = address else {
+            return writeln!(
+                out,
+                "the root value wasn't read from memory, `cd` into a pointer"
+            );
+        };
+        match mem.read_raw(address.into(), len) {
+            Ok(bytes) => writeln!(out, "{:#x}:\n{}", address, format_hexdump(&bytes)),
+            Err(e) => writeln!(out, "error reading {:#x}: {}", address, e),
+        }
+    }
+
+    /// The path `cd argument` leads to from the current one
+    fn entered(&self, argument: &str) -> Vec<String> {
+        let mut path = if argument.is_empty() || argument.starts_with('/') {
+            Vec::new()
+        } else {
+            self.path.clone()
+        };
+        // `..` is split off before the field names, which are separated by dots as well
+        for piece in argument.split(['/', ' ']).filter(|piece| !piece.is_empty()) {
+            if piece == ".." {
+                path.pop();
+                continue;
+            }
+            for part in piece.split('.').filter(|part| !part.is_empty()) {
+                // `players[2]` enters the field and then the element
+                let (name, indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+                if !name.is_empty() {
+                    path.push(name.to_string());
+                }
+                for index in indices.split_inclusive(']') {
+                    path.push(index.to_string());
+                }
+            }
+        }
+        path
+    }
+
+    /// Collect the fields along `path` and the fields of the structure it leads to, unless
+    /// the tree was collected for it already
+    fn collect<M: MemoryView>(&mut self, path: &[String], mem: &mut M) {
+        if self
+            .tree
+            .as_ref()
+            .is_some_and(|(collected, _)| collected == path)
+        {
+            return;
+        }
+        // Every segment follows at most one pointer
+        let tree = collect_path(&self.value, mem, &self.config, path, path.len() + 1);
+        self.tree = Some((path.to_vec(), tree));
+    }
+
+    /// Resolve `path` in the tree collected by [`collect`](Self::collect)
+    fn locate(&self, path: &[String]) -> Result<Location<'_>, String> {
+        let Some((_, tree)) = &self.tree else {
+            return Err("nothing was read yet".to_string());
+        };
+        let mut location = Location::Node(tree);
+        for segment in path {
+            let (address, target) = match location {
+                Location::Node(node) => {
+                    let field = node
+                        .fields
+                        .iter()
+                        .find(|field| field.name == *segment)
+                        .ok_or_else(|| format!("{} has no field `{}`", node.type_name, segment))?;
+                    match &field.value {
+                        PointerDebugValue::Nested(nested) => {
+                            location = Location::Node(nested);
+                            continue;
+                        }
+                        PointerDebugValue::Pointer { address, target } => (*address, target),
+                        PointerDebugValue::Value(value) => {
+                            return Err(format!("`{}` is the value {}", segment, value));
+                        }
+                    }
+                }
+                Location::Items { items, .. } => {
+                    let index = segment
+                        .strip_prefix('[')
+                        .and_then(|index| index.strip_suffix(']'))
+                        .and_then(|index| index.parse::<usize>().ok());
+                    match index.and_then(|index| items.get(index)) {
+                        Some((address, item)) => (*address, *item),
+                        None => {
+                            return Err(format!(
+                                "there is no element `{}` of {}",
+                                segment,
+                                items.len()
+                            ));
+                        }
+                    }
+                }
+            };
+            location = match target {
+                PointerTarget::Node(node) => Location::Node(node),
+                PointerTarget::Array { items, .. } => Location::Items {
+                    address,
+                    items: items
+                        .iter()
+                        .map(|item| (item_address(item, address), item))
+                        .collect(),
+                },
+                PointerTarget::Chain { items, .. } => Location::Items {
+                    address,
+                    items: chain_addresses(address, items)
+                        .into_iter()
+                        .zip(items)
+                        .collect(),
+                },
+                PointerTarget::List { items, .. } => Location::Items {
+                    address,
+                    items: items
+                        .iter()
+                        .map(|(address, item)| (*address, item))
+                        .collect(),
+                },
+                target => {
+                    let target = describe(segment, address, target, &self.config);
+                    return Err(format!("can't enter {}", target));
+                }
+            };
+        }
+        Ok(location)
+    }
+}
+
+/// One line describing the pointer `name` to `address` and where it led
+fn describe(
+    name: &str,
+    address: u64,
+    target: &PointerTarget,
+    config: &PointerPrintConfig,
+) -> String {
+    if let Some(note) = target_note(target, address) {
+        let separator = if note.leads { " -> " } else { ": " };
+        return format!("{}{}{}", name, separator, note.text);
+    }
+    match target {
+        PointerTarget::Node(node) => match &node.value {
+            Some(value) => format!(
+                "{} -> {} = {} @ {:#x}",
+                name, node.type_name, value, address
+            ),
+            None => format!("{} -> {} @ {:#x}", name, node.type_name, address),
+        },
+        PointerTarget::MaxDepth => format!("{} -> {{..}} @ {:#x}", name, address),
+        PointerTarget::Array { len, .. } => format!("{} -> [{}] @ {:#x}", name, len, address),
+        PointerTarget::Chain { items, .. } => {
+            format!("{} -> [{}] @ {:#x}", name, items.len(), address)
+        }
+        PointerTarget::List { items, .. } => {
+            format!("{} -> [{}] @ {:#x}", name, items.len(), address)
+        }
+        PointerTarget::Text(text) => format!("{} -> {}", name, quoted_text(text, config)),
+        PointerTarget::Bytes(bytes) => format!("{} -> b\"{}\"", name, bytes.escape_ascii()),
+        PointerTarget::ReadError(e) => format!("{} -> error reading {:#x}: {}", name, address, e),
+        // Described by the note above
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,138 @@
+//! Memory views recording reads and replaying them later.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use memflow::error::{Error, ErrorKind, ErrorOrigin, Result};
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+use serde::{Deserialize, Serialize};
+
+use crate::SnapshotRegion;
+use crate::snapshot::{merge, read_regions, record_reads, regions_metadata};
+
+/// A memory view passing reads through to `M` and recording every byte read.
+///
+/// The recording is saved with [`save`](Self::save) and served again by a [`ReplayView`],
+/// so traversals of derived structures can be repeated without the target, e.g. for golden
+/// output tests:
+///
+/// ```rust,no_run
+/// use memflow::prelude::v1::{MemoryView, Pod, Pointer64};
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrint, RecordingView, ReplayView};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// fn record(mem: impl MemoryView, node: &Node) -> std::io::Result<()> {
+///     let mut recording = RecordingView::new(mem);
+///     node.pointer_print(&mut recording);
+///     recording.save("tests/node.replay.json")
+/// }
+///
+/// fn check(node: &Node) -> std::io::Result<()> {
+///     let mut replay = ReplayView::load("tests/node.replay.json")?;
+///     let tree = node.pointer_collect(&mut replay, 5);
+///     assert_eq!(tree.to_string(), std::fs::read_to_string("tests/node.golden")?);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordingView<M> {
+    mem: M,
+    reads: Vec<(u64, Vec<u8>)>,
+}
+
+impl<M: MemoryView> RecordingView<M> {
+    /// Record the reads of `mem`
+    pub fn new(mem: M) -> Self {
+        Self {
+            mem,
+            reads: Vec::new(),
+        }
+    }
+
+    /// A view serving the bytes recorded so far
+    pub fn replay(&self) -> ReplayView {
+        let metadata = self.mem.metadata();
+        ReplayView {
+            regions: merge(self.reads.clone()),
+            arch_bits: metadata.arch_bits,
+            little_endian: metadata.little_endian,
+        }
+    }
+
+    /// Write the bytes recorded so far to `path` as JSON, an existing file is replaced
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.replay().save(path)
+    }
+
+    /// Stop recording and take back the wrapped view
+    pub fn into_inner(self) -> M {
+        self.mem
+    }
+}
+
+impl<M: MemoryView> MemoryView for RecordingView<M> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        record_reads(&mut self.mem, &mut self.reads, data)
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.mem.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.mem.metadata()
+    }
+}
+
+/// A read-only memory view serving the bytes recorded by a [`RecordingView`].
+///
+/// A traversal reading the same addresses as the recorded one gets exactly the same bytes,
+/// reads of anything that wasn't recorded fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayView {
+    /// Sorted, non-overlapping regions that were read successfully
+    regions: Vec<SnapshotRegion>,
+    arch_bits: u8,
+    little_endian: bool,
+}
+
+impl ReplayView {
+    /// The recorded memory in ascending order
+    pub fn regions(&self) -> &[SnapshotRegion] {
+        &self.regions
+    }
+
+    /// Write the recording to `path` as JSON, an existing file is replaced
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+
+    /// Read a recording written by [`RecordingView::save`] or [`save`](Self::save)
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+impl MemoryView for ReplayView {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        read_regions(&self.regions, data)
+    }
+
+    fn write_raw_iter(&mut self, _: WriteRawMemOps) -> Result<()> {
+        Err(Error(ErrorOrigin::Memory, ErrorKind::ReadOnly))
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        regions_metadata(&self.regions, self.arch_bits, self.little_endian)
+    }
+}
@@ -0,0 +1,133 @@
+//! Aggregated traversal errors.
+
+use core::fmt;
+
+use memflow::error::Error;
+
+use crate::node::chain_addresses;
+use crate::prelude::*;
+use crate::{PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// A single pointer that could not be read during the traversal
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerReadFailure {
+    /// Dotted path of the pointer field starting at the root, e.g. `peb.ldr`
+    pub path: String,
+    /// The address that failed to read
+    pub address: u64,
+    /// The error returned by memflow
+    #[cfg_attr(feature = "serde", serde(with = "crate::snapshot::error_code"))]
+    pub error: Error,
+}
+
+/// Every failed read of a traversal.
+///
+/// Returned by [`PointerPrint::try_pointer_print`](crate::PointerPrint::try_pointer_print)
+/// when at least one pointer target could not be read.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerDebugReport {
+    /// The failed reads in traversal order
+    pub failures: Vec<PointerReadFailure>,
+    /// Number of pointers that were not followed because the read budget was exhausted
+    pub skipped: usize,
+    /// Set if the traversal was cancelled before it was complete
+    pub cancelled: bool,
+}
+
+impl PointerDebugReport {
+    /// Collect all read errors contained in a tree
+    pub fn from_node(root: &PointerDebugNode) -> Self {
+        let mut report = Self::default();
+        report.add_node("", root);
+        report
+    }
+
+    /// Returns true if no read failed, pointers skipped because of the read budget are not
+    /// failures
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn add_node(&mut self, prefix: &str, node: &PointerDebugNode) {
+        for field in &node.fields {
+            // Elements of embedded arrays are indexed like array pointers
+            let path = if prefix.is_empty() || field.name.starts_with('[') {
+                format!("{}{}", prefix, field.name)
+            } else {
+                format!("{}.{}", prefix, field.name)
+            };
+            match &field.value {
+                PointerDebugValue::Pointer { address, target } => {
+                    self.add_target(path, *address, target);
+                }
+                PointerDebugValue::Nested(nested) => self.add_node(&path, nested),
+                PointerDebugValue::Value(_) => {}
+            }
+        }
+    }
+
+    fn add_target(&mut self, path: String, address: u64, target: &PointerTarget) {
+        match target {
+            PointerTarget::Node(child) => self.add_node(&path, child),
+            PointerTarget::Array { items, .. } => {
+                for (i, item) in items.iter().enumerate() {
+                    self.add_target(format!("{}[{}]", path, i), address, item);
+                }
+            }
+            PointerTarget::Chain { items, .. } => {
+                for (i, (item, item_address)) in items
+                    .iter()
+                    .zip(chain_addresses(address, items))
+                    .enumerate()
+                {
+                    self.add_target(format!("{}[{}]", path, i), item_address, item);
+                }
+            }
+            PointerTarget::List { items, .. } => {
+                for (i, (item_address, item)) in items.iter().enumerate() {
+                    self.add_target(format!("{}[{}]", path, i), *item_address, item);
+                }
+            }
+            PointerTarget::ReadError(error) => self.failures.push(PointerReadFailure {
+                path,
+                address,
+                error: *error,
+            }),
+            PointerTarget::AlreadyVisited
+            | PointerTarget::MaxDepth
+            | PointerTarget::Text(_)
+            | PointerTarget::Bytes(_)
+            | PointerTarget::ChainLink
+            | PointerTarget::Null
+            | PointerTarget::Invalid
+            | PointerTarget::Unmapped
+            | PointerTarget::NotFollowed => {}
+            PointerTarget::BudgetExhausted => self.skipped += 1,
+            PointerTarget::Cancelled => self.cancelled = true,
+        }
+    }
+}
+
+impl fmt::Display for PointerDebugReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} pointer read(s) failed", self.failures.len())?;
+        if self.skipped > 0 {
+            write!(f, ", {} pointer(s) skipped over budget", self.skipped)?;
+        }
+        if self.cancelled {
+            write!(f, ", traversal cancelled")?;
+        }
+        for failure in &self.failures {
+            write!(
+                f,
+                "\n  {} @ {:#x}: {}",
+                failure.path, failure.address, failure.error
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for PointerDebugReport {}
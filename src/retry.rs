@@ -0,0 +1,141 @@
+//! Retrying reads that failed on flaky connectors.
+
+use core::time::Duration;
+
+use memflow::error::Result;
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+
+use crate::PointerPrintConfig;
+use crate::read::read_each;
+
+/// How often a failed read is tried again, see [`PointerPrintConfig::retry`].
+///
+/// DMA and network connectors drop reads now and then that succeed when they are issued
+/// again. A read is tried up to [`attempts`](Self::attempts) times, waiting
+/// [`delay`](Self::delay) before the first retry and [`backoff`](Self::backoff) times as
+/// long before each further one. Reads of memory that isn't mapped fail every attempt,
+/// keep the attempts and the delay low when the pointers may be garbage.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use memflow_pointer_debug::{PointerPrintConfig, RetryPolicy};
+///
+/// // Waits 1ms, 2ms and 4ms before the retries
+/// let policy = RetryPolicy::new(4).delay(Duration::from_millis(1)).backoff(2);
+/// assert_eq!(policy.retry_delay(3), Duration::from_millis(4));
+///
+/// let config = PointerPrintConfig::new().retry(policy).show_stats(true);
+/// assert_eq!(config.retry, Some(policy));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetryPolicy {
+    /// Number of times a read is tried in total, 1 doesn't retry
+    pub attempts: u32,
+    /// Time waited before the first retry, there is no sleep without the `std` feature and
+    /// reads are retried right away
+    pub delay: Duration,
+    /// Factor the delay grows by with each further retry, 1 waits the same every time
+    pub backoff: u32,
+}
+
+impl RetryPolicy {
+    /// Try every read up to `attempts` times without waiting in between
+    pub fn new(attempts: u32) -> Self {
+        Self {
+            attempts,
+            delay: Duration::ZERO,
+            backoff: 1,
+        }
+    }
+
+    /// Wait `delay` before the first retry
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Wait `backoff` times as long before each retry as before the one before it
+    pub fn backoff(mut self, backoff: u32) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Time waited before retry number `retry`, counted from 1
+    pub fn retry_delay(&self, retry: u32) -> Duration {
+        let factor = self.backoff.saturating_pow(retry.saturating_sub(1));
+        self.delay.saturating_mul(factor)
+    }
+
+    /// Wait before retry number `retry`
+    fn wait(&self, retry: u32) {
+        #[cfg(feature = "std")]
+        {
+            let delay = self.retry_delay(retry);
+            if !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = retry;
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, 1ms before the first retry and twice as long before the second
+    fn default() -> Self {
+        Self::new(3).delay(Duration::from_millis(1)).backoff(2)
+    }
+}
+
+/// Retries the failed reads of a traversal with the [`RetryPolicy`] of its configuration
+/// and counts the retries. Without a policy the reads are passed through as they are
+pub(crate) struct RetryView<'a, M> {
+    mem: &'a mut M,
+    policy: Option<RetryPolicy>,
+    /// Number of reads issued again so far
+    pub(crate) retries: usize,
+}
+
+impl<'a, M: MemoryView> RetryView<'a, M> {
+    /// Wrap `mem` for a traversal with `config`
+    pub(crate) fn new(mem: &'a mut M, config: &PointerPrintConfig) -> Self {
+        Self {
+            mem,
+            policy: config.retry.filter(|policy| policy.attempts > 1),
+            retries: 0,
+        }
+    }
+}
+
+impl<M: MemoryView> MemoryView for RetryView<'_, M> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        let Some(policy) = self.policy else {
+            return self.mem.read_raw_iter(data);
+        };
+        // Every request is read on its own so only the ones that failed are retried
+        read_each(data, |address, buf| {
+            let mut attempt = 1;
+            loop {
+                if self.mem.read_raw_into(address, buf).is_ok() {
+                    return true;
+                }
+                if attempt >= policy.attempts {
+                    return false;
+                }
+                policy.wait(attempt);
+                self.retries += 1;
+                attempt += 1;
+            }
+        })
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.mem.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.mem.metadata()
+    }
+}
@@ -0,0 +1,199 @@
+//! RON style rendering with labels for shared nodes.
+//!
+//! Nodes that are pointed to more than once get a label like `#1` where they are first
+//! printed, later pointers to them are printed as `-> #1`, so the shape of the graph is
+//! readable without comparing raw addresses.
+
+use core::fmt::Write;
+
+use crate::node::{
+    Labels, Shown, chain_addresses, element_type, is_hidden, item_address, node_label, quoted_text,
+    shared_labels, shown_elements,
+};
+use crate::prelude::*;
+use crate::{ErrorPolicy, PointerDebugNode, PointerDebugValue, PointerPrintConfig, PointerTarget};
+
+/// Render `node` in the [`OutputFormat::Ron`](crate::OutputFormat::Ron) format
+pub(crate) fn to_ron(node: &PointerDebugNode, config: &PointerPrintConfig) -> String {
+    let mut writer = RonWriter {
+        out: String::new(),
+        labels: shared_labels(node),
+        config,
+    };
+    writer.node(node, 0);
+    writer.out.push('\n');
+    writer.out
+}
+
+struct RonWriter<'a> {
+    out: String,
+    /// Label of every shared node
+    labels: Labels<'a>,
+    config: &'a PointerPrintConfig,
+}
+
+impl RonWriter<'_> {
+    /// Write `node` starting at the current position, its fields one level below `depth`
+    fn node(&mut self, node: &PointerDebugNode, depth: usize) {
+        if let Some(label) = node_label(node, &self.labels) {
+            let _ = write!(self.out, "#{} ", label);
+        }
+        if let Some(value) = &node.value {
+            self.out.push_str(value);
+            return;
+        }
+
+        self.out.push_str(&node.type_name);
+        self.out.push('(');
+        let fields: Vec<_> = node
+            .shown_fields(self.config)
+            .into_iter()
+            .filter(|shown| match shown {
+                Shown::Item(_, field) => match &field.value {
+                    PointerDebugValue::Pointer { target, .. } => !is_hidden(target, self.config),
+                    _ => true,
+                },
+                Shown::Gap(_) => true,
+            })
+            .collect();
+        if fields.is_empty() {
+            self.out.push(')');
+            return;
+        }
+
+        self.out.push('\n');
+        for shown in fields {
+            self.indent(depth + 1);
+            let field = match shown {
+                Shown::Item(_, field) => field,
+                Shown::Gap(more) => {
+                    let _ = writeln!(self.out, "// {} more", more);
+                    continue;
+                }
+            };
+            let _ = write!(self.out, "{}: ", field.name);
+            match &field.value {
+                PointerDebugValue::Value(value) => self.out.push_str(value),
+                PointerDebugValue::Pointer { address, target } => {
+                    self.target(*address, target, depth + 1)
+                }
+                PointerDebugValue::Nested(node) => self.node(node, depth + 1),
+            }
+            self.out.push_str(",\n");
+        }
+        self.indent(depth);
+        self.out.push(')');
+    }
+
+    /// Write the target of a pointer to `address` held at `depth`
+    fn target(&mut self, address: u64, target: &PointerTarget, depth: usize) {
+        match target {
+            PointerTarget::Node(node) => self.node(node, depth),
+            PointerTarget::AlreadyVisited => self.visited(address, None),
+            PointerTarget::Text(text) => self.out.push_str(&quoted_text(text, self.config)),
+            PointerTarget::Bytes(bytes) => {
+                let _ = write!(self.out, "b\"{}\"", bytes.escape_ascii());
+            }
+            PointerTarget::Array { len, items } => {
+                let unread = len.saturating_sub(items.len());
+                let items: Vec<_> = items
+                    .iter()
+                    .map(|item| (item_address(item, address), item))
+                    .collect();
+                self.items(items, unread, false, depth);
+            }
+            PointerTarget::Chain { items, more } => {
+                let items: Vec<_> = chain_addresses(address, items)
+                    .into_iter()
+                    .zip(items)
+                    .collect();
+                self.items(items, 0, *more, depth);
+            }
+            PointerTarget::List { items, more } => {
+                let items = items
+                    .iter()
+                    .map(|(address, item)| (*address, item))
+                    .collect();
+                self.items(items, 0, *more, depth);
+            }
+            PointerTarget::Null => self.out.push_str("None"),
+            PointerTarget::MaxDepth => self.status("MaxDepth", address),
+            PointerTarget::ChainLink => self.status("Next", address),
+            PointerTarget::Invalid => self.status("Invalid", address),
+            PointerTarget::Unmapped => self.status("Unmapped", address),
+            PointerTarget::BudgetExhausted => self.status("BudgetExhausted", address),
+            PointerTarget::Cancelled => self.status("Cancelled", address),
+            PointerTarget::NotFollowed => self.status("NotFollowed", address),
+            PointerTarget::ReadError(_) if self.config.error_policy == ErrorPolicy::Placeholder => {
+                self.out.push_str("Unreadable")
+            }
+            PointerTarget::ReadError(e) => {
+                let _ = write!(self.out, "Error({:?})", e.to_string());
+            }
+        }
+    }
+
+    /// Write the elements of an array, chain or list, `unread` is the number of elements
+    /// after them that were not read and `continues` is set if the number is not known
+    fn items(
+        &mut self,
+        items: Vec<(u64, &PointerTarget)>,
+        unread: usize,
+        continues: bool,
+        depth: usize,
+    ) {
+        self.out.push_str("[\n");
+        let element = element_type(&items);
+        for shown in shown_elements(&items, unread, self.config) {
+            match shown {
+                Shown::Item(_, (address, item)) => {
+                    if is_hidden(item, self.config) {
+                        continue;
+                    }
+                    self.indent(depth + 1);
+                    if **item == PointerTarget::AlreadyVisited {
+                        self.visited(*address, element);
+                    } else {
+                        self.target(*address, item, depth + 1);
+                    }
+                    self.out.push_str(",\n");
+                }
+                Shown::Gap(more) => {
+                    self.indent(depth + 1);
+                    let _ = writeln!(self.out, "// {} more", more);
+                }
+            }
+        }
+        if continues {
+            self.indent(depth + 1);
+            self.out.push_str("// continues\n");
+        }
+        self.indent(depth);
+        self.out.push(']');
+    }
+
+    /// Write a pointer to the already visited `address`, `type_name` is the type of its
+    /// target if known
+    fn visited(&mut self, address: u64, type_name: Option<&str>) {
+        match self.labels.referred(address, type_name) {
+            Some((label, _)) => {
+                let _ = write!(self.out, "-> #{}", label);
+            }
+            // The root was not read from memory or the type of the target is not known,
+            // there is no node to match
+            None => {
+                let _ = write!(self.out, "-> {:#x}", address);
+            }
+        }
+    }
+
+    fn status(&mut self, status: &str, address: u64) {
+        let _ = write!(self.out, "{}({:#x})", status, address);
+    }
+
+    fn indent(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.out.push_str(&self.config.indent);
+        }
+    }
+}
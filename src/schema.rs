@@ -0,0 +1,106 @@
+//! Descriptions of derived types that don't read any memory.
+
+use crate::prelude::*;
+
+/// The fields of a derived type, returned by
+/// [`pointer_debug_schema`](crate::DerefDebugPrint::pointer_debug_schema).
+///
+/// The schema is built from the declaration alone, so tools can check a layout, export it
+/// to ReClass or build a UI for it without a target to read from.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{DerefDebugPrint, PointerDerefDebugPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     #[pointer_debug(rename = "armour")]
+///     armor: u32,
+///     team: Pointer64<Team>,
+///     #[pointer_debug(deref_as = "Team")]
+///     last_team: u64,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Team {
+///     id: u64,
+/// }
+///
+/// let schema = Player::pointer_debug_schema();
+/// assert_eq!(schema.name, "Player");
+/// assert_eq!(schema.size, 0x18);
+///
+/// let team = schema.field("team").unwrap();
+/// assert_eq!(team.ty, "Pointer64<Team>");
+/// assert_eq!((team.offset, team.size), (Some(0x8), 8));
+/// assert!(team.pointer);
+/// assert_eq!(team.target.as_deref(), Some("Team"));
+///
+/// assert_eq!(schema.field("armour").unwrap().offset, Some(0x4));
+/// assert_eq!(schema.field("last_team").unwrap().target.as_deref(), Some("Team"));
+/// assert_eq!(schema.pointers().count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeSchema {
+    /// Name of the type
+    pub name: String,
+    /// Size of the type in bytes
+    pub size: usize,
+    /// Every declared field in declaration order, including skipped and padding fields,
+    /// the fields of all variants of enums one after another
+    pub fields: Vec<FieldSchema>,
+}
+
+/// A field of a [`TypeSchema`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldSchema {
+    /// Name the field is printed with
+    pub name: String,
+    /// Variant of an enum the field belongs to, `None` for structures and unions
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub variant: Option<String>,
+    /// Type of the field as declared, e.g. `Pointer64<Team>`
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: String,
+    /// Offset of the field from the start of the type in bytes, `None` for fields of enums
+    pub offset: Option<usize>,
+    /// Size of the field in bytes
+    pub size: usize,
+    /// Whether the field is followed as a pointer, which includes `deref_as` fields
+    pub pointer: bool,
+    /// Type pointed to, as declared, `None` for fields that aren't pointers
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub target: Option<String>,
+}
+
+impl TypeSchema {
+    /// Create a schema without fields
+    pub fn new(name: impl Into<String>, size: usize) -> Self {
+        Self {
+            name: name.into(),
+            size,
+            fields: Vec::new(),
+        }
+    }
+
+    /// The field printed as `name`, the first one if several variants of an enum have it
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// The fields that are followed as pointers
+    pub fn pointers(&self) -> impl Iterator<Item = &FieldSchema> {
+        self.fields.iter().filter(|field| field.pointer)
+    }
+}
@@ -0,0 +1,136 @@
+//! Several dumps sharing what they read and printed.
+
+use memflow::mem::MemoryView;
+
+use crate::node::PrintedNodes;
+use crate::prelude::*;
+use crate::retry::RetryView;
+use crate::stats::Stopwatch;
+use crate::{
+    CachedView, DerefDebugPrint, PointerDebugNode, PointerDebugReport, PointerPrintConfig,
+    TraversalState, TraversalStats, VisitedSet, finish_output,
+};
+
+/// Dumps of several related roots that share one traversal.
+///
+/// The session keeps the targets read, the labels handed out and the pages read by every
+/// dump. A target printed by an earlier dump is not read or printed again, the pointer refers
+/// back to it as `next → see Node #3 (0x1000)` if it was labeled or as
+/// `next → see Node above (0x1000)` otherwise, and labels are numbered across the dumps. The
+/// RON and compact output don't refer back to earlier dumps.
+///
+/// Reads go through a [`CachedView`] that is kept for the whole session, use
+/// [`refresh`](Self::refresh) to pick up changes of the target memory and
+/// [`reset`](Self::reset) to start over.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDebugSession, PointerDerefDebugPrint, PointerPrintConfig};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     _pad: u32,
+///     team: Pointer64<Team>,
+/// }
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Team {
+///     id: u64,
+/// }
+///
+/// fn example(mem: &mut impl MemoryView, players: &[Pointer64<Player>]) {
+///     let mut session = PointerDebugSession::new(mem, &PointerPrintConfig::new());
+///     for player in players {
+///         // The team shared by the players is printed by the first dump only
+///         println!("{}", session.render(player));
+///     }
+/// }
+/// ```
+pub struct PointerDebugSession<'a, M> {
+    mem: CachedView<'a, M>,
+    config: PointerPrintConfig,
+    /// Targets read by every dump so far
+    visited: VisitedSet,
+    /// Nodes printed by every dump so far
+    printed: PrintedNodes,
+}
+
+impl<'a, M: MemoryView> PointerDebugSession<'a, M> {
+    /// Start a session reading from `mem` with the options of `config`
+    pub fn new(mem: &'a mut M, config: &PointerPrintConfig) -> Self {
+        Self {
+            mem: CachedView::new(mem),
+            config: config.clone(),
+            visited: VisitedSet::new(),
+            printed: PrintedNodes::default(),
+        }
+    }
+
+    /// The cached memory of the session, e.g. to read the next root
+    pub fn memory(&mut self) -> &mut CachedView<'a, M> {
+        &mut self.mem
+    }
+
+    /// The targets read by the dumps so far
+    pub fn visited(&self) -> &VisitedSet {
+        &self.visited
+    }
+
+    /// Render `value` like [`pointer_print_with_config`](crate::PointerPrint::pointer_print_with_config)
+    /// prints it, leaving out what the earlier dumps printed
+    pub fn render<T: DerefDebugPrint + ?Sized>(&mut self, value: &T) -> String {
+        let (node, stats) = self.collect(value);
+        let report = PointerDebugReport::from_node(&node);
+        let output = self.printed.display(&node, &self.config);
+        finish_output(output, &node, &stats, &report, &self.config)
+    }
+
+    /// Print `value` like [`pointer_print_with_config`](crate::PointerPrint::pointer_print_with_config),
+    /// leaving out what the earlier dumps printed
+    #[cfg(feature = "std")]
+    pub fn print<T: DerefDebugPrint + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), PointerDebugReport> {
+        let (node, stats) = self.collect(value);
+        let printed = &mut self.printed;
+        crate::print_rendered(&node, &stats, &self.config, |node| {
+            printed.display(node, &self.config)
+        })
+    }
+
+    /// Forget the cached pages so the next dump reads the target memory again, what was
+    /// printed is still not printed again
+    pub fn refresh(&mut self) {
+        self.mem.clear();
+    }
+
+    /// Forget everything read and printed, the next dump starts like a new session
+    pub fn reset(&mut self) {
+        self.mem.clear();
+        self.visited = VisitedSet::new();
+        self.printed.clear();
+    }
+
+    fn collect<T: DerefDebugPrint + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> (PointerDebugNode, TraversalStats) {
+        let stopwatch = Stopwatch::start();
+        let mut state = TraversalState::with_config(&self.config);
+        state.visited_addresses = self.visited.clone();
+
+        let mut mem = RetryView::new(&mut self.mem, &self.config);
+        let node = value.pointer_collect_internal(&mut mem, 0, &mut state);
+        state.finish_root(&node);
+
+        let mut stats = TraversalStats::from_node(&node);
+        stats.bytes = state.bytes_read;
+        stats.retries = mem.retries;
+        stats.elapsed = stopwatch.elapsed();
+        (node, stats)
+    }
+}
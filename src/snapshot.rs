@@ -0,0 +1,336 @@
+//! Collected trees saved along with the memory they were read from.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use memflow::error::{Error, ErrorKind, ErrorOrigin, Result};
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+use memflow::types::{Address, umem};
+use serde::{Deserialize, Serialize};
+
+use crate::read::read_each;
+use crate::retry::RetryView;
+use crate::{
+    DerefDebugPrint, PointerDebugDisplay, PointerDebugNode, PointerPrintConfig, TraversalState,
+};
+
+/// A collected tree together with every byte read to collect it.
+///
+/// A snapshot can be written to disk with [`save`](Snapshot::save) and loaded again with
+/// [`load`](Snapshot::load) for offline analysis, or to [`diff`](PointerDebugNode::diff)
+/// against a later run. It renders exactly like the live dump it was taken from, and with
+/// [`memory`](Snapshot::memory) the recorded bytes can be read again, e.g. to print the
+/// structure with other options. Files are JSON, the type implements `Serialize` and
+/// `Deserialize` for any other format.
+///
+/// ```rust,no_run
+/// use memflow::prelude::v1::{MemoryView, Pod, Pointer64};
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrintConfig, Snapshot};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// fn save(mem: &mut impl MemoryView, node: &Node) -> std::io::Result<()> {
+///     Snapshot::capture(node, mem, &PointerPrintConfig::new()).save("node.json")
+/// }
+///
+/// fn compare(mem: &mut impl MemoryView, node: &Node) -> std::io::Result<()> {
+///     let old = Snapshot::load("node.json")?;
+///     let new = Snapshot::capture(node, mem, &PointerPrintConfig::new());
+///     print!("{}", old.tree().diff(new.tree()));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    tree: PointerDebugNode,
+    /// Sorted, non-overlapping regions that were read successfully
+    regions: Vec<SnapshotRegion>,
+    arch_bits: u8,
+    little_endian: bool,
+}
+
+/// A run of bytes recorded by a [`Snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotRegion {
+    /// Address of the first byte
+    pub address: u64,
+    /// The bytes that were read, stored as a hex string
+    #[serde(with = "hex")]
+    pub bytes: Vec<u8>,
+}
+
+impl Snapshot {
+    /// Collect `value` with the given options and record every read made along the way
+    pub fn capture<T, M>(value: &T, mem: &mut M, config: &PointerPrintConfig) -> Self
+    where
+        T: DerefDebugPrint + ?Sized,
+        M: MemoryView,
+    {
+        let metadata = mem.metadata();
+        // The recording holds what the retries eventually read
+        let mut mem = RetryView::new(mem, config);
+        let mut recorder = Recorder {
+            mem: &mut mem,
+            reads: Vec::new(),
+        };
+        let mut state = TraversalState::with_config(config);
+        let tree = value.pointer_collect_internal(&mut recorder, 0, &mut state);
+        Self {
+            tree,
+            regions: merge(recorder.reads),
+            arch_bits: metadata.arch_bits,
+            little_endian: metadata.little_endian,
+        }
+    }
+
+    /// Wrap a tree collected earlier, the snapshot holds no memory.
+    ///
+    /// ```rust
+    /// use memflow::error::{Error, ErrorKind, ErrorOrigin};
+    /// use memflow_pointer_debug::{
+    ///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget, Snapshot,
+    /// };
+    ///
+    /// let error = Error(ErrorOrigin::Memory, ErrorKind::OutOfBounds);
+    /// let mut node = PointerDebugNode::new("Node");
+    /// node.fields.push(PointerDebugField {
+    ///     name: "next".to_string(),
+    ///     type_name: "Pointer64".to_string(),
+    ///     value: PointerDebugValue::Pointer {
+    ///         address: 0x1000,
+    ///         target: PointerTarget::ReadError(error),
+    ///     },
+    /// });
+    ///
+    /// let snapshot = Snapshot::from_tree(node);
+    /// let json = serde_json::to_string(&snapshot).unwrap();
+    /// assert_eq!(serde_json::from_str::<Snapshot>(&json).unwrap(), snapshot);
+    /// ```
+    pub fn from_tree(tree: PointerDebugNode) -> Self {
+        Self {
+            tree,
+            regions: Vec::new(),
+            arch_bits: 64,
+            little_endian: true,
+        }
+    }
+
+    /// The collected tree
+    pub fn tree(&self) -> &PointerDebugNode {
+        &self.tree
+    }
+
+    /// Take the collected tree
+    pub fn into_tree(self) -> PointerDebugNode {
+        self.tree
+    }
+
+    /// The recorded memory in ascending order
+    pub fn regions(&self) -> &[SnapshotRegion] {
+        &self.regions
+    }
+
+    /// A read-only view of the recorded memory, reads of bytes that weren't recorded fail
+    pub fn memory(&self) -> SnapshotMemory<'_> {
+        SnapshotMemory { snapshot: self }
+    }
+
+    /// Render the tree with the given options instead of the defaults used by `Display`
+    pub fn display<'a>(&'a self, config: &'a PointerPrintConfig) -> PointerDebugDisplay<'a> {
+        self.tree.display(config)
+    }
+
+    /// Write the snapshot to `path` as JSON, an existing file is replaced
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+
+    /// Read a snapshot written by [`save`](Snapshot::save)
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.tree, f)
+    }
+}
+
+/// The memory recorded by a [`Snapshot`], see [`Snapshot::memory`]
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotMemory<'a> {
+    snapshot: &'a Snapshot,
+}
+
+impl MemoryView for SnapshotMemory<'_> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        read_regions(&self.snapshot.regions, data)
+    }
+
+    fn write_raw_iter(&mut self, _: WriteRawMemOps) -> Result<()> {
+        Err(Error(ErrorOrigin::Memory, ErrorKind::ReadOnly))
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        regions_metadata(
+            &self.snapshot.regions,
+            self.snapshot.arch_bits,
+            self.snapshot.little_endian,
+        )
+    }
+}
+
+/// Serve `data` from the sorted `regions`, reads of bytes that weren't recorded fail
+pub(crate) fn read_regions(regions: &[SnapshotRegion], data: ReadRawMemOps) -> Result<()> {
+    read_each(data, |address, buf| {
+        match region_bytes(regions, address.to_umem(), buf.len()) {
+            Some(bytes) => {
+                buf.copy_from_slice(bytes);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// The recorded bytes at `address`, `None` unless all `len` of them were recorded
+fn region_bytes(regions: &[SnapshotRegion], address: u64, len: usize) -> Option<&[u8]> {
+    let index =
+        regions.partition_point(|region| region.address + region.bytes.len() as u64 <= address);
+    let region = regions.get(index)?;
+    let offset = address.checked_sub(region.address)? as usize;
+    region.bytes.get(offset..offset.checked_add(len)?)
+}
+
+/// Metadata of a read-only view of the sorted `regions`
+pub(crate) fn regions_metadata(
+    regions: &[SnapshotRegion],
+    arch_bits: u8,
+    little_endian: bool,
+) -> MemoryViewMetadata {
+    let end = regions
+        .last()
+        .map_or(0, |region| region.address + region.bytes.len() as u64);
+    MemoryViewMetadata {
+        max_address: Address::from(end.saturating_sub(1)),
+        real_size: end as umem,
+        readonly: true,
+        little_endian,
+        arch_bits,
+    }
+}
+
+/// Passes reads through to the wrapped memory and keeps a copy of everything read
+struct Recorder<'a, M> {
+    mem: &'a mut M,
+    reads: Vec<(u64, Vec<u8>)>,
+}
+
+impl<M: MemoryView> MemoryView for Recorder<'_, M> {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        record_reads(self.mem, &mut self.reads, data)
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.mem.write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.mem.metadata()
+    }
+}
+
+/// Read `data` from `mem` and keep a copy of everything that was read in `reads`
+pub(crate) fn record_reads<M: MemoryView>(
+    mem: &mut M,
+    reads: &mut Vec<(u64, Vec<u8>)>,
+    data: ReadRawMemOps,
+) -> Result<()> {
+    read_each(data, |address, buf| {
+        let read = mem.read_raw_into(address, buf).is_ok();
+        if read {
+            reads.push((address.to_umem(), buf.to_vec()));
+        }
+        read
+    })
+}
+
+/// Sort the recorded reads and join the ones that overlap or touch, earlier reads win
+pub(crate) fn merge(mut reads: Vec<(u64, Vec<u8>)>) -> Vec<SnapshotRegion> {
+    reads.sort_by_key(|(address, _)| *address);
+
+    let mut regions: Vec<SnapshotRegion> = Vec::with_capacity(reads.len());
+    for (address, bytes) in reads {
+        if let Some(last) = regions.last_mut() {
+            let end = last.address + last.bytes.len() as u64;
+            if address <= end {
+                let skip = (end - address) as usize;
+                if let Some(rest) = bytes.get(skip..) {
+                    last.bytes.extend_from_slice(rest);
+                }
+                continue;
+            }
+        }
+        regions.push(SnapshotRegion { address, bytes });
+    }
+    regions
+}
+
+/// Stores memflow errors by their integer code, they don't implement serde themselves
+pub(crate) mod error_code {
+    use std::num::NonZeroI32;
+
+    use memflow::cglue::IntError;
+    use memflow::error::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+    pub fn serialize<S: Serializer>(error: &Error, serializer: S) -> Result<S::Ok, S::Error> {
+        error.into_int_err().get().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Error, D::Error> {
+        let code = i32::deserialize(deserializer)?;
+        NonZeroI32::new(code)
+            .map(Error::from_int_err)
+            .ok_or_else(|| de::Error::custom("error code 0 is not an error"))
+    }
+}
+
+/// Stores bytes as a hex string instead of a list of numbers
+mod hex {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut hex = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            hex.push_str(&format!("{:02x}", byte));
+        }
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if hex.len() % 2 != 0 {
+            return Err(de::Error::custom("odd number of hex digits"));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| de::Error::custom("invalid hex digit"))
+            })
+            .collect()
+    }
+}
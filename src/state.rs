@@ -0,0 +1,718 @@
+//! State threaded through a traversal by the generated code.
+
+use alloc::sync::Arc;
+
+use memflow::dataview::{Pod, PodMethods};
+use memflow::error::Error;
+use memflow::mem::MemoryView;
+use memflow::types::Address;
+
+use crate::impls;
+use crate::prelude::*;
+use crate::sync::{Mutex, MutexGuard};
+use crate::trace::{self, DerefSpan};
+use crate::{
+    ByteOrder, DerefDebugPrint, ErrorPolicy, HashMap, HashSet, PointerDebugField, PointerDebugNode,
+    PointerDebugValue, PointerLike, PointerPrintConfig, PointerTarget, ProgressEvent,
+    read_cstr_bytes, read_utf16_units,
+};
+
+/// Pointer targets read by a traversal, by address and the type they were read as.
+///
+/// A target read as one type is still read when another pointer interprets the same
+/// address as a different type. Every visit is counted, see
+/// [`PointerPrintConfig::max_revisits`].
+///
+/// Clones share the same set.
+///
+/// ```rust
+/// use memflow_pointer_debug::VisitedSet;
+///
+/// let visited = VisitedSet::new();
+/// assert!(visited.insert(0x1000, "Player"));
+/// assert!(!visited.insert(0x1000, "Player"));
+/// assert!(visited.insert(0x1000, "Entity"));
+/// assert_eq!(visited.visits(0x1000, "Player"), 2);
+/// assert!(visited.contains_address(0x1000));
+/// assert_eq!(visited.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VisitedSet {
+    visits: Arc<Mutex<Visits>>,
+    /// Addresses and types looked up, only recorded by the sets of parallel branches
+    lookups: Option<Arc<Mutex<Lookups>>>,
+}
+
+/// Number of visits by address and type name
+type Visits = HashMap<u64, Vec<(String, usize)>>;
+
+/// Addresses and type names looked up
+type Lookups = HashSet<(u64, String)>;
+
+impl VisitedSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if `address` was visited as `type_name`
+    pub fn contains(&self, address: u64, type_name: &str) -> bool {
+        self.visits(address, type_name) > 0
+    }
+
+    /// Returns true if `address` was visited as any type
+    pub fn contains_address(&self, address: u64) -> bool {
+        self.lock().contains_key(&address)
+    }
+
+    /// Number of times `address` was visited as `type_name`
+    pub fn visits(&self, address: u64, type_name: &str) -> usize {
+        if let Some(lookups) = &self.lookups {
+            lookups.lock().insert((address, type_name.to_string()));
+        }
+        self.lock().get(&address).map_or(0, |types| {
+            types
+                .iter()
+                .find(|(name, _)| name == type_name)
+                .map_or(0, |(_, visits)| *visits)
+        })
+    }
+
+    /// Count a visit of `address` as `type_name`, returns false if it was visited as that
+    /// type already
+    pub fn insert(&self, address: u64, type_name: &str) -> bool {
+        let mut set = self.lock();
+        let types = set.entry(address).or_default();
+        match types.iter_mut().find(|(name, _)| name == type_name) {
+            Some((_, visits)) => {
+                *visits += 1;
+                false
+            }
+            None => {
+                types.push((type_name.to_string(), 1));
+                true
+            }
+        }
+    }
+
+    /// Number of visited addresses and type pairs
+    pub fn len(&self) -> usize {
+        self.lock().values().map(Vec::len).sum()
+    }
+
+    /// Returns true if nothing was visited yet
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Independent copy of this set
+    #[cfg(feature = "parallel")]
+    pub(crate) fn snapshot(&self) -> Self {
+        Self {
+            visits: Arc::new(Mutex::new(self.lock().clone())),
+            lookups: None,
+        }
+    }
+
+    /// Independent copy of this set for a branch of a parallel traversal, it records every
+    /// address and type looked up
+    #[cfg(feature = "parallel")]
+    pub(crate) fn branch(&self) -> Self {
+        Self {
+            lookups: Some(Arc::default()),
+            ..self.snapshot()
+        }
+    }
+
+    /// Returns true if any address and type looked up in `branch` was visited more often in
+    /// this set than in `base`, the set the branch was copied from
+    #[cfg(feature = "parallel")]
+    pub(crate) fn conflicts(&self, branch: &VisitedSet, base: &VisitedSet) -> bool {
+        let Some(lookups) = &branch.lookups else {
+            return false;
+        };
+        let lookups = lookups.lock().clone();
+        lookups
+            .iter()
+            .any(|(address, name)| self.visits(*address, name) != base.visits(*address, name))
+    }
+
+    /// Add the visits `branch` made after it was copied from `base` to this set
+    #[cfg(feature = "parallel")]
+    pub(crate) fn merge(&self, branch: &VisitedSet, base: &VisitedSet) {
+        let visits = branch.lock().clone();
+        let mut set = self.lock();
+        for (address, types) in visits {
+            for (name, count) in types {
+                let added = count - base.visits(address, &name);
+                if added == 0 {
+                    continue;
+                }
+                let types = set.entry(address).or_default();
+                match types.iter_mut().find(|(visited, _)| *visited == name) {
+                    Some((_, visits)) => *visits += added,
+                    None => types.push((name, added)),
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Visits> {
+        // The set stays consistent even if a branch panicked while holding the lock
+        self.visits.lock()
+    }
+}
+
+/// Mutable state shared by every node of one traversal.
+///
+/// This is created by the [`PointerPrint`](crate::PointerPrint) methods and passed down to
+/// [`DerefDebugPrint::pointer_collect_internal`](crate::DerefDebugPrint::pointer_collect_internal),
+/// it is not intended to be used directly.
+#[derive(Debug, Clone, Default)]
+pub struct TraversalState {
+    /// Maximum recursion depth
+    pub max_depth: usize,
+    /// Pointer targets that were already read, use [`is_visited`](Self::is_visited) and
+    /// [`visit`](Self::visit) to respect [`PointerPrintConfig::max_revisits`]
+    pub visited_addresses: VisitedSet,
+    /// Set right before collecting an element of a `follow_chain` field so the element
+    /// does not start following the chain again. Taken by the element on entry.
+    pub chain_element: bool,
+    /// Options of this traversal
+    pub config: PointerPrintConfig,
+    /// Number of nodes read so far
+    pub nodes_read: usize,
+    /// Number of bytes read so far
+    pub bytes_read: usize,
+    /// Number of lines the fields and nodes collected so far about take up in the output,
+    /// checked against [`PointerPrintConfig::max_output_lines`]
+    pub output_lines: usize,
+    /// Set once the read budget of the configuration was exceeded, nothing is read after
+    pub budget_exhausted: bool,
+    /// Names of the fields leading to the current one, only tracked when a progress
+    /// callback or a query is set
+    pub path: Vec<String>,
+    /// Path of the field a query looks for, split like [`path`](Self::path). Pointers that
+    /// don't lead to it are not followed, empty for a full traversal
+    pub query: Vec<String>,
+    /// Pointer targets fetched by [`prefetch_targets`](Self::prefetch_targets) that were
+    /// not collected yet, by address
+    pub prefetched: HashMap<u64, Vec<u8>>,
+    /// Set once a read failed with [`ErrorPolicy::Abort`], nothing is read after
+    pub aborted: bool,
+}
+
+impl TraversalState {
+    /// Create the state for a new traversal
+    pub fn new(max_depth: usize) -> Self {
+        Self::with_config(&PointerPrintConfig::new().max_depth(max_depth))
+    }
+
+    /// Create the state for a new traversal with the given options
+    pub fn with_config(config: &PointerPrintConfig) -> Self {
+        Self {
+            max_depth: config.max_depth,
+            config: config.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Target of a pointer to `address` that is not read at all.
+    ///
+    /// Returns [`PointerTarget::Null`] for null pointers, [`PointerTarget::Cancelled`] once
+    /// the traversal was cancelled or [`aborted`](Self::aborted), [`PointerTarget::Invalid`] for addresses rejected by
+    /// [`PointerPrintConfig::is_valid_address`] and [`PointerTarget::Unmapped`] for
+    /// addresses outside of the configured memory map and [`PointerTarget::MaxDepth`] for
+    /// pointers that don't lead to the field of a [`query`](Self::query), `None` if the
+    /// address can be read.
+    pub fn skip_target(&self, address: u64) -> Option<PointerTarget> {
+        if address == 0 {
+            Some(PointerTarget::Null)
+        } else if self.aborted || self.config.is_cancelled() {
+            Some(PointerTarget::Cancelled)
+        } else if !self.config.is_valid_address(address) {
+            Some(PointerTarget::Invalid)
+        } else if !self.config.is_mapped_address(address) {
+            Some(PointerTarget::Unmapped)
+        } else if !self.on_query_path() {
+            // Left out of a query like a target past the maximum depth
+            Some(PointerTarget::MaxDepth)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the target at `address` was read as `type_name` as often as
+    /// [`PointerPrintConfig::max_revisits`] allows, it is then printed as
+    /// [`PointerTarget::AlreadyVisited`]
+    pub fn is_visited(&self, address: u64, type_name: &str) -> bool {
+        self.visited_addresses.visits(address, type_name) > self.config.max_revisits
+    }
+
+    /// Count a read of the target at `address` as `type_name`
+    pub fn visit(&mut self, address: u64, type_name: &str) {
+        self.visited_addresses.insert(address, type_name);
+    }
+
+    /// Account for a read at `address` of `nodes` nodes taking `bytes` bytes, issued by a
+    /// structure at `depth`.
+    ///
+    /// Returns false without accounting anything if the read would exceed
+    /// [`PointerPrintConfig::max_nodes`] or [`PointerPrintConfig::max_bytes`], the budget
+    /// then stays exhausted for the rest of the traversal and the target is
+    /// [`PointerTarget::BudgetExhausted`]. Otherwise the read is announced to the progress
+    /// callback.
+    pub fn take_budget(&mut self, address: u64, depth: usize, nodes: usize, bytes: usize) -> bool {
+        let nodes_read = self.nodes_read.saturating_add(nodes);
+        let bytes_read = self.bytes_read.saturating_add(bytes);
+        if self.budget_exhausted
+            || self.config.max_nodes.is_some_and(|max| nodes_read > max)
+            || self.config.max_bytes.is_some_and(|max| bytes_read > max)
+            || self
+                .config
+                .max_output_lines
+                .is_some_and(|max| self.output_lines >= max)
+        {
+            self.budget_exhausted = true;
+            return false;
+        }
+
+        self.nodes_read = nodes_read;
+        self.bytes_read = bytes_read;
+        // The closing line of every node
+        self.output_lines = self.output_lines.saturating_add(nodes);
+
+        if let Some(progress) = &self.config.progress {
+            progress.call(ProgressEvent {
+                depth,
+                path: self.path_string(),
+                address,
+                nodes,
+                bytes,
+                nodes_read,
+                bytes_read,
+            });
+        }
+        true
+    }
+
+    /// Read a NUL-terminated UTF-8 string of up to `max_len` bytes at `address`.
+    ///
+    /// The limit is clamped to [`PointerPrintConfig::max_string_bytes`], the bytes are
+    /// decoded with [`PointerPrintConfig::string_decoding`] and the
+    /// [`PointerPrintConfig::string_truncation_marker`] is appended if no NUL was found.
+    pub fn read_cstr<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        address: Address,
+        max_len: usize,
+    ) -> Result<String, Error> {
+        let (bytes, truncated) = read_cstr_bytes(mem, address, self.config.string_limit(max_len))?;
+        Ok(self.finish_text(self.config.string_decoding.decode_utf8(&bytes), truncated))
+    }
+
+    /// Read a UTF-16 string of up to `max_chars` code units at `address`, like
+    /// [`read_cstr`](Self::read_cstr).
+    ///
+    /// `len_bytes` is the length of the string if it is known, see
+    /// [`read_utf16_units`](crate::read_utf16_units).
+    pub fn read_wstr<M: MemoryView>(
+        &self,
+        mem: &mut M,
+        address: Address,
+        len_bytes: Option<usize>,
+        max_chars: usize,
+    ) -> Result<String, Error> {
+        let max_chars = self.config.string_limit(max_chars * 2) / 2;
+        let (mut units, truncated) = read_utf16_units(mem, address, len_bytes, max_chars)?;
+        // The units are read as little-endian
+        if self.config.byte_order == ByteOrder::Big {
+            units.iter_mut().for_each(|unit| *unit = unit.swap_bytes());
+        }
+        Ok(self.finish_text(self.config.string_decoding.decode_utf16(&units), truncated))
+    }
+
+    fn finish_text(&self, mut text: String, truncated: bool) -> String {
+        if truncated {
+            text.push_str(&self.config.string_truncation_marker);
+        }
+        text
+    }
+
+    /// Account for reading `count` elements at `pointer`, see [`take_budget`](Self::take_budget)
+    pub fn take_pointer_budget<P: PointerLike>(
+        &mut self,
+        pointer: &P,
+        depth: usize,
+        count: usize,
+    ) -> bool {
+        let bytes = count.saturating_mul(core::mem::size_of::<P::Target>());
+        self.take_budget(pointer.pointer_address(), depth, count, bytes)
+    }
+
+    /// Span of the dereference of a pointer to `address` in a structure at `depth`, to be
+    /// kept from right before the read until the target was collected
+    pub fn deref_span(&self, address: u64, depth: usize) -> DerefSpan {
+        DerefSpan::new(self.path_string(), address, depth)
+    }
+
+    /// End the dereference of `span` that resulted in `target`.
+    ///
+    /// With [`ErrorPolicy::Abort`] a failed read aborts the traversal.
+    pub fn finish_deref(&mut self, span: DerefSpan, target: &PointerTarget) {
+        if matches!(target, PointerTarget::ReadError(_))
+            && self.config.error_policy == ErrorPolicy::Abort
+        {
+            self.aborted = true;
+        }
+        if let Some(stream) = &self.config.stream
+            && !stream.send_target(span.path(), span.address(), target)
+        {
+            // Nobody is listening anymore
+            self.aborted = true;
+        }
+        span.finish(target);
+    }
+
+    /// End the traversal that resulted in `root`, the values of the root structure are
+    /// emitted like the targets of [`finish_deref`](Self::finish_deref)
+    pub(crate) fn finish_root(&mut self, root: &PointerDebugNode) {
+        if let Some(stream) = &self.config.stream {
+            stream.send_node("", root);
+        }
+        trace::root_events(root);
+    }
+
+    /// Enter the field `name` of the current structure, must be paired with
+    /// [`leave_field`](Self::leave_field)
+    pub fn enter_field(&mut self, name: &str) {
+        self.output_lines += 1;
+        if self.tracks_path() {
+            self.path.push(name.to_string());
+        }
+    }
+
+    /// Enter the element `index` of the current array, chain or list
+    pub fn enter_index(&mut self, index: usize) {
+        self.output_lines += 1;
+        if self.tracks_path() {
+            self.path.push(format!("[{}]", index));
+        }
+    }
+
+    /// Leave the field or element entered last
+    pub fn leave_field(&mut self) {
+        self.path.pop();
+    }
+
+    fn tracks_path(&self) -> bool {
+        self.config.progress.is_some()
+            || self.config.stream.is_some()
+            || !self.query.is_empty()
+            || self.config.filters_paths()
+            || trace::enabled()
+    }
+
+    /// Returns true if the field `name` of the current structure passes the path filters of
+    /// [`PointerPrintConfig::include_path`] and [`PointerPrintConfig::exclude_path`]
+    pub fn is_selected(&self, name: &str) -> bool {
+        if !self.config.filters_paths() {
+            return true;
+        }
+        let mut path = self.path_string();
+        if !path.is_empty() && !name.starts_with('[') {
+            path.push('.');
+        }
+        path.push_str(name);
+        self.config.is_path_selected(&path)
+    }
+
+    /// Returns true if the current field is the one queried or leads to it
+    fn on_query_path(&self) -> bool {
+        self.query.is_empty() || self.query.starts_with(&self.path)
+    }
+
+    /// Dotted path of the current field, elements are appended as `[i]`
+    fn path_string(&self) -> String {
+        let mut path = String::new();
+        for name in &self.path {
+            if !path.is_empty() && !name.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(name);
+        }
+        path
+    }
+
+    /// Read the direct pointer targets of `value` with a single batched read.
+    ///
+    /// Called before the fields of a structure at `depth` are collected, the targets are
+    /// then taken from [`take_prefetched`](Self::take_prefetched) instead of being read one
+    /// at a time. Targets that won't be read are left out and the batch is kept within the
+    /// read budget. Targets that overlap or follow each other, like the elements of an
+    /// embedded array of pointers into one allocation, are fetched with a single read of the
+    /// range they cover. If the batch fails nothing is kept, the targets are read
+    /// individually to report the exact errors.
+    pub fn prefetch_targets<T, M>(&mut self, value: &T, mem: &mut M, depth: usize)
+    where
+        T: DerefDebugPrint + ?Sized,
+        M: MemoryView,
+    {
+        // The batch would read the targets of fields left out by the path filters too
+        if !self.config.batch_reads || self.config.filters_paths() || depth + 1 >= self.max_depth {
+            return;
+        }
+
+        let mut targets = Vec::new();
+        value.pointer_targets(&mut targets);
+
+        let mut nodes_read = self.nodes_read;
+        let mut bytes_read = self.bytes_read;
+        let mut seen = HashSet::new();
+        let mut ranges = Vec::new();
+        for (address, size) in targets {
+            if self.skip_target(address).is_some()
+                || self.visited_addresses.contains_address(address)
+                || self.prefetched.contains_key(&address)
+                || !seen.insert(address)
+            {
+                continue;
+            }
+
+            nodes_read += 1;
+            bytes_read += size;
+            if self.config.max_nodes.is_some_and(|max| nodes_read > max)
+                || self.config.max_bytes.is_some_and(|max| bytes_read > max)
+            {
+                break;
+            }
+
+            ranges.push((address, size));
+        }
+
+        // A single read doesn't benefit from batching
+        if ranges.len() < 2 {
+            return;
+        }
+
+        let mut buffers = coalesce_ranges(&ranges)
+            .into_iter()
+            .map(|(address, size)| (address, vec![0u8; size]))
+            .collect::<Vec<_>>();
+        let bytes = buffers.iter().map(|(_, buffer)| buffer.len()).sum();
+        let span = DerefSpan::batch(self.path_string(), buffers.len(), bytes);
+        let committed = {
+            let mut batcher = mem.batcher();
+            for (address, buffer) in buffers.iter_mut() {
+                batcher.read_raw_into((*address).into(), buffer);
+            }
+            batcher.commit_rw().is_ok()
+        };
+        span.read_done();
+        if !committed {
+            return;
+        }
+        // Every target is cut out of the range it was read with
+        for (address, size) in ranges {
+            let (start, buffer) = buffers
+                .iter()
+                .rev()
+                .find(|(start, _)| *start <= address)
+                .expect("every target is covered by a range");
+            let offset = (address - start) as usize;
+            self.prefetched
+                .insert(address, buffer[offset..offset + size].to_vec());
+        }
+    }
+
+    /// Take the target at `address` fetched by [`prefetch_targets`](Self::prefetch_targets)
+    pub fn take_prefetched<T: Pod>(&mut self, address: u64) -> Option<T> {
+        let bytes = self.prefetched.remove(&address)?;
+        // The target may have been fetched for a pointer of another type
+        if bytes.len() != core::mem::size_of::<T>() {
+            return None;
+        }
+        let mut value: T = memflow::dataview::zeroed();
+        value.as_bytes_mut().copy_from_slice(&bytes);
+        Some(value)
+    }
+
+    /// The bytes of `value` as it was read for [`PointerDebugNode::raw_bytes`], `None` unless
+    /// [`PointerPrintConfig::show_raw_bytes`] is set
+    pub fn raw_bytes<T: Pod>(&self, value: &T) -> Option<Vec<u8>> {
+        self.config
+            .show_raw_bytes
+            .then(|| value.as_bytes().to_vec())
+    }
+
+    /// Convert `value`, read from the target memory, to the byte order of the host, see
+    /// [`PointerPrintConfig::byte_order`]
+    pub fn to_host_order<T: DerefDebugPrint>(&self, value: &mut T) {
+        value.pointer_to_host_order(self.config.byte_order);
+    }
+
+    /// Read the fields of `value`, read at `address`, that have another offset in
+    /// [`PointerPrintConfig::offsets`] from there.
+    ///
+    /// Fields that can't be read at their new offset are zeroed.
+    pub fn apply_offsets<T, M>(&mut self, value: &mut T, address: u64, mem: &mut M)
+    where
+        T: DerefDebugPrint + Pod,
+        M: MemoryView,
+    {
+        let Some(offsets) = self.config.offsets.clone() else {
+            return;
+        };
+        for (field, offset) in offsets.fields(&value.pointer_type_name()) {
+            let Some((start, size)) = T::pointer_field_location(field) else {
+                continue;
+            };
+            let bytes = &mut value.as_bytes_mut()[start..start + size];
+            let field_address = address.wrapping_add(offset as u64);
+            if mem.read_raw_into(field_address.into(), bytes).is_err() {
+                bytes.fill(0);
+            }
+        }
+    }
+
+    /// Collect `value`, reached through a pointer or as an element, at `depth`.
+    ///
+    /// Values of a type registered in [`PointerPrintConfig::printers`] are printed by their
+    /// printer instead of being collected.
+    pub fn collect_node<T, M>(&mut self, value: &T, mem: &mut M, depth: usize) -> PointerDebugNode
+    where
+        T: DerefDebugPrint + 'static,
+        M: MemoryView,
+    {
+        match self.printed(value, mem) {
+            Some(text) => {
+                // The value doesn't take the flag of a chain element itself
+                self.chain_element = false;
+                PointerDebugNode::scalar(value.pointer_type_name(), text)
+            }
+            None => value.pointer_collect_internal(mem, depth, self),
+        }
+    }
+
+    /// The value of a field embedded in a derived structure printed by the printer of
+    /// [`PointerPrintConfig::printers`] registered for its type, `None` if there is none
+    pub fn printed_value<T: 'static, M: MemoryView>(
+        &self,
+        value: &T,
+        mem: &mut M,
+    ) -> Option<PointerDebugValue> {
+        self.printed(value, mem).map(PointerDebugValue::Value)
+    }
+
+    fn printed<T: 'static, M: MemoryView>(&self, value: &T, mem: &mut M) -> Option<String> {
+        self.config.printers.as_ref()?.print(value, mem)
+    }
+
+    /// Record the offset and size of the collected fields of `value` in `node` if
+    /// [`PointerPrintConfig::show_offsets`] or [`PointerPrintConfig::sort_by_offset`] is
+    /// set, the fields are sorted by them with the latter. Fields moved by
+    /// [`PointerPrintConfig::offsets`] are recorded at the offset they were read from
+    pub fn record_locations<T>(&self, value: &T, node: &mut PointerDebugNode)
+    where
+        T: DerefDebugPrint + ?Sized,
+    {
+        if !self.config.show_offsets && !self.config.sort_by_offset {
+            return;
+        }
+        for field in &node.fields {
+            let Some((offset, size)) = value.pointer_field_offset(&field.name) else {
+                continue;
+            };
+            let offset = self
+                .config
+                .offsets
+                .as_ref()
+                .and_then(|offsets| offsets.get(&node.type_name, &field.name))
+                .unwrap_or(offset);
+            node.field_locations
+                .insert(field.name.clone(), (offset, size));
+        }
+        if self.config.sort_by_offset {
+            node.sort_fields_by_offset();
+        }
+    }
+
+    /// Name of the type `pointer` points to, used as the type of its target in
+    /// [`is_visited`](Self::is_visited) and [`visit`](Self::visit)
+    pub fn target_type_name<P: PointerLike>(_pointer: &P) -> &'static str {
+        core::any::type_name::<P::Target>()
+    }
+
+    /// Address and size of the target of `pointer`, as added by
+    /// [`DerefDebugPrint::pointer_targets`]
+    pub fn pointer_target<P: PointerLike>(pointer: &P) -> (u64, usize) {
+        (pointer.pointer_address(), core::mem::size_of::<P::Target>())
+    }
+
+    /// Split the address of a tagged `pointer` into the pointer to the address with the bits
+    /// outside `mask` cleared and the cleared bits
+    pub fn mask_pointer<P: PointerLike>(pointer: &P, mask: u64) -> (P, u64) {
+        let address = pointer.pointer_address();
+        (pointer.with_address(address & mask), address & !mask)
+    }
+
+    /// Number of elements to read for a run capped at `max` by its attribute
+    pub fn element_limit(&self, max: usize) -> usize {
+        self.config.max_elements.map_or(max, |limit| limit.min(max))
+    }
+
+    /// Collect the embedded array of pointers `items` at `depth`, labeling the elements by
+    /// their index. Only the first [`max_elements`](PointerPrintConfig::max_elements)
+    /// pointers are followed, the ones after them are printed as not followed
+    pub fn collect_pointer_array<P, M, const N: usize>(
+        &mut self,
+        items: &[P; N],
+        mem: &mut M,
+        depth: usize,
+    ) -> PointerDebugNode
+    where
+        P: PointerLike + DerefDebugPrint,
+        P::Target: DerefDebugPrint + Pod,
+        M: MemoryView,
+    {
+        let limit = self.element_limit(N);
+        self.prefetch_targets(&items[..limit], mem, depth);
+
+        let mut node = PointerDebugNode::new(impls::short_type_name::<[P; N]>());
+        for (i, item) in items.iter().enumerate() {
+            self.enter_index(i);
+            let value = if i < limit {
+                item.collect_target(mem, depth, self)
+            } else {
+                PointerDebugValue::Pointer {
+                    address: item.pointer_address(),
+                    target: PointerTarget::NotFollowed,
+                }
+            };
+            self.leave_field();
+            node.fields.push(PointerDebugField {
+                name: format!("[{}]", i),
+                type_name: impls::short_type_name::<P>(),
+                value,
+            });
+        }
+        node
+    }
+}
+
+/// Merge the `(address, size)` ranges that overlap or follow each other, sorted by address
+fn coalesce_ranges(ranges: &[(u64, usize)]) -> Vec<(u64, usize)> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_unstable();
+    let mut merged: Vec<(u64, usize)> = Vec::with_capacity(sorted.len());
+    for (address, size) in sorted {
+        let end = address.saturating_add(size as u64);
+        match merged.last_mut() {
+            Some((start, len)) if address <= start.saturating_add(*len as u64) => {
+                *len = (end.max(start.saturating_add(*len as u64)) - *start) as usize;
+            }
+            _ => merged.push((address, size)),
+        }
+    }
+    merged
+}
@@ -0,0 +1,198 @@
+//! Totals of a traversal.
+
+use core::fmt;
+use core::time::Duration;
+
+use crate::{PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// Totals of one traversal.
+///
+/// Returned by [`PointerPrint::pointer_collect_with_stats`](crate::PointerPrint::pointer_collect_with_stats)
+/// and printed after the dump with [`PointerPrintConfig::show_stats`](crate::PointerPrintConfig::show_stats):
+///
+/// ```text
+/// ... 12 node(s), 11 pointer(s) followed, 1536 byte(s) read, 1 read(s) failed, 2 cycle(s), depth 3, 1.21ms
+/// ```
+///
+/// With a [`RetryPolicy`](crate::RetryPolicy) the reads that were tried again follow the
+/// failures as `3 retry(s)` if there were any, structures read from a misaligned address
+/// follow as `2 misaligned`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraversalStats {
+    /// Number of structures collected, including the root
+    pub nodes: usize,
+    /// Number of pointers whose target was read
+    pub pointers: usize,
+    /// Number of bytes read
+    pub bytes: usize,
+    /// Number of reads that failed
+    pub failures: usize,
+    /// Number of times a failed read was tried again, see
+    /// [`PointerPrintConfig::retry`](crate::PointerPrintConfig::retry)
+    pub retries: usize,
+    /// Number of structures read from an address that isn't aligned to their type, see
+    /// [`PointerDebugNode::misaligned`]
+    pub misaligned: usize,
+    /// Number of pointers to an already visited target
+    pub cycles: usize,
+    /// Deepest level a structure was collected at, the root is at depth 0
+    pub depth: usize,
+    /// Time the traversal took, always 0 without the `std` feature
+    pub elapsed: Duration,
+}
+
+impl TraversalStats {
+    /// Count the nodes, pointers, failures, cycles and misaligned nodes of a collected tree
+    /// and its depth.
+    ///
+    /// The tree doesn't record how much was read and how long it took, the
+    /// [`bytes`](Self::bytes), [`retries`](Self::retries) and [`elapsed`](Self::elapsed)
+    /// are left at 0.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{
+    ///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget, TraversalStats,
+    /// };
+    ///
+    /// let mut root = PointerDebugNode::new("Node");
+    /// root.fields.push(PointerDebugField {
+    ///     name: "next".to_string(),
+    ///     type_name: "Node".to_string(),
+    ///     value: PointerDebugValue::Pointer {
+    ///         address: 0x1000,
+    ///         target: PointerTarget::AlreadyVisited,
+    ///     },
+    /// });
+    ///
+    /// let stats = TraversalStats::from_node(&root);
+    /// assert_eq!((stats.nodes, stats.pointers, stats.cycles), (1, 0, 1));
+    /// ```
+    pub fn from_node(root: &PointerDebugNode) -> Self {
+        let mut stats = Self {
+            nodes: 1,
+            misaligned: usize::from(root.misaligned),
+            ..Self::default()
+        };
+        stats.add_node(root, 0);
+        stats
+    }
+
+    fn add_node(&mut self, node: &PointerDebugNode, depth: usize) {
+        for field in &node.fields {
+            match &field.value {
+                PointerDebugValue::Pointer { target, .. } => {
+                    // Arrays, chains and lists count every element read on their own
+                    if !matches!(
+                        target,
+                        PointerTarget::Chain { .. } | PointerTarget::List { .. }
+                    ) && is_read(target)
+                    {
+                        self.pointers += 1;
+                    }
+                    self.add_target(target, depth + 1);
+                }
+                PointerDebugValue::Nested(nested) => self.add_node(nested, depth),
+                PointerDebugValue::Value(_) => {}
+            }
+        }
+    }
+
+    fn add_target(&mut self, target: &PointerTarget, depth: usize) {
+        match target {
+            PointerTarget::Node(node) => {
+                self.nodes += 1;
+                self.misaligned += usize::from(node.misaligned);
+                self.depth = self.depth.max(depth);
+                self.add_node(node, depth);
+            }
+            PointerTarget::Array { items, .. } => {
+                for item in items {
+                    self.add_target(item, depth);
+                }
+            }
+            PointerTarget::Chain { items, .. } => {
+                for item in items {
+                    self.pointers += usize::from(is_read(item));
+                    self.add_target(item, depth);
+                }
+            }
+            PointerTarget::List { items, .. } => {
+                for (_, item) in items {
+                    self.pointers += usize::from(is_read(item));
+                    self.add_target(item, depth);
+                }
+            }
+            PointerTarget::ReadError(_) => self.failures += 1,
+            PointerTarget::AlreadyVisited => self.cycles += 1,
+            PointerTarget::Text(_) | PointerTarget::Bytes(_) => self.depth = self.depth.max(depth),
+            PointerTarget::MaxDepth
+            | PointerTarget::ChainLink
+            | PointerTarget::Null
+            | PointerTarget::Invalid
+            | PointerTarget::Unmapped
+            | PointerTarget::BudgetExhausted
+            | PointerTarget::Cancelled
+            | PointerTarget::NotFollowed => {}
+        }
+    }
+}
+
+/// Returns true if `target` was read from memory, whether it succeeded or not
+fn is_read(target: &PointerTarget) -> bool {
+    matches!(
+        target,
+        PointerTarget::Node(_)
+            | PointerTarget::Text(_)
+            | PointerTarget::Bytes(_)
+            | PointerTarget::Array { .. }
+            | PointerTarget::Chain { .. }
+            | PointerTarget::List { .. }
+            | PointerTarget::ReadError(_)
+    )
+}
+
+impl fmt::Display for TraversalStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} node(s), {} pointer(s) followed, {} byte(s) read, {} read(s) failed, ",
+            self.nodes, self.pointers, self.bytes, self.failures
+        )?;
+        if self.retries > 0 {
+            write!(f, "{} retry(s), ", self.retries)?;
+        }
+        if self.misaligned > 0 {
+            write!(f, "{} misaligned, ", self.misaligned)?;
+        }
+        write!(
+            f,
+            "{} cycle(s), depth {}, {:.2?}",
+            self.cycles, self.depth, self.elapsed
+        )
+    }
+}
+
+/// Measures how long a traversal takes, there is no clock without `std` and the time is 0
+pub(crate) struct Stopwatch {
+    #[cfg(feature = "std")]
+    start: std::time::Instant,
+}
+
+impl Stopwatch {
+    /// Start measuring now
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Time passed since [`start`](Self::start)
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(feature = "std")]
+        return self.start.elapsed();
+        #[cfg(not(feature = "std"))]
+        Duration::ZERO
+    }
+}
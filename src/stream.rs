@@ -0,0 +1,232 @@
+//! Events sent while a traversal collects a structure.
+
+use alloc::sync::Arc;
+use core::fmt;
+
+use memflow::error::Error;
+
+use crate::prelude::*;
+use crate::{PointerDebugField, PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// A part of a traversal, sent to the sender set with
+/// [`PointerPrintConfig::stream`](crate::PointerPrintConfig::stream) as soon as it was read.
+///
+/// The events are sent in post-order: a structure is sent as one group from
+/// [`EnterNode`](Self::EnterNode) to [`ExitNode`](Self::ExitNode) once it and everything its
+/// pointers lead to were collected, so the targets of its pointers come before it and the
+/// root comes last. The group holds the values of the structure and the structures embedded
+/// in it, not the targets of its pointers. A receiver rendering progressively shows the
+/// structures as they complete, deepest first, and places them by path. Paths are dotted
+/// starting at the root like `peb.ldr`, elements are appended as `[i]`: a structure sent at
+/// the path of a pointer field is its target. Pointers that aren't followed, like null
+/// pointers and targets past the maximum depth, aren't sent, the collected tree has them.
+///
+/// ```rust
+/// use std::sync::mpsc;
+///
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerPrint, PointerPrintConfig, PointerStreamEvent};
+///
+/// fn example<M, T>(mut mem: M, root: T)
+/// where
+///     M: MemoryView + Send + 'static,
+///     T: PointerPrint + Send + 'static,
+/// {
+///     let (sender, receiver) = mpsc::channel();
+///     std::thread::spawn(move || {
+///         root.pointer_stream(&mut mem, sender, &PointerPrintConfig::new().max_depth(64));
+///     });
+///     // A pointer field `team` of the root arrives as the structure at `team` before the
+///     // root at the empty path
+///     for event in receiver {
+///         match event {
+///             PointerStreamEvent::EnterNode { path, type_name, .. } => {
+///                 println!("read {} at `{}`", type_name, path)
+///             }
+///             PointerStreamEvent::Field { path, value, .. } => println!("  {} = {}", path, value),
+///             PointerStreamEvent::Error { path, error, .. } => println!("failed `{}`: {}", path, error),
+///             PointerStreamEvent::ExitNode { .. } => {}
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerStreamEvent {
+    /// The structure at `path` was read, its fields follow
+    EnterNode {
+        /// Path of the structure, empty for the root
+        path: String,
+        /// Name of the structure's type
+        type_name: String,
+        /// Address the structure was read from, `None` for the root value
+        address: Option<u64>,
+    },
+    /// A plain value of the structure entered last, a string or a hex dump read through a
+    /// pointer, or a scalar read through a pointer like `Pointer64<u64>`
+    Field {
+        /// Path of the value
+        path: String,
+        /// Declared type of the value
+        type_name: String,
+        /// The value as it is printed
+        value: String,
+    },
+    /// Reading the target of the pointer at `path` failed
+    Error {
+        /// Path of the pointer
+        path: String,
+        /// Address the pointer holds
+        address: u64,
+        /// What went wrong
+        error: Error,
+    },
+    /// All fields of the structure at `path` were sent
+    ExitNode {
+        /// Path of the structure
+        path: String,
+        /// Name of the structure's type
+        type_name: String,
+    },
+}
+
+/// Receives the events of a traversal, see [`PointerPrintConfig::stream`](crate::PointerPrintConfig::stream).
+///
+/// Implemented for the senders of `std::sync::mpsc` and for closures, e.g.
+/// `move |event| sender.send(event).is_ok()` to send into a crossbeam channel.
+pub trait StreamSender: Send + Sync {
+    /// Send `event`, returns false if the receiving end is gone and the traversal can stop
+    fn send_event(&self, event: PointerStreamEvent) -> bool;
+}
+
+impl<F: Fn(PointerStreamEvent) -> bool + Send + Sync> StreamSender for F {
+    fn send_event(&self, event: PointerStreamEvent) -> bool {
+        self(event)
+    }
+}
+
+#[cfg(feature = "std")]
+impl StreamSender for std::sync::mpsc::Sender<PointerStreamEvent> {
+    fn send_event(&self, event: PointerStreamEvent) -> bool {
+        self.send(event).is_ok()
+    }
+}
+
+#[cfg(feature = "std")]
+impl StreamSender for std::sync::mpsc::SyncSender<PointerStreamEvent> {
+    fn send_event(&self, event: PointerStreamEvent) -> bool {
+        self.send(event).is_ok()
+    }
+}
+
+/// Sender of the events of a traversal.
+///
+/// The sender is shared by clones of the configuration it was set on.
+#[derive(Clone)]
+pub struct StreamCallback(Arc<dyn StreamSender>);
+
+impl StreamCallback {
+    /// Wrap a sender
+    pub fn new(sender: impl StreamSender + 'static) -> Self {
+        Self(Arc::new(sender))
+    }
+
+    /// Send `event`, returns false if the receiving end is gone
+    pub fn send(&self, event: PointerStreamEvent) -> bool {
+        self.0.send_event(event)
+    }
+
+    /// Send the events of `target`, the target of the pointer at `path` holding `address`.
+    /// The structures behind the pointers of a structure were sent on their own already
+    pub(crate) fn send_target(&self, path: &str, address: u64, target: &PointerTarget) -> bool {
+        match target {
+            PointerTarget::Node(node) => self.send_node(path, node),
+            PointerTarget::Array { items, .. } => items.iter().enumerate().all(|(i, item)| {
+                let path = format!("{}[{}]", path, i);
+                match item {
+                    PointerTarget::Node(node) => self.send_node(&path, node),
+                    item => self.send_target(&path, address, item),
+                }
+            }),
+            PointerTarget::Text(text) => self.send(PointerStreamEvent::Field {
+                path: path.to_string(),
+                type_name: "str".to_string(),
+                value: format!("{:?}", text),
+            }),
+            PointerTarget::Bytes(bytes) => self.send(PointerStreamEvent::Field {
+                path: path.to_string(),
+                type_name: "[u8]".to_string(),
+                value: bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            }),
+            PointerTarget::ReadError(error) => self.send(PointerStreamEvent::Error {
+                path: path.to_string(),
+                address,
+                error: *error,
+            }),
+            // The elements of chains and lists are read and sent one by one
+            _ => true,
+        }
+    }
+
+    /// Send `node` at `path` with its values and the structures embedded in it
+    pub(crate) fn send_node(&self, path: &str, node: &PointerDebugNode) -> bool {
+        if !self.send(PointerStreamEvent::EnterNode {
+            path: path.to_string(),
+            type_name: node.type_name.clone(),
+            address: node.address,
+        }) {
+            return false;
+        }
+        if let Some(value) = &node.value
+            && !self.send(PointerStreamEvent::Field {
+                path: path.to_string(),
+                type_name: node.type_name.clone(),
+                value: value.clone(),
+            })
+        {
+            return false;
+        }
+        for PointerDebugField {
+            name,
+            type_name,
+            value,
+        } in &node.fields
+        {
+            let path = if path.is_empty() || name.starts_with('[') {
+                format!("{}{}", path, name)
+            } else {
+                format!("{}.{}", path, name)
+            };
+            let sent = match value {
+                PointerDebugValue::Value(value) => self.send(PointerStreamEvent::Field {
+                    path,
+                    type_name: type_name.clone(),
+                    value: value.clone(),
+                }),
+                PointerDebugValue::Nested(node) => self.send_node(&path, node),
+                PointerDebugValue::Pointer { .. } => true,
+            };
+            if !sent {
+                return false;
+            }
+        }
+        self.send(PointerStreamEvent::ExitNode {
+            path: path.to_string(),
+            type_name: node.type_name.clone(),
+        })
+    }
+}
+
+impl fmt::Debug for StreamCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StreamCallback(..)")
+    }
+}
+
+/// Senders are only equal to clones of themselves
+impl PartialEq for StreamCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for StreamCallback {}
@@ -0,0 +1,49 @@
+//! ANSI colors of the rendered output.
+
+use core::fmt::Display;
+
+use crate::PointerPrintConfig;
+use crate::prelude::*;
+
+/// Role of a piece of the output, decides its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Style {
+    TypeName,
+    FieldName,
+    Address,
+    /// Read failures
+    Error,
+    /// Targets that were skipped without reading them
+    Skipped,
+    /// Targets that were not followed, e.g. already visited ones
+    Dim,
+    /// Values that changed since the last read
+    #[cfg(feature = "std")]
+    Changed,
+}
+
+impl Style {
+    #[cfg(feature = "color")]
+    fn code(self) -> &'static str {
+        match self {
+            Style::TypeName => "32",
+            Style::FieldName => "36",
+            Style::Address => "35",
+            Style::Error => "31",
+            Style::Skipped => "33",
+            Style::Dim => "2",
+            #[cfg(feature = "std")]
+            Style::Changed => "1;33",
+        }
+    }
+}
+
+/// `text` in the color of `style` if colors are enabled
+#[cfg_attr(not(feature = "color"), allow(unused_variables))]
+pub(crate) fn paint(text: impl Display, style: Style, config: &PointerPrintConfig) -> String {
+    #[cfg(feature = "color")]
+    if config.color {
+        return format!("\x1b[{}m{}\x1b[0m", style.code(), text);
+    }
+    text.to_string()
+}
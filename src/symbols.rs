@@ -0,0 +1,195 @@
+//! Module and export names of a process.
+
+use core::ops::Range;
+
+use memflow::os::Process;
+use memflow::types::{Address, PageType};
+
+use crate::prelude::*;
+
+/// The modules of a process and their exports, used to name vtable and function pointers.
+///
+/// Set on a [`PointerPrintConfig`](crate::PointerPrintConfig) with
+/// [`symbols`](crate::PointerPrintConfig::symbols), fields that look like a vtable pointer
+/// are printed as the module and offset they point into, with the nearest export before
+/// them if there is one:
+///
+/// ```text
+/// vtable: Pointer64 = client.dll+0x1a2b30 (CreateInterface+0x40)
+/// ```
+///
+/// ```rust
+/// use memflow_pointer_debug::Symbols;
+///
+/// let symbols = Symbols::new()
+///     .module("client.dll", 0x7ff6_0000_0000..0x7ff6_0020_0000)
+///     .export("CreateInterface", 0x7ff6_001a_2af0);
+///
+/// assert_eq!(
+///     symbols.resolve(0x7ff6_001a_2b30).as_deref(),
+///     Some("client.dll+0x1a2b30 (CreateInterface+0x40)")
+/// );
+/// assert_eq!(symbols.resolve(0x1000), None);
+/// ```
+///
+/// Fields marked `#[pointer_debug(function)]` and address fields pointing into the
+/// executable memory of a module are printed as the export they point into, so callback
+/// tables and hooked functions can be read at a glance:
+///
+/// ```text
+/// on_open: u64 = kernel32.dll!CreateFileW+0x0
+/// ```
+///
+/// ```rust
+/// use memflow_pointer_debug::Symbols;
+///
+/// let symbols = Symbols::new()
+///     .module("kernel32.dll", 0x7ffa_0000_0000..0x7ffa_000c_0000)
+///     .export("CreateFileW", 0x7ffa_0002_4b60)
+///     .code(0x7ffa_0000_1000..0x7ffa_0008_0000);
+///
+/// assert_eq!(
+///     symbols.resolve_function(0x7ffa_0002_4b60).as_deref(),
+///     Some("kernel32.dll!CreateFileW+0x0")
+/// );
+/// assert_eq!(
+///     symbols.resolve_function(0x7ffa_0000_0800).as_deref(),
+///     Some("kernel32.dll+0x800")
+/// );
+/// // Only addresses inside executable memory look like a function
+/// assert_eq!(symbols.resolve_code(0x7ffa_0000_0800), None);
+/// assert!(symbols.resolve_code(0x7ffa_0002_4b70).is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Symbols {
+    /// Modules sorted by their base address
+    modules: Vec<(Range<u64>, String)>,
+    /// Exports sorted by their address
+    exports: Vec<(u64, String)>,
+    /// Executable memory
+    code: Vec<Range<u64>>,
+}
+
+impl Symbols {
+    /// Create an empty list of symbols
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the module `name` loaded at `range`
+    pub fn module(mut self, name: impl Into<String>, range: Range<u64>) -> Self {
+        let index = self
+            .modules
+            .partition_point(|(module, _)| module.start <= range.start);
+        self.modules.insert(index, (range, name.into()));
+        self
+    }
+
+    /// Add the export `name` at `address`, it is only used for addresses inside the same
+    /// module
+    pub fn export(mut self, name: impl Into<String>, address: u64) -> Self {
+        let index = self
+            .exports
+            .partition_point(|(export, _)| *export <= address);
+        self.exports.insert(index, (address, name.into()));
+        self
+    }
+
+    /// Add executable memory at `range`, address fields pointing into it are printed as a
+    /// function if they lie inside a module
+    pub fn code(mut self, range: Range<u64>) -> Self {
+        self.code.push(range);
+        self
+    }
+
+    /// Query the modules of a process, their exports and the executable memory.
+    ///
+    /// # Parameters
+    ///
+    /// * `process` - The process whose modules are listed, modules whose exports can't be
+    ///   read are added without them. Executable memory is only known if the OS layer
+    ///   provides a memory map
+    pub fn from_process<P: Process>(process: &mut P) -> Self {
+        let mut symbols = Self::new();
+        for region in process.mapped_mem_range_vec(0, Address::null(), Address::invalid()) {
+            if !region.2.contains(PageType::NOEXEC) {
+                let start = region.0.to_umem();
+                symbols = symbols.code(start..start.saturating_add(region.1));
+            }
+        }
+        for module in process.module_list().unwrap_or_default() {
+            let base = module.base.to_umem();
+            for export in process.module_export_list(&module).unwrap_or_default() {
+                symbols = symbols.export(export.name.to_string(), base + export.offset);
+            }
+            symbols = symbols.module(
+                module.name.to_string(),
+                base..base.saturating_add(module.size),
+            );
+        }
+        symbols
+    }
+
+    /// Name `address` by the module it lies in and the nearest export of that module before
+    /// it, `None` if it is outside every module
+    pub fn resolve(&self, address: u64) -> Option<String> {
+        let (range, name) = self.module_of(address)?;
+        let mut symbol = format!("{}+{:#x}", name, address - range.start);
+        if let Some((export, name)) = self.export_before(address, range) {
+            symbol += &match address - export {
+                0 => format!(" ({})", name),
+                offset => format!(" ({}+{:#x})", name, offset),
+            };
+        }
+        Some(symbol)
+    }
+
+    /// Name the function at `address` like `kernel32.dll!CreateFileW+0x0` by the nearest
+    /// export of its module before it, or like `kernel32.dll+0x1a2b` without one. `None` if
+    /// it is outside every module
+    pub fn resolve_function(&self, address: u64) -> Option<String> {
+        let (range, module) = self.module_of(address)?;
+        Some(match self.export_before(address, range) {
+            Some((export, name)) => format!("{}!{}+{:#x}", module, name, address - export),
+            None => format!("{}+{:#x}", module, address - range.start),
+        })
+    }
+
+    /// Name `address` like [`resolve_function`](Self::resolve_function) if it lies in
+    /// executable memory added with [`code`](Self::code), `None` otherwise
+    pub fn resolve_code(&self, address: u64) -> Option<String> {
+        if !self.code.iter().any(|code| code.contains(&address)) {
+            return None;
+        }
+        self.resolve_function(address)
+    }
+
+    /// The module `address` lies in
+    fn module_of(&self, address: u64) -> Option<&(Range<u64>, String)> {
+        let index = self
+            .modules
+            .partition_point(|(module, _)| module.start <= address);
+        self.modules[..index]
+            .iter()
+            .rev()
+            .find(|(module, _)| module.contains(&address))
+    }
+
+    /// The nearest export at or before `address` inside the module at `range`
+    fn export_before(&self, address: u64, range: &Range<u64>) -> Option<(u64, &str)> {
+        let index = self
+            .exports
+            .partition_point(|(export, _)| *export <= address);
+        index
+            .checked_sub(1)
+            .map(|index| &self.exports[index])
+            .filter(|(export, _)| *export >= range.start)
+            .map(|(export, name)| (*export, name.as_str()))
+    }
+
+    /// Returns true if no module is known
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
@@ -0,0 +1,119 @@
+//! A lock for state shared between traversals.
+
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use core::cell::UnsafeCell;
+#[cfg(not(feature = "std"))]
+use core::ops::{Deref, DerefMut};
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// The mutex of `std`, without it a spin lock.
+///
+/// Locking never fails: the state behind the locks of this crate stays consistent even if
+/// a traversal panicked while holding one, so poisoning is ignored.
+pub(crate) struct Mutex<T: ?Sized> {
+    #[cfg(feature = "std")]
+    inner: std::sync::Mutex<T>,
+    #[cfg(not(feature = "std"))]
+    locked: AtomicBool,
+    #[cfg(not(feature = "std"))]
+    value: UnsafeCell<T>,
+}
+
+#[cfg(feature = "std")]
+pub(crate) type MutexGuard<'a, T> = std::sync::MutexGuard<'a, T>;
+
+/// Access to the value of a locked [`Mutex`], it is unlocked when this is dropped
+#[cfg(not(feature = "std"))]
+pub(crate) struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+// Like `std`'s mutex the lock hands out the value to one thread at a time
+#[cfg(not(feature = "std"))]
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+#[cfg(not(feature = "std"))]
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    /// Create an unlocked mutex
+    pub(crate) fn new(value: T) -> Self {
+        #[cfg(feature = "std")]
+        return Self {
+            inner: std::sync::Mutex::new(value),
+        };
+        #[cfg(not(feature = "std"))]
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Block until the lock is acquired
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        #[cfg(feature = "std")]
+        return self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        #[cfg(not(feature = "std"))]
+        {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+            MutexGuard { mutex: self }
+        }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "std")]
+        return self.inner.fmt(f);
+        #[cfg(not(feature = "std"))]
+        {
+            // Formatting a value while it is locked would spin forever
+            if self.locked.swap(true, Ordering::Acquire) {
+                return f.write_str("Mutex { <locked> }");
+            }
+            let guard = MutexGuard { mutex: self };
+            f.debug_struct("Mutex").field("data", &&*guard).finish()
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard holds the lock
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the guard holds the lock
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,187 @@
+//! An in-memory fake of a target for tests.
+
+use core::ops::Range;
+
+use memflow::cglue::{CTup2, CTup3};
+use memflow::dataview::{Pod, PodMethods};
+use memflow::error::{Error, ErrorKind, ErrorOrigin, Result};
+use memflow::mem::{
+    MemOps, MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps, opt_call,
+};
+use memflow::types::{Address, Pointer64, umem};
+
+use crate::HashMap;
+use crate::prelude::*;
+
+/// A memory view backed by a map of addresses to bytes, for testing derived structures
+/// without a connector.
+///
+/// Values are placed at fixed addresses, so structures can point at each other before
+/// they are placed. Writes place the bytes written. Bytes that were never placed read as
+/// zeros like a partial read of a real process, a read none of whose bytes were placed is
+/// reported to memflow as failed. Reads of the ranges passed to
+/// [`fail_reads`](Self::fail_reads) return an error.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{FakeMemory, PointerDerefDebugPrint, PointerPrint};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Node {
+///     value: u64,
+///     next: Pointer64<Node>,
+/// }
+///
+/// let mut mem = FakeMemory::new();
+/// let second = mem.place(0x2000, &Node { value: 2, next: Pointer64::null() });
+/// mem.place(0x1000, &Node { value: 1, next: second });
+///
+/// let head = Node { value: 0, next: Pointer64::from(0x1000u64) };
+/// assert_eq!(
+///     head.pointer_collect(&mut mem, 5).to_string(),
+///     "Node {
+///   value: u64 = 0
+///   next-> Node
+///     value: u64 = 1
+///     next-> Node
+///       value: u64 = 2
+///       next: NULL
+///     }
+///   }
+/// }
+/// "
+/// );
+///
+/// mem.fail_reads(0x2000..0x2010);
+/// assert!(head.pointer_collect(&mut mem, 5).to_string().contains("→ Error reading"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FakeMemory {
+    bytes: HashMap<u64, u8>,
+    /// Reads touching these ranges return an error
+    faults: Vec<Range<u64>>,
+}
+
+impl FakeMemory {
+    /// Create a memory without any bytes placed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place `value` at `address` and return a pointer to it
+    pub fn place<T: Pod>(&mut self, address: u64, value: &T) -> Pointer64<T> {
+        self.place_bytes(address, value.as_bytes());
+        Pointer64::from(address)
+    }
+
+    /// Place `bytes` starting at `address`
+    pub fn place_bytes(&mut self, address: u64, bytes: &[u8]) {
+        for (offset, byte) in bytes.iter().enumerate() {
+            self.bytes.insert(address + offset as u64, *byte);
+        }
+    }
+
+    /// Place a NUL-terminated string at `address`, e.g. for a `cstr` field.
+    ///
+    /// ```rust
+    /// use memflow::prelude::v1::*;
+    /// use memflow_pointer_debug::{FakeMemory, PointerDerefDebugPrint, PointerPrint};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+    /// struct Player {
+    ///     #[pointer_debug(cstr)]
+    ///     name: Pointer64<u8>,
+    /// }
+    ///
+    /// // The string is read with a longer read than the bytes placed
+    /// let mut mem = FakeMemory::new();
+    /// let player = Player { name: mem.place_cstr(0x1000, "hello") };
+    /// assert!(player.pointer_collect(&mut mem, 5).to_string().contains("\"hello\""));
+    /// ```
+    pub fn place_cstr(&mut self, address: u64, text: &str) -> Pointer64<u8> {
+        self.place_bytes(address, text.as_bytes());
+        self.place_bytes(address + text.len() as u64, &[0]);
+        Pointer64::from(address)
+    }
+
+    /// Make every read touching `range` fail with an error, e.g. to test how a structure with
+    /// unreadable targets prints
+    pub fn fail_reads(&mut self, range: Range<u64>) {
+        self.faults.push(range);
+    }
+
+    /// Returns true if every byte of the `len` bytes at `address` was placed
+    pub fn contains(&self, address: u64, len: usize) -> bool {
+        (0..len as u64).all(|offset| self.bytes.contains_key(&(address + offset)))
+    }
+}
+
+impl MemoryView for FakeMemory {
+    fn read_raw_iter(
+        &mut self,
+        MemOps {
+            inp,
+            mut out,
+            mut out_fail,
+        }: ReadRawMemOps,
+    ) -> Result<()> {
+        let mut result = Ok(());
+        for CTup3(address, meta, buf) in inp {
+            let buf: &mut [u8] = buf.into();
+            let address = address.to_umem();
+            let end = address + buf.len() as u64;
+            if self
+                .faults
+                .iter()
+                .any(|fault| fault.start < end && address < fault.end)
+            {
+                result = Err(Error(ErrorOrigin::Memory, ErrorKind::OutOfBounds));
+                opt_call(out_fail.as_deref_mut(), CTup2(meta, buf.into()));
+            } else if (0..buf.len() as u64)
+                .any(|offset| self.bytes.contains_key(&(address + offset)))
+            {
+                // Bytes that were not placed read as zeros
+                for (offset, byte) in buf.iter_mut().enumerate() {
+                    *byte = self
+                        .bytes
+                        .get(&(address + offset as u64))
+                        .copied()
+                        .unwrap_or(0);
+                }
+                opt_call(out.as_deref_mut(), CTup2(meta, buf.into()));
+            } else {
+                opt_call(out_fail.as_deref_mut(), CTup2(meta, buf.into()));
+            }
+        }
+        result
+    }
+
+    fn write_raw_iter(
+        &mut self,
+        MemOps {
+            inp,
+            mut out,
+            out_fail: _,
+        }: WriteRawMemOps,
+    ) -> Result<()> {
+        for CTup3(address, meta, buf) in inp {
+            let buf: &[u8] = buf.into();
+            self.place_bytes(address.to_umem(), buf);
+            opt_call(out.as_deref_mut(), CTup2(meta, buf.into()));
+        }
+        Ok(())
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        let end = self.bytes.keys().max().map_or(0, |address| address + 1);
+        MemoryViewMetadata {
+            max_address: Address::from(end.saturating_sub(1)),
+            real_size: end as umem,
+            readonly: false,
+            little_endian: true,
+            arch_bits: 64,
+        }
+    }
+}
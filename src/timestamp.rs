@@ -0,0 +1,73 @@
+//! Dates and times of timestamp fields.
+
+use crate::prelude::*;
+
+/// Encoding of a timestamp, see [`format_timestamp`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimestampFormat {
+    /// 100 nanosecond intervals since 1601-01-01 like the Windows `FILETIME`
+    FileTime,
+    /// Seconds since 1970-01-01
+    UnixSecs,
+    /// Milliseconds since 1970-01-01
+    UnixMillis,
+}
+
+/// 100 nanosecond intervals between 1601-01-01 and 1970-01-01
+const FILETIME_UNIX_EPOCH: i128 = 116_444_736_000_000_000;
+
+/// Format `value` as a UTC date and time followed by the raw value, e.g.
+/// `2023-11-14 22:13:20 UTC (1700000000)`.
+///
+/// Formats with a sub-second resolution print milliseconds as well. This is used by the
+/// `#[pointer_debug(timestamp = "...")]` attribute.
+///
+/// ```rust
+/// use memflow_pointer_debug::{TimestampFormat, format_timestamp};
+///
+/// assert_eq!(
+///     format_timestamp(1_700_000_000, TimestampFormat::UnixSecs),
+///     "2023-11-14 22:13:20 UTC (1700000000)"
+/// );
+/// assert_eq!(
+///     format_timestamp(133_444_736_001_230_000, TimestampFormat::FileTime),
+///     "2023-11-14 22:13:20.123 UTC (133444736001230000)"
+/// );
+/// ```
+pub fn format_timestamp(value: i64, format: TimestampFormat) -> String {
+    let millis = match format {
+        TimestampFormat::FileTime => (i128::from(value) - FILETIME_UNIX_EPOCH).div_euclid(10_000),
+        TimestampFormat::UnixSecs => i128::from(value) * 1000,
+        TimestampFormat::UnixMillis => i128::from(value),
+    };
+    let secs = millis.div_euclid(1000) as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    let (hour, minute, second) = (time / 3600, time / 60 % 60, time % 60);
+
+    let mut out = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+    if format != TimestampFormat::UnixSecs {
+        out.push_str(&format!(".{:03}", millis.rem_euclid(1000)));
+    }
+    format!("{} UTC ({})", out, value)
+}
+
+/// Year, month and day of the day `days` after 1970-01-01 in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    // Count from 0000-03-01 so leap days end the year, eras repeat every 400 years
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,151 @@
+//! Spans and events emitted with the `tracing` feature.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+use crate::prelude::*;
+#[cfg(feature = "tracing")]
+use crate::{PointerDebugField, PointerDebugValue};
+use crate::{PointerDebugNode, PointerTarget};
+
+/// Target of every span and event of the traversal
+#[cfg(feature = "tracing")]
+const TARGET: &str = "memflow_pointer_debug";
+
+/// Returns true if a subscriber is interested in the spans of the traversal
+pub(crate) fn enabled() -> bool {
+    #[cfg(feature = "tracing")]
+    return tracing::enabled!(target: TARGET, tracing::Level::DEBUG);
+    #[cfg(not(feature = "tracing"))]
+    false
+}
+
+/// Span of one pointer dereference, see [`TraversalState::deref_span`](crate::TraversalState::deref_span)
+/// and [`TraversalState::finish_deref`](crate::TraversalState::finish_deref).
+///
+/// The span is entered until this is dropped, so the dereferences made while collecting
+/// the target are nested inside it. Without the `tracing` feature this does nothing.
+#[must_use]
+pub struct DerefSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    /// Path of the pointer, taken when the dereference starts
+    path: String,
+    address: u64,
+    #[cfg(feature = "tracing")]
+    start: Instant,
+}
+
+impl DerefSpan {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn new(path: String, address: u64, depth: usize) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!(
+                target: TARGET,
+                "deref",
+                path = %path,
+                address = %format_args!("{:#x}", address),
+                depth,
+                latency_us = tracing::field::Empty,
+            )
+            .entered(),
+            path,
+            address,
+            #[cfg(feature = "tracing")]
+            start: Instant::now(),
+        }
+    }
+
+    /// Span of the batched read of `count` pointer targets taking `bytes` bytes
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn batch(path: String, count: usize, bytes: usize) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!(
+                target: TARGET,
+                "batch_read",
+                path = %path,
+                count,
+                bytes,
+                latency_us = tracing::field::Empty,
+            )
+            .entered(),
+            path,
+            address: 0,
+            #[cfg(feature = "tracing")]
+            start: Instant::now(),
+        }
+    }
+
+    /// Path of the pointer that is dereferenced
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Address that is read
+    pub(crate) fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Record how long the read of the target took, call right after the read
+    pub fn read_done(&self) {
+        #[cfg(feature = "tracing")]
+        self.span
+            .record("latency_us", self.start.elapsed().as_micros() as u64);
+    }
+
+    /// Emit the values of the collected `target` as events
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn finish(self, target: &PointerTarget) {
+        #[cfg(feature = "tracing")]
+        match target {
+            PointerTarget::Node(node) => node_events(&self.path, node),
+            PointerTarget::Text(text) => {
+                tracing::trace!(target: TARGET, path = %self.path, value = %text.escape_debug())
+            }
+            PointerTarget::Bytes(bytes) => {
+                tracing::trace!(target: TARGET, path = %self.path, value = %bytes.escape_ascii())
+            }
+            PointerTarget::ReadError(error) => {
+                tracing::debug!(target: TARGET, path = %self.path, error = %error, "read failed")
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Emit the values of the root structure of a traversal as events
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn root_events(node: &PointerDebugNode) {
+    #[cfg(feature = "tracing")]
+    node_events("", node);
+}
+
+/// Emit an event per value of `node` and the structures embedded in it, the values behind
+/// its pointers have their own span
+#[cfg(feature = "tracing")]
+fn node_events(path: &str, node: &PointerDebugNode) {
+    if let Some(value) = &node.value {
+        tracing::trace!(target: TARGET, path = %path, r#type = %node.type_name, value = %value);
+    }
+    for PointerDebugField {
+        name,
+        type_name,
+        value,
+    } in &node.fields
+    {
+        let path = match path {
+            "" => name.clone(),
+            _ if name.starts_with('[') => format!("{}{}", path, name),
+            _ => format!("{}.{}", path, name),
+        };
+        match value {
+            PointerDebugValue::Value(value) => {
+                tracing::trace!(target: TARGET, path = %path, r#type = %type_name, value = %value)
+            }
+            PointerDebugValue::Nested(node) => node_events(&path, node),
+            PointerDebugValue::Pointer { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Memory views of other address spaces.
+
+use alloc::sync::Arc;
+use core::fmt;
+
+use memflow::error::Result;
+use memflow::mem::{MemoryView, MemoryViewMetadata, ReadRawMemOps, WriteRawMemOps};
+
+use crate::sync::{Mutex, MutexGuard};
+
+/// Name of the view of the physical memory fields marked `#[pointer_debug(phys)]` are read
+/// from, see [`PointerPrintConfig::physical_memory`](crate::PointerPrintConfig::physical_memory)
+pub const PHYSICAL_VIEW: &str = "phys";
+
+/// The part of [`MemoryView`] a [`SharedView`] forwards to, it can be used as a trait object
+pub(crate) trait RawMemoryView: Send {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()>;
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()>;
+    fn metadata(&self) -> MemoryViewMetadata;
+}
+
+impl<M: MemoryView> RawMemoryView for M {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        MemoryView::read_raw_iter(self, data)
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        MemoryView::write_raw_iter(self, data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        MemoryView::metadata(self)
+    }
+}
+
+/// A memory view of another address space, registered by name with
+/// [`PointerPrintConfig::view`](crate::PointerPrintConfig::view).
+///
+/// Fields marked `#[pointer_debug(view = "kernel")]` are read through the view registered as
+/// `kernel` instead of the memory the traversal started in, e.g. the kernel address space
+/// for a pointer of a user mode structure into kernel memory, or another process. Clones
+/// share the same view, reads through them are serialized.
+#[derive(Clone)]
+pub struct SharedView(Arc<Mutex<dyn RawMemoryView>>);
+
+impl SharedView {
+    /// Share `view`
+    pub fn new(view: impl MemoryView + 'static) -> Self {
+        Self(Arc::new(Mutex::new(view)))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, dyn RawMemoryView + 'static> {
+        // A panic inside a read leaves the view itself intact
+        self.0.lock()
+    }
+}
+
+impl MemoryView for SharedView {
+    fn read_raw_iter(&mut self, data: ReadRawMemOps) -> Result<()> {
+        self.lock().read_raw_iter(data)
+    }
+
+    fn write_raw_iter(&mut self, data: WriteRawMemOps) -> Result<()> {
+        self.lock().write_raw_iter(data)
+    }
+
+    fn metadata(&self) -> MemoryViewMetadata {
+        self.lock().metadata()
+    }
+}
+
+impl fmt::Debug for SharedView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedView(..)")
+    }
+}
+
+/// Views are only equal to clones of themselves
+impl PartialEq for SharedView {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedView {}
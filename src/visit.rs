@@ -0,0 +1,134 @@
+//! Callback based access to a collected tree.
+
+use memflow::error::Error;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::{PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// Callbacks invoked for every structure, value and pointer of a collected tree.
+///
+/// Paths are dotted starting at the root like `peb.ldr`, elements of arrays, chains and
+/// lists are appended as `[i]`, the root structure itself has the empty path. Every method
+/// does nothing by default, implement the ones you need.
+///
+/// ```rust
+/// use memflow_pointer_debug::{
+///     PointerDebugField, PointerDebugNode, PointerDebugValue, PointerVisitor,
+/// };
+///
+/// #[derive(Default)]
+/// struct Scalars(Vec<String>);
+///
+/// impl PointerVisitor for Scalars {
+///     fn field_scalar(&mut self, path: &str, _type_name: &str, value: &str) {
+///         self.0.push(format!("{} = {}", path, value));
+///     }
+/// }
+///
+/// let mut node = PointerDebugNode::new("Player");
+/// node.fields.push(PointerDebugField {
+///     name: "health".to_string(),
+///     type_name: "u32".to_string(),
+///     value: PointerDebugValue::Value("100".to_string()),
+/// });
+///
+/// let mut scalars = Scalars::default();
+/// node.visit(&mut scalars);
+/// assert_eq!(scalars.0, ["health = 100"]);
+/// ```
+pub trait PointerVisitor {
+    /// A structure at `path` is entered, its fields follow
+    fn enter_struct(&mut self, path: &str, type_name: &str, address: Option<u64>) {
+        let _ = (path, type_name, address);
+    }
+
+    /// A field holding a plain value, or a scalar read through a pointer like `Pointer64<u64>`
+    fn field_scalar(&mut self, path: &str, type_name: &str, value: &str) {
+        let _ = (path, type_name, value);
+    }
+
+    /// A pointer holding `address`, called before the structure or elements it leads to are
+    /// visited
+    fn field_pointer(&mut self, path: &str, address: u64, target: &PointerTarget) {
+        let _ = (path, address, target);
+    }
+
+    /// Reading the target of the pointer at `path` failed, called after
+    /// [`field_pointer`](PointerVisitor::field_pointer)
+    fn read_error(&mut self, path: &str, address: u64, error: &Error) {
+        let _ = (path, address, error);
+    }
+
+    /// All fields of the structure at `path` were visited
+    fn exit_struct(&mut self, path: &str, type_name: &str) {
+        let _ = (path, type_name);
+    }
+}
+
+impl PointerDebugNode {
+    /// Invoke the callbacks of `visitor` for this tree in traversal order
+    pub fn visit<V: PointerVisitor + ?Sized>(&self, visitor: &mut V) {
+        visit_node(visitor, "", self);
+    }
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() || name.starts_with('[') {
+        format!("{}{}", prefix, name)
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+fn visit_node<V: PointerVisitor + ?Sized>(visitor: &mut V, path: &str, node: &PointerDebugNode) {
+    // Scalars read through a pointer have a value instead of fields
+    if let Some(value) = &node.value {
+        visitor.field_scalar(path, &node.type_name, value);
+        return;
+    }
+
+    visitor.enter_struct(path, &node.type_name, node.address);
+    for field in &node.fields {
+        let path = join(path, &field.name);
+        match &field.value {
+            PointerDebugValue::Value(value) => visitor.field_scalar(&path, &field.type_name, value),
+            PointerDebugValue::Pointer { address, target } => {
+                visit_target(visitor, &path, *address, target)
+            }
+            PointerDebugValue::Nested(nested) => visit_node(visitor, &path, nested),
+        }
+    }
+    visitor.exit_struct(path, &node.type_name);
+}
+
+fn visit_target<V: PointerVisitor + ?Sized>(
+    visitor: &mut V,
+    path: &str,
+    address: u64,
+    target: &PointerTarget,
+) {
+    visitor.field_pointer(path, address, target);
+
+    let items: Vec<(u64, &PointerTarget)> = match target {
+        PointerTarget::Node(node) => return visit_node(visitor, path, node),
+        PointerTarget::ReadError(error) => return visitor.read_error(path, address, error),
+        PointerTarget::Array { items, .. } => items
+            .iter()
+            .map(|item| (item_address(item, address), item))
+            .collect(),
+        PointerTarget::Chain { items, .. } => chain_addresses(address, items)
+            .into_iter()
+            .zip(items)
+            .collect(),
+        PointerTarget::List { items, .. } => items
+            .iter()
+            .map(|(address, item)| (*address, item))
+            .collect(),
+        _ => return,
+    };
+
+    for (i, (address, item)) in items.into_iter().enumerate() {
+        visit_target(visitor, &format!("{}[{}]", path, i), address, item);
+    }
+}
@@ -0,0 +1,134 @@
+//! Polling a structure for changed fields.
+
+use std::thread;
+use std::time::Duration;
+
+use memflow::mem::MemoryView;
+
+use crate::diff::{FlatField, flatten};
+use crate::retry::RetryView;
+use crate::style::{Style, paint};
+use crate::{
+    DerefDebugPrint, FieldChange, PointerDebugDiff, PointerPrintConfig, TraversalState,
+    write_output,
+};
+
+/// Reads a structure again every `interval` and yields the fields that changed.
+///
+/// The value is collected once up front, every item is the list of changes of the next poll
+/// that changed anything. Iteration ends once the
+/// [`cancellation`](PointerPrintConfig::cancellation) token of the configuration is
+/// cancelled.
+///
+/// Values passed by reference aren't read again, only what their pointers lead to. To watch
+/// a structure itself, watch a pointer to it.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use memflow::prelude::v1::{MemoryView, Pod, Pointer64};
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, PointerPrintConfig, PointerWatch};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct Player {
+///     health: u32,
+///     armor: u32,
+/// }
+///
+/// fn watch(mem: &mut impl MemoryView, player: Pointer64<Player>) {
+///     let config = PointerPrintConfig::new();
+///     for changes in PointerWatch::new(&player, mem, Duration::from_millis(100), &config) {
+///         for change in changes {
+///             println!("{}", change);
+///         }
+///     }
+/// }
+/// ```
+pub struct PointerWatch<'a, T: ?Sized, M> {
+    value: &'a T,
+    mem: &'a mut M,
+    interval: Duration,
+    config: PointerPrintConfig,
+    /// Flattened fields of the last poll
+    last: Option<Vec<FlatField>>,
+}
+
+impl<'a, T: DerefDebugPrint + ?Sized, M: MemoryView> PointerWatch<'a, T, M> {
+    /// Watch `value`, polling `mem` every `interval`
+    pub fn new(
+        value: &'a T,
+        mem: &'a mut M,
+        interval: Duration,
+        config: &PointerPrintConfig,
+    ) -> Self {
+        Self {
+            value,
+            mem,
+            interval,
+            config: config.clone(),
+            last: None,
+        }
+    }
+
+    fn poll(&mut self) -> Vec<FlatField> {
+        let mut state = TraversalState::with_config(&self.config);
+        let mut mem = RetryView::new(&mut *self.mem, &self.config);
+        let node = self.value.pointer_collect_internal(&mut mem, 0, &mut state);
+        flatten(&node)
+    }
+}
+
+impl<T: DerefDebugPrint + ?Sized, M: MemoryView> Iterator for PointerWatch<'_, T, M> {
+    type Item = Vec<FieldChange>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.last.is_none() {
+            self.last = Some(self.poll());
+        }
+
+        loop {
+            if self.config.is_cancelled() {
+                return None;
+            }
+            thread::sleep(self.interval);
+            // A poll that was cut short by cancelling would report every field as gone
+            let fields = self.poll();
+            if self.config.is_cancelled() {
+                return None;
+            }
+
+            let last = self.last.as_deref().unwrap_or_default();
+            let changes = PointerDebugDiff::from_fields(last, &fields).into_changes();
+            self.last = Some(fields);
+            if !changes.is_empty() {
+                return Some(changes);
+            }
+        }
+    }
+}
+
+/// Print the changes of every poll of `value` until the configuration is cancelled
+pub(crate) fn print_watch<T, M>(
+    value: &T,
+    mem: &mut M,
+    interval: Duration,
+    config: &PointerPrintConfig,
+) where
+    T: DerefDebugPrint + ?Sized,
+    M: MemoryView,
+{
+    for changes in PointerWatch::new(value, mem, interval, config) {
+        for change in changes {
+            let old = change.old.as_deref().unwrap_or("<absent>");
+            let new = change.new.as_deref().unwrap_or("<absent>");
+            let line = format!(
+                "{}: {} → {}\n",
+                paint(&change.path, Style::FieldName, config),
+                paint(old, Style::Dim, config),
+                paint(new, Style::Changed, config)
+            );
+            write_output(&line, config);
+        }
+    }
+}
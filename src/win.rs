@@ -0,0 +1,174 @@
+//! Ready-made printers for common Windows structures.
+//!
+//! The layouts are those of 64-bit Windows processes and kernels. The process and loader
+//! structures are only declared up to the members printed here, reading them doesn't touch
+//! the rest of the real structure.
+
+use memflow::dataview::Pod;
+use memflow::mem::MemoryView;
+use memflow::types::Pointer64;
+
+use crate::prelude::*;
+use crate::{
+    DerefDebugPrint, ListEntry64, PointerDebugField, PointerDebugNode, PointerDebugValue,
+    PointerDerefDebugPrint, TraversalState,
+};
+
+/// A `LIST_ENTRY` collected with `nested` is printed with its links in hex, they are not
+/// followed. The `list` attribute of [`PointerDerefDebugPrint`] on the list head prints the
+/// linked structures
+impl DerefDebugPrint for ListEntry64 {
+    fn pointer_collect_internal<M>(
+        &self,
+        _mem: &mut M,
+        _depth: usize,
+        _state: &mut TraversalState,
+    ) -> PointerDebugNode
+    where
+        M: MemoryView,
+    {
+        let mut node = PointerDebugNode::new("ListEntry64");
+        for (name, link) in [("flink", self.flink), ("blink", self.blink)] {
+            node.fields.push(PointerDebugField {
+                name: name.to_string(),
+                type_name: "u64".to_string(),
+                value: PointerDebugValue::Value(format!("{:#x}", link)),
+            });
+        }
+        node
+    }
+}
+
+/// A 64-bit `UNICODE_STRING`, printed with the string its `buffer` points to.
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{PointerDerefDebugPrint, UnicodeString64};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Debug, Pod, PointerDerefDebugPrint)]
+/// struct ObjectName {
+///     #[pointer_debug(nested)]
+///     name: UnicodeString64,
+///     object: u64,
+/// }
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, PointerDerefDebugPrint)]
+pub struct UnicodeString64 {
+    /// Length of the string in bytes, without a terminating NUL
+    pub length: u16,
+    /// Size of the buffer in bytes
+    pub maximum_length: u16,
+    #[pointer_debug(pad)]
+    pub _pad: u32,
+    /// The UTF-16 characters, `length` bytes of them are read
+    #[pointer_debug(wstr(len_field = "length", max_len = 32768))]
+    pub buffer: Pointer64<u16>,
+}
+
+/// A 64-bit `RTL_BALANCED_NODE` of the red-black and AVL trees of the kernel and the loader.
+///
+/// The children are followed, the parent and the balance bits sharing its address are
+/// printed in hex.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, PointerDerefDebugPrint)]
+pub struct RtlBalancedNode64 {
+    /// The left child
+    pub left: Pointer64<RtlBalancedNode64>,
+    /// The right child
+    pub right: Pointer64<RtlBalancedNode64>,
+    /// The parent in the upper bits, `Red` or `Balance` in the lowest two bits
+    #[pointer_debug(format = "hex")]
+    pub parent_value: u64,
+}
+
+/// The start of the 64-bit `PEB` of a process, up to its `ProcessParameters`.
+///
+/// Printing it lists the loaded modules of the process:
+///
+/// ```rust
+/// use memflow::prelude::v1::*;
+/// use memflow_pointer_debug::{Peb64, PointerPrint};
+///
+/// fn print_modules(mem: &mut impl MemoryView, peb: Address) -> Result<()> {
+///     let peb: Peb64 = mem.read(peb)?;
+///     peb.pointer_print(mem);
+///     Ok(())
+/// }
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, PointerDerefDebugPrint)]
+pub struct Peb64 {
+    pub inherited_address_space: u8,
+    pub read_image_file_exec_options: u8,
+    pub being_debugged: u8,
+    #[pointer_debug(flags(
+        IMAGE_USES_LARGE_PAGES = 0x1,
+        IS_PROTECTED_PROCESS = 0x2,
+        IS_IMAGE_DYNAMICALLY_RELOCATED = 0x4,
+        SKIP_PATCHING_USER32_FORWARDERS = 0x8,
+        IS_PACKAGED_PROCESS = 0x10,
+        IS_APP_CONTAINER = 0x20,
+        IS_PROTECTED_PROCESS_LIGHT = 0x40,
+        IS_LONG_PATH_AWARE_PROCESS = 0x80
+    ))]
+    pub bit_field: u8,
+    #[pointer_debug(pad)]
+    pub _pad: [u8; 4],
+    #[pointer_debug(format = "hex")]
+    pub mutant: u64,
+    #[pointer_debug(format = "hex")]
+    pub image_base_address: u64,
+    /// The loader data listing the modules
+    pub ldr: Pointer64<PebLdrData64>,
+    #[pointer_debug(format = "hex")]
+    pub process_parameters: u64,
+}
+
+/// The 64-bit `PEB_LDR_DATA`, the heads of the module lists of a process.
+///
+/// The modules are printed in load order, the heads of the other two lists holding the same
+/// modules in another order only print their links.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, PointerDerefDebugPrint)]
+pub struct PebLdrData64 {
+    pub length: u32,
+    pub initialized: u8,
+    #[pointer_debug(pad)]
+    pub _pad: [u8; 3],
+    #[pointer_debug(format = "hex")]
+    pub ss_handle: u64,
+    #[pointer_debug(list(
+        entry = "LdrDataTableEntry64",
+        field = "in_load_order_links",
+        max = 1024
+    ))]
+    pub in_load_order_module_list: ListEntry64,
+    pub in_memory_order_module_list: ListEntry64,
+    pub in_initialization_order_module_list: ListEntry64,
+}
+
+/// The start of the 64-bit `LDR_DATA_TABLE_ENTRY` of a loaded module, up to its
+/// `BaseDllName`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, PointerDerefDebugPrint)]
+pub struct LdrDataTableEntry64 {
+    pub in_load_order_links: ListEntry64,
+    pub in_memory_order_links: ListEntry64,
+    pub in_initialization_order_links: ListEntry64,
+    #[pointer_debug(format = "hex")]
+    pub dll_base: u64,
+    #[pointer_debug(format = "hex")]
+    pub entry_point: u64,
+    #[pointer_debug(format = "hex")]
+    pub size_of_image: u32,
+    #[pointer_debug(pad)]
+    pub _pad: u32,
+    /// Path of the module file
+    #[pointer_debug(nested)]
+    pub full_dll_name: UnicodeString64,
+    /// File name of the module
+    #[pointer_debug(nested)]
+    pub base_dll_name: UnicodeString64,
+}
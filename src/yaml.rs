@@ -0,0 +1,216 @@
+//! YAML export of a collected tree.
+//!
+//! [`PointerDebugNode::to_yaml`] writes the tree as a YAML document so dumps can be
+//! archived and compared with standard tools. Field order is kept, plain values are
+//! written as strings exactly as they would be printed.
+
+use core::fmt::Write;
+
+use crate::node::{chain_addresses, item_address};
+use crate::prelude::*;
+use crate::{PointerDebugNode, PointerDebugValue, PointerTarget};
+
+/// A YAML value, written by [`Yaml::write`]
+enum Yaml {
+    /// A scalar written as is, e.g. a number or an already quoted string
+    Plain(String),
+    Map(Vec<(String, Yaml)>),
+    Seq(Vec<Yaml>),
+}
+
+impl Yaml {
+    /// A string scalar, quoted if needed
+    fn string(s: &str) -> Self {
+        Yaml::Plain(quote(s))
+    }
+
+    fn address(address: u64) -> Self {
+        Yaml::Plain(format!("{:#x}", address))
+    }
+
+    /// Write this value after a `key:` or `-` that was written already
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Yaml::Plain(s) => {
+                let _ = writeln!(out, " {}", s);
+            }
+            Yaml::Map(entries) if entries.is_empty() => out.push_str(" {}\n"),
+            Yaml::Seq(items) if items.is_empty() => out.push_str(" []\n"),
+            Yaml::Map(entries) => {
+                out.push('\n');
+                write_entries(out, entries, indent);
+            }
+            Yaml::Seq(items) => {
+                out.push('\n');
+                for item in items {
+                    let _ = write!(out, "{:indent$}-", "", indent = indent);
+                    match item {
+                        // The first entry of a map shares the line of its `-`
+                        Yaml::Map(entries) if !entries.is_empty() => {
+                            let mut map = String::new();
+                            write_entries(&mut map, entries, indent + 2);
+                            out.push(' ');
+                            out.push_str(&map[indent + 2..]);
+                        }
+                        _ => item.write(out, indent + 2),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write `key: value` lines at the given indentation
+fn write_entries(out: &mut String, entries: &[(String, Yaml)], indent: usize) {
+    for (key, value) in entries {
+        let _ = write!(out, "{:indent$}{}:", "", quote(key), indent = indent);
+        value.write(out, indent + 2);
+    }
+}
+
+/// Quote `s` unless it can be written as a plain scalar that reads back as the same string
+fn quote(s: &str) -> String {
+    let plain = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !matches!(
+            s.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null"
+        );
+    if plain {
+        return s.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl PointerDebugNode {
+    /// Render this tree as a YAML document.
+    ///
+    /// Every node is a mapping with its `type`, the `address` it was read from and either
+    /// the `value` of a scalar or its `fields` by name. Pointer fields hold the `address`
+    /// they point to and the `node` found there, the `text` of a string, the `items` of an
+    /// array, chain or list, the `error` of a failed read or the `status` of a target that
+    /// was not read, e.g. `visited` or `max_depth`.
+    ///
+    /// ```rust
+    /// use memflow_pointer_debug::{PointerDebugField, PointerDebugNode, PointerDebugValue};
+    ///
+    /// let mut node = PointerDebugNode::new("Node");
+    /// node.fields.push(PointerDebugField {
+    ///     name: "value".to_string(),
+    ///     type_name: "u32".to_string(),
+    ///     value: PointerDebugValue::Value("1".to_string()),
+    /// });
+    ///
+    /// assert_eq!(node.to_yaml(), "type: Node\nfields:\n  value: \"1\"\n");
+    /// ```
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        if let Yaml::Map(entries) = node_yaml(self) {
+            write_entries(&mut out, &entries, 0);
+        }
+        out
+    }
+}
+
+fn node_yaml(node: &PointerDebugNode) -> Yaml {
+    let mut entries = vec![("type".to_string(), Yaml::string(&node.type_name))];
+    if let Some(address) = node.address {
+        entries.push(("address".to_string(), Yaml::address(address)));
+    }
+
+    match &node.value {
+        Some(value) => entries.push(("value".to_string(), Yaml::string(value))),
+        None => {
+            let fields = node
+                .fields
+                .iter()
+                .map(|field| {
+                    let value = match &field.value {
+                        PointerDebugValue::Value(value) => Yaml::string(value),
+                        PointerDebugValue::Pointer { address, target } => {
+                            target_yaml(*address, target)
+                        }
+                        PointerDebugValue::Nested(node) => node_yaml(node),
+                    };
+                    (field.name.clone(), value)
+                })
+                .collect();
+            entries.push(("fields".to_string(), Yaml::Map(fields)));
+        }
+    }
+
+    Yaml::Map(entries)
+}
+
+/// Mapping describing the target of a pointer to `address`
+fn target_yaml(address: u64, target: &PointerTarget) -> Yaml {
+    let mut entries = vec![("address".to_string(), Yaml::address(address))];
+    let status = |status: &str| ("status".to_string(), Yaml::Plain(status.to_string()));
+
+    match target {
+        PointerTarget::Node(node) => entries.push(("node".to_string(), node_yaml(node))),
+        PointerTarget::AlreadyVisited => entries.push(status("visited")),
+        PointerTarget::MaxDepth => entries.push(status("max_depth")),
+        PointerTarget::ChainLink => entries.push(status("next_in_chain")),
+        PointerTarget::Null => entries.push(status("null_pointer")),
+        PointerTarget::Invalid => entries.push(status("invalid")),
+        PointerTarget::Unmapped => entries.push(status("unmapped")),
+        PointerTarget::BudgetExhausted => entries.push(status("budget_exhausted")),
+        PointerTarget::Cancelled => entries.push(status("cancelled")),
+        PointerTarget::NotFollowed => entries.push(status("not_followed")),
+        PointerTarget::Text(text) => entries.push(("text".to_string(), Yaml::string(text))),
+        PointerTarget::Bytes(bytes) => {
+            let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            entries.push(("bytes".to_string(), Yaml::string(&hex)))
+        }
+        PointerTarget::ReadError(e) => {
+            entries.push(("error".to_string(), Yaml::string(&e.to_string())))
+        }
+        PointerTarget::Array { len, items } => {
+            let items = items
+                .iter()
+                .map(|item| target_yaml(item_address(item, address), item))
+                .collect();
+            entries.push(("len".to_string(), Yaml::Plain(len.to_string())));
+            entries.push(("items".to_string(), Yaml::Seq(items)));
+        }
+        PointerTarget::Chain { items, more } => {
+            let items = chain_addresses(address, items)
+                .into_iter()
+                .zip(items)
+                .map(|(address, item)| target_yaml(address, item))
+                .collect();
+            entries.push(("items".to_string(), Yaml::Seq(items)));
+            entries.push(("more".to_string(), Yaml::Plain(more.to_string())));
+        }
+        PointerTarget::List { items, more } => {
+            let items = items
+                .iter()
+                .map(|(address, item)| target_yaml(*address, item))
+                .collect();
+            entries.push(("items".to_string(), Yaml::Seq(items)));
+            entries.push(("more".to_string(), Yaml::Plain(more.to_string())));
+        }
+    }
+
+    Yaml::Map(entries)
+}